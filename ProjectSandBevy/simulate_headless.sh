@@ -0,0 +1,14 @@
+#!/usr/bin/env sh
+# Smoke test for the headless binary: simulate a small seeded world and check a save was written
+set -eu
+
+cd "$(dirname "$0")"
+output="$(mktemp -d)/headless.sand"
+
+cargo run --quiet --release --bin sand-headless -- --steps 200 --width 160 --height 90 --seed 42 --output "$output"
+
+if [ ! -s "$output" ]; then
+    echo "No save written to $output" >&2
+    exit 1
+fi
+echo "OK: $(wc -c < "$output") bytes written to $output"
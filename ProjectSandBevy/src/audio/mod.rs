@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+
+/// Sample rate of the generated sounds
+pub const SAMPLE_RATE: u32 = 44_100;
+
+/// Frames between music updates
+pub const MUSIC_UPDATE_INTERVAL: u32 = 4;
+
+/// Share of the grid covered by Water before the calm ambient layer fades in
+pub const WATER_LAYER_COVERAGE: f32 = 0.2;
+
+/// How far the smoothed counts move toward the real counts each update
+pub const COUNT_SMOOTHING: f32 = 0.15;
+
+/// Embers playback rate with no fire and with the grid full of fire
+const EMBERS_MIN_SPEED: f32 = 0.6;
+const EMBERS_MAX_SPEED: f32 = 2.0;
+
+/// Fire coverage that plays the embers at full speed and volume
+const EMBERS_FULL_COVERAGE: f32 = 0.05;
+
+/// Music state following the simulation, updated by the `generate_music` system
+#[derive(Resource, Default, Debug)]
+pub struct SimulationAudio {
+    pub fire_count_smooth: f32,
+    pub water_count_smooth: f32,
+    /// An explosive detonated since the last update, a percussion hit is played
+    pub explosion_occurred: bool,
+}
+
+/// "Mute" and "Volume" under "Audio" in the UI
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct AudioSettings {
+    pub muted: bool,
+    /// Master volume, 0 to 1
+    pub volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { muted: false, volume: 0.5 }
+    }
+}
+
+impl AudioSettings {
+    /// Volume to play at, 0 when muted
+    #[must_use]
+    pub const fn effective_volume(&self) -> f32 {
+        if self.muted { 0.0 } else { self.volume }
+    }
+}
+
+/// The open audio device with the looping layers, missing when no device could be opened
+/// The stream has to stay on the thread that opened it, so this is a non-send resource
+pub struct AudioOutput {
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+    pub embers: Sink,
+    pub water: Sink,
+    percussion: Vec<f32>,
+}
+
+impl AudioOutput {
+    /// Open the default audio device and start both loops silent
+    ///
+    /// # Errors
+    /// Returns the device error if no output device or sink could be opened.
+    pub fn open() -> Result<Self, String> {
+        let (stream, handle) = OutputStream::try_default().map_err(|e| e.to_string())?;
+        let embers = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+        embers.set_volume(0.0);
+        embers.append(SamplesBuffer::new(1, SAMPLE_RATE, embers_loop()).repeat_infinite());
+        let water = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+        water.set_volume(0.0);
+        water.append(SamplesBuffer::new(1, SAMPLE_RATE, water_loop()).repeat_infinite());
+        Ok(Self {
+            _stream: stream,
+            handle,
+            embers,
+            water,
+            percussion: percussion_hit(),
+        })
+    }
+
+    /// Play the percussion hit once at `volume`
+    pub fn play_percussion(&self, volume: f32) {
+        match Sink::try_new(&self.handle) {
+            Ok(sink) => {
+                sink.set_volume(volume);
+                sink.append(SamplesBuffer::new(1, SAMPLE_RATE, self.percussion.clone()));
+                sink.detach();
+            }
+            Err(e) => bevy::log::warn!("Failed to play percussion: {}", e),
+        }
+    }
+}
+
+/// Move `current` toward `target` by `COUNT_SMOOTHING` of the difference
+#[must_use]
+pub fn smooth_toward(current: f32, target: f32) -> f32 {
+    (target - current).mul_add(COUNT_SMOOTHING, current)
+}
+
+/// Embers playback rate for the share of the grid on fire
+#[must_use]
+pub fn embers_speed(fire_coverage: f32) -> f32 {
+    let t = (fire_coverage / EMBERS_FULL_COVERAGE).clamp(0.0, 1.0);
+    (EMBERS_MAX_SPEED - EMBERS_MIN_SPEED).mul_add(t, EMBERS_MIN_SPEED)
+}
+
+/// Embers volume (before the master volume) for the share of the grid on fire, silent without fire
+#[must_use]
+pub fn embers_volume(fire_coverage: f32) -> f32 {
+    (fire_coverage / EMBERS_FULL_COVERAGE).sqrt().clamp(0.0, 1.0)
+}
+
+/// Water layer volume (before the master volume), fading in over the 10% above `WATER_LAYER_COVERAGE`
+#[must_use]
+pub fn water_volume(water_coverage: f32) -> f32 {
+    ((water_coverage - WATER_LAYER_COVERAGE) / 0.1).clamp(0.0, 1.0)
+}
+
+/// Two second loop of crackling embers: a low rumble with sparse sharp crackles
+#[must_use]
+pub fn embers_loop() -> Vec<f32> {
+    let mut rng = SmallRng::seed_from_u64(0xE3BE_5000);
+    let mut rumble = 0.0_f32;
+    let mut crackle = 0.0_f32;
+    (0..SAMPLE_RATE * 2)
+        .map(|_| {
+            // Low-passed noise
+            rumble += (rng.gen_range(-1.0..1.0) - rumble) * 0.02;
+            if rng.gen_bool(0.0006) {
+                crackle = rng.gen_range(0.3..0.7);
+            }
+            crackle *= 0.995;
+            rumble.mul_add(0.6, crackle * rng.gen_range(-1.0..1.0))
+        })
+        .collect()
+}
+
+/// Four second loop of a soft A major chord swelling twice
+/// Every frequency fits a whole number of cycles in the loop, so it repeats without a click
+#[must_use]
+pub fn water_loop() -> Vec<f32> {
+    const CHORD: [f32; 3] = [220.0, 275.0, 330.0];
+    (0..SAMPLE_RATE * 4)
+        .map(|i| {
+            let t = sample_time(i);
+            let swell = (std::f32::consts::TAU * 0.5 * t).sin().mul_add(0.4, 0.6);
+            // Average of the three notes
+            let chord: f32 = CHORD.iter().map(|&f| (std::f32::consts::TAU * f * t).sin()).sum();
+            chord / 3.0 * 0.3 * swell
+        })
+        .collect()
+}
+
+/// Half a second drum hit: a thump falling from 120 Hz to 50 Hz over a burst of noise
+#[must_use]
+pub fn percussion_hit() -> Vec<f32> {
+    let mut rng = SmallRng::seed_from_u64(0xD0_0E50);
+    let mut phase = 0.0_f32;
+    (0..SAMPLE_RATE / 2)
+        .map(|i| {
+            let t = sample_time(i);
+            let frequency = (-t * 20.0).exp().mul_add(70.0, 50.0);
+            phase += std::f32::consts::TAU * frequency * sample_time(1);
+            let thump = phase.sin() * (-t * 8.0).exp();
+            let noise = rng.gen_range(-1.0..1.0) * (-t * 30.0).exp();
+            thump * 0.8 + noise * 0.2
+        })
+        .collect()
+}
+
+/// Time in seconds of the sample at index i
+#[allow(clippy::cast_precision_loss)] // The sounds are a few seconds long, well within f32's exact integers
+fn sample_time(i: u32) -> f32 {
+    i as f32 / SAMPLE_RATE as f32
+}
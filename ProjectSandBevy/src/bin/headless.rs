@@ -0,0 +1,73 @@
+//! Run the simulation without a window and save the result
+//! Saves open in the game like any other save
+
+use clap::Parser;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use ProjectSandBevy::generation::{generate_caves, generate_terrain};
+use ProjectSandBevy::save::WorldSaveFile;
+use ProjectSandBevy::simulation::{GameGrid, SimulationRules};
+use ProjectSandBevy::spigots::Spigots;
+use ProjectSandBevy::systems::{simulate_ticks, RainbowSandPlacementTimes};
+use ProjectSandBevy::SIZE;
+
+#[derive(Parser)]
+#[command(about = "Run the falling sand simulation without a window and save the result")]
+struct Args {
+    /// Number of ticks to simulate
+    #[arg(long, default_value_t = 600)]
+    steps: u32,
+    /// Grid width in cells
+    #[arg(long, default_value_t = SIZE.x)]
+    width: u32,
+    /// Grid height in cells
+    #[arg(long, default_value_t = SIZE.y)]
+    height: u32,
+    /// Seed for the generated world and the simulation, which is then deterministic
+    /// Without one the world and simulation are random
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Where to write the save
+    #[arg(long, default_value = "headless.sand")]
+    output: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    if args.width == 0 || args.height == 0 {
+        eprintln!("Width and height must be at least 1");
+        return ExitCode::FAILURE;
+    }
+
+    let mut grid = GameGrid::new(args.width, args.height);
+    let world_seed = args.seed.unwrap_or_else(rand::random);
+    generate_terrain(&mut grid, world_seed);
+    generate_caves(&mut grid, world_seed);
+
+    let spigots = Spigots::default();
+    let mut rainbow_sand_times = RainbowSandPlacementTimes::default();
+    simulate_ticks(&mut grid, &spigots, args.steps, &SimulationRules::default(), args.seed, &mut rainbow_sand_times);
+
+    let bytes = match WorldSaveFile::new(&grid, &spigots, &rainbow_sand_times.0).to_bytes() {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("Failed to serialize the save: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = std::fs::write(&args.output, bytes) {
+        eprintln!("Failed to write {}: {e}", args.output.display());
+        return ExitCode::FAILURE;
+    }
+
+    let stats = grid.statistics();
+    println!(
+        "Simulated {} ticks of a {}x{} world (seed {world_seed}), {} non-empty cells, saved to {}",
+        args.steps,
+        args.width,
+        args.height,
+        stats.non_empty_cells,
+        args.output.display()
+    );
+    ExitCode::SUCCESS
+}
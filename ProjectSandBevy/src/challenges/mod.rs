@@ -0,0 +1,124 @@
+use crate::elements::Element;
+use crate::simulation::{GameGrid, GridStatistics};
+use bevy::prelude::*;
+use std::path::Path;
+
+/// Where challenges are read from at startup
+pub const CHALLENGES_PATH: &str = "assets/challenges.toml";
+
+/// Copy of the shipped challenges, used when the file can't be read
+const BUILT_IN_CHALLENGES: &str = include_str!("../../assets/challenges.toml");
+
+/// What has to be true on the grid for a challenge to be complete
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ChallengeCondition {
+    /// At least `min` cells of `element`
+    ElementCount { element: Element, min: u32 },
+    /// No cells of the element, counted only once the element has been on the grid
+    ElementAbsent(Element),
+    /// The cell at (x, y) is Fire
+    FireAt { x: u32, y: u32 },
+}
+
+impl ChallengeCondition {
+    /// How close the grid is to meeting the condition, from 0 to 1
+    pub fn progress(&self, grid: &GameGrid, stats: &GridStatistics) -> f32 {
+        match *self {
+            Self::ElementCount { element, min } => {
+                let count = stats.histogram[usize::from(element.index())];
+                if min == 0 { 1.0 } else { (count as f32 / min as f32).min(1.0) }
+            }
+            Self::ElementAbsent(_) | Self::FireAt { .. } => {
+                if evaluate_challenge(grid, stats, self) { 1.0 } else { 0.0 }
+            }
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChallengeStatus {
+    #[default]
+    Active,
+    Complete,
+}
+
+/// An objective shown in the Challenges window
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Challenge {
+    pub name: String,
+    pub description: String,
+    pub condition: ChallengeCondition,
+    #[serde(default)]
+    pub status: ChallengeStatus,
+    /// Whether an `ElementAbsent` element has been seen, so an empty grid doesn't complete it
+    #[serde(skip)]
+    pub armed: bool,
+}
+
+/// Challenges as written in challenges.toml
+#[derive(serde::Deserialize)]
+struct ChallengesFile {
+    challenges: Vec<Challenge>,
+}
+
+/// Check a condition against the grid and its current statistics
+pub fn evaluate_challenge(grid: &GameGrid, stats: &GridStatistics, cond: &ChallengeCondition) -> bool {
+    match *cond {
+        ChallengeCondition::ElementCount { element, min } => stats.histogram[usize::from(element.index())] >= min,
+        ChallengeCondition::ElementAbsent(element) => stats.histogram[usize::from(element.index())] == 0,
+        ChallengeCondition::FireAt { x, y } => grid.is_valid(x, y) && grid.get(x, y) == Element::Fire,
+    }
+}
+
+/// Parse challenges from TOML text
+///
+/// # Errors
+/// Returns an error if the text isn't a valid challenges file.
+pub fn parse_challenges(text: &str) -> Result<Vec<Challenge>, String> {
+    toml::from_str::<ChallengesFile>(text)
+        .map(|file| file.challenges)
+        .map_err(|e| e.to_string())
+}
+
+/// Resource with every challenge and the last one completed, for the toast
+#[derive(Resource, Default)]
+pub struct Challenges {
+    pub challenges: Vec<Challenge>,
+    /// Name of the last completed challenge and when it was completed (in seconds since startup)
+    pub completed: Option<(String, f32)>,
+}
+
+impl Challenges {
+    /// Load challenges from `CHALLENGES_PATH`, falling back to the built-in copy
+    pub fn load() -> Self {
+        let challenges = std::fs::read_to_string(Path::new(CHALLENGES_PATH))
+            .map_err(|e| e.to_string())
+            .and_then(|text| parse_challenges(&text))
+            .unwrap_or_else(|e| {
+                bevy::log::warn!("Using built-in challenges, couldn't load {}: {}", CHALLENGES_PATH, e);
+                parse_challenges(BUILT_IN_CHALLENGES).unwrap_or_default()
+            });
+        Self { challenges, completed: None }
+    }
+
+    /// Mark newly met challenges complete, returning the names of those completed by this call
+    pub fn update(&mut self, grid: &GameGrid, stats: &GridStatistics) -> Vec<String> {
+        let mut newly_completed = Vec::new();
+        for challenge in &mut self.challenges {
+            if challenge.status == ChallengeStatus::Complete {
+                continue;
+            }
+            if let ChallengeCondition::ElementAbsent(element) = challenge.condition
+                && stats.histogram[usize::from(element.index())] > 0
+            {
+                challenge.armed = true;
+            }
+            let armed = challenge.armed || !matches!(challenge.condition, ChallengeCondition::ElementAbsent(_));
+            if armed && evaluate_challenge(grid, stats, &challenge.condition) {
+                challenge.status = ChallengeStatus::Complete;
+                newly_completed.push(challenge.name.clone());
+            }
+        }
+        newly_completed
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use rand::Rng;
 
 /// Element types in the simulation.
 /// 
@@ -46,6 +47,21 @@ pub enum Element {
     ChargedNitro = 35,
     BurningThermite = 36,
     RainbowSand = 37,
+    Honey = 38,
+    HardWall = 39,
+    Clone = 46,
+    Bubble = 47,
+    Virus = 48,
+    Portal = 49,
+    Mud = 50,
+    Magma = 51,
+    Quicksand = 52,
+    Catalyst = 53,
+    Slime = 54,
+    Emitter = 55,
+    Creature = 56,
+    AntiGravity = 57,
+    Nuke = 58,
     // More elements will be added here
 }
 
@@ -91,6 +107,21 @@ impl Element {
             Element::ChargedNitro => LinearRgba::rgb(0.96, 0.38, 0.31), // 245, 98, 78
             Element::BurningThermite => LinearRgba::rgb(1.0, 0.51, 0.51), // 255, 130, 130
             Element::RainbowSand => LinearRgba::rgb(0.76, 0.70, 0.50), // Base color similar to sand, but will be shifted
+            Element::Clone => LinearRgba::rgb(0.80, 0.80, 0.40), // 204, 204, 102
+            Element::Bubble => LinearRgba::new(0.75, 0.90, 1.0, 0.5), // 191, 230, 255, 50% alpha
+            Element::Virus => LinearRgba::rgb(0.80, 0.10, 0.80), // 204, 26, 204
+            Element::Portal => LinearRgba::rgb(0.50, 0.0, 1.0), // 127, 0, 255
+            Element::Mud => LinearRgba::rgb(0.35, 0.20, 0.08), // 89, 51, 20
+            Element::Magma => LinearRgba::rgb(0.70, 0.20, 0.05), // 179, 51, 13
+            Element::Quicksand => LinearRgba::rgb(0.85, 0.75, 0.45), // 217, 191, 115
+            Element::Catalyst => LinearRgba::rgb(0.90, 0.80, 0.0), // 230, 204, 0
+            Element::Slime => LinearRgba::rgb(0.15, 0.80, 0.20), // 38, 204, 51
+            Element::Honey => LinearRgba::rgb(0.95, 0.70, 0.10), // 242, 179, 26
+            Element::HardWall => LinearRgba::rgb(0.35, 0.35, 0.40), // 89, 89, 102
+            Element::Emitter => LinearRgba::rgb(1.0, 0.40, 0.80), // 255, 102, 204
+            Element::Creature => LinearRgba::rgb(0.85, 0.55, 0.20), // 217, 140, 51
+            Element::AntiGravity => LinearRgba::rgb(0.4, 0.0, 0.8), // 102, 0, 204
+            Element::Nuke => LinearRgba::rgb(0.5, 0.5, 0.5), // 127, 127, 127
         }
     }
 
@@ -99,6 +130,16 @@ impl Element {
         *self as u8
     }
 
+    /// Density relative to water (see `ELEMENT_DENSITY`)
+    pub fn density(&self) -> f32 {
+        ELEMENT_DENSITY[self.index() as usize]
+    }
+
+    /// How freely the element flows, from 0 (doesn't flow) to 1 (see `ELEMENT_VISCOSITY`)
+    pub fn viscosity(&self) -> f32 {
+        ELEMENT_VISCOSITY[self.index() as usize]
+    }
+
     /// Convert from element index
     pub fn from_index(index: u8) -> Self {
         match index {
@@ -140,6 +181,21 @@ impl Element {
             35 => Element::ChargedNitro,
             36 => Element::BurningThermite,
             37 => Element::RainbowSand,
+            38 => Element::Honey,
+            39 => Element::HardWall,
+            46 => Element::Clone,
+            47 => Element::Bubble,
+            48 => Element::Virus,
+            49 => Element::Portal,
+            50 => Element::Mud,
+            51 => Element::Magma,
+            52 => Element::Quicksand,
+            53 => Element::Catalyst,
+            54 => Element::Slime,
+            55 => Element::Emitter,
+            56 => Element::Creature,
+            57 => Element::AntiGravity,
+            58 => Element::Nuke,
             _ => Element::Background,
         }
     }
@@ -237,12 +293,13 @@ impl Element {
 
     /// Check if element is solid (doesn't fall)
     pub fn is_solid(&self) -> bool {
-        matches!(self, Element::Wall)
+        matches!(self, Element::Wall | Element::HardWall)
     }
 
     /// Check if element is liquid (flows and spreads)
+    /// Mud counts as a liquid even though it is non-Newtonian (flows much slower than water)
     pub fn is_liquid(&self) -> bool {
-        matches!(self, Element::Water | Element::Oil | Element::SaltWater | Element::Nitro | Element::Napalm | Element::Acid)
+        matches!(self, Element::Water | Element::Oil | Element::SaltWater | Element::Nitro | Element::Napalm | Element::Acid | Element::Mud | Element::Slime | Element::Honey)
     }
 
     /// Check if element is powder (falls like sand)
@@ -250,15 +307,45 @@ impl Element {
         matches!(self, Element::Sand | Element::Salt | Element::Gunpowder | Element::Soil | Element::WetSoil | Element::Thermite | Element::Pollen | Element::Mystery | Element::ChargedNitro)
     }
 
+    /// Check if element can keep a fire burning
+    pub fn is_flammable(&self) -> bool {
+        self.in_group(ElementGroup::Organic)
+    }
+
+    /// Check if element belongs to the given group
+    pub fn in_group(&self, group: ElementGroup) -> bool {
+        element_groups(*self).contains(&group)
+    }
+
+    /// Age-based transformation for an element that has been in the same cell for `age` ticks
+    /// Returns the element to replace it with, if any
+    pub fn age_effects(&self, age: u16) -> Option<Element> {
+        match self {
+            // Old plants wither away (0.5% chance per tick)
+            Element::Plant if age > 500 && crate::simulation::sim_rng().gen_bool(0.005) => Some(Element::Background),
+            // Old concrete cures into acid-proof wall
+            Element::Concrete if age > 1000 => Some(Element::HardWall),
+            _ => None,
+        }
+    }
+
+    /// Check if element only changes in response to its neighbors
+    /// Settled cells of these elements can be skipped until something nearby changes
+    /// Elements with spontaneous behavior (growth, decay, temperature) must never settle
+    /// Sand only turns into Quicksand next to it, so its action keeps those cells awake instead
+    pub fn can_settle(&self) -> bool {
+        matches!(self, Element::Wall | Element::HardWall | Element::Sand | Element::RainbowSand | Element::Gunpowder | Element::Thermite)
+    }
+
     /// Check if element is empty/background
     pub fn is_empty(&self) -> bool {
         matches!(self, Element::Background)
     }
 
     /// Check if element is valid for spigots (anything affected by gravity)
-    /// Excludes: Background, Wall, Fire, Ice, Steam, Plant, Wax, Fuse, C4, Cryo, Methane, Spout, Well, Torch, Branch, Leaf, FallingWax, ChilledIce, BurningThermite
+    /// Excludes: Background, Wall, Fire, Ice, Steam, Plant, Wax, Fuse, C4, Cryo, Methane, Spout, Well, Torch, Branch, Leaf, FallingWax, ChilledIce, BurningThermite, Clone, Bubble, Virus, Portal, Catalyst, HardWall, Emitter, Creature, AntiGravity, Nuke
     pub fn is_valid_for_spigot(&self) -> bool {
-        !matches!(self, Element::Background | Element::Wall | Element::Fire | Element::Ice | Element::Steam | Element::Plant | Element::Wax | Element::Fuse | Element::C4 | Element::Cryo | Element::Methane | Element::Spout | Element::Well | Element::Torch | Element::Branch | Element::Leaf | Element::FallingWax | Element::ChilledIce | Element::BurningThermite)
+        !matches!(self, Element::Background | Element::Wall | Element::Fire | Element::Ice | Element::Steam | Element::Plant | Element::Wax | Element::Fuse | Element::C4 | Element::Cryo | Element::Methane | Element::Spout | Element::Well | Element::Torch | Element::Branch | Element::Leaf | Element::FallingWax | Element::ChilledIce | Element::BurningThermite | Element::Clone | Element::Bubble | Element::Virus | Element::Portal | Element::Catalyst | Element::HardWall | Element::Emitter | Element::Creature | Element::AntiGravity | Element::Nuke)
     }
 
     /// Get all elements that are valid for spigots (affected by gravity)
@@ -283,13 +370,202 @@ impl Element {
             Element::Pollen,
             Element::Mystery,
             Element::ChargedNitro,
+            Element::Magma,
         ]
     }
 }
 
+/// Broad categories an element belongs to, used for palette filtering and physics queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementGroup {
+    Liquid,
+    Powder,
+    Gas,
+    Solid,
+    Organic,
+    Explosive,
+    Corrosive,
+    Thermal,
+    Special,
+}
+
+impl ElementGroup {
+    pub const ALL: [ElementGroup; 9] = [
+        ElementGroup::Liquid,
+        ElementGroup::Powder,
+        ElementGroup::Gas,
+        ElementGroup::Solid,
+        ElementGroup::Organic,
+        ElementGroup::Explosive,
+        ElementGroup::Corrosive,
+        ElementGroup::Thermal,
+        ElementGroup::Special,
+    ];
+}
+
+/// Get the groups an element belongs to, most defining group first
+pub const fn element_groups(e: Element) -> &'static [ElementGroup] {
+    match e {
+        Element::Background => &[],
+        Element::Wall | Element::HardWall | Element::Rock => &[ElementGroup::Solid],
+        Element::Sand | Element::RainbowSand | Element::Salt | Element::Soil | Element::WetSoil | Element::Quicksand => &[ElementGroup::Powder],
+        Element::Water | Element::SaltWater | Element::Mud | Element::Slime | Element::Honey | Element::Concrete | Element::FallingWax => &[ElementGroup::Liquid],
+        Element::Fire | Element::Steam => &[ElementGroup::Gas, ElementGroup::Thermal],
+        Element::Oil => &[ElementGroup::Liquid, ElementGroup::Organic],
+        Element::Ice | Element::ChilledIce => &[ElementGroup::Solid, ElementGroup::Thermal],
+        Element::Lava | Element::Magma => &[ElementGroup::Liquid, ElementGroup::Thermal],
+        Element::Plant | Element::Wax | Element::Fuse | Element::Branch | Element::Leaf => &[ElementGroup::Solid, ElementGroup::Organic],
+        Element::Pollen => &[ElementGroup::Powder, ElementGroup::Organic],
+        Element::Gunpowder | Element::ChargedNitro => &[ElementGroup::Powder, ElementGroup::Explosive],
+        Element::Nitro => &[ElementGroup::Liquid, ElementGroup::Explosive],
+        Element::Napalm => &[ElementGroup::Liquid, ElementGroup::Explosive, ElementGroup::Thermal],
+        Element::C4 | Element::Nuke => &[ElementGroup::Solid, ElementGroup::Explosive],
+        Element::Methane => &[ElementGroup::Gas, ElementGroup::Explosive],
+        Element::Acid => &[ElementGroup::Liquid, ElementGroup::Corrosive],
+        Element::Cryo => &[ElementGroup::Thermal],
+        Element::Thermite | Element::BurningThermite => &[ElementGroup::Powder, ElementGroup::Thermal],
+        Element::Torch => &[ElementGroup::Special, ElementGroup::Thermal],
+        Element::Mystery => &[ElementGroup::Powder, ElementGroup::Special],
+        Element::Bubble => &[ElementGroup::Gas, ElementGroup::Special],
+        Element::Virus => &[ElementGroup::Special, ElementGroup::Corrosive],
+        Element::Creature => &[ElementGroup::Special, ElementGroup::Organic],
+        Element::Spout | Element::Well | Element::Clone | Element::Portal | Element::Catalyst | Element::Emitter | Element::AntiGravity => &[ElementGroup::Special],
+    }
+}
+
 impl Default for Element {
     fn default() -> Self {
         Element::Background
     }
 }
 
+/// Density of each element relative to water, indexed by `Element::index()`
+/// Heavier elements sink through lighter liquids, elements left at 0 never sink
+pub const ELEMENT_DENSITY: [f32; 256] = {
+    let mut density = [0.0; 256];
+    // Gases
+    density[Element::Steam as usize] = 0.0006;
+    density[Element::Methane as usize] = 0.0007;
+    // Liquids
+    density[Element::Oil as usize] = 0.85;
+    density[Element::Napalm as usize] = 0.9;
+    density[Element::Water as usize] = 1.0;
+    density[Element::SaltWater as usize] = 1.025;
+    density[Element::Slime as usize] = 1.1;
+    density[Element::Acid as usize] = 1.2;
+    density[Element::Nitro as usize] = 1.3;
+    density[Element::Honey as usize] = 1.4;
+    density[Element::Mud as usize] = 1.7;
+    density[Element::Lava as usize] = 3.1;
+    density[Element::Magma as usize] = 3.1;
+    // Powders and solids
+    density[Element::Pollen as usize] = 0.3;
+    density[Element::Soil as usize] = 1.3;
+    density[Element::WetSoil as usize] = 1.4;
+    density[Element::Quicksand as usize] = 1.45;
+    density[Element::ChargedNitro as usize] = 1.5;
+    density[Element::Sand as usize] = 1.6;
+    density[Element::RainbowSand as usize] = 1.6;
+    density[Element::Gunpowder as usize] = 1.7;
+    density[Element::Thermite as usize] = 2.0;
+    density[Element::BurningThermite as usize] = 2.0;
+    density[Element::Salt as usize] = 2.2;
+    density[Element::Concrete as usize] = 2.4;
+    density[Element::Rock as usize] = 2.7;
+    density
+};
+
+/// Fluidity of each element, indexed by `Element::index()`
+/// Multiplies the per-tick chance that a liquid falls, so lower values flow more sluggishly
+/// Elements not listed flow freely (1.0)
+pub const ELEMENT_VISCOSITY: [f32; 256] = {
+    let mut viscosity = [1.0; 256];
+    viscosity[Element::Water as usize] = 1.0;
+    viscosity[Element::Acid as usize] = 1.0;
+    viscosity[Element::Oil as usize] = 0.8;
+    viscosity[Element::Mud as usize] = 0.40;
+    viscosity[Element::Lava as usize] = 0.30;
+    viscosity[Element::Slime as usize] = 0.15;
+    viscosity[Element::Honey as usize] = 0.1;
+    viscosity
+};
+
+/// Ambient temperature in °C (what empty space settles to)
+pub const AMBIENT_TEMPERATURE: f32 = 20.0;
+
+/// Natural temperature of an element in °C
+/// Heat sources (Fire, Lava, Cryo, etc.) are held at this temperature, other elements drift toward it
+pub fn element_base_temp(e: Element) -> f32 {
+    match e {
+        Element::Fire | Element::Torch => 800.0,
+        Element::Lava => 1200.0,
+        Element::Magma => 1300.0,
+        Element::BurningThermite => 2500.0,
+        Element::Steam => 100.0,
+        Element::Ice => -10.0,
+        Element::ChilledIce => -30.0,
+        Element::Cryo => -80.0,
+        _ => AMBIENT_TEMPERATURE,
+    }
+}
+
+/// Durability a cell of the element starts with, worn down by Acid, explosions and Lava before
+/// the cell is destroyed (see `GameGrid::damage`)
+/// Elements not listed have none and are destroyed by the first hit
+pub const fn element_default_durability(e: Element) -> u8 {
+    match e {
+        Element::Wall | Element::HardWall => 255,
+        Element::Rock => 200,
+        Element::Concrete => 150,
+        Element::Sand => 50,
+        Element::Branch => 40,
+        Element::Plant => 30,
+        _ => 0,
+    }
+}
+
+/// Thermal conductivity of an element (fraction of the temperature difference exchanged with
+/// each neighbor per tick, kept <= 0.25 so diffusion stays stable)
+pub fn element_conductivity(e: Element) -> f32 {
+    match e {
+        Element::Background | Element::Methane => 0.005,
+        Element::Steam => 0.02,
+        Element::Water | Element::SaltWater | Element::Mud => 0.15,
+        Element::Ice | Element::ChilledIce | Element::Cryo => 0.20,
+        Element::Fire | Element::Lava | Element::Magma | Element::BurningThermite => 0.20,
+        Element::Rock | Element::Concrete | Element::Wall => 0.10,
+        Element::Oil | Element::Wax | Element::Slime => 0.08,
+        _ => 0.05,
+    }
+}
+
+/// Color of an element at (x, y) on a given frame: Fire flickers, Lava pulses and Water ripples,
+/// every other element uses its static color
+pub fn animated_element_color(element: Element, x: u32, _y: u32, frame: u32) -> LinearRgba {
+    let base = element.to_encoded_color();
+    let scaled = |brightness: f32| {
+        LinearRgba::new(
+            (base.red * brightness).min(1.0),
+            (base.green * brightness).min(1.0),
+            (base.blue * brightness).min(1.0),
+            base.alpha,
+        )
+    };
+    match element {
+        // ±15% brightness flicker
+        Element::Fire => scaled(1.0 + 0.15 * (frame as f32 * 0.3 + x as f32 * 0.7).sin()),
+        // Pulse between orange and yellow-white on a 2 second cycle (at 60 frames per second)
+        Element::Lava => {
+            let t = 0.5 + 0.5 * (frame as f32 * std::f32::consts::TAU / 120.0).sin();
+            LinearRgba::new(
+                base.red + (1.0 - base.red) * t,
+                base.green + (0.95 - base.green) * t,
+                base.blue + (0.7 - base.blue) * t,
+                base.alpha,
+            )
+        }
+        // ±3% brightness horizontal ripple
+        Element::Water => scaled(1.0 + 0.03 * (x as f32 * 0.5 + frame as f32 * 0.1).sin()),
+        _ => base,
+    }
+}
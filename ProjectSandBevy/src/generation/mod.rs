@@ -0,0 +1,130 @@
+use crate::elements::Element;
+use crate::simulation::GameGrid;
+use noise::{NoiseFn, Perlin};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+/// Sand cells on top of every column
+const SAND_DEPTH: u32 = 3;
+
+/// Generations of the cave automaton
+const CAVE_ITERATIONS: u32 = 6;
+
+/// Chance a cell in the Soil/Rock layer starts out as a cave
+const CAVE_SEED_CHANCE: f64 = 0.06;
+
+/// Share of the deep Soil band turned into Rock pebbles
+const PEBBLE_DENSITY: f32 = 0.04;
+
+/// Surface height (distance from the top) of every column, from two octaves of 1D Perlin noise
+/// Surfaces fall between 40% and 60% of the grid height
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Folding the seed, and surfaces are clamped inside the grid
+fn heightmap(width: u32, height: u32, seed: u64) -> Vec<u32> {
+    // Perlin takes a 32 bit seed, fold the high half in so every bit of the seed matters
+    let perlin = Perlin::new((seed ^ (seed >> 32)) as u32);
+    let base = f64::from(height) * 0.5;
+    let amplitude = f64::from(height) * 0.1;
+    (0..width)
+        .map(|x| {
+            let x = f64::from(x);
+            // Perlin is 0 at integer coordinates, so the second axis is kept off the lattice
+            let value = perlin.get([x * 0.01, 0.5]).mul_add(0.75, perlin.get([x * 0.04, 0.5]) * 0.25);
+            value.clamp(-1.0, 1.0).mul_add(amplitude, base) as u32
+        })
+        .collect()
+}
+
+/// Replace the grid with layered terrain, with Water filling the depressions
+///
+/// Rock fills the bottom 20%, then Soil (next 15%), `WetSoil` (next 5%) and a few cells of Sand on the surface
+/// Rock pebbles are scattered through the deep Soil band
+pub fn generate_terrain(grid: &mut GameGrid, seed: u64) {
+    grid.clear();
+    let (width, height) = (grid.width, grid.height);
+    if width == 0 || height == 0 {
+        return;
+    }
+    let surface = heightmap(width, height, seed);
+
+    let rock_top = height - height / 5;
+    let soil_top = rock_top - height * 15 / 100;
+    let wet_soil_top = soil_top - height / 20;
+
+    // Water settles up to a bit below the average surface, filling only the lower valleys
+    let mean_surface = surface.iter().map(|&y| u64::from(y)).sum::<u64>() / u64::from(width);
+    let water_level = u32::try_from(mean_surface).unwrap_or(height) + height / 40;
+
+    for x in 0..width {
+        let surface_y = surface[x as usize];
+        for y in surface_y..height {
+            // Anything between the Sand and the WetSoil band on high ground is Soil
+            let element = if y >= rock_top {
+                Element::Rock
+            } else if y >= soil_top {
+                Element::Soil
+            } else if y >= wet_soil_top {
+                Element::WetSoil
+            } else if y < surface_y + SAND_DEPTH {
+                Element::Sand
+            } else {
+                Element::Soil
+            };
+            grid.set(x, y, element);
+        }
+        for y in water_level..surface_y {
+            grid.set(x, y, Element::Water);
+        }
+    }
+    // The band is below every surface, so it is all Soil
+    let mut rng = SmallRng::seed_from_u64(seed ^ 0x0000_9EBB_1E00);
+    grid.apply_noise(0, soil_top, width, rock_top - soil_top, Element::Rock, PEBBLE_DENSITY, true, &mut rng);
+    grid.mark_all_dirty();
+}
+
+/// Carve Background pockets into the Soil and Rock layers with the B3/S12345 ("maze") automaton
+/// Sparse random cells seed the automaton, which grows them into winding tunnels
+pub fn generate_caves(grid: &mut GameGrid, seed: u64) {
+    let (width, height) = (grid.width as usize, grid.height as usize);
+    let mut rng = SmallRng::seed_from_u64(seed ^ 0x00CA_7E5E_ED00);
+    let carvable: Vec<bool> = grid
+        .elements
+        .iter()
+        .map(|&element| matches!(element, Element::Soil | Element::Rock))
+        .collect();
+    let mut alive: Vec<bool> = carvable.iter().map(|&can_carve| can_carve && rng.gen_bool(CAVE_SEED_CHANCE)).collect();
+
+    for _ in 0..CAVE_ITERATIONS {
+        let mut next = vec![false; alive.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                if !carvable[i] {
+                    continue;
+                }
+                let mut neighbors = 0;
+                for dy in -1..=1_isize {
+                    for dx in -1..=1_isize {
+                        if dx == 0 && dy == 0 {
+                            continue;
+                        }
+                        let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                            continue;
+                        };
+                        if nx < width && ny < height {
+                            neighbors += u32::from(alive[ny * width + nx]);
+                        }
+                    }
+                }
+                // Born with exactly 3 neighbors, survives with 1 to 5
+                next[i] = if alive[i] { (1..=5).contains(&neighbors) } else { neighbors == 3 };
+            }
+        }
+        alive = next;
+    }
+
+    for (i, &is_cave) in alive.iter().enumerate() {
+        if is_cave {
+            grid.set_index(i, Element::Background);
+        }
+    }
+}
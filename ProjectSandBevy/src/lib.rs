@@ -1,6 +1,11 @@
+pub mod audio;
+pub mod challenges;
 pub mod elements;
+pub mod generation;
 pub mod particles;
 pub mod plugins;
+pub mod rendering;
+pub mod save;
 pub mod simulation;
 pub mod spigots;
 pub mod systems;
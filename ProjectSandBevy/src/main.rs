@@ -2,11 +2,11 @@
 
 use bevy::prelude::*;
 use bevy_egui::{EguiPlugin, EguiPrimaryContextPass};
-use ProjectSandBevy::{DISPLAY_FACTOR, SIZE, systems};
+use ProjectSandBevy::{DISPLAY_FACTOR, SIZE, plugins::GridTexturePlugin, simulation::ShockwaveEvent, systems};
 
 fn main() {
-    App::new()
-        .insert_resource(ClearColor(Color::BLACK))
+    let mut app = App::new();
+    app.insert_resource(ClearColor(Color::BLACK))
         .add_plugins((
             DefaultPlugins
                 .set(WindowPlugin {
@@ -21,24 +21,52 @@ fn main() {
                 })
                 .set(ImagePlugin::default_nearest()),
             EguiPlugin::default(),
+            GridTexturePlugin,
         ))
-        .add_systems(Startup, systems::setup)
-        .add_systems(EguiPrimaryContextPass, systems::ui_system)
+        .add_message::<ShockwaveEvent>()
+        .add_systems(Startup, (systems::setup, systems::setup_audio))
+        .add_systems(
+            EguiPrimaryContextPass,
+            (
+                systems::update_cursor_info.before(systems::ui_system),
+                systems::update_grid_stats.before(systems::ui_system),
+                systems::ui_system,
+                systems::minimap_ui,
+                systems::screenshot_toast_ui,
+                systems::rules_reloaded_toast_ui,
+                systems::challenges_ui,
+            ),
+        )
         .add_systems(
             Update,
             (
                 systems::handle_window_resize,
-                systems::handle_save_load,
-                systems::update_game_simulation,
+                (systems::auto_save_system, systems::handle_save_load, systems::handle_world_gen, systems::handle_replace_element).chain(),
+                (systems::handle_quick_save, systems::reload_rules_on_change, systems::check_challenges, systems::update_anti_gravity_sources),
+                systems::update_game_simulation.run_if(not(systems::recording_playing)),
+                systems::play_recorded_frame.run_if(systems::recording_playing),
+                (systems::process_shockwaves, systems::detect_explosions, systems::generate_music, systems::trigger_camera_shake),
                 systems::update_particles,
                 systems::render_grid_to_texture,
+                systems::render_glow,
                 systems::render_particles,
                 systems::composite_particles,
-                systems::handle_mouse_clicks_cpu,
+                systems::prepare_texture_upload,
+                systems::handle_screenshot,
+                (systems::handle_spigot_drag, systems::handle_mouse_clicks_cpu).chain(),
                 systems::handle_mouse_scroll,
-                systems::draw_circle_preview,
+                systems::handle_camera_controls,
+                systems::handle_keyboard_shortcuts,
+                systems::handle_selection,
+                systems::handle_undo_redo,
+                (systems::draw_circle_preview, systems::draw_spigot_indicators, systems::draw_anti_gravity_sources),
             )
-                .chain(), // Ensure order: resize -> save/load -> update -> render grid -> render particles -> composite
-        )
-        .run();
+                .chain(), // Ensure order: resize -> save/load -> update -> render grid -> render particles -> composite -> upload
+        );
+
+    // HTTP control of the running game, see `plugins::remote`
+    #[cfg(feature = "remote")]
+    app.add_plugins(ProjectSandBevy::plugins::remote::RemoteControlPlugin::default());
+
+    app.run();
 }
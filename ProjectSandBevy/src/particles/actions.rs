@@ -1,12 +1,12 @@
 use crate::particles::types::{Particle, ParticleType, MAGIC_COLORS};
-use crate::simulation::GameGrid;
+use crate::simulation::{ignite_at, sim_rng, GameGrid};
 use crate::SIZE;
 use crate::elements::Element;
 use rand::Rng;
 
 /// Initialize a particle based on its type
 pub fn particle_init(particle: &mut Particle, grid: &GameGrid) {
-    let mut rng = rand::thread_rng();
+    let mut rng = sim_rng();
     
     match particle.particle_type {
         ParticleType::Unknown => {
@@ -42,9 +42,29 @@ pub fn particle_init(particle: &mut Particle, grid: &GameGrid) {
         ParticleType::Nuke => {
             nuke_particle_init(particle, &mut rng);
         }
+        ParticleType::Debris => {
+            // Debris is normally initialized where it's spawned, fall back to its color
+            let element_source = particle.debris_element.unwrap_or(particle.color);
+            debris_particle_init(particle, element_source, &mut rng);
+        }
     }
 }
 
+/// Count down a particle's lifetime, fading it out over the last quarter
+/// Returns true once the lifetime runs out, particles without one live until their action removes them
+pub fn tick_lifetime(particle: &mut Particle) -> bool {
+    let Some(remaining) = particle.lifetime else {
+        return false;
+    };
+    let remaining = remaining.saturating_sub(1);
+    particle.lifetime = Some(remaining);
+    // Every action tick has used up one tick of lifetime, so this is the starting lifetime
+    let total = remaining + particle.action_iterations;
+    let fraction_left = remaining as f32 / total.max(1) as f32;
+    particle.alpha = (fraction_left / 0.25).min(1.0);
+    remaining == 0
+}
+
 /// Update a particle each frame
 /// Returns true if particle should be removed
 /// particle_list is only needed for tree particles (to create branches)
@@ -52,9 +72,12 @@ pub fn particle_action(
     particle: &mut Particle,
     particle_list: Option<&mut crate::particles::manager::ParticleList>,
     particle_idx: usize,
-    grid: &GameGrid,
+    grid: &mut GameGrid,
 ) -> bool {
     particle.action_iterations += 1;
+    if tick_lifetime(particle) {
+        return true;
+    }
     
     match particle.particle_type {
         ParticleType::Unknown => {
@@ -64,13 +87,13 @@ pub fn particle_action(
             return nitro_particle_action(particle, grid);
         }
         ParticleType::Napalm => {
-            return napalm_particle_action(particle);
+            return napalm_particle_action(particle, grid);
         }
         ParticleType::C4 => {
             return c4_particle_action(particle);
         }
         ParticleType::Lava => {
-            return lava_particle_action(particle);
+            return lava_particle_action(particle, grid);
         }
         ParticleType::Magic1 => {
             // Magic1 particles - simplified for now (full version would create spokes)
@@ -97,6 +120,9 @@ pub fn particle_action(
         ParticleType::Nuke => {
             return nuke_particle_action(particle);
         }
+        ParticleType::Debris => {
+            return debris_particle_action(particle, grid);
+        }
     }
 }
 
@@ -109,12 +135,23 @@ fn nitro_particle_init(particle: &mut Particle, rng: &mut impl Rng) {
     particle.set_velocity(velocity, angle);
     
     particle.size = 2.0 + rng.gen_range(0.0..1.0) * 7.0;
+    // Usually shrinks away well before this
+    particle.lifetime = Some(60);
 }
 
-fn nitro_particle_action(particle: &mut Particle, grid: &GameGrid) -> bool {
+fn nitro_particle_action(particle: &mut Particle, grid: &mut GameGrid) -> bool {
+    // Bounce off walls, losing some speed
+    if let Some(normal) = wall_normal(grid, particle.x, particle.y, particle.x_velocity, particle.y_velocity) {
+        let (x_velocity, y_velocity) = reflect((particle.x_velocity, particle.y_velocity), normal);
+        particle.x_velocity = x_velocity * NITRO_RESTITUTION;
+        particle.y_velocity = y_velocity * NITRO_RESTITUTION;
+        particle.velocity *= NITRO_RESTITUTION;
+    }
+
     // Move particle
     particle.x += particle.x_velocity;
     particle.y += particle.y_velocity;
+    ignite_cell_under(particle, grid);
     
     // Shrink over time
     if particle.action_iterations % 5 == 0 {
@@ -137,29 +174,72 @@ fn nitro_particle_action(particle: &mut Particle, grid: &GameGrid) -> bool {
     false
 }
 
+/// Fraction of speed a Nitro particle keeps when it bounces off a wall
+const NITRO_RESTITUTION: f32 = 0.7;
+
+/// Check if the cell nearest (x, y) is a wall, positions off the grid are open
+fn is_wall_at(grid: &GameGrid, x: f32, y: f32) -> bool {
+    let (x, y) = (x.round(), y.round());
+    x >= 0.0
+        && y >= 0.0
+        && x <= grid.max_x() as f32
+        && y <= grid.max_y() as f32
+        && grid.get(x as u32, y as u32) == Element::Wall
+}
+
+/// Unit normal of the wall face struck moving from (x, y) by (vx, vy), None if the move doesn't hit a wall
+/// Each axis is checked on its own to tell side faces from top and bottom faces, a corner hit reflects both
+fn wall_normal(grid: &GameGrid, x: f32, y: f32, vx: f32, vy: f32) -> Option<(f32, f32)> {
+    if !is_wall_at(grid, x + vx, y + vy) {
+        return None;
+    }
+    let hit_x = is_wall_at(grid, x + vx, y);
+    let hit_y = is_wall_at(grid, x, y + vy);
+    // Points back against the direction of travel, 0 when not moving along the axis
+    let against = |v: f32| if v > 0.0 { -1.0 } else if v < 0.0 { 1.0 } else { 0.0 };
+    let nx: f32 = if hit_x || !hit_y { against(vx) } else { 0.0 };
+    let ny: f32 = if hit_y || !hit_x { against(vy) } else { 0.0 };
+    let length = (nx * nx + ny * ny).sqrt();
+    (length > 0.0).then(|| (nx / length, ny / length))
+}
+
+/// Reflect a velocity off a surface with unit normal n: v' = v - 2(v·n)n
+fn reflect((vx, vy): (f32, f32), (nx, ny): (f32, f32)) -> (f32, f32) {
+    let dot = vx * nx + vy * ny;
+    (vx - 2.0 * dot * nx, vy - 2.0 * dot * ny)
+}
+
+/// Set fire to the flammable cell a fire particle is passing over
+fn ignite_cell_under(particle: &Particle, grid: &mut GameGrid) {
+    let (x, y) = (particle.x.round(), particle.y.round());
+    if x < 0.0 || y < 0.0 || x > grid.max_x() as f32 || y > grid.max_y() as f32 {
+        return;
+    }
+    let (x, y) = (x as u32, y as u32);
+    match grid.get(x, y) {
+        Element::Methane | Element::Fuse => grid.set(x, y, Element::Fire),
+        Element::Gunpowder => ignite_at(grid, x, y),
+        _ => {}
+    }
+}
+
 // NAPALM_PARTICLE
 fn napalm_particle_init(particle: &mut Particle, rng: &mut impl Rng) {
     particle.set_color(crate::elements::Element::Fire);
     particle.size = rng.gen_range(0.0..1.0) * 8.0 + 6.0;
     particle.x_velocity = rng.gen_range(0.0..1.0) * 8.0 - 4.0;
     particle.y_velocity = -(rng.gen_range(0.0..1.0) * 4.0 + 4.0);
-    particle.max_iterations = Some(rng.gen_range(5..=15));
+    particle.lifetime = Some(rng.gen_range(6..=16));
 }
 
-fn napalm_particle_action(particle: &mut Particle) -> bool {
+fn napalm_particle_action(particle: &mut Particle, grid: &mut GameGrid) -> bool {
     // Move particle
     particle.x += particle.x_velocity;
     particle.y += particle.y_velocity;
+    ignite_cell_under(particle, grid);
     
-    // Grow over time
-    particle.size *= 1.0 + rand::thread_rng().gen_range(0.0..1.0) * 0.1;
-    
-    // Remove after max iterations
-    if let Some(max_iter) = particle.max_iterations {
-        if particle.action_iterations > max_iter {
-            return true;
-        }
-    }
+    // Grow over time, removed once its lifetime runs out
+    particle.size *= 1.0 + sim_rng().gen_range(0.0..1.0) * 0.1;
     
     false
 }
@@ -177,6 +257,8 @@ fn c4_particle_init(particle: &mut Particle, rng: &mut impl Rng) {
     } else {
         particle.size = rng.gen_range(0.0..1.0) * 128.0 + 3.0;
     }
+    // Usually shrinks away well before this
+    particle.lifetime = Some(30);
 }
 
 fn c4_particle_action(particle: &mut Particle) -> bool {
@@ -208,17 +290,25 @@ fn lava_particle_init(particle: &mut Particle, rng: &mut impl Rng) {
     
     particle.size = 4.0 + rng.gen_range(0.0..1.0) * 3.0;
     particle.y -= particle.size;
+    particle.lifetime = Some(240);
 }
 
-fn lava_particle_action(particle: &mut Particle) -> bool {
+fn lava_particle_action(particle: &mut Particle, grid: &GameGrid) -> bool {
     // Move with acceleration
-    particle.x += particle.x_velocity;
-    if let (Some(init_y_vel), Some(y_accel)) = (particle.init_y_velocity, particle.y_acceleration) {
+    let new_x = particle.x + particle.x_velocity;
+    let new_y = if let (Some(init_y_vel), Some(y_accel)) = (particle.init_y_velocity, particle.y_acceleration) {
         let iterations = particle.action_iterations as f32;
-        particle.y = particle.init_y + init_y_vel * iterations + (y_accel * iterations * iterations) / 2.0;
+        particle.init_y + init_y_vel * iterations + (y_accel * iterations * iterations) / 2.0
     } else {
-        particle.y += particle.y_velocity;
+        particle.y + particle.y_velocity
+    };
+
+    // Lava splatters against walls instead of bouncing
+    if is_wall_at(grid, new_x, new_y) {
+        return true;
     }
+    particle.x = new_x;
+    particle.y = new_y;
     
     // Check for collisions (simplified - would check grid in full version)
     if particle.off_canvas(SIZE.x as f32, SIZE.y as f32) {
@@ -245,6 +335,7 @@ fn magic1_particle_init(particle: &mut Particle, rng: &mut impl Rng, _grid: &Gam
     // For simplicity, create one spoke - in full version would create all spokes
     particle.set_velocity(velocity, 0.0); // Start at angle 0, caller can adjust
     particle.size = spoke_size;
+    particle.lifetime = Some(120);
 }
 
 fn magic1_particle_action(
@@ -283,6 +374,7 @@ fn magic2_particle_init(particle: &mut Particle, rng: &mut impl Rng) {
     particle.magic_2_speed = Some(20.0);
     particle.magic_2_radius_spacing = Some(25.0 + rng.gen_range(0.0..1.0) * 55.0);
     particle.magic_2_radius = Some(particle.magic_2_radius_spacing.unwrap());
+    particle.lifetime = Some(600);
 }
 
 fn magic2_particle_action(particle: &mut Particle, grid: &GameGrid) -> bool {
@@ -318,20 +410,18 @@ fn magic2_particle_action(particle: &mut Particle, grid: &GameGrid) -> bool {
 fn methane_particle_init(particle: &mut Particle, rng: &mut impl Rng) {
     particle.set_color(crate::elements::Element::Fire);
     particle.size = 10.0 + rng.gen_range(0.0..1.0) * 10.0;
+    // Removed on its 3rd iteration (matches TypeScript)
+    particle.lifetime = Some(3);
 }
 
 fn methane_particle_action(
-    particle: &mut Particle,
+    _particle: &mut Particle,
     _grid: &GameGrid,
     _particle_list: Option<&mut crate::particles::manager::ParticleList>,
 ) -> bool {
-    // Remove after 2 iterations (matches TypeScript)
+    // Only lives for its lifetime
     // Note: Fire spreading to adjacent methane is handled in the methane element action
     // by checking for nearby methane particles
-    if particle.action_iterations > 2 {
-        return true;
-    }
-    
     false
 }
 
@@ -359,6 +449,7 @@ fn tree_particle_init(particle: &mut Particle, rng: &mut impl Rng) {
         particle.tree_type = Some(1); // Tree2 (Tree1 is excluded)
     }
     
+    // Trees end when they run out of branches, so no lifetime
 }
 
 fn tree_particle_action(
@@ -426,12 +517,12 @@ fn tree_particle_action(
             let branch_angles = match tree_type {
                 0 => {
                     // Tree0: two branches (left and right)
-                    let branch_angle = std::f32::consts::PI / 8.0 + rand::thread_rng().gen_range(0.0..1.0) * std::f32::consts::PI / 4.0;
+                    let branch_angle = std::f32::consts::PI / 8.0 + sim_rng().gen_range(0.0..1.0) * std::f32::consts::PI / 4.0;
                     vec![current_angle + branch_angle, current_angle - branch_angle]
                 }
                 1 => {
                     // Tree2: three branches (straight, left, right)
-                    let branch_angle = rand::thread_rng().gen_range(0.0..1.0) * std::f32::consts::PI / 16.0 + std::f32::consts::PI / 8.0;
+                    let branch_angle = sim_rng().gen_range(0.0..1.0) * std::f32::consts::PI / 16.0 + std::f32::consts::PI / 8.0;
                     vec![current_angle, current_angle + branch_angle, current_angle - branch_angle]
                 }
                 _ => vec![current_angle], // Fallback
@@ -480,7 +571,7 @@ fn tree_particle_action(
             if updated_branch_spacing > 45 {
                 updated_branch_spacing = (updated_branch_spacing as f32 * 0.8) as u32;
             }
-            let next_branch_time = iterations + (updated_branch_spacing as f32 * (0.65 + rand::thread_rng().gen_range(0.0..1.0) * 0.35)) as u32;
+            let next_branch_time = iterations + (updated_branch_spacing as f32 * (0.65 + sim_rng().gen_range(0.0..1.0) * 0.35)) as u32;
             particle.tree_next_branch = Some(next_branch_time);
             particle.tree_branch_spacing = Some(updated_branch_spacing);
         }
@@ -501,10 +592,10 @@ fn charged_nitro_particle_init(particle: &mut Particle, grid: &GameGrid) {
     
     // Search upwards for a WALL collision (but don't check every pixel)
     particle.min_y = Some(-1.0);
-    let step = (3 + rand::thread_rng().gen_range(0..=2)) * grid.width as usize;
+    let step = (3 + sim_rng().gen_range(0..=2)) * grid.width as usize;
     let mut idx = particle.init_i;
     while idx > 0 {
-        if grid.get_index(idx) == crate::elements::Element::Wall {
+        if grid.get_index_checked(idx) == Some(crate::elements::Element::Wall) {
             particle.min_y = Some((idx / grid.width as usize) as f32);
             break;
         }
@@ -513,6 +604,7 @@ fn charged_nitro_particle_init(particle: &mut Particle, grid: &GameGrid) {
         }
         idx -= step;
     }
+    particle.lifetime = Some(120);
 }
 
 fn charged_nitro_particle_action(particle: &mut Particle, grid: &GameGrid) -> bool {
@@ -545,13 +637,63 @@ fn nuke_particle_init(particle: &mut Particle, rng: &mut impl Rng) {
     particle.set_color(crate::elements::Element::Fire);
     let max_dimension = SIZE.x.max(SIZE.y) as f32;
     particle.size = max_dimension / 4.0 + (rng.gen_range(0.0..1.0) * max_dimension) / 8.0;
+    // Removed on its 5th iteration
+    particle.lifetime = Some(5);
 }
 
-fn nuke_particle_action(particle: &mut Particle) -> bool {
-    // Remove after 4 iterations
-    if particle.action_iterations > 4 {
-        return true;
+fn nuke_particle_action(_particle: &mut Particle) -> bool {
+    // Only lives for its lifetime
+    false
+}
+
+// DEBRIS_PARTICLE
+pub fn debris_particle_init(particle: &mut Particle, element_source: Element, rng: &mut impl Rng) {
+    particle.set_color(element_source);
+    particle.debris_element = Some(element_source);
+    particle.size = 2.0;
+
+    let velocity = 3.0 + rng.gen_range(0.0..1.0) * 5.0;
+    let angle = rng.gen_range(0.0..1.0) * 2.0 * std::f32::consts::PI;
+    particle.set_velocity(velocity, angle);
+    particle.y_acceleration = Some(0.15);
+    particle.lifetime = Some(300);
+}
+
+fn debris_particle_action(particle: &mut Particle, grid: &mut GameGrid) -> bool {
+    particle.y_velocity += particle.y_acceleration.unwrap_or(0.0);
+
+    // Move a pixel at a time so fast debris can't pass through thin surfaces
+    let steps = particle.x_velocity.abs().max(particle.y_velocity.abs()).ceil().max(1.0);
+    let (step_x, step_y) = (particle.x_velocity / steps, particle.y_velocity / steps);
+    for _ in 0..steps as u32 {
+        let (next_x, next_y) = (particle.x + step_x, particle.y + step_y);
+        let (cell_x, cell_y) = (next_x.round(), next_y.round());
+        let blocked = cell_x < 0.0
+            || cell_y < 0.0
+            || cell_x > grid.max_x() as f32
+            || cell_y > grid.max_y() as f32
+            || grid.get(cell_x as u32, cell_y as u32) != Element::Background;
+        if blocked {
+            land_debris(particle, grid);
+            return true;
+        }
+        particle.x = next_x;
+        particle.y = next_y;
     }
-    
+
     false
 }
+
+/// Leave the debris' element in the empty cell it came to rest in
+fn land_debris(particle: &Particle, grid: &mut GameGrid) {
+    let (x, y) = (particle.x.round(), particle.y.round());
+    if x < 0.0 || y < 0.0 || x > grid.max_x() as f32 || y > grid.max_y() as f32 {
+        return;
+    }
+    let (x, y) = (x as u32, y as u32);
+    if let Some(element) = particle.debris_element
+        && grid.get(x, y) == Element::Background
+    {
+        grid.set(x, y, element);
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
-use crate::particles::types::{Particle, ParticleType, MAX_NUM_PARTICLES};
+use crate::particles::spatial::{particles_in_radius, ParticleSpatialHash};
+use crate::particles::types::{Particle, ParticleType, MAX_NUM_PARTICLES, PARTICLE_TYPE_COUNT};
 
 /// Resource to manage all particles in the system
 /// Uses a pool of pre-allocated particles to avoid allocation overhead
@@ -7,12 +8,16 @@ use crate::particles::types::{Particle, ParticleType, MAX_NUM_PARTICLES};
 pub struct ParticleList {
     /// All particles (pre-allocated pool)
     pub particles: Vec<Particle>,
-    /// Indices of active particles
-    pub active_indices: Vec<usize>,
+    /// Whether each particle in the pool is active, indexed like `particles`
+    pub is_active: Vec<bool>,
+    /// Number of active particles
+    pub active_count: usize,
     /// Indices of inactive particles (available for reuse)
     pub inactive_indices: Vec<usize>,
     /// Count of each particle type
-    pub particle_counts: [u32; 11], // 11 particle types (0-10)
+    pub particle_counts: [u32; PARTICLE_TYPE_COUNT],
+    /// Active particles bucketed by position, rebuilt each particle update
+    pub spatial_hash: ParticleSpatialHash,
 }
 
 impl Default for ParticleList {
@@ -28,9 +33,11 @@ impl Default for ParticleList {
         
         Self {
             particles,
-            active_indices: Vec::new(),
+            is_active: vec![false; MAX_NUM_PARTICLES],
+            active_count: 0,
             inactive_indices,
-            particle_counts: [0; 11],
+            particle_counts: [0; PARTICLE_TYPE_COUNT],
+            spatial_hash: ParticleSpatialHash::default(),
         }
     }
 }
@@ -68,8 +75,10 @@ impl ParticleList {
         particle.action_iterations = 0;
         particle.reinitialized = false;
         
-        // Move to active list
-        self.active_indices.push(particle_idx);
+        // Move to active list, and make it visible to proximity queries before the next rebuild
+        self.is_active[particle_idx] = true;
+        self.active_count += 1;
+        self.spatial_hash.insert(particle_idx, x, y);
         self.particle_counts[particle_type.index() as usize] += 1;
         
         Some(particle_idx)
@@ -86,10 +95,9 @@ impl ParticleList {
         particle.active = false;
         self.particle_counts[particle_type.index() as usize] -= 1;
         
-        // Remove from active list
-        if let Some(pos) = self.active_indices.iter().position(|&i| i == particle_idx) {
-            self.active_indices.remove(pos);
-        }
+        // Remove from active list, a flag flip instead of searching a list
+        self.is_active[particle_idx] = false;
+        self.active_count -= 1;
         
         // Add to inactive list
         self.inactive_indices.push(particle_idx);
@@ -98,6 +106,20 @@ impl ParticleList {
         particle.reset();
     }
     
+    /// Make every particle inactive and zero the per-type counts
+    pub fn clear_all(&mut self) {
+        for particle in &mut self.particles {
+            particle.active = false;
+            particle.reset();
+        }
+        self.is_active.fill(false);
+        self.active_count = 0;
+        self.inactive_indices.clear();
+        self.inactive_indices.extend(0..self.particles.len());
+        self.particle_counts = [0; PARTICLE_TYPE_COUNT];
+        self.spatial_hash.clear();
+    }
+    
     /// Check if a particle type is currently active
     pub fn particle_active(&self, particle_type: ParticleType) -> bool {
         self.particle_counts[particle_type.index() as usize] > 0
@@ -124,9 +146,34 @@ impl ParticleList {
         particle.action_iterations = 0;
     }
     
-    /// Get all active particle indices
-    pub fn active_particles(&self) -> &[usize] {
-        &self.active_indices
+    /// Get all active particle indices, in pool order
+    pub fn active_particles(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..MAX_NUM_PARTICLES).filter(|&i| self.is_active[i])
+    }
+
+    /// Number of active particles
+    pub fn active_count(&self) -> usize {
+        self.active_count
+    }
+    
+    /// Re-bucket all active particles by their current positions
+    pub fn rebuild_spatial_hash(&mut self) {
+        self.spatial_hash.clear();
+        for particle_idx in (0..MAX_NUM_PARTICLES).filter(|&i| self.is_active[i]) {
+            let particle = &self.particles[particle_idx];
+            self.spatial_hash.insert(particle_idx, particle.x, particle.y);
+        }
+    }
+
+    /// Active particles within distance r of (x, y)
+    pub fn query_radius(&self, x: f32, y: f32, r: f32) -> impl Iterator<Item = &Particle> + '_ {
+        particles_in_radius(&self.spatial_hash, x, y, r)
+            .filter_map(|particle_idx| self.particles.get(particle_idx))
+            .filter(move |particle| {
+                let dx = particle.x - x;
+                let dy = particle.y - y;
+                particle.active && dx * dx + dy * dy <= r * r
+            })
     }
     
     /// Get mutable access to a particle
@@ -2,9 +2,11 @@ pub mod types;
 pub mod manager;
 pub mod render;
 pub mod actions;
+pub mod spatial;
 
 pub use types::*;
 pub use manager::ParticleList;
 pub use render::*;
 pub use actions::{particle_init, particle_action};
+pub use spatial::{particles_in_radius, ParticleSpatialHash};
 
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use crate::particles::types::{Particle, PAINTABLE_PARTICLE_COLORS};
+use crate::particles::types::Particle;
 use crate::particles::manager::ParticleList;
 use crate::elements::Element;
 
@@ -7,59 +7,64 @@ use crate::elements::Element;
 #[derive(Resource)]
 pub struct ParticleTexture(pub Handle<Image>);
 
+/// Blank particle texture for a grid of the given size
+/// Kept in the main world too: compositing reads the pixels back on the CPU every frame,
+/// and a render-world-only image would be dropped from `Assets` after its first upload
+pub fn new_particle_image(width: u32, height: u32) -> Image {
+    let mut image = Image::new_target_texture(width, height, bevy::render::render_resource::TextureFormat::Rgba8Unorm);
+    image.data = Some(vec![0u8; (width * height * 4) as usize]);
+    image.asset_usage = bevy::asset::RenderAssetUsages::MAIN_WORLD | bevy::asset::RenderAssetUsages::RENDER_WORLD;
+    image.texture_descriptor.usage = bevy::render::render_resource::TextureUsages::COPY_DST | bevy::render::render_resource::TextureUsages::TEXTURE_BINDING;
+    image
+}
+
 /// Render particles to the particle texture
 /// Particles are drawn as circles or lines depending on type
+/// The texture is redrawn in place, a new image is only made if it is missing or the grid was resized
 pub fn render_particles_to_texture(
     particle_list: Res<ParticleList>,
     grid: Res<crate::simulation::GameGrid>,
     mut images: ResMut<Assets<Image>>,
     mut particle_texture: ResMut<ParticleTexture>,
 ) {
-    // Create pixel data for particle texture (transparent black background)
+    let size_matches = images.get(&particle_texture.0).is_some_and(|image| {
+        image.width() == grid.width && image.height() == grid.height && image.data.is_some()
+    });
+    if !size_matches {
+        particle_texture.0 = images.add(new_particle_image(grid.width, grid.height));
+    }
+    // get_mut marks the image as modified, so Bevy re-uploads it
+    let Some(particle_pixels) = images.get_mut(&particle_texture.0).and_then(|image| image.data.as_mut()) else {
+        return;
+    };
+    
     // IMPORTANT: We need to clear the texture each frame, otherwise old particles will remain
-    let mut particle_pixels = vec![0u8; (grid.width * grid.height * 4) as usize];
+    particle_pixels.fill(0);
     
     // Draw each active particle
-    for &particle_idx in particle_list.active_particles() {
+    for particle_idx in particle_list.active_particles() {
         if let Some(particle) = particle_list.get_particle(particle_idx) {
             if particle.particle_type == crate::particles::types::ParticleType::Tree {
                 // For tree particles, draw line from previous position to current
                 if particle.prev_x >= 0.0 && particle.prev_y >= 0.0 {
-                    draw_line(particle.prev_x, particle.prev_y, particle.x, particle.y, particle.size, &mut particle_pixels, grid.width, grid.height, particle.color);
+                    draw_line(particle.prev_x, particle.prev_y, particle.x, particle.y, particle.size, particle_pixels, grid.width, grid.height, particle.color, particle.alpha);
                 } else {
                     // First frame - just draw a circle
-                    draw_circle_helper(particle.x, particle.y, particle.size, &mut particle_pixels, grid.width, grid.height, particle.color);
+                    draw_circle_helper(particle.x, particle.y, particle.size, particle_pixels, grid.width, grid.height, particle.color, particle.alpha);
                 }
             } else if particle.particle_type == crate::particles::types::ParticleType::ChargedNitro {
                 // ChargedNitro particles draw a vertical fire column from init position to current position
                 // This creates the upward fire column effect
-                draw_line(particle.init_x, particle.init_y, particle.x, particle.y, particle.size, &mut particle_pixels, grid.width, grid.height, particle.color);
+                draw_line(particle.init_x, particle.init_y, particle.x, particle.y, particle.size, particle_pixels, grid.width, grid.height, particle.color, particle.alpha);
             } else {
-                draw_particle(particle, &mut particle_pixels, grid.width, grid.height);
+                draw_particle(particle, particle_pixels, grid.width, grid.height);
             }
         }
     }
-    
-    // Update particle texture using the same pattern as main render texture
-    // Create a new Image each frame to force Bevy to re-upload the texture
-    let mut new_particle_image = Image::new_target_texture(grid.width, grid.height, bevy::render::render_resource::TextureFormat::Rgba8Unorm);
-    new_particle_image.data = Some(particle_pixels);
-    new_particle_image.asset_usage = bevy::asset::RenderAssetUsages::RENDER_WORLD;
-    new_particle_image.texture_descriptor.usage = bevy::render::render_resource::TextureUsages::COPY_DST | bevy::render::render_resource::TextureUsages::TEXTURE_BINDING;
-    
-    // Add the new image and update the resource handle (same pattern as main render texture)
-    let new_handle = images.add(new_particle_image);
-    particle_texture.0 = new_handle;
 }
 
 /// Draw a single particle to the pixel buffer
 fn draw_particle(particle: &Particle, pixels: &mut [u8], width: u32, height: u32) {
-    let color = particle.color.color();
-    let r = (color.red * 255.0) as u8;
-    let g = (color.green * 255.0) as u8;
-    let b = (color.blue * 255.0) as u8;
-    let a = (color.alpha * 255.0) as u8;
-    
     match particle.particle_type {
         crate::particles::types::ParticleType::Nitro
         | crate::particles::types::ParticleType::Lava
@@ -69,29 +74,29 @@ fn draw_particle(particle: &Particle, pixels: &mut [u8], width: u32, height: u32
             // Draw as line (from previous position to current)
             // For tree particles, we need to track previous position
             // For now, draw as circle at current position (will be improved)
-            draw_circle_internal(particle.x, particle.y, particle.size, r, g, b, a, pixels, width, height);
+            draw_circle_internal(particle.x, particle.y, particle.size, particle.color, particle.alpha, pixels, width, height);
         }
         crate::particles::types::ParticleType::Napalm
         | crate::particles::types::ParticleType::C4
         | crate::particles::types::ParticleType::Methane
         | crate::particles::types::ParticleType::Nuke => {
             // Draw as circle
-            draw_circle_internal(particle.x, particle.y, particle.size, r, g, b, a, pixels, width, height);
+            draw_circle_internal(particle.x, particle.y, particle.size, particle.color, particle.alpha, pixels, width, height);
         }
         crate::particles::types::ParticleType::Magic2 => {
             // Draw as line for spiral
             // For simplicity, draw as small circle
-            draw_circle_internal(particle.x, particle.y, particle.size, r, g, b, a, pixels, width, height);
+            draw_circle_internal(particle.x, particle.y, particle.size, particle.color, particle.alpha, pixels, width, height);
         }
         _ => {
             // Default: draw as circle
-            draw_circle_internal(particle.x, particle.y, particle.size, r, g, b, a, pixels, width, height);
+            draw_circle_internal(particle.x, particle.y, particle.size, particle.color, particle.alpha, pixels, width, height);
         }
     }
 }
 
 /// Draw a line from (x1, y1) to (x2, y2) with given width
-fn draw_line(x1: f32, y1: f32, x2: f32, y2: f32, width: f32, pixels: &mut [u8], canvas_width: u32, canvas_height: u32, color: Element) {
+fn draw_line(x1: f32, y1: f32, x2: f32, y2: f32, width: f32, pixels: &mut [u8], canvas_width: u32, canvas_height: u32, color: Element, alpha: f32) {
     // Simple line drawing using Bresenham-like algorithm
     let dx = x2 - x1;
     let dy = y2 - y1;
@@ -99,7 +104,7 @@ fn draw_line(x1: f32, y1: f32, x2: f32, y2: f32, width: f32, pixels: &mut [u8],
     
     if dist < 0.1 {
         // Points are too close, just draw a circle
-        draw_circle_helper(x1, y1, width, pixels, canvas_width, canvas_height, color);
+        draw_circle_helper(x1, y1, width, pixels, canvas_width, canvas_height, color, alpha);
         return;
     }
     
@@ -111,18 +116,19 @@ fn draw_line(x1: f32, y1: f32, x2: f32, y2: f32, width: f32, pixels: &mut [u8],
         let x = x1 + dx * t;
         let y = y1 + dy * t;
         // Draw circles with radius = width/2 to create a continuous line
-        draw_circle_helper(x, y, width / 2.0, pixels, canvas_width, canvas_height, color);
+        draw_circle_helper(x, y, width / 2.0, pixels, canvas_width, canvas_height, color, alpha);
     }
 }
 
 /// Draw a circle helper that takes Element color
-fn draw_circle_helper(x: f32, y: f32, radius: f32, pixels: &mut [u8], canvas_width: u32, canvas_height: u32, color: Element) {
-    let color_rgba = color.color();
-    let r = (color_rgba.red * 255.0) as u8;
-    let g = (color_rgba.green * 255.0) as u8;
-    let b = (color_rgba.blue * 255.0) as u8;
-    let a = (color_rgba.alpha * 255.0) as u8;
-    draw_circle_internal(x, y, radius, r, g, b, a, pixels, canvas_width, canvas_height);
+fn draw_circle_helper(x: f32, y: f32, radius: f32, pixels: &mut [u8], canvas_width: u32, canvas_height: u32, color: Element, alpha: f32) {
+    draw_circle_internal(x, y, radius, color, alpha, pixels, canvas_width, canvas_height);
+}
+
+/// Alpha written to the particle texture for a particle's opacity
+/// Never 0, which is left for pixels no particle painted
+pub fn particle_alpha(alpha: f32) -> u8 {
+    (alpha * 255.0).round().clamp(1.0, 255.0) as u8
 }
 
 /// Draw a filled circle at the given position (internal helper)
@@ -130,14 +136,21 @@ fn draw_circle_internal(
     x: f32,
     y: f32,
     radius: f32,
-    r: u8,
-    g: u8,
-    b: u8,
-    a: u8,
+    element: Element,
+    alpha: f32,
     pixels: &mut [u8],
     width: u32,
     height: u32,
 ) {
+    // Fully faded particles aren't drawn at all
+    if alpha <= 0.0 {
+        return;
+    }
+    let color = element.color();
+    let r = (color.red * 255.0) as u8;
+    let g = (color.green * 255.0) as u8;
+    let b = (color.blue * 255.0) as u8;
+    let a = particle_alpha(alpha);
     let radius_sq = radius * radius;
     let x_center = x.round() as i32;
     let y_center = y.round() as i32;
@@ -166,13 +179,14 @@ fn draw_circle_internal(
 }
 
 /// Composite particle texture onto main texture
-/// Only copies pixels that match paintable particle colors
+/// Blends every pixel a particle painted by its alpha
 pub fn composite_particles_to_main(
     grid: Res<crate::simulation::GameGrid>,
     _particle_list: Res<ParticleList>,
-    mut images: ResMut<Assets<Image>>,
+    images: ResMut<Assets<Image>>,
     particle_texture: ResMut<ParticleTexture>,
-    render_texture: Res<crate::systems::RenderTexture>,
+    mut pixels: ResMut<crate::systems::GridPixels>,
+    mut upload: ResMut<crate::systems::TextureUpload>,
 ) {
     // Get particle texture data (clone to avoid borrow issues)
     let particle_data = {
@@ -187,151 +201,38 @@ pub fn composite_particles_to_main(
         }
     };
     
-    // Get main texture
-    let (main_data, width, height) = {
-        if let Some(main_image) = images.get_mut(&render_texture.0) {
-            if let Some(data) = main_image.data.as_mut() {
-                (data, grid.width, grid.height)
-            } else {
-                return; // No main texture data yet
-            }
-        } else {
-            return; // Main texture not found
-        }
-    };
+    // Get main texture pixels
+    let width = grid.width;
+    let height = grid.height;
+    let main_data = &mut pixels.data;
+    // Area painted with particles, redrawn from the grid next frame
+    let mut painted = None;
     
     // Composite particles onto main texture
-    // Only copy pixels that match paintable colors
+    // Any pixel a particle painted has a non-zero alpha (see `particle_alpha`)
     for y in 0..height {
         for x in 0..width {
             let idx = ((y * width + x) * 4) as usize;
             if idx + 3 >= particle_data.len() || idx + 3 >= main_data.len() {
                 continue;
             }
-            
-            // Check if particle pixel is non-black
-            let pr = particle_data[idx];
-            let pg = particle_data[idx + 1];
-            let pb = particle_data[idx + 2];
-            let pa = particle_data[idx + 3];
-            
-            if pr == 0 && pg == 0 && pb == 0 && pa == 0 {
-                continue; // Skip black pixels (background)
-            }
-            
-            // Check if color matches a paintable particle color
-            // For simplicity, check if it matches any element color closely
-            let mut matches = false;
-            for &color_elem in PAINTABLE_PARTICLE_COLORS {
-                let color = color_elem.color();
-                let cr = (color.red * 255.0) as u8;
-                let cg = (color.green * 255.0) as u8;
-                let cb = (color.blue * 255.0) as u8;
-                
-                // Allow some tolerance for anti-aliasing (increased from 10 to 20)
-                if (pr as i16 - cr as i16).abs() < 20
-                    && (pg as i16 - cg as i16).abs() < 20
-                    && (pb as i16 - cb as i16).abs() < 20
-                {
-                    matches = true;
-                    break;
-                }
+            if particle_data[idx + 3] == 0 {
+                continue; // Nothing painted here
             }
             
-            if matches {
-                // Copy particle pixel to main texture
-                main_data[idx] = pr;
-                main_data[idx + 1] = pg;
-                main_data[idx + 2] = pb;
-                main_data[idx + 3] = pa;
-            } else {
-                // Try to find nearby valid color (anti-aliasing fix)
-                let aliasing_search = 3;
-                let mut found_color = None;
-                
-                // Search left
-                if x >= aliasing_search {
-                    let search_idx = (((y * width + (x - aliasing_search)) * 4)) as usize;
-                    if search_idx + 3 < particle_data.len() {
-                        found_color = check_paintable_color(
-                            &particle_data[search_idx..search_idx + 4],
-                        );
-                    }
-                }
-                
-                // Search right
-                if found_color.is_none() && x + aliasing_search < width {
-                    let search_idx = (((y * width + (x + aliasing_search)) * 4)) as usize;
-                    if search_idx + 3 < particle_data.len() {
-                        found_color = check_paintable_color(
-                            &particle_data[search_idx..search_idx + 4],
-                        );
-                    }
-                }
-                
-                // Search up
-                if found_color.is_none() && y >= aliasing_search {
-                    let search_idx = ((((y - aliasing_search) * width + x) * 4)) as usize;
-                    if search_idx + 3 < particle_data.len() {
-                        found_color = check_paintable_color(
-                            &particle_data[search_idx..search_idx + 4],
-                        );
-                    }
-                }
-                
-                // Search down
-                if found_color.is_none() && y + aliasing_search < height {
-                    let search_idx = ((((y + aliasing_search) * width + x) * 4)) as usize;
-                    if search_idx + 3 < particle_data.len() {
-                        found_color = check_paintable_color(
-                            &particle_data[search_idx..search_idx + 4],
-                        );
-                    }
-                }
-                
-                if let Some((r, g, b, a)) = found_color {
-                    main_data[idx] = r;
-                    main_data[idx + 1] = g;
-                    main_data[idx + 2] = b;
-                    main_data[idx + 3] = a;
-                }
+            // Blend particle pixel over the main texture, which stays fully opaque
+            let alpha = u32::from(particle_data[idx + 3]);
+            for channel in 0..3 {
+                let under = u32::from(main_data[idx + channel]);
+                let over = u32::from(particle_data[idx + channel]);
+                main_data[idx + channel] = ((over * alpha + under * (255 - alpha) + 127) / 255) as u8;
             }
+            main_data[idx + 3] = 255;
+            painted = crate::systems::union_rects(painted, Some((x, y, x, y)));
         }
     }
-    
-}
 
-/// Check if a pixel color matches a paintable particle color
-fn check_paintable_color(pixel: &[u8]) -> Option<(u8, u8, u8, u8)> {
-    if pixel.len() < 4 {
-        return None;
-    }
+    pixels.particle_rect = painted;
+    upload.rect = crate::systems::union_rects(upload.rect, painted);
     
-    let pr = pixel[0];
-    let pg = pixel[1];
-    let pb = pixel[2];
-    let pa = pixel[3];
-    
-    if pr == 0 && pg == 0 && pb == 0 {
-        return None; // Black background
-    }
-    
-    // Check against paintable colors
-    for &color_elem in PAINTABLE_PARTICLE_COLORS {
-        let color = color_elem.color();
-        let cr = (color.red * 255.0) as u8;
-        let cg = (color.green * 255.0) as u8;
-        let cb = (color.blue * 255.0) as u8;
-        
-        // Allow tolerance for anti-aliasing
-        if (pr as i16 - cr as i16).abs() < 10
-            && (pg as i16 - cg as i16).abs() < 10
-            && (pb as i16 - cb as i16).abs() < 10
-        {
-            return Some((cr, cg, cb, pa));
-        }
-    }
-    
-    None
 }
-
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+/// Side length of a spatial hash cell, in grid pixels
+pub const SPATIAL_HASH_CELL_SIZE: f32 = 16.0;
+
+/// Buckets active particle indices by position so nearby particles can be found
+/// without scanning the whole active list
+pub struct ParticleSpatialHash {
+    pub grid: HashMap<(i32, i32), Vec<usize>>,
+    pub cell_size: f32,
+}
+
+impl Default for ParticleSpatialHash {
+    fn default() -> Self {
+        Self {
+            grid: HashMap::new(),
+            cell_size: SPATIAL_HASH_CELL_SIZE,
+        }
+    }
+}
+
+impl ParticleSpatialHash {
+    /// Cell containing the given position
+    fn cell(&self, x: f32, y: f32) -> (i32, i32) {
+        ((x / self.cell_size).floor() as i32, (y / self.cell_size).floor() as i32)
+    }
+
+    pub fn clear(&mut self) {
+        self.grid.clear();
+    }
+
+    pub fn insert(&mut self, particle_idx: usize, x: f32, y: f32) {
+        let cell = self.cell(x, y);
+        self.grid.entry(cell).or_default().push(particle_idx);
+    }
+}
+
+/// Indices of particles in the cells overlapping the circle at (x, y) with radius r
+/// Candidates can lie just outside the circle, so callers still check the exact distance
+pub fn particles_in_radius(hash: &ParticleSpatialHash, x: f32, y: f32, r: f32) -> impl Iterator<Item = usize> + '_ {
+    let (min_x, min_y) = hash.cell(x - r, y - r);
+    let (max_x, max_y) = hash.cell(x + r, y + r);
+    (min_y..=max_y)
+        .flat_map(move |cell_y| (min_x..=max_x).map(move |cell_x| (cell_x, cell_y)))
+        .filter_map(|cell| hash.grid.get(&cell))
+        .flatten()
+        .copied()
+}
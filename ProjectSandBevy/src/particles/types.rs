@@ -6,7 +6,7 @@ use crate::elements::Element;
 pub const MAX_NUM_PARTICLES: usize = 2048;
 
 /// Particle types (matching TypeScript indices)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 #[repr(u8)]
 pub enum ParticleType {
     Unknown = 0,
@@ -20,8 +20,12 @@ pub enum ParticleType {
     Tree = 8,
     ChargedNitro = 9,
     Nuke = 10,
+    Debris = 11,
 }
 
+/// Number of particle types, for per-type tables
+pub const PARTICLE_TYPE_COUNT: usize = 12;
+
 impl ParticleType {
     pub fn from_index(index: u8) -> Self {
         match index {
@@ -36,6 +40,7 @@ impl ParticleType {
             8 => ParticleType::Tree,
             9 => ParticleType::ChargedNitro,
             10 => ParticleType::Nuke,
+            11 => ParticleType::Debris,
             _ => ParticleType::Unknown,
         }
     }
@@ -66,9 +71,10 @@ pub struct Particle {
     pub action_iterations: u32,
     pub active: bool,
     pub reinitialized: bool,
+    pub lifetime: Option<u32>,  // Action ticks left before removal, None lives until its action removes it
+    pub alpha: f32,  // Opacity, fades out over the last quarter of the lifetime
     
     // Type-specific data (stored as Option to avoid boxing)
-    pub max_iterations: Option<u32>,  // For particles with fixed lifetimes
     pub min_y: Option<f32>,  // For charged nitro (wall collision)
     pub magic_2_max_radius: Option<f32>,  // For magic2 spiral
     pub magic_2_theta: Option<f32>,
@@ -77,6 +83,7 @@ pub struct Particle {
     pub magic_2_radius: Option<f32>,
     pub y_acceleration: Option<f32>,  // For lava particles
     pub init_y_velocity: Option<f32>,  // For lava particles
+    pub debris_element: Option<Element>,  // For debris particles (element it lands as)
     // Tree particle data
     pub tree_generation: Option<u32>,  // Generation number
     pub tree_branch_spacing: Option<u32>,  // Spacing between branches
@@ -106,7 +113,8 @@ impl Default for Particle {
             action_iterations: 0,
             active: false,
             reinitialized: false,
-            max_iterations: None,
+            lifetime: None,
+            alpha: 1.0,
             min_y: None,
             magic_2_max_radius: None,
             magic_2_theta: None,
@@ -115,6 +123,7 @@ impl Default for Particle {
             magic_2_radius: None,
             y_acceleration: None,
             init_y_velocity: None,
+            debris_element: None,
             tree_generation: None,
             tree_branch_spacing: None,
             tree_max_branches: None,
@@ -167,7 +176,8 @@ impl Particle {
         self.action_iterations = 0;
         self.active = false;
         self.reinitialized = false;
-        self.max_iterations = None;
+        self.lifetime = None;
+        self.alpha = 1.0;
         self.min_y = None;
         self.magic_2_max_radius = None;
         self.magic_2_theta = None;
@@ -176,6 +186,7 @@ impl Particle {
         self.magic_2_radius = None;
         self.y_acceleration = None;
         self.init_y_velocity = None;
+        self.debris_element = None;
         self.tree_generation = None;
         self.tree_branch_spacing = None;
         self.tree_max_branches = None;
@@ -187,6 +198,7 @@ impl Particle {
 
 /// Paintable particle colors - colors that can be copied from particle canvas to main canvas
 /// These match element colors that particles can represent
+/// Unused by compositing now, painted pixels are found by their alpha (see `particle_alpha`)
 pub const PAINTABLE_PARTICLE_COLORS: &[Element] = &[
     Element::Fire,
     Element::Wall,
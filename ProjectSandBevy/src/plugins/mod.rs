@@ -1,8 +1,13 @@
+#[cfg(feature = "remote")]
+pub mod remote;
+
+use crate::systems::{upload_texture_rect, TextureUpload};
 use crate::{SIZE, WORKGROUP_SIZE};
 use bevy::{
     prelude::*,
     render::{
-        extract_resource::ExtractResource,
+        Render, RenderApp, RenderSystems,
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
         render_graph::{self, RenderLabel},
         render_resource::{CachedPipelineState, ComputePassDescriptor, PipelineCache, BindGroup, BindGroupLayout, CachedComputePipelineId, ShaderType},
         renderer::RenderContext,
@@ -48,6 +53,19 @@ impl Plugin for FallingSandComputePlugin {
     }
 }
 
+/// Uploads the changed region of the grid texture each frame instead of the whole image
+pub struct GridTexturePlugin;
+
+impl Plugin for GridTexturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ExtractResourcePlugin::<TextureUpload>::default());
+        app.sub_app_mut(RenderApp).add_systems(
+            Render,
+            upload_texture_rect.in_set(RenderSystems::PrepareResources),
+        );
+    }
+}
+
 impl Default for FallingSandNode {
     fn default() -> Self {
         Self {
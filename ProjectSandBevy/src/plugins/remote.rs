@@ -0,0 +1,191 @@
+//! HTTP API for controlling a running game, built with the `remote` feature
+//!
+//! - `GET /api/state` grid statistics as JSON
+//! - `POST /api/place` with `{"x": 10, "y": 20, "element": "Sand"}` places one cell
+//! - `POST /api/clear` clears the grid
+//! - `GET /api/screenshot` the grid as a PNG, one pixel per cell
+//!
+//! The server runs on its own thread and hands every request to the Bevy main thread over a channel,
+//! requests that need an answer carry a channel for the reply
+
+// Bevy systems take their resources by value, and axum handlers must be async even when they don't await
+#![allow(clippy::needless_pass_by_value, clippy::unused_async)]
+
+use crate::elements::Element;
+use crate::save::png::encode_png;
+use crate::simulation::GameGrid;
+use crate::systems::{ClearGrid, RainbowSandPlacementTimes};
+use axum::extract::{Json, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::Router;
+use bevy::prelude::*;
+use crossbeam_channel::{Receiver, Sender};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// How long a request waits for the main thread to answer
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Serves the remote control API on `addr`, 127.0.0.1:3030 by default
+pub struct RemoteControlPlugin {
+    pub addr: SocketAddr,
+}
+
+impl Default for RemoteControlPlugin {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::from(([127, 0, 0, 1], 3030)),
+        }
+    }
+}
+
+impl Plugin for RemoteControlPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let addr = self.addr;
+        std::thread::spawn(move || {
+            match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(serve(addr, sender)),
+                Err(e) => bevy::log::error!("Failed to start the remote control runtime: {}", e),
+            }
+        });
+        app.insert_resource(RemoteCommands(receiver))
+            .add_systems(Update, process_remote_commands);
+    }
+}
+
+/// A request from the HTTP thread for the main thread
+pub enum RemoteCommand {
+    State(Sender<RemoteState>),
+    Place { x: u32, y: u32, element: Element },
+    Clear,
+    /// Replies with the PNG bytes, None if encoding failed
+    Screenshot(Sender<Option<Vec<u8>>>),
+}
+
+/// Body of `GET /api/state`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RemoteState {
+    pub width: u32,
+    pub height: u32,
+    pub total_cells: u32,
+    pub non_empty_cells: u32,
+    pub empty_pct: f32,
+    pub most_common: Element,
+    pub least_common: Option<Element>,
+}
+
+/// Body of `POST /api/place`
+#[derive(serde::Deserialize)]
+struct PlaceRequest {
+    x: u32,
+    y: u32,
+    element: Element,
+}
+
+/// Resource receiving commands from the HTTP thread
+#[derive(Resource)]
+pub struct RemoteCommands(pub Receiver<RemoteCommand>);
+
+/// Apply every command that arrived since the last frame
+pub fn process_remote_commands(
+    commands: Res<RemoteCommands>,
+    mut grid: ResMut<GameGrid>,
+    mut clear_grid: ResMut<ClearGrid>,
+    mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
+) {
+    // A dropped reply channel means the request timed out, there's nobody left to answer
+    for command in commands.0.try_iter() {
+        match command {
+            RemoteCommand::State(reply) => {
+                let stats = grid.statistics();
+                let _ = reply.send(RemoteState {
+                    width: grid.width,
+                    height: grid.height,
+                    total_cells: stats.total_cells,
+                    non_empty_cells: stats.non_empty_cells,
+                    empty_pct: stats.empty_pct,
+                    most_common: stats.most_common,
+                    least_common: stats.least_common,
+                });
+            }
+            RemoteCommand::Place { x, y, element } => {
+                if grid.is_valid(x, y) {
+                    let idx = grid.xy_to_index(x, y);
+                    grid.set(x, y, element);
+                    // Placed RainbowSand takes its color from its position
+                    rainbow_sand_times.0.remove(&idx);
+                }
+            }
+            RemoteCommand::Clear => clear_grid.0 = true,
+            RemoteCommand::Screenshot(reply) => {
+                let png = encode_png(&grid, &rainbow_sand_times)
+                    .inspect_err(|e| bevy::log::error!("Failed to encode remote screenshot: {}", e))
+                    .ok();
+                let _ = reply.send(png);
+            }
+        }
+    }
+}
+
+async fn serve(addr: SocketAddr, commands: Sender<RemoteCommand>) {
+    let router = Router::new()
+        .route("/api/state", get(state))
+        .route("/api/place", post(place))
+        .route("/api/clear", post(clear))
+        .route("/api/screenshot", get(screenshot))
+        .with_state(commands);
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            bevy::log::error!("Remote control can't listen on {}: {}", addr, e);
+            return;
+        }
+    };
+    bevy::log::info!("Remote control listening on http://{}", addr);
+    if let Err(e) = axum::serve(listener, router).await {
+        bevy::log::error!("Remote control server stopped: {}", e);
+    }
+}
+
+/// Wait for the main thread's reply without blocking the server
+async fn wait_for_reply<T: Send + 'static>(reply: Receiver<T>) -> Result<T, StatusCode> {
+    tokio::task::spawn_blocking(move || reply.recv_timeout(REPLY_TIMEOUT))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|_| StatusCode::GATEWAY_TIMEOUT)
+}
+
+async fn state(State(commands): State<Sender<RemoteCommand>>) -> Result<Json<RemoteState>, StatusCode> {
+    let (reply, response) = crossbeam_channel::bounded(1);
+    commands.send(RemoteCommand::State(reply)).map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    wait_for_reply(response).await.map(Json)
+}
+
+async fn place(State(commands): State<Sender<RemoteCommand>>, Json(request): Json<PlaceRequest>) -> StatusCode {
+    let command = RemoteCommand::Place {
+        x: request.x,
+        y: request.y,
+        element: request.element,
+    };
+    match commands.send(command) {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+async fn clear(State(commands): State<Sender<RemoteCommand>>) -> StatusCode {
+    match commands.send(RemoteCommand::Clear) {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+async fn screenshot(State(commands): State<Sender<RemoteCommand>>) -> Result<impl IntoResponse, StatusCode> {
+    let (reply, response) = crossbeam_channel::bounded(1);
+    commands.send(RemoteCommand::Screenshot(reply)).map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    let png = wait_for_reply(response).await?.ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(([(header::CONTENT_TYPE, "image/png")], png))
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+/// Color correction for color vision deficiencies, chosen under "Accessibility" in the UI
+#[derive(Resource, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum ColorblindMode {
+    /// Colors unchanged
+    #[default]
+    Normal,
+    /// Red-green, weak green cones
+    Deuteranopia,
+    /// Strong red-green, weak red cones
+    Protanopia,
+    /// Blue-yellow, weak blue cones
+    Tritanopia,
+}
+
+impl ColorblindMode {
+    pub const ALL: [Self; 4] = [Self::Normal, Self::Deuteranopia, Self::Protanopia, Self::Tritanopia];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Deuteranopia => "Deuteranopia (red-green)",
+            Self::Protanopia => "Protanopia (strong red-green)",
+            Self::Tritanopia => "Tritanopia (blue-yellow)",
+        }
+    }
+
+    /// Row-major correction matrix, None for Normal
+    #[must_use]
+    pub const fn matrix(self) -> Option<&'static [[f32; 3]; 3]> {
+        match self {
+            Self::Normal => None,
+            Self::Deuteranopia => Some(&DEUTERANOPIA),
+            Self::Protanopia => Some(&PROTANOPIA),
+            Self::Tritanopia => Some(&TRITANOPIA),
+        }
+    }
+}
+
+// Daltonization matrices: the color the deficient eye loses (the original minus its Machado et al.
+// 2009 simulation) is shifted from red into green and blue, which spreads out the element colors
+// that look alike without correction
+
+const DEUTERANOPIA: [[f32; 3]; 3] = [
+    [1.0, 0.0, 0.0],
+    [0.1628, 0.7250, 0.1122],
+    [0.4547, -0.6454, 1.1907],
+];
+
+const PROTANOPIA: [[f32; 3]; 3] = [
+    [1.0, 0.0, 0.0],
+    [0.4789, 0.4769, 0.0442],
+    [0.5973, -0.6887, 1.0914],
+];
+
+const TRITANOPIA: [[f32; 3]; 3] = [
+    [1.0, 0.0, 0.0],
+    [-0.1005, 1.1229, -0.0225],
+    [-0.1836, -0.6376, 1.8212],
+];
+
+/// Correct an rgb color (0 to 1) for a color vision deficiency, the result is clamped to 0 to 1
+#[must_use]
+pub fn apply_colorblind(color: [f32; 3], mode: &ColorblindMode) -> [f32; 3] {
+    let Some(matrix) = mode.matrix() else {
+        return color;
+    };
+    matrix.map(|row| row[2].mul_add(color[2], row[0].mul_add(color[0], row[1] * color[1])).clamp(0.0, 1.0))
+}
+
+/// `apply_colorblind` for `Rgba8Unorm` bytes, alpha is kept
+#[must_use]
+pub fn apply_colorblind_bytes(pixel: [u8; 4], mode: &ColorblindMode) -> [u8; 4] {
+    if *mode == ColorblindMode::Normal {
+        return pixel;
+    }
+    let [r, g, b] = apply_colorblind(
+        [f32::from(pixel[0]) / 255.0, f32::from(pixel[1]) / 255.0, f32::from(pixel[2]) / 255.0],
+        mode,
+    );
+    [to_byte(r), to_byte(g), to_byte(b), pixel[3]]
+}
+
+/// Round a 0 to 1 channel to the nearest byte
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to the u8 range first
+fn to_byte(channel: f32) -> u8 {
+    (channel * 255.0).round().clamp(0.0, 255.0) as u8
+}
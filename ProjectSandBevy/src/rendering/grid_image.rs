@@ -0,0 +1,141 @@
+use crate::elements::{animated_element_color, Element};
+use crate::rendering::{
+    apply_colorblind_bytes, channel_to_byte, color_to_bytes, needs_cell_color, temp_to_heat_color, ColorblindMode, RenderMode, COLOR_LUT,
+};
+use crate::simulation::{rainbow_position_hash, GameGrid};
+use crate::systems::{detect_water_surface, RainbowSandPlacementTimes};
+use bevy::color::LinearRgba;
+
+/// How the grid's pixels are drawn, gathered from the visual settings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderRules {
+    pub render_mode: RenderMode,
+    pub colorblind_mode: ColorblindMode,
+    /// Fire flickers, Lava pulses and Water ripples (see `animated_element_color`)
+    pub animated_colors: bool,
+    /// Surface water is drawn shifted sideways and brighter (see `ripple_targets`)
+    pub water_ripples: bool,
+}
+
+/// Draw the whole grid as `Rgba8Unorm` pixels, one per cell in row order
+#[must_use]
+pub fn grid_to_image(grid: &GameGrid, rainbow_times: &RainbowSandPlacementTimes, frame: u32, rules: &RenderRules) -> Vec<u8> {
+    let mut pixels = vec![0; grid.elements.len() * 4];
+    if grid.width == 0 || grid.height == 0 {
+        return pixels;
+    }
+    draw_cells(&mut pixels, grid, rainbow_times, frame, rules, (0, 0, grid.max_x(), grid.max_y()));
+    if rules.water_ripples && rules.render_mode == RenderMode::Normal {
+        draw_ripples(&mut pixels, grid, rainbow_times, frame, rules, &ripple_targets(grid, frame));
+    }
+    pixels
+}
+
+/// Draw the cells inside `(min_x, min_y, max_x, max_y)` into pixels covering the whole grid
+pub fn draw_cells(
+    pixels: &mut [u8],
+    grid: &GameGrid,
+    rainbow_times: &RainbowSandPlacementTimes,
+    frame: u32,
+    rules: &RenderRules,
+    (min_x, min_y, max_x, max_y): (u32, u32, u32, u32),
+) {
+    // Most cells are a straight table lookup, only rainbow and animated cells need their color computed
+    let lut = COLOR_LUT.map(|color| apply_colorblind_bytes(color, &rules.colorblind_mode));
+    let heat_map = rules.render_mode == RenderMode::HeatMap;
+    for y in min_y..=max_y {
+        let start = grid.xy_to_index(min_x, y);
+        let end = grid.xy_to_index(max_x, y) + 1;
+        let row = pixels[start * 4..end * 4].chunks_exact_mut(4).zip(&grid.elements[start..end]);
+        for (idx, (pixel, &element)) in (start..).zip(row) {
+            if heat_map {
+                pixel.copy_from_slice(&apply_colorblind_bytes(temp_to_heat_color(grid.temperatures[idx]), &rules.colorblind_mode));
+            } else if needs_cell_color(element, rules.animated_colors) {
+                let color = color_to_bytes(cell_color(grid, rainbow_times, idx, rules.animated_colors, frame));
+                pixel.copy_from_slice(&apply_colorblind_bytes(color, &rules.colorblind_mode));
+            } else {
+                pixel.copy_from_slice(&lut[element.index() as usize]);
+            }
+        }
+    }
+}
+
+/// Where each surface water cell is drawn this frame as (cell index, pixel index)
+/// Cells are shifted sideways by up to one pixel, wrapping around the grid edges
+#[must_use]
+#[allow(clippy::cast_precision_loss)] // Only the phase of the wave depends on x and frame
+pub fn ripple_targets(grid: &GameGrid, frame: u32) -> Vec<(usize, usize)> {
+    detect_water_surface(grid)
+        .into_iter()
+        .map(|idx| {
+            let (x, y) = grid.index_to_xy(idx);
+            let wave = (x as f32).mul_add(0.4, frame as f32 * 0.15).sin() * 1.5;
+            // Shift a pixel toward whichever side the wave is past halfway on
+            let target_x = if wave >= 0.5 {
+                (x + 1) % grid.width
+            } else if wave <= -0.5 {
+                (x + grid.width - 1) % grid.width
+            } else {
+                x
+            };
+            (idx, grid.xy_to_index(target_x, y))
+        })
+        .collect()
+}
+
+/// Post-pass: draw surface water 20% brighter at its rippled position
+pub fn draw_ripples(
+    pixels: &mut [u8],
+    grid: &GameGrid,
+    rainbow_times: &RainbowSandPlacementTimes,
+    frame: u32,
+    rules: &RenderRules,
+    ripples: &[(usize, usize)],
+) {
+    for &(idx, target_idx) in ripples {
+        let color = cell_color(grid, rainbow_times, idx, rules.animated_colors, frame);
+        let brightened = [
+            channel_to_byte(color.red * 1.2),
+            channel_to_byte(color.green * 1.2),
+            channel_to_byte(color.blue * 1.2),
+            channel_to_byte(color.alpha),
+        ];
+        let offset = target_idx * 4;
+        pixels[offset..offset + 4].copy_from_slice(&apply_colorblind_bytes(brightened, &rules.colorblind_mode));
+    }
+}
+
+/// Color of the cell at index idx
+#[must_use]
+pub fn cell_color(
+    grid: &GameGrid,
+    rainbow_sand_times: &RainbowSandPlacementTimes,
+    idx: usize,
+    animated: bool,
+    frame: u32,
+) -> LinearRgba {
+    let element = grid.elements[idx];
+    if element == Element::RainbowSand {
+        // RainbowSand: use placement time to determine color
+        // The color is determined when placed and stays fixed
+        // Get the placement time for this position, or use position-based hash as fallback
+        let placement_time = rainbow_sand_times.0.get(&idx).copied();
+        let (x, y) = grid.index_to_xy(idx);
+
+        // Fallback: if no placement time found, use position hash
+        // This handles cases where sand moved and we lost the placement time
+        let placement_time = placement_time.unwrap_or_else(|| rainbow_position_hash(x, y));
+
+        // Use placement time to create color shift across full 360 degree hue range
+        // Use modulo 256 to get full u8 range, which will be mapped to 0-360 degrees
+        let shift = u8::try_from(placement_time % 256).unwrap_or_default();
+
+        element.to_encoded_color_with_shift(shift)
+    } else if animated {
+        let (x, y) = grid.index_to_xy(idx);
+        animated_element_color(element, x, y, frame)
+    } else {
+        // Normal elements: no color shift
+        element.to_encoded_color()
+    }
+}
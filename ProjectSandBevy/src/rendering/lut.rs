@@ -0,0 +1,34 @@
+use crate::elements::Element;
+use bevy::prelude::*;
+use std::sync::LazyLock;
+
+/// `Rgba8Unorm` bytes of each element's unshifted color, indexed by `Element::index()`
+/// Indices without an element stay transparent black
+pub static COLOR_LUT: LazyLock<[[u8; 4]; 256]> = LazyLock::new(|| {
+    let mut lut = [[0; 4]; 256];
+    for index in 0..=u8::MAX {
+        let element = Element::from_index(index);
+        if element.index() == index {
+            lut[index as usize] = color_to_bytes(element.to_encoded_color());
+        }
+    }
+    lut
+});
+
+/// Convert a color to `Rgba8Unorm` bytes
+#[must_use]
+pub fn color_to_bytes(color: LinearRgba) -> [u8; 4] {
+    [channel_to_byte(color.red), channel_to_byte(color.green), channel_to_byte(color.blue), channel_to_byte(color.alpha)]
+}
+
+/// Convert a 0.0 to 1.0 color channel to a byte
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to the u8 range first
+pub(crate) fn channel_to_byte(channel: f32) -> u8 {
+    (channel * 255.0).clamp(0.0, 255.0) as u8
+}
+
+/// Check if a cell's color depends on more than its element, so it can't come from `COLOR_LUT`
+#[must_use]
+pub fn needs_cell_color(element: Element, animated: bool) -> bool {
+    element == Element::RainbowSand || (animated && matches!(element, Element::Fire | Element::Water | Element::Lava))
+}
@@ -0,0 +1,9 @@
+pub mod colorblind;
+pub mod grid_image;
+pub mod lut;
+pub mod modes;
+
+pub use colorblind::*;
+pub use grid_image::*;
+pub use lut::*;
+pub use modes::*;
@@ -0,0 +1,46 @@
+use crate::elements::AMBIENT_TEMPERATURE;
+use bevy::prelude::*;
+
+/// How the grid is drawn
+#[derive(Resource, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum RenderMode {
+    /// Element colors
+    #[default]
+    Normal,
+    /// Cell temperatures, from blue (cold) through green and red to white (extreme heat)
+    HeatMap,
+}
+
+/// Heat map color stops as (temperature in °C, rgb)
+const HEAT_STOPS: [(f32, [u8; 3]); 4] = [
+    (0.0, [0, 0, 255]),
+    (AMBIENT_TEMPERATURE, [0, 255, 0]),
+    (800.0, [255, 0, 0]),
+    (1200.0, [255, 255, 255]),
+];
+
+/// Heat map color (`Rgba8Unorm`) for a temperature, interpolated between the heat stops
+#[must_use]
+pub fn temp_to_heat_color(temp: f32) -> [u8; 4] {
+    let (first_temp, [r, g, b]) = HEAT_STOPS[0];
+    if temp <= first_temp {
+        return [r, g, b, 255];
+    }
+    for pair in HEAT_STOPS.windows(2) {
+        let (low_temp, low_color) = pair[0];
+        let (high_temp, high_color) = pair[1];
+        if temp <= high_temp {
+            let t = (temp - low_temp) / (high_temp - low_temp);
+            let channel = |c: usize| lerp_channel(low_color[c], high_color[c], t);
+            return [channel(0), channel(1), channel(2), 255];
+        }
+    }
+    [255, 255, 255, 255]
+}
+
+/// Blend between two color channels, t from 0.0 (low) to 1.0 (high)
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to the u8 range first
+fn lerp_channel(low: u8, high: u8, t: f32) -> u8 {
+    let (low, high) = (f32::from(low), f32::from(high));
+    (high - low).mul_add(t, low).clamp(0.0, 255.0) as u8
+}
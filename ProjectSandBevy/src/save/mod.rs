@@ -0,0 +1,353 @@
+pub mod png;
+
+use crate::elements::{element_default_durability, Element};
+use crate::particles::{ParticleList, ParticleType};
+use crate::simulation::{ActiveTreeBranches, GameGrid};
+use crate::spigots::Spigots;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version written by `WorldSaveFile::to_bytes`
+/// Version 1 saves are a bare bincode `GameGrid` with no version tag
+/// Version 2 saves store `grid.elements` as is, version 3 run-length encodes them (see `rle_encode`)
+/// Version 4 adds the tree branch count and a snapshot of the active particles
+/// Version 5 adds dragged spigot positions (`Spigots::custom_x`)
+/// Version 6 allows any number of spigots
+/// Version 7 adds the side wall spigots
+pub const SAVE_VERSION: u32 = 7;
+
+/// Spigots in every save before version 6
+const LEGACY_NUM_SPIGOTS: usize = 4;
+
+/// Compress runs of identical elements into (element index, run length) pairs
+/// Runs longer than `u16::MAX` are split across several pairs
+pub fn rle_encode(elements: &[Element]) -> Vec<(u8, u16)> {
+    let mut pairs: Vec<(u8, u16)> = Vec::new();
+    for element in elements {
+        let index = element.index();
+        match pairs.last_mut() {
+            Some((last, count)) if *last == index && *count < u16::MAX => *count += 1,
+            _ => pairs.push((index, 1)),
+        }
+    }
+    pairs
+}
+
+/// Expand (element index, run length) pairs from `rle_encode` back into elements
+pub fn rle_decode(pairs: &[(u8, u16)]) -> Vec<Element> {
+    pairs
+        .iter()
+        .flat_map(|&(index, count)| std::iter::repeat_n(Element::from_index(index), usize::from(count)))
+        .collect()
+}
+
+/// Summary of a save, readable without looking at the grid
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SaveHeader {
+    pub width: u32,
+    pub height: u32,
+    pub timestamp_secs: i64,
+    /// Number of non-background cells
+    pub element_count: u32,
+}
+
+impl SaveHeader {
+    pub fn for_grid(grid: &GameGrid, timestamp_secs: i64) -> Self {
+        Self {
+            width: grid.width,
+            height: grid.height,
+            timestamp_secs,
+            element_count: grid.elements.iter().filter(|&&element| element != Element::Background).count() as u32,
+        }
+    }
+}
+
+/// Everything written to a save file
+/// `version` must stay the first field, the loader reads it from the first 4 bytes
+/// On disk `grid.elements` is left empty and the elements are stored in `elements_rle`
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct WorldSaveFile {
+    pub version: u32,
+    pub header: SaveHeader,
+    pub grid: GameGrid,
+    pub spigots: Spigots,
+    pub rainbow_times: HashMap<usize, u32>,
+    pub elements_rle: Vec<(u8, u16)>,
+    /// Number of tree branches still growing when the world was saved
+    pub active_branches_count: usize,
+    /// Type and position of every active particle
+    pub particle_list_active: Vec<(ParticleType, f32, f32)>,
+}
+
+/// Spigots as saved before version 5, without dragged positions
+#[derive(serde::Deserialize)]
+struct LegacySpigots {
+    elements: [Element; LEGACY_NUM_SPIGOTS],
+    sizes: [u32; LEGACY_NUM_SPIGOTS],
+}
+
+impl From<LegacySpigots> for Spigots {
+    fn from(spigots: LegacySpigots) -> Self {
+        Self {
+            elements: spigots.elements.to_vec(),
+            sizes: spigots.sizes.to_vec(),
+            custom_x: vec![None; LEGACY_NUM_SPIGOTS],
+            wall_spigots: Vec::new(),
+        }
+    }
+}
+
+/// Spigots as saved in version 5, always four of them
+#[derive(serde::Deserialize)]
+struct SpigotsV5 {
+    elements: [Element; LEGACY_NUM_SPIGOTS],
+    sizes: [u32; LEGACY_NUM_SPIGOTS],
+    custom_x: [Option<u32>; LEGACY_NUM_SPIGOTS],
+}
+
+impl From<SpigotsV5> for Spigots {
+    fn from(spigots: SpigotsV5) -> Self {
+        Self {
+            elements: spigots.elements.to_vec(),
+            sizes: spigots.sizes.to_vec(),
+            custom_x: spigots.custom_x.to_vec(),
+            wall_spigots: Vec::new(),
+        }
+    }
+}
+
+/// Spigots as saved in version 6, before the side wall spigots
+#[derive(serde::Deserialize)]
+struct SpigotsV6 {
+    elements: Vec<Element>,
+    sizes: Vec<u32>,
+    custom_x: Vec<Option<u32>>,
+}
+
+impl From<SpigotsV6> for Spigots {
+    fn from(spigots: SpigotsV6) -> Self {
+        Self {
+            elements: spigots.elements,
+            sizes: spigots.sizes,
+            custom_x: spigots.custom_x,
+            wall_spigots: Vec::new(),
+        }
+    }
+}
+
+/// Version 4 to 6 layout, the same as the current one apart from the spigots
+/// The spigots are `LegacySpigots` in version 4, `SpigotsV5` in version 5 and `SpigotsV6` in version 6
+#[derive(serde::Deserialize)]
+struct WorldSaveFileV4<S> {
+    version: u32,
+    header: SaveHeader,
+    grid: GameGrid,
+    spigots: S,
+    rainbow_times: HashMap<usize, u32>,
+    elements_rle: Vec<(u8, u16)>,
+    active_branches_count: usize,
+    particle_list_active: Vec<(ParticleType, f32, f32)>,
+}
+
+impl<S: Into<Spigots>> From<WorldSaveFileV4<S>> for WorldSaveFile {
+    fn from(save: WorldSaveFileV4<S>) -> Self {
+        Self {
+            version: save.version,
+            header: save.header,
+            grid: save.grid,
+            spigots: save.spigots.into(),
+            rainbow_times: save.rainbow_times,
+            elements_rle: save.elements_rle,
+            active_branches_count: save.active_branches_count,
+            particle_list_active: save.particle_list_active,
+        }
+    }
+}
+
+/// Version 3 layout, before branches and particles were saved
+#[derive(serde::Deserialize)]
+struct WorldSaveFileV3 {
+    version: u32,
+    header: SaveHeader,
+    grid: GameGrid,
+    spigots: LegacySpigots,
+    rainbow_times: HashMap<usize, u32>,
+    elements_rle: Vec<(u8, u16)>,
+}
+
+impl From<WorldSaveFileV3> for WorldSaveFile {
+    fn from(save: WorldSaveFileV3) -> Self {
+        Self {
+            version: save.version,
+            header: save.header,
+            grid: save.grid,
+            spigots: save.spigots.into(),
+            rainbow_times: save.rainbow_times,
+            elements_rle: save.elements_rle,
+            active_branches_count: 0,
+            particle_list_active: Vec::new(),
+        }
+    }
+}
+
+/// Version 2 layout, before the elements were run-length encoded
+#[derive(serde::Deserialize)]
+struct WorldSaveFileV2 {
+    version: u32,
+    header: SaveHeader,
+    grid: GameGrid,
+    spigots: LegacySpigots,
+    rainbow_times: HashMap<usize, u32>,
+}
+
+impl From<WorldSaveFileV2> for WorldSaveFile {
+    fn from(save: WorldSaveFileV2) -> Self {
+        Self {
+            version: save.version,
+            header: save.header,
+            grid: save.grid,
+            spigots: save.spigots.into(),
+            rainbow_times: save.rainbow_times,
+            elements_rle: Vec::new(),
+            active_branches_count: 0,
+            particle_list_active: Vec::new(),
+        }
+    }
+}
+
+impl WorldSaveFile {
+    /// Build a save of the current world, stamped with the current time
+    pub fn new(grid: &GameGrid, spigots: &Spigots, rainbow_times: &HashMap<usize, u32>) -> Self {
+        let timestamp_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs() as i64);
+        Self {
+            version: SAVE_VERSION,
+            header: SaveHeader::for_grid(grid, timestamp_secs),
+            grid: grid.clone(),
+            spigots: spigots.clone(),
+            rainbow_times: rainbow_times.clone(),
+            elements_rle: Vec::new(),
+            active_branches_count: 0,
+            particle_list_active: Vec::new(),
+        }
+    }
+
+    /// Record the growing tree branches and active particles alongside the grid
+    pub fn with_particles(mut self, active_branches: &ActiveTreeBranches, particle_list: &ParticleList) -> Self {
+        self.active_branches_count = active_branches.branches.len();
+        self.particle_list_active = particle_list
+            .active_particles()
+            .map(|index| {
+                let particle = &particle_list.particles[index];
+                (particle.particle_type, particle.x, particle.y)
+            })
+            .collect();
+        self
+    }
+
+    /// Serialize the save in the current format, with the elements run-length encoded
+    ///
+    /// # Errors
+    /// Returns an error if bincode fails to serialize the save.
+    pub fn to_bytes(&self) -> bincode::Result<Vec<u8>> {
+        let mut grid = self.grid.clone();
+        let elements_rle = rle_encode(&std::mem::take(&mut grid.elements));
+        bincode::serialize(&Self {
+            version: SAVE_VERSION,
+            header: self.header.clone(),
+            grid,
+            spigots: self.spigots.clone(),
+            rainbow_times: self.rainbow_times.clone(),
+            elements_rle,
+            active_branches_count: self.active_branches_count,
+            particle_list_active: self.particle_list_active.clone(),
+        })
+    }
+
+    /// Deserialize a save of any known version, migrating it to the current format
+    ///
+    /// # Errors
+    /// Returns an error if the data is not a valid save of any version.
+    pub fn from_bytes(data: &[u8]) -> bincode::Result<Self> {
+        let version = data
+            .get(..4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+        let mut save = match version {
+            Some(SAVE_VERSION) => bincode::deserialize::<Self>(data)?,
+            Some(6) => bincode::deserialize::<WorldSaveFileV4<SpigotsV6>>(data)?.into(),
+            Some(5) => bincode::deserialize::<WorldSaveFileV4<SpigotsV5>>(data)?.into(),
+            Some(4) => bincode::deserialize::<WorldSaveFileV4<LegacySpigots>>(data)?.into(),
+            Some(3) => bincode::deserialize::<WorldSaveFileV3>(data)?.into(),
+            Some(2) => bincode::deserialize::<WorldSaveFileV2>(data)?.into(),
+            // Version 1 has no tag, its first bytes are the length of the element list
+            _ => migrate_v1_to_v2(data)?,
+        };
+        if !save.elements_rle.is_empty() {
+            save.grid.elements = rle_decode(&std::mem::take(&mut save.elements_rle));
+        }
+        save.version = SAVE_VERSION;
+        // Velocities, staleness and damage aren't saved, start the loaded grid at rest, awake and undamaged
+        save.grid.velocities = vec![Vec2::ZERO; save.grid.elements.len()];
+        save.grid.staleness = vec![0; save.grid.elements.len()];
+        save.grid.durability = save.grid.elements.iter().map(|&element| element_default_durability(element)).collect();
+        save.grid.mark_all_dirty();
+        Ok(save)
+    }
+}
+
+/// Read just the header of a save file, without loading the grid
+/// Returns None for version 1 saves (no header) and files that aren't saves
+pub fn read_header(path: &std::path::Path) -> Option<SaveHeader> {
+    use std::io::Read;
+    // Version tag, then width, height, timestamp and element count
+    let mut prefix = [0u8; 24];
+    std::fs::File::open(path).ok()?.read_exact(&mut prefix).ok()?;
+    let version = u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]);
+    if !(2..=SAVE_VERSION).contains(&version) {
+        return None;
+    }
+    bincode::deserialize(&prefix[4..]).ok()
+}
+
+/// Version 1 layout, a bare grid of elements
+/// Kept separate from `GameGrid` so fields added to the grid don't change how version 1 saves are read
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LegacyGameGridV1 {
+    pub elements: Vec<Element>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Upgrade a version 1 save (a bare `LegacyGameGridV1`) with default spigots and no `RainbowSand` colors
+/// The result is returned in the current `WorldSaveFile` layout
+///
+/// # Errors
+/// Returns an error if the data is not a valid version 1 save.
+pub fn migrate_v1_to_v2(data: &[u8]) -> bincode::Result<WorldSaveFile> {
+    let legacy = bincode::deserialize::<LegacyGameGridV1>(data)?;
+    let mut grid = GameGrid::new(legacy.width, legacy.height);
+    if legacy.elements.len() != grid.elements.len() {
+        return Err(Box::new(bincode::ErrorKind::Custom(format!(
+            "{} elements for a {}x{} grid",
+            legacy.elements.len(),
+            legacy.width,
+            legacy.height
+        ))));
+    }
+    // Through set_index so every cell starts with its element's temperature and durability
+    for (i, element) in legacy.elements.into_iter().enumerate() {
+        grid.set_index(i, element);
+    }
+    Ok(WorldSaveFile {
+        version: SAVE_VERSION,
+        header: SaveHeader::for_grid(&grid, 0),
+        grid,
+        spigots: Spigots::default(),
+        rainbow_times: HashMap::new(),
+        elements_rle: Vec::new(),
+        active_branches_count: 0,
+        particle_list_active: Vec::new(),
+    })
+}
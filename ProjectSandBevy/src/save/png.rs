@@ -0,0 +1,93 @@
+use crate::elements::Element;
+use crate::simulation::GameGrid;
+use crate::systems::RainbowSandPlacementTimes;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Save the grid as a PNG with one pixel per cell (see `grid_to_image`)
+///
+/// # Errors
+/// Returns an error if the image cannot be encoded or written.
+pub fn save_as_png(grid: &GameGrid, rainbow_times: &RainbowSandPlacementTimes, path: &Path) -> std::io::Result<()> {
+    grid_to_image(grid, rainbow_times).save(path).map_err(std::io::Error::other)
+}
+
+/// Encode the grid as PNG bytes with one pixel per cell (see `grid_to_image`)
+///
+/// # Errors
+/// Returns an error if the image cannot be encoded.
+pub fn encode_png(grid: &GameGrid, rainbow_times: &RainbowSandPlacementTimes) -> image::ImageResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    grid_to_image(grid, rainbow_times).write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+/// One pixel per cell, colored with the element encoding (see `Element::to_encoded_color_with_shift`)
+/// so the image looks like the game
+fn grid_to_image(grid: &GameGrid, rainbow_times: &RainbowSandPlacementTimes) -> image::RgbaImage {
+    let mut image = image::RgbaImage::new(grid.width, grid.height);
+    for (i, &element) in grid.elements.iter().enumerate() {
+        // RainbowSand keeps its color through the shift, other elements have none
+        let shift = if element == Element::RainbowSand {
+            rainbow_times.0.get(&i).map_or(0, |&time| u8::try_from(time % 256).unwrap_or_default())
+        } else {
+            0
+        };
+        let color = element.to_encoded_color_with_shift(shift);
+        let (x, y) = grid.index_to_xy(i);
+        image.put_pixel(x, y, image::Rgba([to_byte(color.red), to_byte(color.green), to_byte(color.blue), 255]));
+    }
+    image
+}
+
+/// Load a grid saved with `save_as_png`, along with the `RainbowSand` placement times recovered from the hue
+///
+/// # Errors
+/// Returns an error if the file cannot be read or decoded as an image.
+pub fn load_from_png(path: &Path) -> image::ImageResult<(GameGrid, HashMap<usize, u32>)> {
+    let image = image::open(path)?.to_rgba8();
+    let mut grid = GameGrid::new(image.width(), image.height());
+    let mut rainbow_times = HashMap::new();
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let [r, g, b, _] = pixel.0;
+        // Sample the middle of each byte so from_encoded_color truncates back to the same value
+        let color = LinearRgba::rgb(to_channel(r), to_channel(g), to_channel(b));
+        let element = Element::from_encoded_color(color);
+        let i = grid.xy_to_index(x, y);
+        grid.set_index(i, element);
+        if element == Element::RainbowSand {
+            rainbow_times.insert(i, u32::from(rainbow_shift(r, g, b)));
+        }
+    }
+    grid.mark_all_dirty();
+    Ok((grid, rainbow_times))
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to the u8 range first
+fn to_byte(channel: f32) -> u8 {
+    (channel * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+fn to_channel(byte: u8) -> f32 {
+    (f32::from(byte) + 0.5) / 255.0
+}
+
+/// Recover the `RainbowSand` shift (0-255 around the hue circle) from its color
+fn rainbow_shift(r: u8, g: u8, b: u8) -> u8 {
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    if delta == 0 {
+        return 0;
+    }
+    let delta = f32::from(delta);
+    let (red, green, blue) = (f32::from(r), f32::from(g), f32::from(b));
+    let hue = if max == r {
+        60.0 * ((green - blue) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((blue - red) / delta + 2.0)
+    } else {
+        60.0 * ((red - green) / delta + 4.0)
+    };
+    to_byte(hue / 360.0)
+}
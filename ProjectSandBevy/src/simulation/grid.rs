@@ -1,12 +1,65 @@
-use crate::elements::Element;
+use super::physics::ShockwaveEvent;
+use crate::elements::{element_base_temp, element_default_durability, Element, AMBIENT_TEMPERATURE};
 use crate::SIZE;
 use bevy::prelude::*;
+use rand::Rng;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+/// Cells that go this many ticks without changing are skipped by the simulation loop
+pub const SETTLE_THRESHOLD: u8 = 8;
 
 /// The game grid stores element data in a flat array
 /// Index calculation: i = y * width + x
-#[derive(Resource, serde::Serialize, serde::Deserialize)]
+#[derive(Resource, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GameGrid {
     pub elements: Vec<Element>,
+    /// Age in ticks of each Virus cell (parallel to `elements`)
+    pub virus_age: Vec<u16>,
+    /// How many Quicksand cells each element has sunk through (parallel to `elements`)
+    pub trapped_depth: Vec<u8>,
+    /// Ticks each Slime cell has been touching fire (parallel to `elements`)
+    pub slime_fire_immunity: Vec<u8>,
+    /// Temperature of each cell in °C (parallel to `elements`)
+    pub temperatures: Vec<f32>,
+    /// Ticks since each cell last changed element, saturating at u16::MAX (parallel to `elements`)
+    pub ages: Vec<u16>,
+    /// Damage each cell can still take before it is destroyed (parallel to `elements`)
+    /// Moves with the element when it falls or flows, resets to the element's default when the cell changes element (see `element_default_durability`)
+    /// Not saved: damage is forgotten on load
+    #[serde(skip)]
+    pub durability: Vec<u8>,
+    /// Momentum of each cell in cells per tick, +y is down (parallel to `elements`)
+    /// Not saved: momentum is transient and reset on load
+    #[serde(skip)]
+    pub velocities: Vec<Vec2>,
+    /// Shockwaves emitted by explosions this frame, drained into Bevy messages by the simulation system
+    #[serde(skip)]
+    pub pending_shockwaves: Vec<ShockwaveEvent>,
+    /// Ticks each cell has gone without changing, saturating at 255 (parallel to `elements`)
+    /// Not saved: everything starts awake after a load
+    #[serde(skip)]
+    pub staleness: Vec<u8>,
+    /// Number of element writes that changed a cell, used to detect idle ticks
+    #[serde(skip)]
+    pub write_count: u64,
+    /// Bounding box (min_x, min_y, max_x, max_y) of cells changed since the last render
+    /// Not saved: a loaded grid is redrawn in full
+    #[serde(skip)]
+    pub dirty_rect: Option<(u32, u32, u32, u32)>,
+    /// Planned route of each Creature, keyed by the cell it is in
+    /// Not saved: creatures plan a new route after a load
+    #[serde(skip)]
+    pub creature_paths: HashMap<usize, CreaturePath>,
+    /// AntiGravity cells placed since the last frame, each starts an `AntiGravitySources` entry
+    #[serde(skip)]
+    pub placed_anti_gravity: Vec<usize>,
+    /// Circles (x, y, radius) where gravity points up, copied from `AntiGravitySources` every frame
+    #[serde(skip)]
+    pub anti_gravity_zones: Vec<(u32, u32, u32)>,
+    /// Each linked portal mapped to its pair, copied from `PortalRegistry` every frame
+    #[serde(skip)]
+    pub portal_links: HashMap<usize, usize>,
     pub width: u32,
     pub height: u32,
 }
@@ -17,10 +70,55 @@ impl GameGrid {
         for element in &mut self.elements {
             *element = Element::Background;
         }
+        for age in &mut self.virus_age {
+            *age = 0;
+        }
+        for depth in &mut self.trapped_depth {
+            *depth = 0;
+        }
+        for ticks in &mut self.slime_fire_immunity {
+            *ticks = 0;
+        }
+        for age in &mut self.ages {
+            *age = 0;
+        }
+        for temperature in &mut self.temperatures {
+            *temperature = AMBIENT_TEMPERATURE;
+        }
+        for durability in &mut self.durability {
+            *durability = element_default_durability(Element::Background);
+        }
+        for velocity in &mut self.velocities {
+            *velocity = Vec2::ZERO;
+        }
+        self.pending_shockwaves.clear();
+        self.creature_paths.clear();
+        self.placed_anti_gravity.clear();
+        self.anti_gravity_zones.clear();
+        self.portal_links.clear();
+        for staleness in &mut self.staleness {
+            *staleness = 0;
+        }
+        self.mark_all_dirty();
     }
     pub fn new(width: u32, height: u32) -> Self {
         Self {
             elements: vec![Element::Background; (width * height) as usize],
+            virus_age: vec![0; (width * height) as usize],
+            trapped_depth: vec![0; (width * height) as usize],
+            slime_fire_immunity: vec![0; (width * height) as usize],
+            temperatures: vec![AMBIENT_TEMPERATURE; (width * height) as usize],
+            ages: vec![0; (width * height) as usize],
+            durability: vec![element_default_durability(Element::Background); (width * height) as usize],
+            velocities: vec![Vec2::ZERO; (width * height) as usize],
+            pending_shockwaves: Vec::new(),
+            staleness: vec![0; (width * height) as usize],
+            write_count: 0,
+            dirty_rect: None,
+            creature_paths: HashMap::new(),
+            placed_anti_gravity: Vec::new(),
+            anti_gravity_zones: Vec::new(),
+            portal_links: HashMap::new(),
             width,
             height,
         }
@@ -41,23 +139,130 @@ impl GameGrid {
             return;
         }
         let idx = (y * self.width + x) as usize;
-        self.elements[idx] = element;
+        self.set_index(idx, element);
     }
 
     /// Get element at index i
+    /// Out of bounds reads as Background in release and panics in debug builds
     pub fn get_index(&self, i: usize) -> Element {
+        #[cfg(debug_assertions)]
+        {
+            assert!(i < self.elements.len(), "get_index OOB: {} >= {}", i, self.elements.len());
+        }
         if i >= self.elements.len() {
             return Element::Background;
         }
         self.elements[i]
     }
 
+    /// Get element at index i, or None if it is out of bounds
+    pub fn get_index_checked(&self, i: usize) -> Option<Element> {
+        self.elements.get(i).copied()
+    }
+
     /// Set element at index i
+    /// Out of bounds writes are ignored in release and panic in debug builds
     pub fn set_index(&mut self, i: usize, element: Element) {
-        if i >= self.elements.len() {
+        #[cfg(debug_assertions)]
+        {
+            assert!(i < self.elements.len(), "set_index OOB: {} >= {}", i, self.elements.len());
+        }
+        if i >= self.elements.len() || self.elements[i] == element {
             return;
         }
         self.elements[i] = element;
+        self.ages[i] = 0;
+        self.durability[i] = element_default_durability(element);
+        self.temperatures[i] = element_base_temp(element);
+        self.write_count += 1;
+        if element == Element::AntiGravity {
+            self.placed_anti_gravity.push(i);
+        }
+        self.reset_staleness_around(i);
+        let (x, y) = self.index_to_xy(i);
+        self.mark_dirty(x, y);
+    }
+
+    /// Swap cells a and b along with all of their per-cell state (temperature, age, durability, ...)
+    /// Used for movement, where `set_index` would reset that state as if the element were new
+    pub fn swap_cells(&mut self, a: usize, b: usize) {
+        self.elements.swap(a, b);
+        self.virus_age.swap(a, b);
+        self.trapped_depth.swap(a, b);
+        self.slime_fire_immunity.swap(a, b);
+        self.temperatures.swap(a, b);
+        self.ages.swap(a, b);
+        self.durability.swap(a, b);
+        self.velocities.swap(a, b);
+        if self.elements[a] == self.elements[b] {
+            return;
+        }
+        self.write_count += 1;
+        for i in [a, b] {
+            self.reset_staleness_around(i);
+            let (x, y) = self.index_to_xy(i);
+            self.mark_dirty(x, y);
+        }
+    }
+
+    /// Whether (x, y) is inside an anti-gravity zone, where things fall upward
+    pub fn gravity_inverted(&self, x: u32, y: u32) -> bool {
+        self.anti_gravity_zones.iter().any(|&(zone_x, zone_y, radius)| {
+            let dx = i64::from(x) - i64::from(zone_x);
+            let dy = i64::from(y) - i64::from(zone_y);
+            dx * dx + dy * dy <= i64::from(radius) * i64::from(radius)
+        })
+    }
+
+    /// Wear down the cell at index i by `amount`
+    /// Returns true once its durability is used up and the cell should be destroyed
+    pub fn damage(&mut self, i: usize, amount: u8) -> bool {
+        let Some(durability) = self.durability.get_mut(i) else {
+            return false;
+        };
+        *durability = durability.saturating_sub(amount);
+        *durability == 0
+    }
+
+    /// Grow the dirty rect to include (x, y) so the renderer redraws it
+    pub fn mark_dirty(&mut self, x: u32, y: u32) {
+        self.dirty_rect = Some(match self.dirty_rect {
+            Some((min_x, min_y, max_x, max_y)) => (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)),
+            None => (x, y, x, y),
+        });
+    }
+
+    /// Mark the whole grid as needing a redraw
+    pub fn mark_all_dirty(&mut self) {
+        if self.width > 0 && self.height > 0 {
+            self.dirty_rect = Some((0, 0, self.max_x(), self.max_y()));
+        }
+    }
+
+    /// Wake a cell and its 8 neighbors so the simulation processes them again
+    pub fn reset_staleness_around(&mut self, i: usize) {
+        let (x, y) = self.index_to_xy(i);
+        for ny in y.saturating_sub(1)..=(y + 1).min(self.max_y()) {
+            for nx in x.saturating_sub(1)..=(x + 1).min(self.max_x()) {
+                let idx = self.xy_to_index(nx, ny);
+                self.staleness[idx] = 0;
+            }
+        }
+    }
+
+    /// Wake every cell within `radius` of (x, y), for changes that affect a whole area at once
+    pub fn reset_staleness_in_radius(&mut self, x: u32, y: u32, radius: u32) {
+        for ny in y.saturating_sub(radius)..=(y + radius).min(self.max_y()) {
+            for nx in x.saturating_sub(radius)..=(x + radius).min(self.max_x()) {
+                let idx = self.xy_to_index(nx, ny);
+                self.staleness[idx] = 0;
+            }
+        }
+    }
+
+    /// Check if a cell has been idle long enough to be skipped
+    pub fn is_settled(&self, i: usize) -> bool {
+        self.staleness[i] >= SETTLE_THRESHOLD && self.elements[i].can_settle()
     }
 
     /// Convert index to (x, y)
@@ -86,6 +291,355 @@ impl GameGrid {
     pub fn max_y(&self) -> u32 {
         self.height.saturating_sub(1)
     }
+
+    /// Size of the region starting at (x, y) once clamped to the grid
+    pub fn clamp_region(&self, x: u32, y: u32, w: u32, h: u32) -> (u32, u32) {
+        (w.min(self.width.saturating_sub(x)), h.min(self.height.saturating_sub(y)))
+    }
+
+    /// Copy a region of elements in row order, clamped to the grid (see `clamp_region`)
+    pub fn copy_region(&self, x: u32, y: u32, w: u32, h: u32) -> Vec<Element> {
+        let (w, h) = self.clamp_region(x, y, w, h);
+        let mut elements = Vec::with_capacity((w * h) as usize);
+        for ry in y..y + h {
+            let start = self.xy_to_index(x, ry);
+            elements.extend_from_slice(&self.elements[start..start + w as usize]);
+        }
+        elements
+    }
+
+    /// Paste a clipboard with its top left corner at (x, y), cells past the grid edges are dropped
+    pub fn paste_region(&mut self, x: u32, y: u32, clipboard: &Clipboard) {
+        let (w, h) = self.clamp_region(x, y, clipboard.width, clipboard.height);
+        for ry in 0..h {
+            for rx in 0..w {
+                let element = clipboard.elements[(ry * clipboard.width + rx) as usize];
+                self.set(x + rx, y + ry, element);
+            }
+        }
+    }
+
+    /// Scatter `element` over the region starting at (x0, y0), each cell placed with probability `density`.
+    /// Without `overwrite` only Background cells are filled. The region is clamped to the grid (see `clamp_region`).
+    /// Returns the indices that were set, so callers can update per-cell state such as RainbowSand placement times.
+    pub fn apply_noise(
+        &mut self,
+        x0: u32,
+        y0: u32,
+        w: u32,
+        h: u32,
+        element: Element,
+        density: f32,
+        overwrite: bool,
+        rng: &mut impl Rng,
+    ) -> Vec<usize> {
+        let (w, h) = self.clamp_region(x0, y0, w, h);
+        let density = f64::from(density.clamp(0.0, 1.0));
+        let mut placed = Vec::new();
+        for y in y0..y0 + h {
+            for x in x0..x0 + w {
+                if rng.gen_bool(density) && (overwrite || self.get(x, y) == Element::Background) {
+                    self.set(x, y, element);
+                    placed.push(self.xy_to_index(x, y));
+                }
+            }
+        }
+        placed
+    }
+
+    /// Replace the 4-connected area of `target` cells containing (x, y) with `replacement`.
+    /// Returns the indices that were filled, empty if (x, y) is not `target`.
+    pub fn flood_fill(&mut self, x: u32, y: u32, target: Element, replacement: Element) -> Vec<usize> {
+        let mut filled = Vec::new();
+        if target == replacement || x >= self.width || y >= self.height || self.get(x, y) != target {
+            return filled;
+        }
+
+        let mut queue = VecDeque::from([(x, y)]);
+        self.set(x, y, replacement);
+        while let Some((cx, cy)) = queue.pop_front() {
+            filled.push(self.xy_to_index(cx, cy));
+            let neighbors = [
+                (cx.checked_sub(1), Some(cy)),
+                ((cx + 1 < self.width).then_some(cx + 1), Some(cy)),
+                (Some(cx), cy.checked_sub(1)),
+                (Some(cx), (cy + 1 < self.height).then_some(cy + 1)),
+            ];
+            for (nx, ny) in neighbors {
+                if let (Some(nx), Some(ny)) = (nx, ny)
+                    && self.get(nx, ny) == target
+                {
+                    // Set on enqueue so each cell is only queued once
+                    self.set(nx, ny, replacement);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        filled
+    }
+
+    /// Group the cells of `element` into 4-connected clusters.
+    /// Each component is a sorted list of cell indices, components are ordered by their first index.
+    pub fn connected_components(&self, element: Element) -> Vec<Vec<usize>> {
+        let mut components = Vec::new();
+        let mut visited = vec![false; self.elements.len()];
+        let mut queue = VecDeque::new();
+        for start in 0..self.elements.len() {
+            if visited[start] || self.elements[start] != element {
+                continue;
+            }
+            visited[start] = true;
+            queue.push_back(start);
+            let mut component = Vec::new();
+            while let Some(i) = queue.pop_front() {
+                component.push(i);
+                for n in self.neighbors_4(i).into_iter().flatten() {
+                    if !visited[n] && self.elements[n] == element {
+                        visited[n] = true;
+                        queue.push_back(n);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// Shortest 4-connected route from `start` to `goal` with A*, stepping only onto `traversable` cells.
+    /// The start cell can be anything, the goal must be traversable.
+    /// Returns every cell from `start` to `goal` inclusive, None if the goal can't be reached.
+    pub fn path_find(&self, start: (u32, u32), goal: (u32, u32), traversable: &[Element]) -> Option<Vec<(u32, u32)>> {
+        if !self.is_valid(start.0, start.1) || !self.is_valid(goal.0, goal.1) {
+            return None;
+        }
+        let start_i = self.xy_to_index(start.0, start.1);
+        let goal_i = self.xy_to_index(goal.0, goal.1);
+        if start_i == goal_i {
+            return Some(vec![start]);
+        }
+        if !traversable.contains(&self.elements[goal_i]) {
+            return None;
+        }
+
+        // Manhattan distance, exact on an open grid so A* never overestimates
+        let heuristic = |i: usize| {
+            let (x, y) = self.index_to_xy(i);
+            x.abs_diff(goal.0) + y.abs_diff(goal.1)
+        };
+        let mut cost = vec![u32::MAX; self.elements.len()];
+        let mut came_from = vec![usize::MAX; self.elements.len()];
+        let mut open: BinaryHeap<(Reverse<u32>, usize)> = BinaryHeap::new();
+        cost[start_i] = 0;
+        open.push((Reverse(heuristic(start_i)), start_i));
+
+        while let Some((Reverse(estimate), i)) = open.pop() {
+            if i == goal_i {
+                let mut path = vec![goal];
+                let mut current = goal_i;
+                while current != start_i {
+                    current = came_from[current];
+                    path.push(self.index_to_xy(current));
+                }
+                path.reverse();
+                return Some(path);
+            }
+            // A cheaper route to this cell was queued after this entry
+            if estimate > cost[i] + heuristic(i) {
+                continue;
+            }
+            for n in self.neighbors_4(i).into_iter().flatten() {
+                let next_cost = cost[i] + 1;
+                if next_cost < cost[n] && traversable.contains(&self.elements[n]) {
+                    cost[n] = next_cost;
+                    came_from[n] = i;
+                    open.push((Reverse(next_cost + heuristic(n)), n));
+                }
+            }
+        }
+        None
+    }
+
+    /// Indices of the cells left, right, above and below i, None past the grid edges
+    fn neighbors_4(&self, i: usize) -> [Option<usize>; 4] {
+        let (x, y) = self.index_to_xy(i);
+        [
+            (x > 0).then(|| i - 1),
+            (x + 1 < self.width).then_some(i + 1),
+            (y > 0).then(|| i - self.width as usize),
+            (y + 1 < self.height).then_some(i + self.width as usize),
+        ]
+    }
+
+    /// Rotate the region starting at (x, y) clockwise about its top left corner.
+    /// A quarter turn swaps the region's width and height, cells it no longer covers become Background.
+    /// RainbowSand placement times move with their cells.
+    /// Returns false, leaving the grid untouched, if the region or its rotation extends outside the grid.
+    pub fn rotate_region(
+        &mut self,
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+        rotation: Rotation,
+        rainbow_times: &mut HashMap<usize, u32>,
+    ) -> bool {
+        let (new_w, new_h) = match rotation {
+            Rotation::R180 => (w, h),
+            Rotation::R90 | Rotation::R270 => (h, w),
+        };
+        if w == 0
+            || h == 0
+            || self.clamp_region(x, y, w, h) != (w, h)
+            || self.clamp_region(x, y, new_w, new_h) != (new_w, new_h)
+        {
+            return false;
+        }
+
+        let old = self.copy_region(x, y, w, h);
+        let old_times: Vec<Option<u32>> = (0..h)
+            .flat_map(|oy| (0..w).map(move |ox| (ox, oy)))
+            .map(|(ox, oy)| rainbow_times.remove(&self.xy_to_index(x + ox, y + oy)))
+            .collect();
+
+        for oy in 0..h {
+            for ox in 0..w {
+                let (nx, ny) = match rotation {
+                    Rotation::R90 => (h - 1 - oy, ox),
+                    Rotation::R180 => (w - 1 - ox, h - 1 - oy),
+                    Rotation::R270 => (oy, w - 1 - ox),
+                };
+                let old_i = (oy * w + ox) as usize;
+                self.set(x + nx, y + ny, old[old_i]);
+                if let Some(time) = old_times[old_i] {
+                    rainbow_times.insert(self.xy_to_index(x + nx, y + ny), time);
+                }
+            }
+        }
+        // Part of the old region left uncovered by a non-square quarter turn
+        for oy in 0..h {
+            for ox in 0..w {
+                if ox >= new_w || oy >= new_h {
+                    self.set(x + ox, y + oy, Element::Background);
+                }
+            }
+        }
+        true
+    }
+
+    /// Turn every `from` cell into `to`, returning how many cells changed.
+    /// RainbowSand placement times are dropped for cells that stop being RainbowSand,
+    /// and new RainbowSand gets its color from `rainbow_position_hash`.
+    pub fn replace_element(&mut self, from: Element, to: Element, rainbow_times: &mut HashMap<usize, u32>) -> u32 {
+        if from == to {
+            return 0;
+        }
+        let mut replaced = 0;
+        for i in 0..self.elements.len() {
+            if self.elements[i] != from {
+                continue;
+            }
+            self.set_index(i, to);
+            replaced += 1;
+            if to == Element::RainbowSand {
+                let (x, y) = self.index_to_xy(i);
+                rainbow_times.insert(i, rainbow_position_hash(x, y));
+            } else {
+                rainbow_times.remove(&i);
+            }
+        }
+        replaced
+    }
+
+    /// Count every element on the grid in one pass
+    pub fn statistics(&self) -> GridStatistics {
+        let mut histogram = [0u32; 256];
+        for element in &self.elements {
+            histogram[usize::from(element.index())] += 1;
+        }
+        let background = usize::from(Element::Background.index());
+        let total_cells = self.elements.len() as u32;
+        let non_empty_cells = total_cells - histogram[background];
+
+        // Rarest and most common of the elements actually present, ignoring Background
+        let present = histogram
+            .iter()
+            .enumerate()
+            .filter(|&(index, &count)| count > 0 && index != background)
+            .map(|(index, &count)| (Element::from_index(index as u8), count));
+        let most_common = present.clone().max_by_key(|&(_, count)| count).map_or(Element::Background, |(element, _)| element);
+        let least_common = present.min_by_key(|&(_, count)| count).map(|(element, _)| element);
+
+        GridStatistics {
+            total_cells,
+            non_empty_cells,
+            histogram,
+            most_common,
+            least_common,
+            empty_pct: if total_cells == 0 { 0.0 } else { histogram[background] as f32 / total_cells as f32 * 100.0 },
+        }
+    }
+}
+
+/// Element counts for the whole grid, from `GameGrid::statistics`
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridStatistics {
+    pub total_cells: u32,
+    pub non_empty_cells: u32,
+    /// Cells of each element, indexed by `Element::index`
+    pub histogram: [u32; 256],
+    /// Most common non-background element, Background if the grid is empty
+    pub most_common: Element,
+    /// Rarest non-background element, None if the grid is empty
+    pub least_common: Option<Element>,
+    /// Percentage of cells that are Background
+    pub empty_pct: f32,
+}
+
+impl Default for GridStatistics {
+    fn default() -> Self {
+        Self {
+            total_cells: 0,
+            non_empty_cells: 0,
+            histogram: [0; 256],
+            most_common: Element::Background,
+            least_common: None,
+            empty_pct: 0.0,
+        }
+    }
+}
+
+/// Stand-in RainbowSand placement time for a cell with no recorded time, stable for a given position
+pub const fn rainbow_position_hash(x: u32, y: u32) -> u32 {
+    x.wrapping_mul(73_856_093).wrapping_add(y.wrapping_mul(19_349_663))
+}
+
+/// Clockwise turn for `GameGrid::rotate_region`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    R90,
+    R180,
+    R270,
+}
+
+/// Route a Creature is following, see `GameGrid::path_find`
+#[derive(Clone, Debug, Default)]
+pub struct CreaturePath {
+    /// Cells still to visit, the next step last
+    pub steps: Vec<(u32, u32)>,
+    /// Ticks until the route is planned again
+    pub ticks_until_replan: u32,
+}
+
+/// Elements copied from a rectangular region of the grid
+#[derive(Resource, Default, Clone)]
+pub struct Clipboard {
+    /// Row order, `width * height` entries
+    pub elements: Vec<Element>,
+    /// RainbowSand placement time of each copied cell (parallel to `elements`)
+    pub rainbow_times: Vec<Option<u32>>,
+    pub width: u32,
+    pub height: u32,
 }
 
 impl Default for GameGrid {
@@ -0,0 +1,124 @@
+use crate::elements::Element;
+use crate::simulation::{sim_rng, GameGrid, SimulationRules};
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Reaction between an element at the first index and a neighbor at the second
+pub type InteractionFn = fn(&mut GameGrid, usize, usize);
+
+/// Chance per tick of a reaction happening, read from the rules so it can be tuned
+pub type ChanceFn = fn(&SimulationRules) -> f64;
+
+/// Element pair reactions checked before `execute_element_action`'s per-element rules
+///
+/// Keyed by (element index, neighbor index), so new reactions can be added without touching the main match
+/// `Default` registers the built-in reactions between two bordering cells
+/// Reactions that reach diagonal neighbors, change more than the two cells or depend on per-cell state
+/// (Acid dissolving, Virus spreading, fire spreading, explosions) stay in the match
+#[derive(Resource)]
+pub struct ElementInteractionMatrix {
+    pub interactions: HashMap<(u8, u8), InteractionFn>,
+    /// Chances of the reactions that don't always happen, missing for the ones that do
+    pub chances: HashMap<(u8, u8), ChanceFn>,
+}
+
+/// Built-in reactions used when no matrix resource is passed in
+pub static BUILT_IN_INTERACTIONS: LazyLock<ElementInteractionMatrix> = LazyLock::new(ElementInteractionMatrix::default);
+
+impl Default for ElementInteractionMatrix {
+    fn default() -> Self {
+        let mut matrix = Self::empty();
+        // Fire touching water or salt water goes out and boils the liquid to steam
+        matrix.register_with_chance(Element::Fire, Element::Water, |rules| rules.fire_extinguish_chance, extinguish_fire);
+        matrix.register_with_chance(Element::Fire, Element::SaltWater, |rules| rules.fire_extinguish_chance, extinguish_fire);
+        // Lava touching water or salt water turns to rock and the liquid to steam
+        matrix.register(Element::Lava, Element::Water, quench_lava);
+        matrix.register(Element::Lava, Element::SaltWater, quench_lava);
+        // Chilled ice thaws immediately if bordering salt, salt water, lava, fire, or steam
+        for neighbor in [Element::Salt, Element::SaltWater, Element::Lava, Element::Fire, Element::Steam] {
+            matrix.register(Element::ChilledIce, neighbor, thaw_chilled_ice);
+        }
+        // Plant touching salt dies
+        matrix.register_with_chance(Element::Plant, Element::Salt, |rules| rules.plant_salt_death_chance, kill_plant);
+        matrix
+    }
+}
+
+impl ElementInteractionMatrix {
+    /// A matrix with no reactions at all, not even the built-in ones
+    #[must_use]
+    pub fn empty() -> Self {
+        Self { interactions: HashMap::new(), chances: HashMap::new() }
+    }
+
+    /// Add (or replace) the reaction when `element` borders `neighbor`, happening every tick
+    pub fn register(&mut self, element: Element, neighbor: Element, interaction: InteractionFn) {
+        let key = (element.index(), neighbor.index());
+        self.interactions.insert(key, interaction);
+        self.chances.remove(&key);
+    }
+
+    /// Add (or replace) the reaction when `element` borders `neighbor`, happening with the rules' chance
+    pub fn register_with_chance(&mut self, element: Element, neighbor: Element, chance: ChanceFn, interaction: InteractionFn) {
+        let key = (element.index(), neighbor.index());
+        self.interactions.insert(key, interaction);
+        self.chances.insert(key, chance);
+    }
+
+    /// Run the reactions for the element at `i` against its 4 cardinal neighbors
+    /// Neighbors are checked below, left, right, then above, and it stops once the element at `i` has changed
+    /// A chance is rolled once per pair, so bordering several of the same neighbor doesn't raise it
+    pub fn apply(&self, grid: &mut GameGrid, x: u32, y: u32, i: usize, rules: &SimulationRules) {
+        if self.interactions.is_empty() {
+            return;
+        }
+        let element = grid.get_index(i);
+        let width = grid.width as usize;
+        let neighbors = [
+            (y < grid.max_y()).then(|| i + width),
+            (x > 0).then(|| i - 1),
+            (x < grid.max_x()).then(|| i + 1),
+            (y > 0).then(|| i - width),
+        ];
+        let mut rolled = Vec::new();
+        for neighbor in neighbors.into_iter().flatten() {
+            if grid.get_index(i) != element {
+                return;
+            }
+            let key = (element.index(), grid.get_index(neighbor).index());
+            let Some(interaction) = self.interactions.get(&key) else {
+                continue;
+            };
+            if let Some(chance) = self.chances.get(&key) {
+                if rolled.contains(&key) {
+                    continue;
+                }
+                rolled.push(key);
+                if !sim_rng().gen_bool(chance(rules).clamp(0.0, 1.0)) {
+                    continue;
+                }
+            }
+            interaction(grid, i, neighbor);
+        }
+    }
+}
+
+fn quench_lava(grid: &mut GameGrid, i: usize, neighbor: usize) {
+    grid.set_index(neighbor, Element::Steam);
+    grid.set_index(i, Element::Rock);
+}
+
+fn thaw_chilled_ice(grid: &mut GameGrid, i: usize, _neighbor: usize) {
+    grid.set_index(i, Element::Ice);
+}
+
+fn extinguish_fire(grid: &mut GameGrid, i: usize, neighbor: usize) {
+    grid.set_index(neighbor, Element::Steam);
+    grid.set_index(i, Element::Background);
+}
+
+fn kill_plant(grid: &mut GameGrid, i: usize, _neighbor: usize) {
+    grid.set_index(i, Element::Background);
+}
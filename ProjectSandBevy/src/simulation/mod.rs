@@ -1,7 +1,11 @@
 pub mod physics;
 pub mod grid;
+pub mod interactions;
+pub mod rules;
 
-pub use grid::GameGrid;
+pub use grid::{rainbow_position_hash, Clipboard, CreaturePath, GameGrid, GridStatistics, Rotation, SETTLE_THRESHOLD};
 pub use physics::*;
-pub use physics::{ActiveTreeBranches, TreeBranch};
+pub use interactions::{ElementInteractionMatrix, InteractionFn};
+pub use rules::{SimulationRules, RULES_CONFIG_PATH};
+pub use physics::{ActiveTreeBranches, EmitterConfig, EmitterConfigs, PendingPortal, PortalRegistry, ShockwaveEvent, TreeBranch, WindField};
 
@@ -1,8 +1,58 @@
-use crate::elements::Element;
+use crate::elements::{element_base_temp, element_conductivity, Element};
 use crate::simulation::grid::GameGrid;
-use crate::particles::ParticleList;
+use crate::simulation::rules::SimulationRules;
+use crate::simulation::interactions::{ElementInteractionMatrix, BUILT_IN_INTERACTIONS};
+use crate::particles::{ParticleList, ParticleType};
 use bevy::prelude::*;
-use rand::Rng;
+use rand::rngs::SmallRng;
+use rand::{Rng, RngCore, SeedableRng};
+use std::cell::RefCell;
+
+thread_local! {
+    /// Seeded RNG used by the simulation while deterministic mode is on (see `seed_simulation_rng`)
+    static SEEDED_RNG: RefCell<Option<SmallRng>> = const { RefCell::new(None) };
+}
+
+/// Seed the simulation RNG for the next frame, or go back to `thread_rng` with `None`
+pub fn seed_simulation_rng(seed: Option<u64>) {
+    SEEDED_RNG.with_borrow_mut(|rng| *rng = seed.map(SmallRng::seed_from_u64));
+}
+
+/// Seed for a single simulation frame, derived from the user's seed
+pub const fn frame_seed(seed: u64, frame: u64) -> u64 {
+    seed.wrapping_add(frame).wrapping_mul(6_364_136_223_846_793_005)
+}
+
+/// Handle to the simulation RNG: the seeded RNG when one is set, otherwise `thread_rng`
+/// Like `ThreadRng` it only borrows the generator per call, so it can be held across nested helpers
+pub struct SimulationRng;
+
+impl RngCore for SimulationRng {
+    fn next_u32(&mut self) -> u32 {
+        SEEDED_RNG.with_borrow_mut(|rng| rng.as_mut().map_or_else(|| rand::thread_rng().next_u32(), RngCore::next_u32))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        SEEDED_RNG.with_borrow_mut(|rng| rng.as_mut().map_or_else(|| rand::thread_rng().next_u64(), RngCore::next_u64))
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        SEEDED_RNG.with_borrow_mut(|rng| match rng.as_mut() {
+            Some(rng) => rng.fill_bytes(dest),
+            None => rand::thread_rng().fill_bytes(dest),
+        });
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// RNG for anything that should follow the simulation seed
+pub const fn sim_rng() -> SimulationRng {
+    SimulationRng
+}
 
 /// Helper functions for physics simulation, ported from TypeScript
 
@@ -10,7 +60,7 @@ use rand::Rng;
 fn pick_rand_valid(a: Option<usize>, b: Option<usize>) -> Option<usize> {
     match (a, b) {
         (Some(a_val), Some(b_val)) => {
-            if rand::thread_rng().gen_bool(0.5) {
+            if sim_rng().gen_bool(0.5) {
                 Some(a_val)
             } else {
                 Some(b_val)
@@ -22,119 +72,332 @@ fn pick_rand_valid(a: Option<usize>, b: Option<usize>) -> Option<usize> {
     }
 }
 
+/// Index of the cell offset by (dx, dy) from (x, y), wrapping around the grid edges
+/// Used by the neighbor checks when world wrapping is on (bottom connects to top, left to right)
+pub fn wrap_index(i: usize, x: u32, y: u32, dx: i32, dy: i32, width: u32, height: u32) -> usize {
+    let nx = x as i32 + dx;
+    let ny = y as i32 + dy;
+    if nx >= 0 && ny >= 0 && nx < width as i32 && ny < height as i32 {
+        // Still on the grid, no wrapping needed
+        return (i as isize + dy as isize * width as isize + dx as isize) as usize;
+    }
+    let wrapped_x = nx.rem_euclid(width as i32) as u32;
+    let wrapped_y = ny.rem_euclid(height as i32) as u32;
+    (wrapped_y * width + wrapped_x) as usize
+}
+
+/// Index of the neighbor at (dx, dy), or None if it is off the grid and wrapping is off
+fn neighbor_index(grid: &GameGrid, x: u32, y: u32, i: usize, dx: i32, dy: i32, world_wrap: bool) -> Option<usize> {
+    let nx = x as i32 + dx;
+    let ny = y as i32 + dy;
+    if !world_wrap && (nx < 0 || ny < 0 || nx > grid.max_x() as i32 || ny > grid.max_y() as i32) {
+        return None;
+    }
+    Some(wrap_index(i, x, y, dx, dy, grid.width, grid.height))
+}
+
 /// Check pixel immediately below
-fn below(grid: &GameGrid, y: u32, i: usize, target: Element) -> Option<usize> {
+fn below(grid: &GameGrid, y: u32, i: usize, target: Element, world_wrap: bool) -> Option<usize> {
+    let x = (i % grid.width as usize) as u32;
+    neighbor_index(grid, x, y, i, 0, 1, world_wrap).filter(|&idx| grid.get_index(idx) == target)
+}
+
+/// Check pixel below and the 2 diagonally below
+/// Offsets go through `neighbor_index`, so the diagonals never wrap into the next or previous row
+pub fn below_adjacent(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element, world_wrap: bool) -> Option<usize> {
+    // Check directly below, then below-left, then below-right
+    [0, -1, 1]
+        .into_iter()
+        .filter_map(|dx| neighbor_index(grid, x, y, i, dx, 1, world_wrap))
+        .find(|&idx| grid.get_index(idx) == target)
+}
+
+/// Check pixel immediately above
+fn above(grid: &GameGrid, y: u32, i: usize, target: Element, world_wrap: bool) -> Option<usize> {
+    let x = (i % grid.width as usize) as u32;
+    neighbor_index(grid, x, y, i, 0, -1, world_wrap).filter(|&idx| grid.get_index(idx) == target)
+}
+
+/// Check pixel above and the 2 diagonally above
+/// Offsets go through `neighbor_index`, so the diagonals never wrap into the next or previous row
+pub fn above_adjacent(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element, world_wrap: bool) -> Option<usize> {
+    // Check directly above, then above-left, then above-right
+    [0, -1, 1]
+        .into_iter()
+        .filter_map(|dx| neighbor_index(grid, x, y, i, dx, -1, world_wrap))
+        .find(|&idx| grid.get_index(idx) == target)
+}
+
+/// Check left and right adjacent pixels
+fn adjacent(grid: &GameGrid, x: u32, i: usize, target: Element, world_wrap: bool) -> Option<usize> {
+    let y = (i / grid.width as usize) as u32;
+    let left_match = neighbor_index(grid, x, y, i, -1, 0, world_wrap).filter(|&idx| grid.get_index(idx) == target);
+    let right_match = neighbor_index(grid, x, y, i, 1, 0, world_wrap).filter(|&idx| grid.get_index(idx) == target);
+    
+    pick_rand_valid(left_match, right_match)
+}
+
+/// Check pixel immediately below for an element matching the predicate
+fn below_matching(grid: &GameGrid, y: u32, i: usize, matches: impl Fn(Element) -> bool) -> Option<usize> {
     if y >= grid.max_y() {
         return None;
     }
     let below_idx = i + grid.width as usize;
-    if below_idx < grid.elements.len() && grid.get_index(below_idx) == target {
+    if below_idx < grid.elements.len() && matches(grid.get_index(below_idx)) {
         Some(below_idx)
     } else {
         None
     }
 }
 
-/// Check pixel below and the 2 diagonally below
-fn below_adjacent(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element) -> Option<usize> {
+/// Check below, below-left and below-right for an element matching the predicate
+fn below_adjacent_matching(
+    grid: &GameGrid,
+    x: u32,
+    y: u32,
+    i: usize,
+    matches: impl Fn(Element) -> bool,
+) -> Option<usize> {
+    if let Some(below_idx) = below_matching(grid, y, i, &matches) {
+        return Some(below_idx);
+    }
     if y >= grid.max_y() {
         return None;
     }
     let below_idx = i + grid.width as usize;
     
-    // Check directly below
-    if below_idx < grid.elements.len() && grid.get_index(below_idx) == target {
-        return Some(below_idx);
-    }
+    let below_left = (x > 0).then(|| below_idx - 1).filter(|&idx| matches(grid.get_index(idx)));
+    let below_right = (x < grid.max_x()).then(|| below_idx + 1).filter(|&idx| matches(grid.get_index(idx)));
     
-    // Check below-left
-    if x > 0 {
-        let below_left_idx = below_idx - 1;
-        if below_left_idx < grid.elements.len() && grid.get_index(below_left_idx) == target {
-            return Some(below_left_idx);
-        }
+    pick_rand_valid(below_left, below_right)
+}
+
+/// Check left and right for an element matching the predicate
+fn adjacent_matching(grid: &GameGrid, x: u32, i: usize, matches: impl Fn(Element) -> bool) -> Option<usize> {
+    let left = (x > 0).then(|| i - 1).filter(|&idx| matches(grid.get_index(idx)));
+    let right = (x < grid.max_x()).then(|| i + 1).filter(|&idx| matches(grid.get_index(idx)));
+    
+    pick_rand_valid(left, right)
+}
+
+/// Elements that sink deeper than this many Quicksand cells become trapped
+pub const QUICKSAND_TRAP_DEPTH: u8 = 5;
+
+/// Chance per tick that a heavier element sinks into Quicksand, used instead of the caller's sink chance
+pub const QUICKSAND_SINK_CHANCE: f64 = 0.20;
+
+/// Check if an element is trapped in quicksand
+/// A trapped element can only move again once enough material has piled up above it to push it through
+fn is_trapped(grid: &GameGrid, y: u32, i: usize) -> bool {
+    let depth = grid.trapped_depth[i];
+    if depth <= QUICKSAND_TRAP_DEPTH {
+        return false;
     }
     
-    // Check below-right
-    if x < grid.max_x() {
-        let below_right_idx = below_idx + 1;
-        if below_right_idx < grid.elements.len() && grid.get_index(below_right_idx) == target {
-            return Some(below_right_idx);
+    // Count the mass resting on the column above (everything except background and quicksand)
+    let width = grid.width as usize;
+    let mut mass = 0u32;
+    let mut idx = i;
+    for _ in 0..y {
+        idx -= width;
+        match grid.get_index(idx) {
+            Element::Background => break,
+            Element::Quicksand => {}
+            _ => mass += 1,
         }
     }
     
-    None
+    mass < u32::from(depth)
 }
 
-/// Check pixel immediately above
-fn above(grid: &GameGrid, y: u32, i: usize, target: Element) -> Option<usize> {
-    if y == 0 {
-        return None;
+/// Fraction of velocity kept each tick
+const VELOCITY_DECAY: f32 = 0.85;
+/// Velocities below this length are dropped to zero
+const MIN_VELOCITY: f32 = 0.5;
+/// Maximum velocity a shockwave can give a cell (cells per tick)
+pub const MAX_VELOCITY: f32 = 8.0;
+
+/// Shockwave emitted when an explosive detonates
+/// Pushes elements within radius outward with an impulse of force / distance²
+#[derive(Message, Clone, Copy, Debug)]
+pub struct ShockwaveEvent {
+    pub center: (u32, u32),
+    pub radius: u32,
+    pub force: f32,
+}
+
+/// Queue a shockwave centered at (x, y), sent as a Bevy message at the end of the frame
+fn emit_shockwave(grid: &mut GameGrid, x: u32, y: u32, radius: u32, force: f32) {
+    grid.pending_shockwaves.push(ShockwaveEvent {
+        center: (x, y),
+        radius,
+        force,
+    });
+}
+
+/// Shockwave force of a Nuke, strong enough to shake the camera (see `CameraShake`)
+pub const NUKE_SHOCKWAVE_FORCE: f32 = 50.0;
+
+/// Radius in cells a Nuke clears, a quarter of the grid's shorter side
+pub fn nuke_radius(grid: &GameGrid) -> u32 {
+    grid.width.min(grid.height) / 4
+}
+
+/// Detonate the Nuke at (x, y): flash a Nuke particle and turn everything but Wall within
+/// `nuke_radius` into Background, the Nuke cell included
+pub fn detonate_nuke(grid: &mut GameGrid, x: u32, y: u32, i: usize, particle_list: Option<&mut ParticleList>) {
+    if let Some(plist) = particle_list {
+        plist.add_active_particle(ParticleType::Nuke, x as f32, y as f32, i);
     }
-    let above_idx = i.saturating_sub(grid.width as usize);
-    if grid.get_index(above_idx) == target {
-        Some(above_idx)
-    } else {
-        None
+    let radius = nuke_radius(grid) as i32;
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx * dx + dy * dy > radius * radius {
+                continue;
+            }
+            let nx = x as i32 + dx;
+            let ny = y as i32 + dy;
+            if nx < 0 || ny < 0 || !grid.is_valid(nx as u32, ny as u32) {
+                continue;
+            }
+            let idx = grid.xy_to_index(nx as u32, ny as u32);
+            if grid.get_index(idx) != Element::Wall {
+                grid.set_index(idx, Element::Background);
+            }
+        }
     }
+    emit_shockwave(grid, x, y, radius as u32, NUKE_SHOCKWAVE_FORCE);
+    grid.set_index(i, Element::Background);
 }
 
-/// Check pixel above and the 2 diagonally above
-fn above_adjacent(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element) -> Option<usize> {
-    if y == 0 {
-        return None;
+/// Throw 2-5 debris particles of the exploding element outward from (x, y)
+fn spawn_debris(particle_list: &mut ParticleList, x: u32, y: u32, i: usize, element: Element) {
+    let mut rng = sim_rng();
+    for _ in 0..rng.gen_range(2..=5) {
+        if let Some(particle_idx) = particle_list.add_active_particle(ParticleType::Debris, x as f32, y as f32, i)
+            && let Some(particle) = particle_list.get_particle_mut(particle_idx)
+        {
+            crate::particles::actions::debris_particle_init(particle, element, &mut rng);
+            particle.reinitialized = true;
+        }
     }
-    let above_idx = i.saturating_sub(grid.width as usize);
-    
-    // Check directly above
-    if grid.get_index(above_idx) == target {
-        return Some(above_idx);
+}
+
+/// Explode gunpowder at (x, y): the cell and its 8 neighbors catch fire and a small shockwave is sent out
+/// Cells with durability left (see `GameGrid::damage`) are worn down instead
+pub fn ignite_at(grid: &mut GameGrid, x: u32, y: u32) {
+    if x > grid.max_x() || y > grid.max_y() {
+        return;
     }
-    
-    // Check above-left
-    if x > 0 {
-        let above_left_idx = above_idx - 1;
-        if grid.get_index(above_left_idx) == target {
-            return Some(above_left_idx);
+    emit_shockwave(grid, x, y, 4, 3.0);
+    for ny in y.saturating_sub(1)..=(y + 1).min(grid.max_y()) {
+        for nx in x.saturating_sub(1)..=(x + 1).min(grid.max_x()) {
+            if grid.damage(grid.xy_to_index(nx, ny), EXPLOSION_DAMAGE) {
+                grid.set(nx, ny, Element::Fire);
+            }
         }
     }
+}
+
+/// Apply a shockwave impulse to every element within its radius
+pub fn apply_shockwave(grid: &mut GameGrid, shockwave: &ShockwaveEvent) {
+    let (center_x, center_y) = shockwave.center;
+    let radius = shockwave.radius as i32;
     
-    // Check above-right
-    if x < grid.max_x() {
-        let above_right_idx = above_idx + 1;
-        if above_right_idx < grid.elements.len() && grid.get_index(above_right_idx) == target {
-            return Some(above_right_idx);
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let distance_sq = (dx * dx + dy * dy) as f32;
+            if distance_sq == 0.0 || distance_sq > (radius * radius) as f32 {
+                continue;
+            }
+            
+            let nx = center_x as i32 + dx;
+            let ny = center_y as i32 + dy;
+            if nx < 0 || ny < 0 || !grid.is_valid(nx as u32, ny as u32) {
+                continue;
+            }
+            
+            let idx = grid.xy_to_index(nx as u32, ny as u32);
+            if matches!(grid.get_index(idx), Element::Background | Element::Wall) {
+                continue;
+            }
+            
+            let direction = Vec2::new(dx as f32, dy as f32).normalize();
+            let impulse = direction * (shockwave.force / distance_sq);
+            grid.velocities[idx] = (grid.velocities[idx] + impulse).clamp_length_max(MAX_VELOCITY);
+            grid.staleness[idx] = 0;
         }
     }
-    
-    None
 }
 
-/// Check left and right adjacent pixels
-fn adjacent(grid: &GameGrid, x: u32, i: usize, target: Element) -> Option<usize> {
-    let left_idx = if x > 0 { Some(i - 1) } else { None };
-    let right_idx = if x < grid.max_x() { Some(i + 1) } else { None };
+/// Move an element along its velocity (plus one cell of gravity), stopping at the first obstacle
+/// Velocity decays each tick and is carried to the new cell
+/// Returns true if the element moved
+fn do_momentum(
+    grid: &mut GameGrid,
+    x: u32,
+    y: u32,
+    i: usize,
+    rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
+) -> bool {
+    let velocity = grid.velocities[i];
+    if velocity == Vec2::ZERO {
+        return false;
+    }
+    if velocity.length() < MIN_VELOCITY {
+        grid.velocities[i] = Vec2::ZERO;
+        return false;
+    }
     
-    let left_match = left_idx.and_then(|idx| {
-        if grid.get_index(idx) == target {
-            Some(idx)
-        } else {
-            None
+    // Gravity adds one cell of downward displacement on top of the velocity
+    let displacement = Vec2::new(velocity.x, velocity.y + 1.0);
+    let steps = displacement.x.abs().max(displacement.y.abs()).round() as i32;
+    let step = displacement / steps.max(1) as f32;
+    
+    // Walk toward the target one cell at a time until blocked
+    let mut new_i = None;
+    for n in 1..=steps {
+        let nx = (x as f32 + step.x * n as f32).round() as i32;
+        let ny = (y as f32 + step.y * n as f32).round() as i32;
+        if nx < 0 || ny < 0 || !grid.is_valid(nx as u32, ny as u32) {
+            break;
         }
-    });
+        let idx = grid.xy_to_index(nx as u32, ny as u32);
+        if idx != i && grid.get_index(idx) != Element::Background {
+            break;
+        }
+        if idx != i {
+            new_i = Some(idx);
+        }
+    }
     
-    let right_match = right_idx.and_then(|idx| {
-        if grid.get_index(idx) == target {
-            Some(idx)
-        } else {
-            None
+    let Some(new_idx) = new_i else {
+        // Hit an obstacle, momentum is lost
+        grid.velocities[i] = Vec2::ZERO;
+        return false;
+    };
+    
+    let element = grid.get_index(i);
+    grid.swap_cells(i, new_idx);
+    grid.velocities[new_idx] = velocity * VELOCITY_DECAY;
+    grid.velocities[i] = Vec2::ZERO;
+    grid.trapped_depth[new_idx] = 0;
+    grid.trapped_depth[i] = 0;
+    
+    // Transfer placement time if RainbowSand moved
+    if let Some(times) = rainbow_sand_times.as_mut() {
+        if element == Element::RainbowSand {
+            if let Some(placement_time) = times.remove(&i) {
+                times.insert(new_idx, placement_time);
+            }
         }
-    });
+    }
     
-    pick_rand_valid(left_match, right_match)
+    true
 }
 
 /// Apply gravity to an element
+/// Elements with momentum (from shockwaves) are carried along their velocity first
 /// Returns true if the element moved
 /// fall_into_void: if true, elements disappear at bottom edge; if false, they stop
 pub fn do_gravity(
@@ -146,12 +409,25 @@ pub fn do_gravity(
     chance: f64,
     fall_into_void: bool,
     rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
+    world_wrap: bool,
 ) -> bool {
-    if !rand::thread_rng().gen_bool(chance) {
+    if !sim_rng().gen_bool(chance) {
         return false;
     }
 
-    if y >= grid.max_y() {
+    if is_trapped(grid, y, i) {
+        return false;
+    }
+
+    // Inside an anti-gravity zone things fall upward, without momentum and never into the void
+    let inverted = grid.gravity_inverted(x, y);
+
+    if !inverted && do_momentum(grid, x, y, i, rainbow_sand_times) {
+        return true;
+    }
+
+    // With world wrapping the bottom row falls through to the top (see `below`)
+    if !inverted && y >= grid.max_y() && !world_wrap {
         if fall_into_void {
             let element = grid.get_index(i);
             grid.set_index(i, Element::Background);
@@ -167,15 +443,29 @@ pub fn do_gravity(
         return false;
     }
 
-    let new_i = if fall_adjacent {
-        below_adjacent(grid, x, y, i, Element::Background)
-    } else {
-        below(grid, y, i, Element::Background)
+    // Falling straight into a portal comes out below its pair (above when inverted)
+    let fall_dy = if inverted { -1 } else { 1 };
+    if let Some(portal) = neighbor_index(grid, x, y, i, 0, fall_dy, world_wrap)
+        && enter_portal(grid, i, portal, 0, fall_dy, rainbow_sand_times)
+    {
+        return true;
+    }
+
+    let new_i = match (inverted, fall_adjacent) {
+        (false, true) => below_adjacent(grid, x, y, i, Element::Background, world_wrap),
+        (false, false) => below(grid, y, i, Element::Background, world_wrap),
+        (true, true) => above_adjacent(grid, x, y, i, Element::Background, world_wrap),
+        (true, false) => above(grid, y, i, Element::Background, world_wrap),
     };
 
+    // Flowing sideways into a portal comes out on the far side of its pair
+    if new_i.is_none() && fall_adjacent && enter_side_portal(grid, x, y, i, world_wrap, rainbow_sand_times) {
+        return true;
+    }
+
     let new_i = new_i.or_else(|| {
         if fall_adjacent {
-            adjacent(grid, x, i, Element::Background)
+            adjacent(grid, x, i, Element::Background, world_wrap)
         } else {
             None
         }
@@ -183,8 +473,11 @@ pub fn do_gravity(
 
     if let Some(new_idx) = new_i {
         let element = grid.get_index(i);
-        grid.set_index(new_idx, element);
-        grid.set_index(i, Element::Background);
+        grid.swap_cells(i, new_idx);
+        
+        // Falling into open space means the element is out of any quicksand
+        grid.trapped_depth[new_idx] = 0;
+        grid.trapped_depth[i] = 0;
         
         // Transfer placement time if RainbowSand moved
         if let Some(times) = rainbow_sand_times.as_mut() {
@@ -201,38 +494,77 @@ pub fn do_gravity(
     false
 }
 
+/// Check if the current element can displace the target by density
+/// Only liquids, pollen and quicksand can be pushed aside, and only by something heavier
+fn is_lighter_displaceable(current: Element, target: Element) -> bool {
+    (target.is_liquid() || matches!(target, Element::Pollen | Element::Quicksand))
+        && target.density() < current.density()
+}
+
+/// Apply gravity to a liquid, with the fall chance scaled by its viscosity (see `ELEMENT_VISCOSITY`)
+/// Returns true if the element moved
+pub fn do_viscous_gravity(
+    grid: &mut GameGrid,
+    x: u32,
+    y: u32,
+    i: usize,
+    fall_adjacent: bool,
+    chance: f64,
+    fall_into_void: bool,
+    rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
+    world_wrap: bool,
+) -> bool {
+    let chance = chance * f64::from(grid.get_index(i).viscosity().clamp(0.0, 1.0));
+    do_gravity(grid, x, y, i, fall_adjacent, chance, fall_into_void, rainbow_sand_times, world_wrap)
+}
+
 /// Density sink for solid elements (e.g., sand sinking through water)
-/// The current element sinks through the lighter element below it
+/// The current element sinks through any lighter liquid below it (see `ELEMENT_DENSITY`)
 /// Returns true if the element moved
 pub fn do_density_sink(
     grid: &mut GameGrid,
     x: u32,
     y: u32,
     i: usize,
-    lighter_than: Element,
     sink_adjacent: bool,
     chance: f64,
     _fall_into_void: bool,
     rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
 ) -> bool {
-    if !rand::thread_rng().gen_bool(chance) {
+    if y >= grid.max_y() {
         return false;
     }
 
-    if y >= grid.max_y() {
+    if is_trapped(grid, y, i) {
         return false;
     }
 
+    let current_element = grid.get_index(i);
+    let lighter = |element| is_lighter_displaceable(current_element, element);
     let new_i = if sink_adjacent {
-        below_adjacent(grid, x, y, i, lighter_than)
+        below_adjacent_matching(grid, x, y, i, lighter)
     } else {
-        below(grid, y, i, lighter_than)
+        below_matching(grid, y, i, lighter)
     };
 
     if let Some(new_idx) = new_i {
-        let current_element = grid.get_index(i);
-        grid.set_index(new_idx, current_element);
-        grid.set_index(i, lighter_than);
+        let lighter_element = grid.get_index(new_idx);
+        
+        // Quicksand is thick, sinking into it has its own chance whatever the element
+        let sink_chance = if lighter_element == Element::Quicksand { QUICKSAND_SINK_CHANCE } else { chance };
+        if !sim_rng().gen_bool(sink_chance) {
+            return false;
+        }
+        
+        grid.swap_cells(i, new_idx);
+        
+        // Track how deep the element has sunk into quicksand
+        grid.trapped_depth[new_idx] = if lighter_element == Element::Quicksand {
+            grid.trapped_depth[new_idx].saturating_add(1)
+        } else {
+            0
+        };
+        grid.trapped_depth[i] = 0;
         
         // Transfer placement time if RainbowSand moved
         if let Some(times) = rainbow_sand_times.as_mut() {
@@ -250,36 +582,60 @@ pub fn do_density_sink(
 }
 
 /// Density-based liquid interaction (e.g., water sinking through oil)
+/// The current liquid swaps with any lighter liquid below or beside it (see `ELEMENT_DENSITY`)
 /// Returns true if the element moved
 pub fn do_density_liquid(
     grid: &mut GameGrid,
     x: u32,
     y: u32,
     i: usize,
-    heavier_than: Element,
     sink_chance: f64,
     equalize_chance: f64,
+    rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
 ) -> bool {
+    let current_element = grid.get_index(i);
+    let lighter = |element: Element| element.is_liquid() && element.density() < current_element.density();
     let mut new_i = None;
 
-    if rand::thread_rng().gen_bool(sink_chance) {
-        new_i = below_adjacent(grid, x, y, i, heavier_than);
+    if sim_rng().gen_bool(sink_chance) {
+        new_i = below_adjacent_matching(grid, x, y, i, lighter);
     }
 
-    if new_i.is_none() && rand::thread_rng().gen_bool(equalize_chance) {
-        new_i = adjacent(grid, x, i, heavier_than);
+    if new_i.is_none() && sim_rng().gen_bool(equalize_chance) {
+        new_i = adjacent_matching(grid, x, i, lighter);
     }
 
     if let Some(new_idx) = new_i {
-        let current_element = grid.get_index(i);
-        grid.set_index(new_idx, current_element);
-        grid.set_index(i, heavier_than);
+        let lighter_element = grid.get_index(new_idx);
+        grid.swap_cells(i, new_idx);
+        // Swap placement times along with the elements, dropping any stale ones
+        if let Some(times) = rainbow_sand_times.as_mut() {
+            let current_time = times.remove(&i);
+            let lighter_time = times.remove(&new_idx);
+            if let Some(placement_time) = current_time.filter(|_| current_element == Element::RainbowSand) {
+                times.insert(new_idx, placement_time);
+            }
+            if let Some(placement_time) = lighter_time.filter(|_| lighter_element == Element::RainbowSand) {
+                times.insert(i, placement_time);
+            }
+        }
         return true;
     }
 
     false
 }
 
+/// Drop RainbowSand placement times for cells that no longer hold RainbowSand
+pub fn prune_stale_rainbow_times(grid: &GameGrid, times: &mut std::collections::HashMap<usize, u32>) {
+    times.retain(|&idx, _| idx < grid.elements.len() && grid.get_index(idx) == Element::RainbowSand);
+}
+
+/// Drop Creature routes for cells that no longer hold a Creature
+pub fn prune_creature_paths(grid: &mut GameGrid) {
+    let elements = &grid.elements;
+    grid.creature_paths.retain(|&idx, _| elements.get(idx) == Some(&Element::Creature));
+}
+
 /// Transform element when touching another element
 /// Returns true if transformation occurred
 fn do_transform(
@@ -292,7 +648,7 @@ fn do_transform(
     transform_chance: f64,
     consume_chance: f64,
 ) -> bool {
-    let mut rng = rand::thread_rng();
+    let mut rng = sim_rng();
     if !rng.gen_bool(transform_chance) {
         return false;
     }
@@ -319,7 +675,7 @@ fn do_grow(
     into: Element,
     chance: f64,
 ) -> bool {
-    let mut rng = rand::thread_rng();
+    let mut rng = sim_rng();
     if !rng.gen_bool(chance) {
         return false;
     }
@@ -354,10 +710,174 @@ pub struct ActiveTreeBranches {
     pub branches: Vec<TreeBranch>,
 }
 
+/// Maximum number of linked portal pairs
+pub const MAX_PORTAL_PAIRS: usize = 4;
+
+/// Resource mapping each portal grid index to its paired portal
+/// Both directions are stored so either portal can be used as an entrance
+#[derive(Resource, Default)]
+pub struct PortalRegistry(pub std::collections::HashMap<usize, usize>);
+
+/// Resource holding the last placed portal that is still waiting for its pair
+#[derive(Resource, Default)]
+pub struct PendingPortal(pub Option<usize>);
+
+impl PortalRegistry {
+    /// Number of linked portal pairs
+    pub fn pair_count(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    /// Drop pairs where either end is no longer a portal (e.g., erased or dissolved)
+    pub fn prune(&mut self, grid: &GameGrid) {
+        self.0.retain(|&entry, &mut exit| {
+            grid.get_index(entry) == Element::Portal && grid.get_index(exit) == Element::Portal
+        });
+    }
+}
+
+/// Particle spray settings for a single Emitter cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmitterConfig {
+    pub particle_type: ParticleType,
+    /// Chance per tick of emitting a particle
+    pub rate_per_tick: f32,
+    /// Width in radians of the cone particles are sprayed in, centered straight up
+    pub angle_spread: f32,
+    pub velocity: f32,
+}
+
+impl Default for EmitterConfig {
+    fn default() -> Self {
+        Self {
+            particle_type: ParticleType::Magic1,
+            rate_per_tick: 0.05,
+            angle_spread: 0.5,
+            velocity: 2.0,
+        }
+    }
+}
+
+/// Resource mapping Emitter grid indices to their settings
+/// Emitters without an entry use `EmitterConfig::default()`
+#[derive(Resource, Default)]
+pub struct EmitterConfigs(pub std::collections::HashMap<usize, EmitterConfig>);
+
+impl EmitterConfigs {
+    /// Settings for the emitter at index i
+    pub fn get(&self, i: usize) -> EmitterConfig {
+        self.0.get(&i).copied().unwrap_or_default()
+    }
+
+    /// Drop settings for cells that are no longer emitters
+    pub fn prune(&mut self, grid: &GameGrid) {
+        self.0.retain(|&i, _| grid.get_index_checked(i) == Some(Element::Emitter));
+    }
+}
+
+/// Place a portal at index i, linking it with the pending portal if there is one
+/// Returns false if the maximum number of portal pairs is already in use
+pub fn place_portal(
+    grid: &mut GameGrid,
+    registry: &mut PortalRegistry,
+    pending: &mut PendingPortal,
+    i: usize,
+) -> bool {
+    registry.prune(grid);
+    
+    // Forget the pending portal if it was destroyed or is being placed again
+    if let Some(pending_idx) = pending.0 {
+        if grid.get_index(pending_idx) != Element::Portal || pending_idx == i || registry.0.contains_key(&i) {
+            pending.0 = None;
+        }
+    }
+    
+    if pending.0.is_none() && registry.pair_count() >= MAX_PORTAL_PAIRS {
+        return false;
+    }
+    
+    grid.set_index(i, Element::Portal);
+    if let Some(first) = pending.0.take() {
+        registry.0.insert(first, i);
+        registry.0.insert(i, first);
+    } else {
+        pending.0 = Some(i);
+    }
+    
+    true
+}
+
+/// Move element from src to dst (dst must be empty), carrying RainbowSand placement time
+fn teleport_element(
+    grid: &mut GameGrid,
+    src: usize,
+    dst: usize,
+    rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
+) -> bool {
+    if grid.get_index(dst) != Element::Background {
+        // Exit is blocked, element stays where it is
+        return false;
+    }
+    grid.swap_cells(src, dst);
+    if let Some(times) = rainbow_sand_times.as_mut() {
+        if let Some(placement_time) = times.remove(&src) {
+            times.insert(dst, placement_time);
+        }
+    }
+    true
+}
+
+/// Send the element at i through the portal at `portal`, which it is moving into by (dx, dy)
+/// It comes out of the paired portal on the far side, still moving the same way
+/// Returns false if the portal is unlinked or the exit is blocked, leaving the element where it is
+fn enter_portal(
+    grid: &mut GameGrid,
+    i: usize,
+    portal: usize,
+    dx: i32,
+    dy: i32,
+    rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
+) -> bool {
+    if grid.get_index(portal) != Element::Portal {
+        return false;
+    }
+    let Some(&exit) = grid.portal_links.get(&portal) else {
+        return false; // Unlinked portal
+    };
+    // The link can be stale after a resize or load
+    if grid.get_index_checked(exit) != Some(Element::Portal) {
+        return false;
+    }
+    let (exit_x, exit_y) = grid.index_to_xy(exit);
+    let (Some(out_x), Some(out_y)) = (exit_x.checked_add_signed(dx), exit_y.checked_add_signed(dy)) else {
+        return false;
+    };
+    if out_x > grid.max_x() || out_y > grid.max_y() {
+        return false;
+    }
+    teleport_element(grid, i, grid.xy_to_index(out_x, out_y), rainbow_sand_times)
+}
+
+/// Flow sideways into a portal on either side, trying the two sides in random order
+fn enter_side_portal(
+    grid: &mut GameGrid,
+    x: u32,
+    y: u32,
+    i: usize,
+    world_wrap: bool,
+    rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
+) -> bool {
+    let sides = if sim_rng().gen_bool(0.5) { [-1, 1] } else { [1, -1] };
+    sides.into_iter().any(|dx| {
+        neighbor_index(grid, x, y, i, dx, 0, world_wrap)
+            .is_some_and(|portal| enter_portal(grid, i, portal, dx, 0, rainbow_sand_times))
+    })
+}
+
 /// Start a new tree generation (adds initial branch to active branches)
 /// The tree will grow incrementally over multiple frames
 pub fn start_tree_generation(active_branches: &mut ActiveTreeBranches, start_x: u32, start_y: u32) {
-    let mut rng = rand::thread_rng();
+    let mut rng = sim_rng();
     
     // Tree parameters (similar to TREE_PARTICLE_INIT)
     let initial_angle = -std::f32::consts::PI / 2.0 - std::f32::consts::PI / 8.0 + rng.gen_range(0.0..1.0) * std::f32::consts::PI / 4.0;
@@ -384,7 +904,7 @@ pub fn start_tree_generation(active_branches: &mut ActiveTreeBranches, start_x:
 /// Process tree branches incrementally (called each frame)
 /// Similar to TREE_PARTICLE_ACTION in TypeScript
 pub fn process_tree_branches(grid: &mut GameGrid, active_branches: &mut ActiveTreeBranches) {
-    let mut rng = rand::thread_rng();
+    let mut rng = sim_rng();
     
     let mut new_branches = Vec::new();
     let mut branches_to_remove = Vec::new();
@@ -497,10 +1017,13 @@ pub fn process_tree_branches(grid: &mut GameGrid, active_branches: &mut ActiveTr
         }
     }
     
-    // Remove finished branches (in reverse order to maintain indices)
-    for &idx in branches_to_remove.iter().rev() {
-        active_branches.branches.remove(idx);
+    // Remove finished branches by flag, so the order of `branches_to_remove` doesn't matter
+    let mut finished = vec![false; active_branches.branches.len()];
+    for idx in branches_to_remove {
+        finished[idx] = true;
     }
+    let mut finished = finished.into_iter();
+    active_branches.branches.retain(|_| !finished.next().unwrap_or(false));
     
     // Add new branches
     active_branches.branches.extend(new_branches);
@@ -515,7 +1038,7 @@ fn create_radial_explosion(
     radius: u32,
     num_directions: u32,
 ) {
-    let mut rng = rand::thread_rng();
+    let mut rng = sim_rng();
     
     // Create fire in multiple directions
     for dir in 0..num_directions {
@@ -574,8 +1097,8 @@ fn create_vertical_fire_column(grid: &mut GameGrid, start_x: u32, start_y: u32)
 
 /// Create a large expanding explosion pattern (for C4)
 #[allow(dead_code)]
-fn create_c4_explosion(grid: &mut GameGrid, center_x: u32, center_y: u32) {
-    let mut rng = rand::thread_rng();
+fn create_c4_explosion(grid: &mut GameGrid, center_x: u32, center_y: u32, rules: &SimulationRules) {
+    let mut rng = sim_rng();
     
     // Create multiple expanding rings of fire
     let max_radius = 8;
@@ -600,7 +1123,7 @@ fn create_c4_explosion(grid: &mut GameGrid, center_x: u32, center_y: u32) {
                         Element::Plant | Element::Wax | Element::Oil | Element::Napalm | Element::Gunpowder
                     ) {
                         // Random chance to place fire (creates more interesting pattern)
-                        if rng.gen_bool(0.7) {
+                        if rng.gen_bool(rules.c4_fire_chance) {
                             grid.set_index(idx, Element::Fire);
                         }
                     }
@@ -621,42 +1144,61 @@ fn do_producer(
     overwrite_adjacent: bool,
     chance: f64,
 ) -> bool {
-    let mut rng = rand::thread_rng();
+    let mut rng = sim_rng();
     if !rng.gen_bool(chance) {
         return false;
     }
     
-    // Produce in up, down, left, right directions
-    if y > 0 {
-        let up_idx = i.saturating_sub(grid.width as usize);
-        if overwrite_adjacent || grid.get_index(up_idx) == Element::Background {
-            grid.set_index(up_idx, produce);
-        }
-    }
-    if y < grid.max_y() {
-        let down_idx = i + grid.width as usize;
-        if down_idx < grid.elements.len() {
-            if overwrite_adjacent || grid.get_index(down_idx) == Element::Background {
-                grid.set_index(down_idx, produce);
-            }
+    // Produce in up, down, left, right directions, never onto the producer itself
+    let width = grid.width as usize;
+    let neighbors = [
+        (y > 0).then(|| i.checked_sub(width)).flatten(),
+        (y < grid.max_y()).then_some(i + width),
+        (x > 0).then(|| i.checked_sub(1)).flatten(),
+        (x < grid.max_x()).then_some(i + 1),
+    ];
+    for idx in neighbors.into_iter().flatten() {
+        if idx == i || idx >= grid.elements.len() {
+            continue;
         }
-    }
-    if x > 0 {
-        let left_idx = i - 1;
-        if overwrite_adjacent || grid.get_index(left_idx) == Element::Background {
-            grid.set_index(left_idx, produce);
+        if overwrite_adjacent || grid.get_index(idx) == Element::Background {
+            grid.set_index(idx, produce);
         }
     }
-    if x < grid.max_x() {
-        let right_idx = i + 1;
-        if right_idx < grid.elements.len() {
-            if overwrite_adjacent || grid.get_index(right_idx) == Element::Background {
-                grid.set_index(right_idx, produce);
+    
+    true
+}
+
+/// Place element into a random empty (Background) cell among the 8 neighbors
+/// Returns the index where the element was placed, if any
+fn place_random_neighbor(grid: &mut GameGrid, x: u32, y: u32, element: Element) -> Option<usize> {
+    let mut rng = sim_rng();
+    let mut candidates = Vec::with_capacity(8);
+
+    let x_start = x.saturating_sub(1);
+    let y_start = y.saturating_sub(1);
+    let x_end = (x + 2).min(grid.max_x() + 1);
+    let y_end = (y + 2).min(grid.max_y() + 1);
+
+    for y_iter in y_start..y_end {
+        for x_iter in x_start..x_end {
+            if y_iter == y && x_iter == x {
+                continue;
+            }
+            let idx = grid.xy_to_index(x_iter, y_iter);
+            if grid.get_index(idx) == Element::Background {
+                candidates.push(idx);
             }
         }
     }
-    
-    true
+
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let idx = candidates[rng.gen_range(0..candidates.len())];
+    grid.set_index(idx, element);
+    Some(idx)
 }
 
 /// Check if an element is bordering (up, down, left, right) a target element
@@ -664,19 +1206,19 @@ fn bordering(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element) -> Opti
     // Check below
     if y < grid.max_y() {
         let below_idx = i + grid.width as usize;
-        if grid.get_index(below_idx) == target {
+        if grid.get_index_checked(below_idx) == Some(target) {
             return Some(below_idx);
         }
     }
     
     // Check adjacent (left/right)
-    if let Some(adj_idx) = adjacent(grid, x, i, target) {
+    if let Some(adj_idx) = adjacent(grid, x, i, target, false) {
         return Some(adj_idx);
     }
     
     // Check above
     if y > 0 {
-        if let Some(above_idx) = above(grid, y, i, target) {
+        if let Some(above_idx) = above(grid, y, i, target, false) {
             return Some(above_idx);
         }
     }
@@ -688,19 +1230,19 @@ fn bordering(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element) -> Opti
 fn bordering_adjacent(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element) -> Option<usize> {
     // Check below adjacent
     if y < grid.max_y() {
-        if let Some(below_idx) = below_adjacent(grid, x, y, i, target) {
+        if let Some(below_idx) = below_adjacent(grid, x, y, i, target, false) {
             return Some(below_idx);
         }
     }
     
     // Check adjacent (left/right)
-    if let Some(adj_idx) = adjacent(grid, x, i, target) {
+    if let Some(adj_idx) = adjacent(grid, x, i, target, false) {
         return Some(adj_idx);
     }
     
     // Check above adjacent
     if y > 0 {
-        if let Some(above_idx) = above_adjacent(grid, x, y, i, target) {
+        if let Some(above_idx) = above_adjacent(grid, x, y, i, target, false) {
             return Some(above_idx);
         }
     }
@@ -708,11 +1250,25 @@ fn bordering_adjacent(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element
     None
 }
 
+/// Check if a Catalyst is touching the element (all 8 directions including corners)
+fn has_adjacent_catalyst(grid: &GameGrid, x: u32, y: u32, i: usize) -> bool {
+    bordering_adjacent(grid, x, y, i, Element::Catalyst).is_some()
+}
+
+/// Reaction probability boosted 10x by a nearby catalyst (capped at 1.0)
+fn catalyzed(chance: f64, catalyst: bool) -> f64 {
+    if catalyst {
+        (chance * 10.0).min(1.0)
+    } else {
+        chance
+    }
+}
+
 /// Check if element is surrounded by target element (up, down, left, right only, not corners)
 fn surrounded_by(grid: &GameGrid, x: u32, y: u32, i: usize, target: Element) -> bool {
     if y < grid.max_y() {
         let below_idx = i + grid.width as usize;
-        if grid.get_index(below_idx) != target {
+        if grid.get_index_checked(below_idx) != Some(target) {
             return false;
         }
     }
@@ -746,7 +1302,7 @@ fn surrounded_by_adjacent(grid: &GameGrid, x: u32, y: u32, i: usize, target: Ele
     let above_idx = if !at_top { i.saturating_sub(grid.width as usize) } else { usize::MAX };
     
     if !at_bottom {
-        if grid.get_index(below_idx) != target {
+        if grid.get_index_checked(below_idx) != Some(target) {
             return false;
         }
     }
@@ -797,7 +1353,143 @@ fn surrounded_by_adjacent(grid: &GameGrid, x: u32, y: u32, i: usize, target: Ele
     true
 }
 
+/// Wick water up through a vertical gap of width 1 (capillary action)
+/// The cell above must be Background and flanked by Wall on both sides
+/// Returns true if the element moved
+pub fn do_capillary(grid: &mut GameGrid, x: u32, y: u32, i: usize, chance: f64) -> bool {
+    if y == 0 || x == 0 || x >= grid.max_x() {
+        return false;
+    }
+    
+    let above_idx = i - grid.width as usize;
+    if grid.get_index(above_idx) != Element::Background
+        || grid.get_index(above_idx - 1) != Element::Wall
+        || grid.get_index(above_idx + 1) != Element::Wall
+    {
+        return false;
+    }
+    
+    // Wick upward against gravity
+    if !sim_rng().gen_bool(chance) {
+        return false;
+    }
+    
+    grid.swap_cells(i, above_idx);
+    true
+}
+
+/// Radius in cells of the zone around a newly placed `AntiGravity` cell
+pub const ANTI_GRAVITY_RADIUS: u32 = 12;
+
+/// Seconds an anti-gravity zone lasts
+pub const ANTI_GRAVITY_LIFETIME: f32 = 5.0;
+
+/// Resource with the active anti-gravity zones as (x, y, radius, expiry time in seconds)
+/// Inside a zone `do_gravity` pulls things up instead of down
+#[derive(Resource, Default, Clone, Debug)]
+pub struct AntiGravitySources(pub Vec<(u32, u32, u32, f32)>);
+
+impl AntiGravitySources {
+    /// Start zones for the `AntiGravity` cells placed since the last call and drop the expired ones,
+    /// then hand the zones to the grid for `do_gravity`
+    /// A cell already inside an active zone doesn't start another one, so a brush stroke makes a few zones
+    /// Cells under a zone that starts or expires are woken, since settled sand would otherwise ignore the change
+    pub fn update(&mut self, grid: &mut GameGrid, now: f32) {
+        // A cleared or freshly loaded grid lists no zones, so ours go with it
+        self.0.retain(|&(x, y, radius, _)| grid.anti_gravity_zones.contains(&(x, y, radius)));
+        self.0.retain(|&(x, y, radius, expiry)| {
+            let active = expiry > now;
+            if !active {
+                grid.reset_staleness_in_radius(x, y, radius);
+            }
+            active
+        });
+        for i in std::mem::take(&mut grid.placed_anti_gravity) {
+            let (x, y) = grid.index_to_xy(i);
+            let covered = self.0.iter().any(|&(zone_x, zone_y, radius, _)| {
+                let dx = i64::from(x) - i64::from(zone_x);
+                let dy = i64::from(y) - i64::from(zone_y);
+                dx * dx + dy * dy <= i64::from(radius) * i64::from(radius)
+            });
+            if !covered {
+                grid.reset_staleness_in_radius(x, y, ANTI_GRAVITY_RADIUS);
+                self.0.push((x, y, ANTI_GRAVITY_RADIUS, now + ANTI_GRAVITY_LIFETIME));
+            }
+        }
+        grid.anti_gravity_zones = self.0.iter().map(|&(x, y, radius, _)| (x, y, radius)).collect();
+    }
+}
+
+/// Resource describing the wind blowing across the grid
+/// direction is a unit vector (x > 0 blows right, y > 0 blows up), strength ranges from 0 (calm) to 10
+#[derive(Resource, Default, Clone, Copy)]
+pub struct WindField {
+    pub direction: Vec2,
+    pub strength: f32,
+}
+
+impl WindField {
+    /// Maximum wind strength
+    pub const MAX_STRENGTH: f32 = 10.0;
+
+    /// Lateral drift applied to rising gases, from -1.0 (full left) to 1.0 (full right)
+    #[must_use]
+    pub fn drift(&self) -> f32 {
+        (self.direction.x * self.strength / Self::MAX_STRENGTH).clamp(-1.0, 1.0)
+    }
+
+    /// Vertical push from -1.0 (full downdraft) to 1.0 (full updraft)
+    #[must_use]
+    pub fn lift(&self) -> f32 {
+        (self.direction.y * self.strength / Self::MAX_STRENGTH).clamp(-1.0, 1.0)
+    }
+}
+
+/// Rise chance of a gas in the wind: an updraft raises it, a downdraft holds the gas down
+fn lifted(chance: f64, lift: f32) -> f64 {
+    (chance * (1.0 + f64::from(lift))).clamp(0.0, 1.0)
+}
+
+/// Blow an element in the wind direction (used for light powders)
+/// drift pushes it sideways (-1.0 = left, 1.0 = right), lift up or down (-1.0 = down, 1.0 = up)
+/// Returns true if the element moved
+fn do_wind_drift(grid: &mut GameGrid, x: u32, y: u32, i: usize, drift: f32, lift: f32) -> bool {
+    let mut rng = sim_rng();
+    
+    if drift != 0.0 && rng.gen_bool(f64::from(drift.abs().min(1.0))) {
+        let target = if drift > 0.0 && x < grid.max_x() {
+            Some(i + 1)
+        } else if drift < 0.0 && x > 0 {
+            Some(i - 1)
+        } else {
+            None
+        };
+        if let Some(target) = target.filter(|&target| grid.get_index(target) == Element::Background) {
+            grid.swap_cells(i, target);
+            return true;
+        }
+    }
+    
+    if lift != 0.0 && rng.gen_bool(f64::from(lift.abs().min(1.0))) {
+        let width = grid.width as usize;
+        let target = if lift > 0.0 && y > 0 {
+            Some(i - width)
+        } else if lift < 0.0 && y < grid.max_y() {
+            Some(i + width)
+        } else {
+            None
+        };
+        if let Some(target) = target.filter(|&target| grid.get_index(target) == Element::Background) {
+            grid.swap_cells(i, target);
+            return true;
+        }
+    }
+    
+    false
+}
+
 /// Make element rise (opposite of gravity, for gases)
+/// drift biases the rise toward the diagonal in the wind direction (-1.0 = left, 1.0 = right)
 /// Returns true if the element moved
 pub fn do_rise(
     grid: &mut GameGrid,
@@ -807,28 +1499,92 @@ pub fn do_rise(
     rise_chance: f64,
     adjacent_chance: f64,
     fall_into_void: bool,
+    drift: f32,
+    world_wrap: bool,
 ) -> bool {
-    let mut rng = rand::thread_rng();
+    let mut rng = sim_rng();
     let mut new_i = None;
     
     if rng.gen_bool(rise_chance) {
-        if y == 0 {
+        // With world wrapping the top row rises through to the bottom (see `above_adjacent`)
+        if y == 0 && !world_wrap {
             if fall_into_void {
                 grid.set_index(i, Element::Background);
                 return true;
             }
             return false;
         }
-        new_i = above_adjacent(grid, x, y, i, Element::Background);
+        
+        // Rising straight into a portal comes out above its pair
+        if let Some(portal) = neighbor_index(grid, x, y, i, 0, -1, world_wrap)
+            && enter_portal(grid, i, portal, 0, -1, &mut None)
+        {
+            return true;
+        }
+        
+        // Wind pushes the rise toward the diagonal in its direction
+        if drift != 0.0 && y > 0 && rng.gen_bool(f64::from(drift.abs().min(1.0))) {
+            let above_idx = i - grid.width as usize;
+            let diagonal_idx = if drift > 0.0 && x < grid.max_x() {
+                Some(above_idx + 1)
+            } else if drift < 0.0 && x > 0 {
+                Some(above_idx - 1)
+            } else {
+                None
+            };
+            new_i = diagonal_idx.filter(|&idx| grid.get_index(idx) == Element::Background);
+        }
+        
+        if new_i.is_none() {
+            new_i = above_adjacent(grid, x, y, i, Element::Background, world_wrap);
+        }
     }
     
     if new_i.is_none() && rng.gen_bool(adjacent_chance) {
-        new_i = adjacent(grid, x, i, Element::Background);
+        if enter_side_portal(grid, x, y, i, world_wrap, &mut None) {
+            return true;
+        }
+        new_i = adjacent(grid, x, i, Element::Background, world_wrap);
     }
     
     if let Some(new_idx) = new_i {
-        let current_element = grid.get_index(i);
-        grid.set_index(new_idx, current_element);
+        grid.swap_cells(i, new_idx);
+        return true;
+    }
+    
+    false
+}
+
+/// Number of ticks a Virus cell lives before dying
+pub const VIRUS_LIFETIME: u16 = 200;
+
+/// Number of ticks Slime can touch fire before it ignites
+pub const SLIME_FIRE_IMMUNITY_TICKS: u8 = 2;
+
+/// Durability Acid wears off a cell each time it eats at it
+pub const ACID_DAMAGE: u8 = 50;
+
+/// Durability an explosion wears off each cell it reaches
+pub const EXPLOSION_DAMAGE: u8 = 100;
+
+/// Durability Lava wears off a cell each time it burns it
+pub const LAVA_DAMAGE: u8 = 40;
+
+/// Pop a bubble when it touches heat or reaches the surface
+/// Returns true if the bubble popped
+fn do_bubble_pop(grid: &mut GameGrid, x: u32, y: u32, i: usize, rules: &SimulationRules) -> bool {
+    let mut rng = sim_rng();
+    
+    // Pop into steam when touching fire or lava (80% chance)
+    if bordering(grid, x, y, i, Element::Fire).is_some() || bordering(grid, x, y, i, Element::Lava).is_some() {
+        if rng.gen_bool(rules.bubble_heat_pop_chance) {
+            grid.set_index(i, Element::Steam);
+            return true;
+        }
+    }
+    
+    // Vanish at the surface (5% chance)
+    if above(grid, y, i, Element::Background, false).is_some() && rng.gen_bool(rules.bubble_surface_pop_chance) {
         grid.set_index(i, Element::Background);
         return true;
     }
@@ -836,18 +1592,177 @@ pub fn do_rise(
     false
 }
 
+/// Ticks a Creature follows its route before planning a new one
+pub const CREATURE_REPLAN_TICKS: u32 = 30;
+
+/// Cells a Creature can plan its route through, it only ever steps onto Background
+const CREATURE_TRAVERSABLE: [Element; 2] = [Element::Background, Element::Well];
+
+/// Route from (x, y) to the Well closest by Manhattan distance, without the starting cell, next step last
+fn plan_creature_path(grid: &GameGrid, x: u32, y: u32) -> Vec<(u32, u32)> {
+    let nearest_well = grid
+        .elements
+        .iter()
+        .enumerate()
+        .filter(|&(_, &element)| element == Element::Well)
+        .map(|(idx, _)| grid.index_to_xy(idx))
+        .min_by_key(|&(wx, wy)| wx.abs_diff(x) + wy.abs_diff(y));
+    let Some(goal) = nearest_well else {
+        return Vec::new();
+    };
+    let mut steps = grid.path_find((x, y), goal, &CREATURE_TRAVERSABLE).unwrap_or_default();
+    steps.reverse();
+    steps.pop(); // The creature's own cell
+    steps
+}
+
+/// Move a Creature one step along its route toward the nearest Well
+/// Creatures stop next to the Well, and plan again early when something blocks their way
+fn do_creature(grid: &mut GameGrid, x: u32, y: u32, i: usize) {
+    let mut route = grid.creature_paths.remove(&i).unwrap_or_default();
+    if route.ticks_until_replan == 0 {
+        route.steps = plan_creature_path(grid, x, y);
+        route.ticks_until_replan = CREATURE_REPLAN_TICKS;
+    }
+    route.ticks_until_replan -= 1;
+
+    if let Some(&(nx, ny)) = route.steps.last()
+        && nx.abs_diff(x) + ny.abs_diff(y) == 1
+    {
+        let next = grid.xy_to_index(nx, ny);
+        match grid.get_index(next) {
+            Element::Background => {
+                route.steps.pop();
+                grid.swap_cells(i, next);
+                grid.creature_paths.insert(next, route);
+                return;
+            }
+            // Arrived, wait by the Well
+            Element::Well => {}
+            _ => route.ticks_until_replan = 0,
+        }
+    }
+    grid.creature_paths.insert(i, route);
+}
+
+/// Temperature above which ice melts (°C)
+pub const ICE_MELT_TEMP: f32 = 0.0;
+
+/// Temperature below which water freezes (°C)
+pub const WATER_FREEZE_TEMP: f32 = 0.0;
+
+/// How strongly an element pulls its cell back toward its base temperature each tick
+const TEMPERATURE_RETENTION: f32 = 0.10;
+
+/// Apply age-based transformations (see `Element::age_effects`) to the element at i
+pub fn apply_age_effects(grid: &mut GameGrid, i: usize) {
+    if let Some(aged) = grid.get_index(i).age_effects(grid.ages[i]) {
+        grid.set_index(i, aged);
+    }
+}
+
+/// Run one heat diffusion pass over the grid
+/// Each cell exchanges heat with its 4 neighbors (weighted by the lower of the two conductivities),
+/// then drifts back toward its element's base temperature. Fire, Lava and Cryo are fixed heat sources.
+pub fn diffuse_temperatures(grid: &mut GameGrid) {
+    let width = grid.width as usize;
+    let height = grid.height as usize;
+    let previous = grid.temperatures.clone();
+    
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let element = grid.elements[i];
+            let base_temp = element_base_temp(element);
+            
+            // Heat sources hold their temperature
+            if matches!(element, Element::Fire | Element::Lava | Element::Cryo) {
+                grid.temperatures[i] = base_temp;
+                continue;
+            }
+            
+            let conductivity = element_conductivity(element);
+            let current = previous[i];
+            let mut delta = 0.0;
+            
+            let neighbors = [
+                if y > 0 { Some(i - width) } else { None },
+                if y + 1 < height { Some(i + width) } else { None },
+                if x > 0 { Some(i - 1) } else { None },
+                if x + 1 < width { Some(i + 1) } else { None },
+            ];
+            for neighbor in neighbors.into_iter().flatten() {
+                let weight = conductivity.min(element_conductivity(grid.elements[neighbor]));
+                delta += weight * (previous[neighbor] - current);
+            }
+            
+            let diffused = current + delta;
+            grid.temperatures[i] = diffused + TEMPERATURE_RETENTION * (base_temp - diffused);
+        }
+    }
+}
+
+/// Everything besides the grid and rules that element actions can use
+/// Resources left as None (the `Default`) switch off the behavior that needs them
+#[derive(Default)]
+pub struct SimContext<'a> {
+    /// Elements at the bottom edge fall out of the world instead of stopping
+    pub fall_into_void: bool,
+    /// Elements leaving one edge come back in at the opposite edge
+    pub world_wrap: bool,
+    pub particle_list: Option<&'a mut ParticleList>,
+    pub active_branches: Option<&'a mut ActiveTreeBranches>,
+    pub emitter_configs: Option<&'a EmitterConfigs>,
+    pub wind: Option<&'a WindField>,
+    /// Element pair reactions, the built-in ones when None
+    pub interactions: Option<&'a ElementInteractionMatrix>,
+    /// RainbowSand placement times, moved along with the sand
+    pub rainbow_sand_times: Option<&'a mut std::collections::HashMap<usize, u32>>,
+}
+
 /// Execute element action based on element type
-pub fn execute_element_action(
+pub fn execute_element_action(grid: &mut GameGrid, x: u32, y: u32, i: usize, rules: &SimulationRules, ctx: &mut SimContext) {
+    // Element pair reactions first; a reaction that changes the element skips its rules this tick
+    let element = grid.get_index(i);
+    ctx.interactions.unwrap_or(&BUILT_IN_INTERACTIONS).apply(grid, x, y, i, rules);
+    if grid.get_index(i) != element {
+        return;
+    }
+    execute_element_rules(
+        grid,
+        x,
+        y,
+        i,
+        ctx.fall_into_void,
+        ctx.world_wrap,
+        ctx.particle_list.as_deref_mut(),
+        ctx.active_branches.as_deref_mut(),
+        ctx.emitter_configs,
+        ctx.wind,
+        rules,
+        &mut ctx.rainbow_sand_times,
+    );
+}
+
+/// Per-element rules for `execute_element_action`
+fn execute_element_rules(
     grid: &mut GameGrid,
     x: u32,
     y: u32,
     i: usize,
     fall_into_void: bool,
+    world_wrap: bool,
     particle_list: Option<&mut ParticleList>,
     active_branches: Option<&mut ActiveTreeBranches>,
+    emitter_configs: Option<&EmitterConfigs>,
+    wind: Option<&WindField>,
+    rules: &SimulationRules,
     rainbow_sand_times: &mut Option<&mut std::collections::HashMap<usize, u32>>,
 ) {
     let element = grid.get_index(i);
+    let drift = wind.map_or(0.0, WindField::drift);
+    let lift = wind.map_or(0.0, WindField::lift);
+    grid.ages[i] = grid.ages[i].saturating_add(1);
     
     match element {
         Element::Background => {
@@ -856,48 +1771,76 @@ pub fn execute_element_action(
         Element::Wall => {
             // Wall is static
         }
+        Element::HardWall => {
+            // Hard wall is static and can't be dissolved by acid
+        }
+        Element::AntiGravity => {
+            // AntiGravity is static, its zone is started when it is placed (see `AntiGravitySources`)
+        }
+        Element::Creature => {
+            // Creature crawls toward the nearest Well, planning its route every CREATURE_REPLAN_TICKS
+            do_creature(grid, x, y, i);
+        }
+        Element::Emitter => {
+            // Emitter is static and sprays particles upward at its configured rate
+            let config = emitter_configs.map(|configs| configs.get(i)).unwrap_or_default();
+            let mut rng = sim_rng();
+            if let Some(plist) = particle_list
+                && rng.gen_bool(f64::from(config.rate_per_tick.clamp(0.0, 1.0)))
+            {
+                let angle = -std::f32::consts::FRAC_PI_2 + rng.gen_range(-0.5..=0.5) * config.angle_spread;
+                if let Some(particle_idx) = plist.add_active_particle(config.particle_type, x as f32, y as f32, i)
+                    && let Some(particle) = plist.get_particle_mut(particle_idx)
+                {
+                    // Initialize now so the emitter's velocity isn't overwritten on the particle's first update
+                    crate::particles::particle_init(particle, grid);
+                    particle.set_velocity(config.velocity, angle);
+                    particle.reinitialized = true;
+                }
+            }
+        }
         Element::Sand => {
-            // Sand can sink through liquids (sand is heavier)
-            if y < grid.max_y() {
-                if do_density_sink(grid, x, y, i, Element::Water, true, 0.25, fall_into_void, rainbow_sand_times) {
+            // Dry sand next to quicksand slowly turns into quicksand (0.2% chance)
+            if bordering_adjacent(grid, x, y, i, Element::Quicksand).is_some() {
+                // That can happen without anything nearby changing, so keep it from settling
+                grid.staleness[i] = 0;
+                if sim_rng().gen_bool(rules.sand_quicksand_chance) {
+                    grid.set_index(i, Element::Quicksand);
                     return;
                 }
-                if do_density_sink(grid, x, y, i, Element::SaltWater, true, 0.25, fall_into_void, rainbow_sand_times) {
+            }
+            
+            // Sand can sink through liquids (sand is heavier)
+            if y < grid.max_y() {
+                if do_density_sink(grid, x, y, i, true, rules.sand_sink_chance, fall_into_void, rainbow_sand_times) {
                     return;
                 }
             }
             // Sand falls with gravity, can fall diagonally (fall_adjacent = true)
-            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            do_gravity(grid, x, y, i, true, rules.sand_gravity_chance, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::Water => {
-            // Water falls with gravity (95% chance), can flow adjacent
+            // Water freezes when it gets cold enough
+            if grid.temperatures[i] < WATER_FREEZE_TEMP {
+                grid.set_index(i, Element::Ice);
+                return;
+            }
+            
+            // Water falls with gravity scaled by its viscosity (1.0), can flow adjacent
             // Water can sink through oil (water is heavier than oil)
-            if !do_density_liquid(grid, x, y, i, Element::Oil, 0.25, 0.50) {
-                do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            if !do_density_liquid(grid, x, y, i, rules.water_sink_chance, rules.water_equalize_chance, rainbow_sand_times)
+                && !do_viscous_gravity(grid, x, y, i, true, rules.water_gravity_chance, fall_into_void, rainbow_sand_times, world_wrap)
+            {
+                // Water can climb narrow channels when it can't fall
+                do_capillary(grid, x, y, i, rules.water_capillary_chance);
             }
         }
         Element::Fire => {
-            // Fire spreads and can be extinguished by water
-            let mut rng = rand::thread_rng();
-            
-            // Check for water or salt water to extinguish (80% chance)
-            if rng.gen_bool(0.80) {
-                if let Some(water_loc) = bordering(grid, x, y, i, Element::Water) {
-                    // Extinguish fire, turn water to steam
-                    grid.set_index(water_loc, Element::Steam);
-                    grid.set_index(i, Element::Background);
-                    return;
-                }
-                if let Some(salt_water_loc) = bordering(grid, x, y, i, Element::SaltWater) {
-                    // Extinguish fire, turn salt water to steam
-                    grid.set_index(salt_water_loc, Element::Steam);
-                    grid.set_index(i, Element::Background);
-                    return;
-                }
-            }
+            // Fire spreads, water puts it out through the interaction matrix (see `ElementInteractionMatrix`)
+            let mut rng = sim_rng();
             
             // Fire can spread to plant (20% chance)
-            if rng.gen_bool(0.20) {
+            if rng.gen_bool(rules.fire_spread_to_plant) {
                 if let Some(plant_loc) = bordering_adjacent(grid, x, y, i, Element::Plant) {
                     grid.set_index(plant_loc, Element::Fire);
                     return;
@@ -905,7 +1848,7 @@ pub fn execute_element_action(
             }
             
             // Fire can spread to fuse (80% chance)
-            if rng.gen_bool(0.80) {
+            if rng.gen_bool(rules.fire_spread_to_fuse) {
                 if let Some(fuse_loc) = bordering_adjacent(grid, x, y, i, Element::Fuse) {
                     grid.set_index(fuse_loc, Element::Fire);
                     return;
@@ -913,7 +1856,7 @@ pub fn execute_element_action(
             }
             
             // Fire can spread to branch (20% chance)
-            if rng.gen_bool(0.20) {
+            if rng.gen_bool(rules.fire_spread_to_branch) {
                 if let Some(branch_loc) = bordering_adjacent(grid, x, y, i, Element::Branch) {
                     grid.set_index(branch_loc, Element::Fire);
                     return;
@@ -921,7 +1864,7 @@ pub fn execute_element_action(
             }
             
             // Fire can spread to leaf (20% chance)
-            if rng.gen_bool(0.20) {
+            if rng.gen_bool(rules.fire_spread_to_leaf) {
                 if let Some(leaf_loc) = bordering_adjacent(grid, x, y, i, Element::Leaf) {
                     grid.set_index(leaf_loc, Element::Fire);
                     return;
@@ -929,12 +1872,12 @@ pub fn execute_element_action(
             }
             
             // Fire can spread to wax (1% chance, bordering not adjacent - only direct neighbors)
-            if rng.gen_bool(0.01) {
+            if rng.gen_bool(rules.fire_spread_to_wax) {
                 if let Some(wax_loc) = bordering(grid, x, y, i, Element::Wax) {
                     grid.set_index(wax_loc, Element::Fire);
                     // Create falling wax below the wax if there's space
                     let (_wax_x, wax_y) = grid.index_to_xy(wax_loc);
-                    if let Some(below_idx) = below(grid, wax_y.max(y), wax_loc.max(i), Element::Background) {
+                    if let Some(below_idx) = below(grid, wax_y.max(y), wax_loc.max(i), Element::Background, world_wrap) {
                         grid.set_index(below_idx, Element::FallingWax);
                     }
                     return;
@@ -942,15 +1885,15 @@ pub fn execute_element_action(
             }
             
             // Fire can rise upward (50% chance)
-            if rng.gen_bool(0.50) {
-                if let Some(above_idx) = above(grid, y, i, Element::Background) {
+            if rng.gen_bool(rules.fire_rise_chance) {
+                if let Some(above_idx) = above(grid, y, i, Element::Background, world_wrap) {
                     grid.set_index(above_idx, Element::Fire);
                     return;
                 }
             }
             
             // Fire can spread to oil (20% chance)
-            if rng.gen_bool(0.20) {
+            if rng.gen_bool(rules.fire_spread_to_oil) {
                 if let Some(oil_loc) = bordering_adjacent(grid, x, y, i, Element::Oil) {
                     grid.set_index(oil_loc, Element::Fire);
                     return;
@@ -959,7 +1902,7 @@ pub fn execute_element_action(
             
             // Fire can flame out (40% chance) if no flammable materials nearby
             // Check all 8 adjacent positions for flammable materials
-            if rng.gen_bool(0.40) {
+            if rng.gen_bool(rules.fire_flameout_chance) {
                 let mut has_flammable = false;
                 
                 // Check all 8 directions (including corners)
@@ -986,23 +1929,26 @@ pub fn execute_element_action(
                             continue;
                         }
                         
-                        // Check for flammable materials
-                        if matches!(
-                            elem,
-                            Element::Plant | Element::Fuse | Element::Branch | Element::Leaf
-                        ) {
-                            has_flammable = true;
-                            break;
-                        }
-                        
                         // Wax only counts if directly adjacent (not corners)
-                        if (x_iter == x || y_iter == y) && elem == Element::Wax {
-                            has_flammable = true;
-                            break;
+                        if elem == Element::Wax {
+                            if x_iter == x || y_iter == y {
+                                has_flammable = true;
+                                break;
+                            }
+                            continue;
                         }
                         
                         // Oil has 50% chance to prevent flameout
-                        if elem == Element::Oil && rng.gen_bool(0.50) {
+                        if elem == Element::Oil {
+                            if rng.gen_bool(rules.fire_oil_sustain_chance) {
+                                has_flammable = true;
+                                break;
+                            }
+                            continue;
+                        }
+                        
+                        // Check for flammable materials
+                        if elem.is_flammable() {
                             has_flammable = true;
                             break;
                         }
@@ -1021,24 +1967,25 @@ pub fn execute_element_action(
         }
         Element::Salt => {
             // Salt falls with gravity
-            if do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times) {
+            if do_gravity(grid, x, y, i, true, rules.sand_gravity_chance, fall_into_void, rainbow_sand_times, world_wrap) {
                 return;
             }
             // Salt can dissolve in water to create salt water (25% chance, 50% consume)
-            if do_transform(grid, x, y, i, Element::Water, Element::SaltWater, 0.25, 0.50) {
+            let catalyst = has_adjacent_catalyst(grid, x, y, i);
+            if do_transform(grid, x, y, i, Element::Water, Element::SaltWater, catalyzed(rules.salt_dissolve_chance, catalyst), 0.50) {
                 return;
             }
-            // Salt can sink through salt water
+            // Salt can sink through lighter liquids
             if y < grid.max_y() {
-                if do_density_sink(grid, x, y, i, Element::SaltWater, true, 0.25, fall_into_void, rainbow_sand_times) {
+                if do_density_sink(grid, x, y, i, true, rules.sand_sink_chance, fall_into_void, rainbow_sand_times) {
                     return;
                 }
             }
         }
         Element::Oil => {
             // Oil can catch fire (30% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.30) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.oil_ignite_chance) {
                 if let Some(_fire_loc) = bordering(grid, x, y, i, Element::Fire) {
                     // Set surrounding pixels on fire
                     if y > 0 {
@@ -1069,27 +2016,24 @@ pub fn execute_element_action(
                     return;
                 }
             }
-            // Oil falls with gravity (lighter than water, so floats)
-            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            // Oil falls with gravity (95% chance scaled by viscosity 0.8, lighter than water, so floats)
+            do_viscous_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::Rock => {
             // Rock is heavy and sinks through liquids
             if y < grid.max_y() {
-                // Rock sinks through water, oil (95% chance)
-                if do_density_sink(grid, x, y, i, Element::Water, false, 0.95, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::Oil, false, 0.95, fall_into_void, rainbow_sand_times) {
+                // Rock sinks through lighter liquids and quicksand (95% chance)
+                if do_density_sink(grid, x, y, i, false, rules.rock_sink_chance, fall_into_void, rainbow_sand_times) {
                     return;
                 }
             }
             // Rock falls with gravity (99% chance, no diagonal falling)
-            do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times);
+            do_gravity(grid, x, y, i, false, rules.rock_gravity_chance, fall_into_void, rainbow_sand_times, world_wrap);
             
-            // Rock produces methane when in contact with oil above (1% * 20% = 0.2% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.01) && rng.gen_bool(0.20) {
-                if let Some(oil_loc) = above(grid, y, i, Element::Oil) {
+            // Rock produces methane when in contact with oil above (0.2% chance)
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.rock_methane_chance) {
+                if let Some(oil_loc) = above(grid, y, i, Element::Oil, world_wrap) {
                     if rng.gen_bool(0.50) {
                         grid.set_index(oil_loc, Element::Methane);
                     } else {
@@ -1100,35 +2044,20 @@ pub fn execute_element_action(
             }
         }
         Element::Ice => {
-            // Ice melts when touching heat sources
-            let mut rng = rand::thread_rng();
+            // Ice melts once heat from its surroundings (Steam, Fire, Lava, ...) warms it above freezing
+            if grid.temperatures[i] > ICE_MELT_TEMP {
+                grid.set_index(i, Element::Water);
+                return;
+            }
             
             // Skip if surrounded by ice (optimization)
             if surrounded_by(grid, x, y, i, Element::Ice) {
                 return;
             }
             
-            // Slow melt from water (1% chance)
-            if rng.gen_bool(0.01) {
-                if let Some(_water_loc) = bordering(grid, x, y, i, Element::Water) {
-                    grid.set_index(i, Element::Water);
-                    return;
-                }
-            }
-            
-            // Fast melt from steam (70% chance)
-            if rng.gen_bool(0.70) {
-                if let Some(steam_loc) = bordering(grid, x, y, i, Element::Steam) {
-                    grid.set_index(i, Element::Water);
-                    if rng.gen_bool(0.50) {
-                        grid.set_index(steam_loc, Element::Water);
-                    }
-                    return;
-                }
-            }
-            
-            // Fast melt from salt or salt water (10% chance)
-            if rng.gen_bool(0.10) {
+            // Salt lowers the melting point, so it melts Ice by contact rather than by heat
+            // (10% chance, boosted by a nearby catalyst)
+            if sim_rng().gen_bool(catalyzed(rules.ice_salt_melt_chance, has_adjacent_catalyst(grid, x, y, i))) {
                 if let Some(_salt_loc) = bordering(grid, x, y, i, Element::Salt) {
                     grid.set_index(i, Element::Water);
                     return;
@@ -1138,41 +2067,13 @@ pub fn execute_element_action(
                     return;
                 }
             }
-            
-            // Fast melt from fire (50% chance)
-            if rng.gen_bool(0.50) {
-                if let Some(_fire_loc) = bordering(grid, x, y, i, Element::Fire) {
-                    grid.set_index(i, Element::Water);
-                    return;
-                }
-            }
-            
-            // Fast melt from lava (50% chance)
-            if rng.gen_bool(0.50) {
-                if let Some(_lava_loc) = bordering(grid, x, y, i, Element::Lava) {
-                    grid.set_index(i, Element::Water);
-                    return;
-                }
-            }
         }
         Element::Lava => {
             // Lava falls with gravity and burns things
-            let mut rng = rand::thread_rng();
-            
-            // Lava touching water or salt water turns to rock and liquid to steam
-            if let Some(water_loc) = bordering(grid, x, y, i, Element::Water) {
-                grid.set_index(water_loc, Element::Steam);
-                grid.set_index(i, Element::Rock);
-                return;
-            }
-            if let Some(salt_water_loc) = bordering(grid, x, y, i, Element::SaltWater) {
-                grid.set_index(salt_water_loc, Element::Steam);
-                grid.set_index(i, Element::Rock);
-                return;
-            }
+            let mut rng = sim_rng();
             
             // Lava can burn adjacent elements (25% chance)
-            if rng.gen_bool(0.25) {
+            if rng.gen_bool(rules.lava_burn_chance) {
                 let burn_locs = [
                     if y > 0 { Some(i.saturating_sub(grid.width as usize)) } else { None },
                     if y < grid.max_y() { Some(i + grid.width as usize) } else { None },
@@ -1190,7 +2091,7 @@ pub fn execute_element_action(
                                 Element::Lava | Element::Background | Element::Fire
                                     | Element::Wall | Element::Rock | Element::Water | Element::Steam
                             );
-                            if should_burn {
+                            if should_burn && grid.damage(*burn_loc, LAVA_DAMAGE) {
                                 grid.set_index(*burn_loc, Element::Fire);
                             }
                         }
@@ -1199,7 +2100,7 @@ pub fn execute_element_action(
             }
             
             // Lava can create fire above (6% chance)
-            if rng.gen_bool(0.06) && y > 0 {
+            if rng.gen_bool(rules.lava_fire_chance) && y > 0 {
                 let above_idx = i.saturating_sub(grid.width as usize);
                 if grid.get_index(above_idx) == Element::Background {
                     grid.set_index(above_idx, Element::Fire);
@@ -1211,7 +2112,7 @@ pub fn execute_element_action(
                 let below_idx = i + grid.width as usize;
                 if below_idx < grid.elements.len() {
                     let below_elem = grid.get_index(below_idx);
-                    if below_elem == Element::Steam && rng.gen_bool(0.95) {
+                    if below_elem == Element::Steam && rng.gen_bool(rules.lava_steam_sink_chance) {
                         grid.set_index(below_idx, Element::Lava);
                         grid.set_index(i, Element::Steam);
                         return;
@@ -1219,32 +2120,32 @@ pub fn execute_element_action(
                 }
             }
             
-            // Lava falls with gravity (100% chance, can fall diagonally)
-            do_gravity(grid, x, y, i, true, 1.0, fall_into_void, rainbow_sand_times);
+            // Lava falls with gravity (100% chance scaled by viscosity 0.30, can fall diagonally)
+            do_viscous_gravity(grid, x, y, i, true, 1.0, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::Steam => {
             // Steam rises and condenses
-            let mut rng = rand::thread_rng();
+            let mut rng = sim_rng();
             
-            // Steam rises (70% chance)
-            if do_rise(grid, x, y, i, 0.70, 0.60, fall_into_void) {
+            // Steam rises (70% chance), drifting with the wind and faster in an updraft
+            if do_rise(grid, x, y, i, lifted(rules.steam_rise_chance, lift), 0.60, fall_into_void, drift, world_wrap) {
                 return;
             }
             
             // Condense due to water (5% chance)
-            if rng.gen_bool(0.05) {
+            if rng.gen_bool(rules.steam_condense_chance) {
                 if let Some(_water_loc) = bordering(grid, x, y, i, Element::Water) {
                     grid.set_index(i, Element::Water);
                     return;
                 }
             }
             
-            // Condense/disappear due to air cooling (5% * 40% = 2% chance)
-            if rng.gen_bool(0.05) && rng.gen_bool(0.40) {
-                let below_bg = below(grid, y, i, Element::Background);
-                let above_bg = if y > 0 { above(grid, y, i, Element::Background) } else { None };
+            // Condense/disappear due to air cooling (2% chance)
+            if rng.gen_bool(rules.steam_cool_chance) {
+                let below_bg = below(grid, y, i, Element::Background, world_wrap);
+                let above_bg = if y > 0 { above(grid, y, i, Element::Background, world_wrap) } else { None };
                 if below_bg.is_some() && above_bg.is_none() {
-                    if rng.gen_bool(0.30) {
+                    if rng.gen_bool(rules.steam_rain_chance) {
                         grid.set_index(i, Element::Water);
                     } else {
                         grid.set_index(i, Element::Background);
@@ -1254,16 +2155,16 @@ pub fn execute_element_action(
             }
             
             // Condense due to spout (5% chance)
-            if rng.gen_bool(0.05) {
+            if rng.gen_bool(rules.steam_spout_condense_chance) {
                 if let Some(_spout_loc) = bordering(grid, x, y, i, Element::Spout) {
                     grid.set_index(i, Element::Water);
                     return;
                 }
             }
             
-            // Steam may be trapped; disappear slowly (1% * 5% = 0.05% chance)
-            if rng.gen_bool(0.01) && rng.gen_bool(0.05) {
-                if below(grid, y, i, Element::Steam).is_none() {
+            // Steam may be trapped; disappear slowly (0.05% chance)
+            if rng.gen_bool(rules.steam_vanish_chance) {
+                if below(grid, y, i, Element::Steam, world_wrap).is_none() {
                     grid.set_index(i, Element::Background);
                     return;
                 }
@@ -1272,15 +2173,15 @@ pub fn execute_element_action(
         Element::SaltWater => {
             // Salt water falls with gravity (95% chance)
             // Can mix with water (50% chance each direction)
-            if !do_density_liquid(grid, x, y, i, Element::Water, 0.50, 0.50) {
-                do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            if !do_density_liquid(grid, x, y, i, 0.50, 0.50, rainbow_sand_times) {
+                do_viscous_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap);
             }
         }
         Element::Plant => {
-            // Plant grows with water (50% chance)
+            // Plant grows with water (50% chance, boosted by a nearby catalyst)
             // But don't grow into water that is directly above soil (let soil handle that)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.50) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(catalyzed(rules.plant_growth_chance, has_adjacent_catalyst(grid, x, y, i))) {
                 if let Some(grow_loc) = bordering_adjacent(grid, x, y, i, Element::Water) {
                     // Check if this water is directly above soil - if so, don't convert it
                     // Calculate the y position of the water
@@ -1314,22 +2215,20 @@ pub fn execute_element_action(
                     }
                 }
             }
-            
-            // Plant dies from salt (5% chance)
-            if rng.gen_bool(0.05) {
-                if let Some(_salt_loc) = bordering(grid, x, y, i, Element::Salt) {
-                    grid.set_index(i, Element::Background);
-                    return;
-                }
-            }
         }
         Element::Gunpowder => {
             // Gunpowder explodes when touched by fire (95% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.95) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.gunpowder_ignite_chance) {
                 if let Some(_fire_loc) = bordering(grid, x, y, i, Element::Fire) {
                     // Create explosion pattern - set surrounding pixels on fire
-                    let burn = rng.gen_bool(0.60);
+                    let burn = rng.gen_bool(rules.gunpowder_burn_chance);
+                    if burn {
+                        emit_shockwave(grid, x, y, 4, 3.0);
+                        if let Some(plist) = particle_list {
+                            spawn_debris(plist, x, y, i, Element::Gunpowder);
+                        }
+                    }
                     let replace = if burn { Element::Fire } else { Element::Gunpowder };
                     
                     // Set center
@@ -1349,14 +2248,15 @@ pub fn execute_element_action(
                     
                     for pos_opt in positions.iter() {
                         if let Some(pos) = pos_opt {
-                            if *pos < grid.elements.len() {
+                            // Only a real explosion wears down the cells around it
+                            if *pos < grid.elements.len() && (!burn || grid.damage(*pos, EXPLOSION_DAMAGE)) {
                                 grid.set_index(*pos, replace);
                             }
                         }
                     }
                     
                     // Extended explosion (40% chance, 2 pixels away)
-                    if burn && rng.gen_bool(0.40) {
+                    if burn && rng.gen_bool(rules.gunpowder_blast_chance) {
                         let extended_positions = [
                             if y >= 2 { Some(i.saturating_sub(2 * grid.width as usize)) } else { None },
                             if y + 2 <= grid.max_y() { Some(i + 2 * grid.width as usize) } else { None },
@@ -1368,7 +2268,7 @@ pub fn execute_element_action(
                             if let Some(pos) = pos_opt {
                                 if *pos < grid.elements.len() {
                                     let elem = grid.get_index(*pos);
-                                    if elem != Element::Gunpowder || rng.gen_bool(0.50) {
+                                    if (elem != Element::Gunpowder || rng.gen_bool(rules.gunpowder_chain_chance)) && grid.damage(*pos, EXPLOSION_DAMAGE) {
                                         grid.set_index(*pos, Element::Fire);
                                     }
                                 }
@@ -1380,13 +2280,20 @@ pub fn execute_element_action(
                 }
             }
             
+            // Gunpowder sinks through lighter liquids and quicksand
+            if y < grid.max_y() {
+                if do_density_sink(grid, x, y, i, true, 0.25, fall_into_void, rainbow_sand_times) {
+                    return;
+                }
+            }
+            
             // Gunpowder falls with gravity
-            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::Wax => {
             // Wax is static, but can burn and turn into falling wax
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.01) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.wax_burn_chance) {
                 if let Some(_fire_loc) = bordering(grid, x, y, i, Element::Fire) {
                     // Wax burns - turn to falling wax
                     grid.set_index(i, Element::FallingWax);
@@ -1396,7 +2303,7 @@ pub fn execute_element_action(
         }
         Element::FallingWax => {
             // Falling wax falls with gravity (no diagonal), then turns back to wax
-            if do_gravity(grid, x, y, i, false, 1.0, fall_into_void, rainbow_sand_times) {
+            if do_gravity(grid, x, y, i, false, 1.0, fall_into_void, rainbow_sand_times, world_wrap) {
                 return;
             }
             // If it stopped falling, turn back to wax
@@ -1404,41 +2311,18 @@ pub fn execute_element_action(
         }
         Element::ChilledIce => {
             // Chilled ice thaws back to regular ice (6% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.06) {
-                grid.set_index(i, Element::Ice);
-                return;
-            }
-            
-            // Thaw immediately if bordering salt, salt water, lava, fire, or steam
-            if let Some(_) = bordering(grid, x, y, i, Element::Salt) {
-                grid.set_index(i, Element::Ice);
-                return;
-            }
-            if let Some(_) = bordering(grid, x, y, i, Element::SaltWater) {
-                grid.set_index(i, Element::Ice);
-                return;
-            }
-            if let Some(_) = bordering(grid, x, y, i, Element::Lava) {
-                grid.set_index(i, Element::Ice);
-                return;
-            }
-            if let Some(_) = bordering(grid, x, y, i, Element::Fire) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.chilled_ice_thaw_chance) {
                 grid.set_index(i, Element::Ice);
-                return;
-            }
-            if let Some(_) = bordering(grid, x, y, i, Element::Steam) {
-                grid.set_index(i, Element::Ice);
-                return;
             }
         }
         Element::Mystery => {
             // Mystery element - falls with gravity, special interactions
             // For now, simplified - just falls (particle effects would be added later)
-            let mut rng = rand::thread_rng();
+            let mut rng = sim_rng();
             
             // Reduce computation cost (50% chance to skip)
-            if rng.gen_bool(0.50) {
+            if rng.gen_bool(rules.mystery_skip_chance) {
                 return;
             }
             
@@ -1459,26 +2343,17 @@ pub fn execute_element_action(
             }
             
             // Falls with gravity
-            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::ChargedNitro => {
             // Charged nitro - falls with gravity, sinks through lighter elements, explodes on fire
-            if do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times) {
+            if do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap) {
                 return;
             }
             
             // Sink through lighter elements
             if y < grid.max_y() {
-                if do_density_sink(grid, x, y, i, Element::Soil, true, 0.25, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::WetSoil, true, 0.25, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::Nitro, true, 0.25, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::Pollen, true, 0.25, fall_into_void, rainbow_sand_times) {
+                if do_density_sink(grid, x, y, i, true, 0.25, fall_into_void, rainbow_sand_times) {
                     return;
                 }
             }
@@ -1500,7 +2375,7 @@ pub fn execute_element_action(
         }
         Element::BurningThermite => {
             // Burning thermite - burns adjacent elements, can create charged nitro, consumes itself, burns through walls
-            let mut rng = rand::thread_rng();
+            let mut rng = sim_rng();
             
             // Burn adjacent elements (up, left, right) - except thermite, burning thermite, lava, wall
             if y > 0 {
@@ -1527,8 +2402,8 @@ pub fn execute_element_action(
                 }
             }
             
-            // Chance to create charged nitro explosion (2% * 7% = 0.14% chance)
-            if rng.gen_bool(0.02) && rng.gen_bool(0.07) {
+            // Chance to create charged nitro explosion (0.14% chance)
+            if rng.gen_bool(rules.burning_thermite_nitro_chance) {
                 // Create CHARGED_NITRO_PARTICLE (matches TypeScript: particles.addActiveParticle(CHARGED_NITRO_PARTICLE, x, y, i))
                 if let Some(plist) = particle_list {
                     plist.add_active_particle(
@@ -1543,59 +2418,50 @@ pub fn execute_element_action(
             }
             
             // Chance to consume itself (2% chance)
-            if rng.gen_bool(0.02) {
+            if rng.gen_bool(rules.burning_thermite_consume_chance) {
                 grid.set_index(i, Element::Fire);
                 return;
             }
             
             // Burn through walls (8% chance)
-            if rng.gen_bool(0.08) {
+            if rng.gen_bool(rules.burning_thermite_wall_chance) {
                 // Check adjacent walls
-                if let Some(wall_loc) = adjacent(grid, x, i, Element::Wall) {
+                if let Some(wall_loc) = adjacent(grid, x, i, Element::Wall, world_wrap) {
                     grid.set_index(wall_loc, Element::Background);
                 }
-                if let Some(wall_loc) = below(grid, y, i, Element::Wall) {
+                if let Some(wall_loc) = below(grid, y, i, Element::Wall, world_wrap) {
                     grid.set_index(wall_loc, Element::Background);
                 }
             }
             
             // Clear fire below (to allow falling through)
-            if let Some(fire_loc) = below(grid, y, i, Element::Fire) {
+            if let Some(fire_loc) = below(grid, y, i, Element::Fire, world_wrap) {
                 grid.set_index(fire_loc, Element::Background);
             }
             
             // Falls with gravity
-            if do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times) {
+            if do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times, world_wrap) {
                 return;
             }
             
             // Sink through liquids
             if y < grid.max_y() {
-                if do_density_sink(grid, x, y, i, Element::Water, false, 0.95, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::SaltWater, false, 0.95, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::Oil, false, 0.95, fall_into_void, rainbow_sand_times) {
+                if do_density_sink(grid, x, y, i, false, 0.95, fall_into_void, rainbow_sand_times) {
                     return;
                 }
             }
         }
         Element::Concrete => {
-            // Concrete can sink through water and salt water
+            // Concrete can sink through lighter liquids
             if y < grid.max_y() {
-                if do_density_sink(grid, x, y, i, Element::Water, true, 0.35, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::SaltWater, true, 0.35, fall_into_void, rainbow_sand_times) {
+                if do_density_sink(grid, x, y, i, true, 0.35, fall_into_void, rainbow_sand_times) {
                     return;
                 }
             }
             
-            // Concrete hardens to wall when next to wall (10% * 10% = 1% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.10) && rng.gen_bool(0.10) {
+            // Concrete hardens to wall when next to wall (1% chance)
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.concrete_wall_harden_chance) {
                 if let Some(_wall_loc) = bordering_adjacent(grid, x, y, i, Element::Wall) {
                     grid.set_index(i, Element::Wall);
                     return;
@@ -1603,18 +2469,18 @@ pub fn execute_element_action(
             }
             
             // Concrete falls with gravity
-            if do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times) {
+            if do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap) {
                 return;
             }
             
-            // Concrete can harden on its own (10% * 10% * 5% = 0.05% chance)
-            if rng.gen_bool(0.10) && rng.gen_bool(0.10) && rng.gen_bool(0.05) {
+            // Concrete can harden on its own (0.05% chance)
+            if rng.gen_bool(rules.concrete_harden_chance) {
                 grid.set_index(i, Element::Wall);
             }
         }
         Element::Nitro => {
-            // Nitro falls with gravity
-            if do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times) {
+            // Nitro falls with gravity (95% chance)
+            if do_viscous_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap) {
                 return;
             }
             
@@ -1624,9 +2490,11 @@ pub fn execute_element_action(
             }
             
             // Nitro explodes when touched by fire (30% chance)
-            let mut rng = rand::thread_rng();
+            let mut rng = sim_rng();
             if let Some(_fire_loc) = bordering_adjacent(grid, x, y, i, Element::Fire) {
-                if rng.gen_bool(0.30) {
+                if rng.gen_bool(rules.nitro_explode_chance) {
+                    emit_shockwave(grid, x, y, 6, 5.0);
+                    
                     // Create border burn (set surrounding pixels on fire)
                     if y > 0 {
                         let above_idx = i.saturating_sub(grid.width as usize);
@@ -1654,7 +2522,7 @@ pub fn execute_element_action(
                     }
                     grid.set_index(i, Element::Fire);
                     return;
-                } else if rng.gen_bool(0.20) {
+                } else if rng.gen_bool(rules.nitro_burn_chance) {
                     grid.set_index(i, Element::Fire);
                     return;
                 }
@@ -1662,24 +2530,15 @@ pub fn execute_element_action(
             
             // Nitro sinks through lighter liquids and pollen
             if y < grid.max_y() {
-                if do_density_sink(grid, x, y, i, Element::Oil, true, 0.25, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::Water, true, 0.25, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::SaltWater, true, 0.25, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::Pollen, true, 0.25, fall_into_void, rainbow_sand_times) {
+                if do_density_sink(grid, x, y, i, true, 0.25, fall_into_void, rainbow_sand_times) {
                     return;
                 }
             }
         }
         Element::Napalm => {
             // Napalm catches fire (25% chance) - create spreading fire particles (NAPALM_PARTICLE effect)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.25) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.napalm_ignite_chance) {
                 if let Some(_fire_loc) = bordering(grid, x, y, i, Element::Fire) {
                     // Create NAPALM_PARTICLE (matches TypeScript: particles.addActiveParticle(NAPALM_PARTICLE, x, y, i))
                     if let Some(plist) = particle_list {
@@ -1699,16 +2558,19 @@ pub fn execute_element_action(
                 }
             }
             
-            // Napalm falls with gravity
-            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            // Napalm falls with gravity (95% chance)
+            do_viscous_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::C4 => {
             // C4 explodes when touched by fire (60% chance) - create large expanding explosion (C4_PARTICLE effect)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.60) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.c4_ignite_chance) {
                 if let Some(_fire_loc) = bordering(grid, x, y, i, Element::Fire) {
+                    emit_shockwave(grid, x, y, 10, 10.0);
+                    
                     // Create C4_PARTICLE (matches TypeScript: particles.addActiveParticle(C4_PARTICLE, x, y, i))
                     if let Some(plist) = particle_list {
+                        spawn_debris(plist, x, y, i, Element::C4);
                         if plist.add_active_particle(
                             crate::particles::ParticleType::C4,
                             x as f32,
@@ -1726,14 +2588,20 @@ pub fn execute_element_action(
             }
             // C4 is static (doesn't fall)
         }
+        Element::Nuke => {
+            // Nuke is static and detonates as soon as fire touches it
+            if bordering(grid, x, y, i, Element::Fire).is_some() {
+                detonate_nuke(grid, x, y, i, particle_list);
+            }
+        }
         Element::Fuse => {
             // Fuse is static (doesn't fall)
             // Fire spreads to it (handled in fire action)
         }
         Element::Acid => {
             // Acid dissolves bordering elements (10% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.10) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.acid_dissolve_chance) {
                 // Check up, down, left, right (not corners)
                 let positions = [
                     if y > 0 { Some(i.saturating_sub(grid.width as usize)) } else { None },
@@ -1755,14 +2623,19 @@ pub fn execute_element_action(
                     if let Some(pos) = pos_opt {
                         if *pos < grid.elements.len() {
                             let elem = grid.get_index(*pos);
-                            // Acid immune elements: Acid, Background, Water, SaltWater, Ice, Steam
+                            // Acid immune elements: Acid, Background, Water, SaltWater, Ice, Steam, Slime, HardWall
                             let can_dissolve = !matches!(
                                 elem,
                                 Element::Acid | Element::Background | Element::Water
                                     | Element::SaltWater | Element::Ice | Element::ChilledIce | Element::Steam | Element::Cryo
+                                    | Element::Slime | Element::HardWall
                             );
                             
                             if can_dissolve {
+                                // Tough cells take several bites before they dissolve
+                                if !grid.damage(*pos, ACID_DAMAGE) {
+                                    return;
+                                }
                                 // If dissolving something above or to the side, just remove it
                                 if *pos != i + grid.width as usize {
                                     grid.set_index(*pos, Element::Background);
@@ -1770,7 +2643,7 @@ pub fn execute_element_action(
                                 } else {
                                     // If dissolving something below, move acid down (75% chance for wall)
                                     grid.set_index(i, Element::Background);
-                                    if elem != Element::Wall || rng.gen_bool(0.75) {
+                                    if elem != Element::Wall || rng.gen_bool(rules.acid_wall_follow_chance) {
                                         grid.set_index(*pos, Element::Acid);
                                     }
                                     return;
@@ -1782,19 +2655,16 @@ pub fn execute_element_action(
             }
             
             // Acid can mix with water/salt water
-            if do_density_liquid(grid, x, y, i, Element::Water, 0.25, 0.30) {
-                return;
-            }
-            if do_density_liquid(grid, x, y, i, Element::SaltWater, 0.25, 0.30) {
+            if do_density_liquid(grid, x, y, i, 0.25, 0.30, rainbow_sand_times) {
                 return;
             }
             
             // Acid falls with gravity (100% chance)
-            do_gravity(grid, x, y, i, true, 1.0, fall_into_void, rainbow_sand_times);
+            do_viscous_gravity(grid, x, y, i, true, 1.0, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::Cryo => {
             // Cryo freezes things and falls with gravity
-            let mut rng = rand::thread_rng();
+            let mut rng = sim_rng();
             
             // Freeze surrounding surfaces
             let x_start = x.saturating_sub(1);
@@ -1822,9 +2692,9 @@ pub fn execute_element_action(
                         return;
                     }
                     
-                    // Freeze ice - can create chilled ice (1% * 5% = 0.05% chance)
+                    // Freeze ice - can create chilled ice (0.05% chance)
                     if elem == Element::Ice {
-                        if rng.gen_bool(0.01) && rng.gen_bool(0.05) {
+                        if rng.gen_bool(rules.cryo_chill_chance) {
                             grid.set_index(idx, Element::ChilledIce);
                             grid.set_index(i, Element::ChilledIce);
                         } else {
@@ -1850,10 +2720,10 @@ pub fn execute_element_action(
             }
             
             // Cryo falls with gravity
-            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap);
             
-            // Can freeze even if no nearby freezable surfaces (1% * 50% = 0.5% chance)
-            if rng.gen_bool(0.01) && rng.gen_bool(0.50) {
+            // Can freeze even if no nearby freezable surfaces (0.5% chance)
+            if rng.gen_bool(rules.cryo_freeze_chance) {
                 if bordering(grid, x, y, i, Element::Background).is_none() && !surrounded_by(grid, x, y, i, Element::Cryo) {
                     grid.set_index(i, Element::Ice);
                 }
@@ -1861,7 +2731,7 @@ pub fn execute_element_action(
         }
         Element::Methane => {
             // Methane is a flammable gas that rises
-            let mut rng = rand::thread_rng();
+            let mut rng = sim_rng();
             
             // Check if there's a methane particle nearby (for chain reaction spreading)
             // This allows fire to propagate through methane clouds
@@ -1869,26 +2739,16 @@ pub fn execute_element_action(
             let mut should_create_particle = false;
             if let Some(plist) = particle_list {
                 let check_radius = 8.0; // Reduced from 15 to 8 pixels for slower propagation
-                let active_indices = plist.active_particles();
-                for &particle_idx in active_indices {
-                    if let Some(particle) = plist.get_particle(particle_idx) {
-                        if particle.particle_type == crate::particles::ParticleType::Methane {
-                            let dx = particle.x - x as f32;
-                            let dy = particle.y - y as f32;
-                            let dist_sq = dx * dx + dy * dy;
-                            if dist_sq <= check_radius * check_radius {
-                                // Add probability to slow down propagation (50% chance)
-                                if rng.gen_bool(0.5) {
-                                    should_create_particle = true;
-                                    break;
-                                }
-                            }
-                        }
+                for particle in plist.query_radius(x as f32, y as f32, check_radius) {
+                    // Add probability to slow down propagation (50% chance)
+                    if particle.particle_type == ParticleType::Methane && rng.gen_bool(rules.methane_chain_chance) {
+                        should_create_particle = true;
+                        break;
                     }
                 }
                 
                 // Also check if methane touches fire (25% chance)
-                if !should_create_particle && rng.gen_bool(0.25) {
+                if !should_create_particle && rng.gen_bool(rules.methane_ignite_chance) {
                     if let Some(_fire_loc) = bordering(grid, x, y, i, Element::Fire) {
                         should_create_particle = true;
                     }
@@ -1911,7 +2771,7 @@ pub fn execute_element_action(
                 }
             } else {
                 // No particle_list available, fall back to simple fire conversion
-                if rng.gen_bool(0.25) {
+                if rng.gen_bool(rules.methane_ignite_chance) {
                     if let Some(_fire_loc) = bordering(grid, x, y, i, Element::Fire) {
                         grid.set_index(i, Element::Fire);
                         return;
@@ -1919,14 +2779,14 @@ pub fn execute_element_action(
                 }
             }
             
-            // Methane rises (25% chance, 65% adjacent)
-            if do_rise(grid, x, y, i, 0.25, 0.65, fall_into_void) {
+            // Methane rises (25% chance, 65% adjacent), drifting with the wind and faster in an updraft
+            if do_rise(grid, x, y, i, lifted(0.25, lift), 0.65, fall_into_void, drift, world_wrap) {
                 return;
             }
             
             // Methane can pass through gas-permeable elements (70% chance)
             // Simplified: just check for common permeable elements
-            if rng.gen_bool(0.70) {
+            if rng.gen_bool(rules.methane_permeate_chance) {
                 if y > 0 {
                     let above_idx = i.saturating_sub(grid.width as usize);
                     let above_elem = grid.get_index(above_idx);
@@ -1936,6 +2796,11 @@ pub fn execute_element_action(
                         Element::Sand | Element::Water | Element::Salt | Element::SaltWater
                             | Element::Oil | Element::Gunpowder | Element::Concrete | Element::Rock
                     ) {
+                        // Methane rising into water can form a bubble instead (2% chance)
+                        if above_elem == Element::Water && rng.gen_bool(rules.methane_bubble_chance) {
+                            grid.set_index(i, Element::Bubble);
+                            return;
+                        }
                         grid.set_index(above_idx, Element::Methane);
                         grid.set_index(i, above_elem);
                         return;
@@ -1945,26 +2810,20 @@ pub fn execute_element_action(
         }
         Element::Soil => {
             // Soil falls with gravity (no diagonal)
-            if do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times) {
+            if do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times, world_wrap) {
                 return;
             }
             
             // Soil can sink through lighter elements
             if y < grid.max_y() {
-                if do_density_sink(grid, x, y, i, Element::Water, true, 0.50, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::SaltWater, true, 0.50, fall_into_void, rainbow_sand_times) {
-                    return;
-                }
-                if do_density_sink(grid, x, y, i, Element::Pollen, true, 0.50, fall_into_void, rainbow_sand_times) {
+                if do_density_sink(grid, x, y, i, true, 0.50, fall_into_void, rainbow_sand_times) {
                     return;
                 }
             }
             
             // Soil transforms nitro to charged nitro (25% chance, 100% of the time)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.25) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.soil_charge_chance) {
                 if let Some(nitro_loc) = bordering_adjacent(grid, x, y, i, Element::Nitro) {
                     grid.set_index(nitro_loc, Element::ChargedNitro);
                     return;
@@ -1973,8 +2832,8 @@ pub fn execute_element_action(
             
             // Soil absorbs water above (15% chance) to become wet soil
             // Just convert soil to wet soil, no tree creation here (trees come from wet soil later)
-            if rng.gen_bool(0.15) {
-                if let Some(water_loc) = above_adjacent(grid, x, y, i, Element::Water) {
+            if rng.gen_bool(rules.soil_absorb_chance) {
+                if let Some(water_loc) = above_adjacent(grid, x, y, i, Element::Water, world_wrap) {
                     grid.set_index(water_loc, Element::Background);
                     grid.set_index(i, Element::WetSoil);
                     return;
@@ -1982,32 +2841,38 @@ pub fn execute_element_action(
             }
         }
         Element::WetSoil => {
+            // Water landing on wet soil turns both into mud (3% chance)
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.wet_soil_mud_chance) {
+                if let Some(water_loc) = above(grid, y, i, Element::Water, world_wrap) {
+                    grid.set_index(water_loc, Element::Mud);
+                    grid.set_index(i, Element::Mud);
+                    return;
+                }
+            }
+            
             // Wet soil can absorb more water (15% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.15) {
-                if let Some(water_loc) = above_adjacent(grid, x, y, i, Element::Water) {
+            if rng.gen_bool(rules.wet_soil_absorb_chance) {
+                if let Some(water_loc) = above_adjacent(grid, x, y, i, Element::Water, world_wrap) {
                     grid.set_index(water_loc, Element::Background);
                 }
             }
             
             // Wet soil falls with gravity (no diagonal)
-            if do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times) {
+            if do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times, world_wrap) {
                 return;
             }
             
             // Wet soil can sink through lighter elements
-            if do_density_sink(grid, x, y, i, Element::Water, true, 0.50, fall_into_void, rainbow_sand_times) {
-                return;
-            }
-            if do_density_sink(grid, x, y, i, Element::SaltWater, true, 0.50, fall_into_void, rainbow_sand_times) {
+            if do_density_sink(grid, x, y, i, true, 0.50, fall_into_void, rainbow_sand_times) {
                 return;
             }
             
             // Wet soil can generate trees or dry to soil
             // In TypeScript: if (random() < 5) { if (random() < 97) { dry to soil } else { try tree } }
             // Tree generation: 5% chance, then 3% of that time (not 97%), then 65% after that
-            if rng.gen_bool(0.05) {
-                if rng.gen_bool(0.97) {
+            if rng.gen_bool(rules.wet_soil_change_chance) {
+                if rng.gen_bool(rules.wet_soil_dry_chance) {
                     // 97% of the time: dry to soil (if no water adjacent)
                     if bordering_adjacent(grid, x, y, i, Element::Water).is_none() {
                         grid.set_index(i, Element::Soil);
@@ -2016,7 +2881,7 @@ pub fn execute_element_action(
                 } else {
                     // 3% of the time: try tree generation
                     // Make tree generation less likely (35% chance to skip)
-                    if rng.gen_bool(0.35) {
+                    if rng.gen_bool(rules.wet_soil_tree_skip_chance) {
                         return; // Skip tree generation
                     }
                     
@@ -2024,9 +2889,9 @@ pub fn execute_element_action(
                     // Check conditions: space above (any of the 3 positions), and soil or wall below (any of the 3 positions)
                     // TypeScript: aboveAdjacent checks directly above, above-left, above-right
                     // TypeScript: belowAdjacent checks directly below, below-left, below-right
-                    if let Some(_) = above_adjacent(grid, x, y, i, Element::Background) {
-                        let below_soil = below_adjacent(grid, x, y, i, Element::Soil);
-                        let below_wall = below_adjacent(grid, x, y, i, Element::Wall);
+                    if let Some(_) = above_adjacent(grid, x, y, i, Element::Background, world_wrap) {
+                        let below_soil = below_adjacent(grid, x, y, i, Element::Soil, world_wrap);
+                        let below_wall = below_adjacent(grid, x, y, i, Element::Wall, world_wrap);
                         if below_soil.is_some() || below_wall.is_some() {
                             // Start tree generation using grid-based approach
                             if let Some(active_branches) = active_branches {
@@ -2047,8 +2912,8 @@ pub fn execute_element_action(
             }
             
             // Thermite turns to burning thermite when near fire (50% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.50) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.thermite_ignite_chance) {
                 if let Some(_fire_loc) = bordering_adjacent(grid, x, y, i, Element::Fire) {
                     // Use the BurningThermite element we already have
                     grid.set_index(i, Element::BurningThermite);
@@ -2057,18 +2922,12 @@ pub fn execute_element_action(
             }
             
             // Thermite sinks through liquids
-            if do_density_sink(grid, x, y, i, Element::Water, false, 0.95, fall_into_void, rainbow_sand_times) {
-                return;
-            }
-            if do_density_sink(grid, x, y, i, Element::SaltWater, false, 0.95, fall_into_void, rainbow_sand_times) {
-                return;
-            }
-            if do_density_sink(grid, x, y, i, Element::Oil, false, 0.95, fall_into_void, rainbow_sand_times) {
+            if do_density_sink(grid, x, y, i, false, 0.95, fall_into_void, rainbow_sand_times) {
                 return;
             }
             
             // Thermite falls with gravity (no diagonal, 99% chance)
-            do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times);
+            do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::Spout => {
             // Spout produces water (5% chance, doesn't overwrite)
@@ -2084,8 +2943,8 @@ pub fn execute_element_action(
         }
         Element::Branch => {
             // Branch is static, burns when touched by fire (3% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.03) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.branch_burn_chance) {
                 if let Some(_fire_loc) = bordering_adjacent(grid, x, y, i, Element::Fire) {
                     grid.set_index(i, Element::Fire);
                     return;
@@ -2094,8 +2953,8 @@ pub fn execute_element_action(
         }
         Element::Leaf => {
             // Leaf is static, burns when touched by fire (5% chance)
-            let mut rng = rand::thread_rng();
-            if rng.gen_bool(0.05) {
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.leaf_burn_chance) {
                 if let Some(_fire_loc) = bordering_adjacent(grid, x, y, i, Element::Fire) {
                     grid.set_index(i, Element::Fire);
                     return;
@@ -2103,34 +2962,275 @@ pub fn execute_element_action(
             }
             
             // Leaf dies from salt (20% chance)
-            if rng.gen_bool(0.20) {
+            if rng.gen_bool(rules.leaf_salt_death_chance) {
                 if let Some(_salt_loc) = bordering_adjacent(grid, x, y, i, Element::Salt) {
                     grid.set_index(i, Element::Background);
                     return;
                 }
             }
             
-            // Leaf produces pollen (1% * 9% = 0.09% chance)
-            if rng.gen_bool(0.01) && rng.gen_bool(0.09) {
+            // Leaf produces pollen (0.09% chance)
+            if rng.gen_bool(rules.leaf_pollen_chance) {
                 do_producer(grid, x, y, i, Element::Pollen, false, 1.0);
             }
         }
         Element::Pollen => {
+            // Pollen is light enough to be blown around (30% of the full wind strength)
+            if do_wind_drift(grid, x, y, i, drift * 0.3, lift * 0.3) {
+                return;
+            }
+            
             // Pollen falls with gravity
-            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times, world_wrap);
         }
         Element::RainbowSand => {
             // RainbowSand behaves like sand - can sink through liquids and falls with gravity
             if y < grid.max_y() {
-                if do_density_sink(grid, x, y, i, Element::Water, true, 0.25, fall_into_void, rainbow_sand_times) {
+                if do_density_sink(grid, x, y, i, true, rules.sand_sink_chance, fall_into_void, rainbow_sand_times) {
                     return;
                 }
-                if do_density_sink(grid, x, y, i, Element::SaltWater, true, 0.25, fall_into_void, rainbow_sand_times) {
+            }
+            // RainbowSand falls with gravity, can fall diagonally (fall_adjacent = true)
+            do_gravity(grid, x, y, i, true, rules.sand_gravity_chance, fall_into_void, rainbow_sand_times, world_wrap);
+        }
+        Element::Clone => {
+            // Clone is static and never consumed, duplicates a neighboring material (5% chance)
+            let mut rng = sim_rng();
+            if !rng.gen_bool(rules.clone_chance) {
+                return;
+            }
+
+            // Nothing to do without an empty neighbor to clone into
+            if bordering_adjacent(grid, x, y, i, Element::Background).is_none() {
+                return;
+            }
+
+            // Collect source cells from all 8 neighbors (ignore Background, Clone and Wall)
+            let mut sources: Vec<(usize, Element)> = Vec::with_capacity(8);
+            let x_start = x.saturating_sub(1);
+            let y_start = y.saturating_sub(1);
+            let x_end = (x + 2).min(grid.max_x() + 1);
+            let y_end = (y + 2).min(grid.max_y() + 1);
+
+            for y_iter in y_start..y_end {
+                for x_iter in x_start..x_end {
+                    if y_iter == y && x_iter == x {
+                        continue;
+                    }
+                    let idx = grid.xy_to_index(x_iter, y_iter);
+                    let elem = grid.get_index(idx);
+                    if !matches!(elem, Element::Background | Element::Clone | Element::Wall) {
+                        sources.push((idx, elem));
+                    }
+                }
+            }
+
+            if sources.is_empty() {
+                return;
+            }
+
+            // Pick one source type at random (each distinct type is equally likely)
+            let mut source_types: Vec<Element> = Vec::with_capacity(sources.len());
+            for &(_, elem) in &sources {
+                if !source_types.contains(&elem) {
+                    source_types.push(elem);
+                }
+            }
+            let source = source_types[rng.gen_range(0..source_types.len())];
+
+            if let Some(new_idx) = place_random_neighbor(grid, x, y, source) {
+                // Cloned RainbowSand keeps the color of the cell it was copied from
+                if let Some(times) = rainbow_sand_times.as_mut() {
+                    if source == Element::RainbowSand {
+                        let source_time = sources
+                            .iter()
+                            .find(|&&(_, elem)| elem == Element::RainbowSand)
+                            .and_then(|&(idx, _)| times.get(&idx).copied());
+                        if let Some(placement_time) = source_time {
+                            times.insert(new_idx, placement_time);
+                        }
+                    }
+                }
+            }
+        }
+        Element::Bubble => {
+            // Bubble pops near heat or at the surface
+            if do_bubble_pop(grid, x, y, i, rules) {
+                return;
+            }
+            
+            // Bubble rises through water and oil (90% chance)
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.bubble_rise_chance) {
+                let liquid_loc = above_adjacent(grid, x, y, i, Element::Water, world_wrap)
+                    .or_else(|| above_adjacent(grid, x, y, i, Element::Oil, world_wrap));
+                if let Some(liquid_idx) = liquid_loc {
+                    let liquid = grid.get_index(liquid_idx);
+                    grid.set_index(liquid_idx, Element::Bubble);
+                    grid.set_index(i, liquid);
                     return;
                 }
             }
-            // RainbowSand falls with gravity, can fall diagonally (fall_adjacent = true)
-            do_gravity(grid, x, y, i, true, 0.95, fall_into_void, rainbow_sand_times);
+            
+            // Out of the liquid, bubble rises like steam
+            do_rise(grid, x, y, i, 0.90, 0.60, fall_into_void, 0.0, world_wrap);
+        }
+        Element::Virus => {
+            // Virus ages every tick and dies of old age
+            let age = grid.virus_age[i].saturating_add(1);
+            grid.virus_age[i] = age;
+            if age >= VIRUS_LIFETIME {
+                grid.virus_age[i] = 0;
+                grid.set_index(i, Element::Background);
+                return;
+            }
+            
+            // Virus infects one random neighbor (15% chance)
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.virus_infect_chance) {
+                let mut targets = Vec::with_capacity(8);
+                let x_start = x.saturating_sub(1);
+                let y_start = y.saturating_sub(1);
+                let x_end = (x + 2).min(grid.max_x() + 1);
+                let y_end = (y + 2).min(grid.max_y() + 1);
+                
+                for y_iter in y_start..y_end {
+                    for x_iter in x_start..x_end {
+                        if y_iter == y && x_iter == x {
+                            continue;
+                        }
+                        let idx = grid.xy_to_index(x_iter, y_iter);
+                        // Virus immune elements: Background, Wall, HardWall, Acid, Cryo (and other viruses)
+                        if !matches!(
+                            grid.get_index(idx),
+                            Element::Background | Element::Wall | Element::HardWall | Element::Acid | Element::Cryo | Element::Virus
+                        ) {
+                            targets.push(idx);
+                        }
+                    }
+                }
+                
+                if !targets.is_empty() {
+                    let target = targets[rng.gen_range(0..targets.len())];
+                    if let Some(times) = rainbow_sand_times.as_mut() {
+                        times.remove(&target);
+                    }
+                    grid.set_index(target, Element::Virus);
+                    grid.virus_age[target] = 0;
+                }
+            }
+        }
+        Element::Portal => {
+            // Portal is static, elements moving into it come out of its pair (see `enter_portal`)
+        }
+        Element::Mud => {
+            // Mud dries to soil when no water is around (1% chance)
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.mud_dry_chance) && bordering_adjacent(grid, x, y, i, Element::Water).is_none() {
+                grid.set_index(i, Element::Soil);
+                return;
+            }
+            
+            // Mud falls slowly, its viscosity of 0.40 is the whole fall chance
+            // Like every liquid it can slide diagonally, which lets it spread out as it settles
+            if do_viscous_gravity(grid, x, y, i, true, 1.0, fall_into_void, rainbow_sand_times, world_wrap) {
+                return;
+            }
+            
+            // Mud spreads sideways sluggishly (15% chance)
+            if rng.gen_bool(rules.mud_spread_chance) {
+                if let Some(spread_loc) = adjacent(grid, x, i, Element::Background, world_wrap) {
+                    grid.swap_cells(i, spread_loc);
+                    return;
+                }
+            }
+        }
+        Element::Magma => {
+            // Magma is too dense to react with water, it just sinks through it (60% chance)
+            if y < grid.max_y() {
+                if do_density_sink(grid, x, y, i, false, 0.60, fall_into_void, rainbow_sand_times) {
+                    return;
+                }
+            }
+            
+            // Magma erupts, pushing lava out above it (0.1% chance)
+            let mut rng = sim_rng();
+            if rng.gen_bool(rules.magma_erupt_chance) {
+                if let Some(above_idx) = above(grid, y, i, Element::Background, world_wrap) {
+                    grid.set_index(above_idx, Element::Lava);
+                    return;
+                }
+            }
+            
+            // Magma slowly solidifies into rock (0.01% chance)
+            if rng.gen_bool(rules.magma_cool_chance) {
+                grid.set_index(i, Element::Rock);
+                return;
+            }
+            
+            // Magma falls with gravity (99% chance, no diagonal falling)
+            do_gravity(grid, x, y, i, false, 0.99, fall_into_void, rainbow_sand_times, world_wrap);
+        }
+        Element::Quicksand => {
+            // Quicksand falls like sand (no diagonal), lighter elements rest on top of it
+            do_gravity(grid, x, y, i, false, 0.95, fall_into_void, rainbow_sand_times, world_wrap);
+        }
+        Element::Catalyst => {
+            // Catalyst is static and never reacts (speeds up reactions of its neighbors)
+        }
+        Element::Slime => {
+            // Slime resists fire for a couple of ticks, then ignites
+            if bordering_adjacent(grid, x, y, i, Element::Fire).is_some() {
+                let ticks = grid.slime_fire_immunity[i].saturating_add(1);
+                if ticks > SLIME_FIRE_IMMUNITY_TICKS {
+                    grid.slime_fire_immunity[i] = 0;
+                    grid.set_index(i, Element::Fire);
+                    return;
+                }
+                grid.slime_fire_immunity[i] = ticks;
+            } else {
+                grid.slime_fire_immunity[i] = 0;
+            }
+            
+            let mut rng = sim_rng();
+            
+            // Slime hanging from a ceiling drips down slowly (20% chance, about one cell every 5 ticks)
+            if above(grid, y, i, Element::Wall, world_wrap).is_some() {
+                if let Some(below_idx) = below(grid, y, i, Element::Background, world_wrap) {
+                    if rng.gen_bool(rules.slime_drip_chance) {
+                        grid.swap_cells(i, below_idx);
+                    }
+                    return;
+                }
+            }
+            
+            // Slime sticks to walls (80% chance)
+            if rng.gen_bool(rules.slime_stick_chance) && bordering_adjacent(grid, x, y, i, Element::Wall).is_some() {
+                return;
+            }
+            
+            // Slime falls slowly, its viscosity of 0.15 is the whole fall chance
+            // Like every liquid it can slide diagonally, which lets it spread out as it settles
+            if do_viscous_gravity(grid, x, y, i, true, 1.0, fall_into_void, rainbow_sand_times, world_wrap) {
+                return;
+            }
+            
+            // Slime spreads sideways slowly (8% chance)
+            if rng.gen_bool(rules.slime_spread_chance) {
+                if let Some(spread_loc) = adjacent(grid, x, i, Element::Background, world_wrap) {
+                    grid.swap_cells(i, spread_loc);
+                    return;
+                }
+            }
+        }
+        Element::Honey => {
+            // Honey oozes down very slowly, its viscosity of 0.1 is the whole fall chance
+            if do_viscous_gravity(grid, x, y, i, true, 1.0, fall_into_void, rainbow_sand_times, world_wrap) {
+                return;
+            }
+            
+            // Honey sinks through lighter liquids
+            do_density_liquid(grid, x, y, i, 0.05, 0.02, rainbow_sand_times);
         }
     }
 }
@@ -0,0 +1,286 @@
+use bevy::prelude::*;
+use std::path::Path;
+
+/// Where rules are read from at startup, and watched for edits while running
+pub const RULES_CONFIG_PATH: &str = "config/rules.toml";
+
+/// Tunable per-tick probabilities used by `execute_element_action`
+/// Defaults match the original hardcoded behavior
+/// Fields missing from a rules file keep their defaults
+#[derive(Resource, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SimulationRules {
+    pub sand_gravity_chance: f64,
+    pub sand_sink_chance: f64,
+    pub sand_quicksand_chance: f64,
+    pub water_gravity_chance: f64,
+    pub water_sink_chance: f64,
+    pub water_equalize_chance: f64,
+    pub water_capillary_chance: f64,
+    pub rock_gravity_chance: f64,
+    pub rock_sink_chance: f64,
+    pub rock_methane_chance: f64,
+    pub salt_dissolve_chance: f64,
+    pub fire_extinguish_chance: f64,
+    pub fire_spread_to_plant: f64,
+    pub fire_spread_to_fuse: f64,
+    pub fire_spread_to_branch: f64,
+    pub fire_spread_to_leaf: f64,
+    pub fire_spread_to_wax: f64,
+    pub fire_spread_to_oil: f64,
+    pub fire_rise_chance: f64,
+    pub fire_flameout_chance: f64,
+    pub fire_oil_sustain_chance: f64,
+    pub oil_ignite_chance: f64,
+    pub gunpowder_ignite_chance: f64,
+    pub gunpowder_burn_chance: f64,
+    pub gunpowder_blast_chance: f64,
+    pub gunpowder_chain_chance: f64,
+    pub lava_burn_chance: f64,
+    pub lava_fire_chance: f64,
+    pub lava_steam_sink_chance: f64,
+    pub steam_rise_chance: f64,
+    pub steam_condense_chance: f64,
+    pub steam_cool_chance: f64,
+    pub steam_rain_chance: f64,
+    pub steam_spout_condense_chance: f64,
+    pub steam_vanish_chance: f64,
+    pub ice_salt_melt_chance: f64,
+    pub plant_growth_chance: f64,
+    pub plant_salt_death_chance: f64,
+    pub wax_burn_chance: f64,
+    pub chilled_ice_thaw_chance: f64,
+    pub mystery_skip_chance: f64,
+    pub thermite_ignite_chance: f64,
+    pub burning_thermite_nitro_chance: f64,
+    pub burning_thermite_consume_chance: f64,
+    pub burning_thermite_wall_chance: f64,
+    pub concrete_wall_harden_chance: f64,
+    pub concrete_harden_chance: f64,
+    pub nitro_explode_chance: f64,
+    pub nitro_burn_chance: f64,
+    pub napalm_ignite_chance: f64,
+    pub c4_ignite_chance: f64,
+    pub c4_fire_chance: f64,
+    pub acid_dissolve_chance: f64,
+    pub acid_wall_follow_chance: f64,
+    pub cryo_chill_chance: f64,
+    pub cryo_freeze_chance: f64,
+    pub methane_chain_chance: f64,
+    pub methane_ignite_chance: f64,
+    pub methane_permeate_chance: f64,
+    pub methane_bubble_chance: f64,
+    pub soil_charge_chance: f64,
+    pub soil_absorb_chance: f64,
+    pub wet_soil_mud_chance: f64,
+    pub wet_soil_absorb_chance: f64,
+    pub wet_soil_change_chance: f64,
+    pub wet_soil_dry_chance: f64,
+    pub wet_soil_tree_skip_chance: f64,
+    pub branch_burn_chance: f64,
+    pub leaf_burn_chance: f64,
+    pub leaf_salt_death_chance: f64,
+    pub leaf_pollen_chance: f64,
+    pub clone_chance: f64,
+    pub bubble_heat_pop_chance: f64,
+    pub bubble_surface_pop_chance: f64,
+    pub bubble_rise_chance: f64,
+    pub virus_infect_chance: f64,
+    pub mud_dry_chance: f64,
+    pub mud_spread_chance: f64,
+    pub magma_erupt_chance: f64,
+    pub magma_cool_chance: f64,
+    pub slime_drip_chance: f64,
+    pub slime_stick_chance: f64,
+    pub slime_spread_chance: f64,
+}
+
+impl Default for SimulationRules {
+    fn default() -> Self {
+        Self {
+            sand_gravity_chance: 0.95,
+            sand_sink_chance: 0.25,
+            sand_quicksand_chance: 0.002,
+            water_gravity_chance: 1.0,
+            water_sink_chance: 0.25,
+            water_equalize_chance: 0.50,
+            water_capillary_chance: 0.20,
+            rock_gravity_chance: 0.99,
+            rock_sink_chance: 0.95,
+            rock_methane_chance: 0.002,
+            salt_dissolve_chance: 0.25,
+            fire_extinguish_chance: 0.80,
+            fire_spread_to_plant: 0.20,
+            fire_spread_to_fuse: 0.80,
+            fire_spread_to_branch: 0.20,
+            fire_spread_to_leaf: 0.20,
+            fire_spread_to_wax: 0.01,
+            fire_spread_to_oil: 0.20,
+            fire_rise_chance: 0.50,
+            fire_flameout_chance: 0.40,
+            fire_oil_sustain_chance: 0.50,
+            oil_ignite_chance: 0.30,
+            gunpowder_ignite_chance: 0.95,
+            gunpowder_burn_chance: 0.60,
+            gunpowder_blast_chance: 0.40,
+            gunpowder_chain_chance: 0.50,
+            lava_burn_chance: 0.25,
+            lava_fire_chance: 0.06,
+            lava_steam_sink_chance: 0.95,
+            steam_rise_chance: 0.70,
+            steam_condense_chance: 0.05,
+            steam_cool_chance: 0.02,
+            steam_rain_chance: 0.30,
+            steam_spout_condense_chance: 0.05,
+            steam_vanish_chance: 0.0005,
+            ice_salt_melt_chance: 0.10,
+            plant_growth_chance: 0.50,
+            plant_salt_death_chance: 0.05,
+            wax_burn_chance: 0.01,
+            chilled_ice_thaw_chance: 0.06,
+            mystery_skip_chance: 0.50,
+            thermite_ignite_chance: 0.50,
+            burning_thermite_nitro_chance: 0.0014,
+            burning_thermite_consume_chance: 0.02,
+            burning_thermite_wall_chance: 0.08,
+            concrete_wall_harden_chance: 0.01,
+            concrete_harden_chance: 0.0005,
+            nitro_explode_chance: 0.30,
+            nitro_burn_chance: 0.20,
+            napalm_ignite_chance: 0.25,
+            c4_ignite_chance: 0.60,
+            c4_fire_chance: 0.70,
+            acid_dissolve_chance: 0.10,
+            acid_wall_follow_chance: 0.75,
+            cryo_chill_chance: 0.0005,
+            cryo_freeze_chance: 0.005,
+            methane_chain_chance: 0.50,
+            methane_ignite_chance: 0.25,
+            methane_permeate_chance: 0.70,
+            methane_bubble_chance: 0.02,
+            soil_charge_chance: 0.25,
+            soil_absorb_chance: 0.15,
+            wet_soil_mud_chance: 0.03,
+            wet_soil_absorb_chance: 0.15,
+            wet_soil_change_chance: 0.05,
+            wet_soil_dry_chance: 0.97,
+            wet_soil_tree_skip_chance: 0.35,
+            branch_burn_chance: 0.03,
+            leaf_burn_chance: 0.05,
+            leaf_salt_death_chance: 0.20,
+            leaf_pollen_chance: 0.0009,
+            clone_chance: 0.05,
+            bubble_heat_pop_chance: 0.80,
+            bubble_surface_pop_chance: 0.05,
+            bubble_rise_chance: 0.90,
+            virus_infect_chance: 0.15,
+            mud_dry_chance: 0.01,
+            mud_spread_chance: 0.15,
+            magma_erupt_chance: 0.001,
+            magma_cool_chance: 0.0001,
+            slime_drip_chance: 0.20,
+            slime_stick_chance: 0.80,
+            slime_spread_chance: 0.08,
+        }
+    }
+}
+
+impl SimulationRules {
+    /// Read rules from a TOML file with one field per probability
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read or isn't valid rules TOML.
+    pub fn from_toml(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        toml::from_str(&text).map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+    }
+
+    /// Label and mutable reference for every field, used to build the UI sliders
+    pub fn fields_mut(&mut self) -> [(&'static str, &mut f64); 83] {
+        [
+            ("Sand gravity", &mut self.sand_gravity_chance),
+            ("Sand sink", &mut self.sand_sink_chance),
+            ("Sand to quicksand", &mut self.sand_quicksand_chance),
+            ("Water gravity", &mut self.water_gravity_chance),
+            ("Water sink", &mut self.water_sink_chance),
+            ("Water equalize", &mut self.water_equalize_chance),
+            ("Water capillary", &mut self.water_capillary_chance),
+            ("Rock gravity", &mut self.rock_gravity_chance),
+            ("Rock sink", &mut self.rock_sink_chance),
+            ("Rock methane", &mut self.rock_methane_chance),
+            ("Salt dissolve", &mut self.salt_dissolve_chance),
+            ("Fire extinguish", &mut self.fire_extinguish_chance),
+            ("Fire to plant", &mut self.fire_spread_to_plant),
+            ("Fire to fuse", &mut self.fire_spread_to_fuse),
+            ("Fire to branch", &mut self.fire_spread_to_branch),
+            ("Fire to leaf", &mut self.fire_spread_to_leaf),
+            ("Fire to wax", &mut self.fire_spread_to_wax),
+            ("Fire to oil", &mut self.fire_spread_to_oil),
+            ("Fire rise", &mut self.fire_rise_chance),
+            ("Fire flame out", &mut self.fire_flameout_chance),
+            ("Fire oil sustain", &mut self.fire_oil_sustain_chance),
+            ("Oil ignite", &mut self.oil_ignite_chance),
+            ("Gunpowder ignite", &mut self.gunpowder_ignite_chance),
+            ("Gunpowder burn", &mut self.gunpowder_burn_chance),
+            ("Gunpowder blast", &mut self.gunpowder_blast_chance),
+            ("Gunpowder chain", &mut self.gunpowder_chain_chance),
+            ("Lava burn", &mut self.lava_burn_chance),
+            ("Lava fire", &mut self.lava_fire_chance),
+            ("Lava steam sink", &mut self.lava_steam_sink_chance),
+            ("Steam rise", &mut self.steam_rise_chance),
+            ("Steam condense", &mut self.steam_condense_chance),
+            ("Steam cool", &mut self.steam_cool_chance),
+            ("Steam rain", &mut self.steam_rain_chance),
+            ("Steam spout condense", &mut self.steam_spout_condense_chance),
+            ("Steam vanish", &mut self.steam_vanish_chance),
+            ("Ice salt melt", &mut self.ice_salt_melt_chance),
+            ("Plant growth", &mut self.plant_growth_chance),
+            ("Plant salt death", &mut self.plant_salt_death_chance),
+            ("Wax burn", &mut self.wax_burn_chance),
+            ("Chilled ice thaw", &mut self.chilled_ice_thaw_chance),
+            ("Mystery skip", &mut self.mystery_skip_chance),
+            ("Thermite ignite", &mut self.thermite_ignite_chance),
+            ("Thermite charged nitro", &mut self.burning_thermite_nitro_chance),
+            ("Thermite consume", &mut self.burning_thermite_consume_chance),
+            ("Thermite burn wall", &mut self.burning_thermite_wall_chance),
+            ("Concrete harden by wall", &mut self.concrete_wall_harden_chance),
+            ("Concrete harden", &mut self.concrete_harden_chance),
+            ("Nitro explode", &mut self.nitro_explode_chance),
+            ("Nitro burn", &mut self.nitro_burn_chance),
+            ("Napalm ignite", &mut self.napalm_ignite_chance),
+            ("C4 ignite", &mut self.c4_ignite_chance),
+            ("C4 fire", &mut self.c4_fire_chance),
+            ("Acid dissolve", &mut self.acid_dissolve_chance),
+            ("Acid through wall", &mut self.acid_wall_follow_chance),
+            ("Cryo chill ice", &mut self.cryo_chill_chance),
+            ("Cryo freeze", &mut self.cryo_freeze_chance),
+            ("Methane chain", &mut self.methane_chain_chance),
+            ("Methane ignite", &mut self.methane_ignite_chance),
+            ("Methane permeate", &mut self.methane_permeate_chance),
+            ("Methane bubble", &mut self.methane_bubble_chance),
+            ("Soil charge nitro", &mut self.soil_charge_chance),
+            ("Soil absorb", &mut self.soil_absorb_chance),
+            ("Wet soil to mud", &mut self.wet_soil_mud_chance),
+            ("Wet soil absorb", &mut self.wet_soil_absorb_chance),
+            ("Wet soil change", &mut self.wet_soil_change_chance),
+            ("Wet soil dry", &mut self.wet_soil_dry_chance),
+            ("Wet soil tree skip", &mut self.wet_soil_tree_skip_chance),
+            ("Branch burn", &mut self.branch_burn_chance),
+            ("Leaf burn", &mut self.leaf_burn_chance),
+            ("Leaf salt death", &mut self.leaf_salt_death_chance),
+            ("Leaf pollen", &mut self.leaf_pollen_chance),
+            ("Clone", &mut self.clone_chance),
+            ("Bubble heat pop", &mut self.bubble_heat_pop_chance),
+            ("Bubble surface pop", &mut self.bubble_surface_pop_chance),
+            ("Bubble rise", &mut self.bubble_rise_chance),
+            ("Virus infect", &mut self.virus_infect_chance),
+            ("Mud dry", &mut self.mud_dry_chance),
+            ("Mud spread", &mut self.mud_spread_chance),
+            ("Magma erupt", &mut self.magma_erupt_chance),
+            ("Magma cool", &mut self.magma_cool_chance),
+            ("Slime drip", &mut self.slime_drip_chance),
+            ("Slime stick", &mut self.slime_stick_chance),
+            ("Slime spread", &mut self.slime_spread_chance),
+        ]
+    }
+}
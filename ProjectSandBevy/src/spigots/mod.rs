@@ -1,33 +1,98 @@
 use crate::elements::Element;
 use bevy::prelude::*;
 
-pub const NUM_SPIGOTS: usize = 4;
+/// Spigots in a new world
+pub const DEFAULT_NUM_SPIGOTS: usize = 4;
+/// Most spigots the "+" button in the UI adds
+pub const MAX_SPIGOTS: usize = 8;
 pub const SPIGOT_HEIGHT: u32 = 10;
 pub const DEFAULT_SPIGOT_SIZE: u32 = 5;
 
-/// Resource storing spigot configuration
-#[derive(Resource, Clone)]
+/// Width and height in cells of the triangles marking each spigot at the top of the grid
+pub const SPIGOT_INDICATOR_SIZE: f32 = 4.0;
+
+/// Wall a wall spigot pours in from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Side {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Spigot on the left or right edge of the grid, filling a band of `size` rows in the edge column
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WallSpigot {
+    pub element: Element,
+    /// Rows covered, 0 = disabled
+    pub size: u32,
+    pub side: Side,
+    /// Height of the band, 0 at the top of the grid and 1 at the bottom
+    pub position_fraction: f32,
+}
+
+impl Default for WallSpigot {
+    fn default() -> Self {
+        Self { element: Element::Water, size: DEFAULT_SPIGOT_SIZE, side: Side::Left, position_fraction: 0.5 }
+    }
+}
+
+/// Resource storing spigot configuration, one entry per spigot in each list
+/// Use `add_spigot` and `remove_spigot` to keep the lists the same length
+#[derive(Resource, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Spigots {
-    pub elements: [Element; NUM_SPIGOTS],
-    pub sizes: [u32; NUM_SPIGOTS], // Size 0 = disabled, 1-6 = enabled with that size
+    pub elements: Vec<Element>,
+    pub sizes: Vec<u32>, // Size 0 = disabled, 1-6 = enabled with that size
+    /// Left edge set by dragging the spigot's indicator, None keeps the even spacing
+    pub custom_x: Vec<Option<u32>>,
+    /// Spigots on the side walls, separate from the ones along the top
+    pub wall_spigots: Vec<WallSpigot>,
 }
 
 impl Default for Spigots {
     fn default() -> Self {
         Self {
-            elements: [
+            elements: vec![
                 Element::RainbowSand,
                 Element::Water,
                 Element::Salt,
                 Element::Oil,
             ],
-            sizes: [DEFAULT_SPIGOT_SIZE; NUM_SPIGOTS], // Default size (5) means enabled
+            sizes: vec![DEFAULT_SPIGOT_SIZE; DEFAULT_NUM_SPIGOTS], // Default size (5) means enabled
+            custom_x: vec![None; DEFAULT_NUM_SPIGOTS],
+            wall_spigots: Vec::new(),
         }
     }
 }
 
 impl Spigots {
+    /// Number of spigots, including disabled ones
+    pub const fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// Add a spigot after the others, evenly spaced until it's dragged
+    pub fn add_spigot(&mut self, element: Element, size: u32) {
+        self.elements.push(element);
+        self.sizes.push(size);
+        self.custom_x.push(None);
+    }
+
+    /// Remove the spigot at `index`, later spigots move down one index
+    pub fn remove_spigot(&mut self, index: usize) {
+        if index >= self.len() {
+            return;
+        }
+        self.elements.remove(index);
+        self.sizes.remove(index);
+        self.custom_x.remove(index);
+    }
+
     /// Get spigot positions evenly distributed across the given width
+    /// Dragged spigots keep their `custom_x`, clamped to fit inside the width
     pub fn get_spigot_positions(&self, width: u32) -> Vec<(u32, u32, u32)> {
         // Calculate spacing: evenly distribute spigots across the width
         // We want equal spacing between spigots and from edges
@@ -55,14 +120,77 @@ impl Spigots {
         let mut positions = Vec::new();
         let mut current_x = start_x;
         
-        for i in 0..NUM_SPIGOTS {
+        for i in 0..self.len() {
             if self.sizes[i] > 0 {
-                positions.push((current_x, self.sizes[i], i as u32));
+                let custom_x = self.custom_x.get(i).copied().flatten();
+                let x = custom_x.map_or(current_x, |x| x.min(width.saturating_sub(self.sizes[i])));
+                positions.push((x, self.sizes[i], i as u32));
                 current_x += self.sizes[i] + spacing; // Move to next spigot with spacing
             }
         }
         
         positions
     }
+
+    /// Top row, size, index and side of each enabled wall spigot, kept inside the given height
+    pub fn get_wall_spigot_positions(&self, height: u32) -> Vec<(u32, u32, u32, Side)> {
+        self.wall_spigots
+            .iter()
+            .enumerate()
+            .filter(|(_, spigot)| spigot.size > 0)
+            .map(|(i, spigot)| {
+                let size = spigot.size.min(height);
+                let y = (spigot.position_fraction.clamp(0.0, 1.0) * (height - size) as f32).round() as u32;
+                (y, size, i as u32, spigot.side)
+            })
+            .collect()
+    }
+}
+
+/// Drag of a spigot's indicator, see `handle_spigot_drag`
+#[derive(Resource, Default, Debug)]
+pub struct SpigotPlacementState {
+    /// Index of the spigot being dragged
+    pub dragging: Option<usize>,
+    /// Cursor x in grid cells when the drag started
+    pub drag_start_x: Option<f32>,
+}
+
+/// Whether `point` (grid cells, +y down) is on the indicator of a spigot at `x` with `size`
+pub fn spigot_indicator_contains(x: u32, size: u32, point: Vec2) -> bool {
+    let center = x as f32 + size as f32 / 2.0;
+    (point.x - center).abs() <= SPIGOT_INDICATOR_SIZE && (0.0..=SPIGOT_INDICATOR_SIZE * 1.5).contains(&point.y)
+}
+
+/// Left edge of a spigot of `size` centered on `cursor_x`, kept inside the width
+pub fn spigot_drag_x(cursor_x: f32, size: u32, width: u32) -> u32 {
+    (cursor_x - size as f32 / 2.0).round().clamp(0.0, width.saturating_sub(size) as f32) as u32
+}
+
+
+/// Spigot positions from `Spigots::get_spigot_positions`, kept until the spigots or grid width change
+#[derive(Resource)]
+pub struct SpigotPositionCache {
+    pub positions: Vec<(u32, u32, u32)>,
+    pub dirty: bool,
+    /// Grid width the positions were laid out for
+    pub width: u32,
 }
 
+impl Default for SpigotPositionCache {
+    fn default() -> Self {
+        Self { positions: Vec::new(), dirty: true, width: 0 }
+    }
+}
+
+impl SpigotPositionCache {
+    /// Recompute the positions if they are stale, then return them
+    pub fn positions(&mut self, spigots: &Spigots, width: u32) -> &[(u32, u32, u32)] {
+        if self.dirty || self.width != width {
+            self.positions = spigots.get_spigot_positions(width);
+            self.width = width;
+            self.dirty = false;
+        }
+        &self.positions
+    }
+}
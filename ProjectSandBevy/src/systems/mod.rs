@@ -6,7 +6,9 @@
     clippy::similar_names
 )]
 
-use crate::elements::Element;
+use crate::audio::{embers_speed, embers_volume, smooth_toward, water_volume, AudioOutput, AudioSettings, SimulationAudio, MUSIC_UPDATE_INTERVAL};
+use crate::challenges::{ChallengeStatus, Challenges};
+use crate::elements::{Element, ElementGroup};
 
 /// Get a description for an element (for tooltips)
 fn get_element_description(element: Element) -> &'static str {
@@ -49,19 +51,55 @@ fn get_element_description(element: Element) -> &'static str {
         Element::Mystery => "Mysterious element with unknown properties",
         Element::ChargedNitro => "Nitro that's been charged",
         Element::BurningThermite => "Thermite that's actively burning",
+        Element::Clone => "Duplicates the material touching it",
+        Element::Bubble => "Rises through liquids, pops at the surface or near heat",
+        Element::Virus => "Infects neighboring materials, dies of old age",
+        Element::Portal => "Teleports materials to its paired portal (place two to link)",
+        Element::Mud => "Thick, slow-flowing mixture of soil and water",
+        Element::Magma => "Extremely dense molten rock, sinks through water and erupts lava",
+        Element::Quicksand => "Swallows heavy materials that sink into it, spreads through sand",
+        Element::Catalyst => "Speeds up nearby reactions without being used up",
+        Element::Slime => "Sticky liquid that clings to walls and resists fire briefly",
+        Element::Honey => "Thick golden liquid that flows very slowly",
+        Element::HardWall => "Cured concrete, static and immune to acid",
+        Element::Emitter => "Sprays particles upward (Shift+click to configure)",
+        Element::Creature => "Crawls through empty space toward the nearest well",
+        Element::AntiGravity => "Static, makes things nearby fall upward for a few seconds after it is placed",
+        Element::Nuke => "⚠️ Nuke: when touched by fire, wipes out everything but Wall within a quarter of the grid",
     }
 }
-use crate::particles::{ParticleList, ParticleTexture};
+use crate::particles::{new_particle_image, ParticleList, ParticleTexture, ParticleType};
+use crate::rendering::{
+    cell_color, draw_cells, draw_ripples, grid_to_image, ripple_targets, ColorblindMode, RenderMode, RenderRules,
+};
 use crate::particles::actions::particle_init;
-use crate::simulation::{execute_element_action, GameGrid, ActiveTreeBranches};
-use crate::spigots::{Spigots, NUM_SPIGOTS};
+use crate::simulation::{execute_element_action, AntiGravitySources, NUKE_SHOCKWAVE_FORCE, prune_creature_paths, prune_stale_rainbow_times, Clipboard, place_portal, GameGrid, GridStatistics, Rotation, ActiveTreeBranches, EmitterConfigs, SimContext, PendingPortal, PortalRegistry, ShockwaveEvent, SimulationRules, RULES_CONFIG_PATH, ElementInteractionMatrix, WindField, frame_seed, seed_simulation_rng, sim_rng, MAX_PORTAL_PAIRS};
+use crate::save::png::{load_from_png, save_as_png};
+use crate::save::WorldSaveFile;
+use crate::generation::{generate_caves, generate_terrain};
+use crate::spigots::{
+    spigot_drag_x, spigot_indicator_contains, Side, SpigotPlacementState, SpigotPositionCache, Spigots, WallSpigot,
+    DEFAULT_SPIGOT_SIZE, MAX_SPIGOTS, SPIGOT_HEIGHT, SPIGOT_INDICATOR_SIZE,
+};
 use crate::{DISPLAY_FACTOR, SIZE};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use bevy::{
     asset::RenderAssetUsages,
-    input::mouse::MouseWheel,
+    ecs::system::SystemParam,
+    input::mouse::{AccumulatedMouseMotion, MouseWheel},
     prelude::*,
-    render::render_resource::{TextureFormat, TextureUsages},
+    render::{
+        extract_resource::ExtractResource,
+        render_asset::RenderAssets,
+        render_resource::{
+            Extent3d, Origin3d, TexelCopyBufferLayout, TexelCopyTextureInfo, TextureAspect, TextureFormat,
+            TextureUsages,
+        },
+        renderer::RenderQueue,
+        texture::GpuImage,
+    },
     window::{PrimaryWindow, WindowResized},
 };
 use bevy_egui::{EguiContexts, egui};
@@ -71,14 +109,108 @@ use rand::Rng;
 #[derive(Resource, Clone, Copy)]
 pub struct SelectedElement(pub Element);
 
+/// Resource holding the element palette search box text and group filter
+#[derive(Resource, Default)]
+pub struct ElementFilter {
+    pub text: String,
+    pub group: Option<ElementGroup>,
+}
+
+impl ElementFilter {
+    /// Whether the element's name contains the filter text, case insensitively,
+    /// and the element belongs to the selected group
+    pub fn matches(&self, element: Element) -> bool {
+        self.group.is_none_or(|group| element.in_group(group))
+            && format!("{element:?}").to_lowercase().contains(&self.text.trim().to_lowercase())
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.text.trim().is_empty() || self.group.is_some()
+    }
+}
+
+/// Resource holding the grid index of the emitter whose settings window is open
+#[derive(Resource, Default)]
+pub struct EmitterEditor(pub Option<usize>);
+
+/// Particle types offered in the emitter settings window
+const EMITTER_PARTICLE_TYPES: [ParticleType; 10] = [
+    ParticleType::Nitro,
+    ParticleType::Napalm,
+    ParticleType::C4,
+    ParticleType::Lava,
+    ParticleType::Magic1,
+    ParticleType::Magic2,
+    ParticleType::Methane,
+    ParticleType::Tree,
+    ParticleType::ChargedNitro,
+    ParticleType::Nuke,
+];
+
 /// Resource to track whether to overwrite existing materials when drawing
 #[derive(Resource, Clone, Copy)]
 pub struct OverwriteMode(pub bool);
 
+/// Resource to track the brush shape used when drawing
+#[derive(Resource, Clone, Copy, Default, PartialEq, Debug)]
+pub enum DrawMode {
+    #[default]
+    Circle,
+    Square,
+    /// Circle where each cell is only drawn with the given probability
+    Spray { density: f32 },
+    /// Flood fill the connected empty area under the cursor
+    Fill,
+}
+
+impl DrawMode {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Circle => "Circle",
+            Self::Square => "Square",
+            Self::Spray { .. } => "Spray",
+            Self::Fill => "Fill",
+        }
+    }
+}
+
+/// Resource to mirror drawing across the grid center
+#[derive(Resource, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum SymmetryMode {
+    #[default]
+    None,
+    /// Mirror across the horizontal center line (top/bottom)
+    Horizontal,
+    /// Mirror across the vertical center line (left/right)
+    Vertical,
+    /// Mirror across both center lines
+    Quad,
+}
+
+impl SymmetryMode {
+    pub const ALL: [Self; 4] = [Self::None, Self::Horizontal, Self::Vertical, Self::Quad];
+
+    /// Positions mirrored from (x, y), not including (x, y) itself
+    pub fn mirror_points(self, grid: &GameGrid, x: u32, y: u32) -> Vec<(u32, u32)> {
+        let mirror_x = grid.max_x() - x.min(grid.max_x());
+        let mirror_y = grid.max_y() - y.min(grid.max_y());
+        match self {
+            Self::None => Vec::new(),
+            Self::Horizontal => vec![(x, mirror_y)],
+            Self::Vertical => vec![(mirror_x, y)],
+            Self::Quad => vec![(x, mirror_y), (mirror_x, y), (mirror_x, mirror_y)],
+        }
+    }
+}
+
 /// Resource to track whether elements fall into the void or stop at edges
 #[derive(Resource, Clone, Copy)]
 pub struct FallIntoVoid(pub bool);
 
+/// Resource to track whether the world wraps around (bottom to top, left to right)
+#[derive(Resource, Clone, Copy)]
+pub struct WorldWrap(pub bool);
+
 /// Resource to track the drawing radius
 #[derive(Resource, Clone, Copy)]
 pub struct DrawRadius(pub f32);
@@ -87,6 +219,58 @@ pub struct DrawRadius(pub f32);
 #[derive(Resource, Default)]
 pub struct ClearGrid(pub bool);
 
+/// Resource to signal that a new world should be generated, from the seed typed into the UI
+#[derive(Resource, Default)]
+pub struct WorldGenRequest {
+    pub seed_text: String,
+    pub generate: bool,
+}
+
+/// Replace the grid with generated terrain and caves when requested
+pub fn handle_world_gen(
+    mut request: ResMut<WorldGenRequest>,
+    mut grid: ResMut<GameGrid>,
+    mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
+    (mut portal_registry, mut pending_portal, mut emitter_configs): (ResMut<PortalRegistry>, ResMut<PendingPortal>, ResMut<EmitterConfigs>),
+    (mut active_branches, mut particle_list): (ResMut<ActiveTreeBranches>, ResMut<ParticleList>),
+) {
+    if !request.generate {
+        return;
+    }
+    request.generate = false;
+
+    let seed: u64 = request.seed_text.trim().parse().unwrap_or_else(|_| rand::random());
+    request.seed_text = seed.to_string();
+    generate_terrain(&mut grid, seed);
+    generate_caves(&mut grid, seed);
+
+    // Nothing from the old world carries over
+    rainbow_sand_times.0.clear();
+    portal_registry.0.clear();
+    pending_portal.0 = None;
+    emitter_configs.0.clear();
+    active_branches.branches.clear();
+    particle_list.clear_all();
+    bevy::log::info!("Generated world with seed {}", seed);
+}
+
+/// Resource to signal that every cell of one element should become another, (from, to)
+#[derive(Resource, Default)]
+pub struct ReplaceElementRequest(pub Option<(Element, Element)>);
+
+/// Replace every cell of an element when requested from the palette
+pub fn handle_replace_element(
+    mut request: ResMut<ReplaceElementRequest>,
+    mut grid: ResMut<GameGrid>,
+    mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
+) {
+    let Some((from, to)) = request.0.take() else {
+        return;
+    };
+    let replaced = grid.replace_element(from, to, &mut rainbow_sand_times.0);
+    bevy::log::info!("Replaced {} {:?} cells with {:?}", replaced, from, to);
+}
+
 /// Resource to signal that the grid should be saved
 #[derive(Resource, Default)]
 pub struct SaveGrid(pub bool);
@@ -95,6 +279,175 @@ pub struct SaveGrid(pub bool);
 #[derive(Resource, Default)]
 pub struct LoadGrid(pub bool);
 
+/// Resource to signal that the grid should be saved as a PNG
+#[derive(Resource, Default)]
+pub struct SavePng(pub bool);
+
+/// Resource to signal that the grid should be loaded from a PNG
+#[derive(Resource, Default)]
+pub struct LoadPng(pub bool);
+
+/// File the grid is saved to as a PNG
+pub const PNG_SAVE_PATH: &str = "sand_save.png";
+
+/// File the grid is saved to in the binary format
+pub const SAVE_PATH: &str = "sand_save.bin";
+
+/// Extension of the save files listed in the save browser
+pub const SAVE_EXTENSION: &str = "sand";
+
+/// A save file found by the save browser
+pub struct SaveInfo {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub modified: Option<std::time::SystemTime>,
+    pub element_count: Option<u32>,
+    /// Width and height, only known for saves with a header (version 2 and up)
+    pub dimensions: Option<(u32, u32)>,
+}
+
+/// Resource for the save browser window, listing `*.sand` files in the current directory
+#[derive(Resource, Default)]
+pub struct SaveBrowser {
+    pub saves: Vec<SaveInfo>,
+    pub refresh_requested: bool,
+    pub open: bool,
+    /// File the Load button reads, `SAVE_PATH` when nothing is selected
+    pub load_target: Option<PathBuf>,
+    /// File the next save writes, set by "Save As..." and cleared once the save starts
+    pub save_target: Option<PathBuf>,
+    pub save_as_name: String,
+}
+
+impl SaveBrowser {
+    /// Rescan the current directory for save files, newest first
+    pub fn refresh(&mut self) {
+        self.refresh_requested = false;
+        self.saves.clear();
+        let Ok(entries) = std::fs::read_dir(".") else {
+            bevy::log::warn!("Failed to read the save directory");
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|extension| extension != SAVE_EXTENSION) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let header = crate::save::read_header(&path);
+            self.saves.push(SaveInfo {
+                size_bytes: metadata.len(),
+                modified: metadata.modified().ok(),
+                element_count: header.as_ref().map(|header| header.element_count),
+                dimensions: header.map(|header| (header.width, header.height)),
+                path,
+            });
+        }
+        self.saves.sort_by(|a, b| b.modified.cmp(&a.modified));
+    }
+}
+
+/// Default seconds between auto-saves
+pub const DEFAULT_AUTO_SAVE_INTERVAL: f32 = 300.0;
+
+/// Resource for periodic background saves to `path`
+#[derive(Resource)]
+pub struct AutoSave {
+    pub interval_secs: f32,
+    /// Time (in seconds since startup) of the last successful auto-save
+    pub last_save_secs: f32,
+    pub enabled: bool,
+    pub path: PathBuf,
+    /// An auto-save has been started and hasn't finished yet
+    pub in_progress: bool,
+    /// Whether any auto-save has succeeded yet, `last_save_secs` is only meaningful once it has
+    pub has_saved: bool,
+}
+
+impl Default for AutoSave {
+    fn default() -> Self {
+        Self {
+            interval_secs: DEFAULT_AUTO_SAVE_INTERVAL,
+            last_save_secs: 0.0,
+            enabled: true,
+            path: PathBuf::from(format!("autosave.{SAVE_EXTENSION}")),
+            in_progress: false,
+            has_saved: false,
+        }
+    }
+}
+
+/// Start a background save to `AutoSave::path` every `interval_secs`
+pub fn auto_save_system(
+    time: Res<Time>,
+    mut auto_save: ResMut<AutoSave>,
+    mut save_load_task: ResMut<SaveLoadTask>,
+    grid: Res<GameGrid>,
+    spigots: Res<Spigots>,
+    rainbow_sand_times: Res<RainbowSandPlacementTimes>,
+    (active_branches, particle_list): (Res<ActiveTreeBranches>, Res<ParticleList>),
+) {
+    let now = time.elapsed_secs();
+
+    // Wait for our save to finish, `last_saved` is only set if it succeeded
+    if auto_save.in_progress {
+        if save_load_task.is_saving() {
+            return;
+        }
+        auto_save.in_progress = false;
+        if save_load_task.last_saved.as_ref() == Some(&auto_save.path) {
+            save_load_task.last_saved = None;
+            auto_save.has_saved = true;
+        }
+        // Retry after a full interval on failure too, rather than every frame
+        auto_save.last_save_secs = now;
+        return;
+    }
+
+    if !auto_save.enabled || now - auto_save.last_save_secs < auto_save.interval_secs || save_load_task.is_saving() {
+        return;
+    }
+    let save = WorldSaveFile::new(&grid, &spigots, &rainbow_sand_times.0).with_particles(&active_branches, &particle_list);
+    let path = auto_save.path.clone();
+    save_load_task.start_save(save, path);
+    auto_save.in_progress = true;
+}
+
+/// Background save/load threads so file IO doesn't block the frame
+/// `pending_load` holds a finished load until it is applied on the main thread
+#[derive(Resource, Default)]
+pub struct SaveLoadTask {
+    pub save_thread: Option<std::thread::JoinHandle<Result<(), String>>>,
+    pub load_thread: Option<std::thread::JoinHandle<Result<WorldSaveFile, String>>>,
+    pub pending_load: Option<WorldSaveFile>,
+    /// File the running save thread is writing
+    pub saving_path: Option<PathBuf>,
+    /// File the last successful save wrote, for callers that need to know their save finished
+    pub last_saved: Option<PathBuf>,
+}
+
+impl SaveLoadTask {
+    pub const fn is_saving(&self) -> bool {
+        self.save_thread.is_some()
+    }
+
+    /// Serialize and write `save` to `path` on a background thread
+    pub fn start_save(&mut self, save: WorldSaveFile, path: PathBuf) {
+        bevy::log::info!("Saving grid to {}", path.display());
+        self.saving_path = Some(path.clone());
+        self.save_thread = Some(std::thread::spawn(move || {
+            let data = save.to_bytes().map_err(|e| format!("Failed to serialize save: {e}"))?;
+            std::fs::write(&path, data).map_err(|e| format!("Failed to save grid to {}: {e}", path.display()))
+        }));
+    }
+
+    pub const fn is_loading(&self) -> bool {
+        self.load_thread.is_some() || self.pending_load.is_some()
+    }
+}
+
 /// Resource to track line drawing state for shift-key straight lines
 #[derive(Resource, Default)]
 pub struct LineDrawingState {
@@ -107,6 +460,150 @@ pub struct LineDrawingState {
 #[derive(Resource, Default)]
 pub struct FrameCount(pub u32);
 
+/// Resource for deterministic mode: when enabled, the simulation RNG is reseeded every frame
+/// so the same seed and the same inputs always produce the same result
+#[derive(Resource, Default)]
+pub struct SimulationSeed {
+    pub seed: u64,
+    pub enabled: bool,
+    /// Simulation frames run since the seed was applied
+    pub frame: u64,
+}
+
+/// Maximum number of simulation ticks kept in a recording
+pub const MAX_RECORDED_FRAMES: usize = 1000;
+
+/// File recordings are saved to and loaded from
+pub const RECORDING_PATH: &str = "recording.sandrec";
+
+/// Cells that changed during one recorded simulation tick
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct RecordedFrame {
+    pub tick: u64,
+    pub changed_cells: Vec<(usize, Element)>,
+}
+
+/// Resource holding a recording of the simulation as per-tick deltas from the grid it started on
+#[derive(Resource, Default, serde::Serialize, serde::Deserialize)]
+pub struct RecordingState {
+    /// Whether ticks are currently being recorded
+    #[serde(skip)]
+    pub active: bool,
+    pub frames: Vec<RecordedFrame>,
+    /// Grid elements when the recording started, playback starts from here
+    pub initial_elements: Vec<Element>,
+    pub width: u32,
+    pub height: u32,
+    /// Grid elements after the last recorded tick, used to find the next delta
+    #[serde(skip)]
+    pub last_elements: Vec<Element>,
+    /// Whether the recording is being played back instead of simulating
+    #[serde(skip)]
+    pub playing: bool,
+    /// Next frame to apply during playback
+    #[serde(skip)]
+    pub playback_frame: usize,
+}
+
+impl RecordingState {
+    /// Start a new recording from the current grid
+    pub fn start(&mut self, grid: &GameGrid) {
+        self.frames.clear();
+        self.initial_elements.clone_from(&grid.elements);
+        self.last_elements.clone_from(&grid.elements);
+        self.width = grid.width;
+        self.height = grid.height;
+        self.playing = false;
+        self.active = true;
+    }
+
+    /// Record the cells that changed since the last tick, stopping once the recording is full
+    pub fn record_tick(&mut self, grid: &GameGrid) {
+        if self.width != grid.width || self.height != grid.height {
+            // The grid was resized, the deltas no longer line up
+            self.active = false;
+            return;
+        }
+        let changed_cells = grid
+            .elements
+            .iter()
+            .zip(&self.last_elements)
+            .enumerate()
+            .filter(|(_, (current, last))| current != last)
+            .map(|(i, (&current, _))| (i, current))
+            .collect();
+        self.frames.push(RecordedFrame { tick: self.frames.len() as u64, changed_cells });
+        self.last_elements.clone_from(&grid.elements);
+        if self.frames.len() >= MAX_RECORDED_FRAMES {
+            bevy::log::info!("Recording stopped at the {} frame limit", MAX_RECORDED_FRAMES);
+            self.active = false;
+        }
+    }
+
+    /// Start playing the recording from its first frame, if it fits the grid
+    pub fn play(&mut self, grid: &GameGrid) {
+        if self.frames.is_empty() {
+            return;
+        }
+        if self.width != grid.width || self.height != grid.height {
+            bevy::log::warn!("Recording is {}x{} but the grid is {}x{}", self.width, self.height, grid.width, grid.height);
+            return;
+        }
+        self.active = false;
+        self.playing = true;
+        self.playback_frame = 0;
+    }
+
+    pub fn save(&self) {
+        match bincode::serialize(self) {
+            Ok(data) => {
+                if let Err(e) = std::fs::write(RECORDING_PATH, data) {
+                    bevy::log::error!("Failed to save recording: {}", e);
+                } else {
+                    bevy::log::info!("Recording saved to {}", RECORDING_PATH);
+                }
+            }
+            Err(e) => bevy::log::error!("Failed to serialize recording: {}", e),
+        }
+    }
+
+    pub fn load(&mut self) {
+        let Ok(data) = std::fs::read(RECORDING_PATH) else {
+            bevy::log::warn!("No recording found ({})", RECORDING_PATH);
+            return;
+        };
+        match bincode::deserialize::<Self>(&data) {
+            Ok(recording) => {
+                *self = recording;
+                bevy::log::info!("Recording loaded from {}", RECORDING_PATH);
+            }
+            Err(e) => bevy::log::error!("Failed to deserialize recording: {}", e),
+        }
+    }
+}
+
+/// Run condition: a recording is being played back
+pub fn recording_playing(recording: Res<RecordingState>) -> bool {
+    recording.playing
+}
+
+/// Apply the next recorded frame to the grid, used instead of `update_game_simulation` during playback
+pub fn play_recorded_frame(mut grid: ResMut<GameGrid>, mut recording: ResMut<RecordingState>) {
+    if recording.playback_frame == 0 {
+        for (i, &element) in recording.initial_elements.iter().enumerate() {
+            grid.set_index(i, element);
+        }
+    }
+    let Some(frame) = recording.frames.get(recording.playback_frame) else {
+        recording.playing = false;
+        return;
+    };
+    for &(i, element) in &frame.changed_cells {
+        grid.set_index(i, element);
+    }
+    recording.playback_frame += 1;
+}
+
 /// Resource to track simulation speed (0.0 = paused, 1.0 = normal, 2.0 = 2x speed)
 #[derive(Resource)]
 pub struct SimulationSpeed(pub f32);
@@ -117,136 +614,928 @@ impl Default for SimulationSpeed {
     }
 }
 
-/// Resource to track placement counter for RainbowSand gradient effect
-/// This increments each time RainbowSand is placed, creating a gradient over time
+/// Resource to advance a paused simulation by a single tick, set by the "Step" button or Space
 #[derive(Resource, Default)]
-pub struct RainbowSandPlacementCounter {
-    pub counter: u32,
-    pub last_mouse_pressed: bool,
-    pub frame_since_last_increment: u32,
+pub struct StepMode {
+    pub step_requested: bool,
 }
 
-/// Resource to track placement time for each RainbowSand element
-/// Maps grid index to the placement counter value when that position was last set to RainbowSand
-#[derive(Resource, Default)]
-pub struct RainbowSandPlacementTimes(pub HashMap<usize, u32>);
+/// Resource to toggle animated colors for Fire, Water and Lava
+#[derive(Resource, Clone, Copy)]
+pub struct AnimatedColors(pub bool);
 
-pub fn setup(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
-    // Create a single image for rendering (CPU-based, no double buffering needed)
-    // Use Rgba8Unorm for simpler byte-based updates
-    let mut image = Image::new_target_texture(SIZE.x, SIZE.y, TextureFormat::Rgba8Unorm);
-    image.asset_usage = RenderAssetUsages::RENDER_WORLD;
-    image.texture_descriptor.usage = TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
-    let texture_handle = image_assets.add(image);
+/// Resource to track the camera zoom target (1.0 = the whole grid fills the window)
+#[derive(Resource, Clone, Copy)]
+pub struct CameraZoom(pub f32);
 
-    commands.spawn((
-        Sprite {
-            image: texture_handle.clone(),
-            custom_size: Some(SIZE.as_vec2()),
-            ..default()
-        },
-        // DISPLAY_FACTOR is u32, cast to f32 for scale
-        Transform::from_scale(Vec3::splat(DISPLAY_FACTOR as f32)),
-    ));
-    commands.spawn(Camera2d);
+impl Default for CameraZoom {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
 
-    // Store texture handle for rendering (will be updated each frame)
-    commands.insert_resource(RenderTexture(texture_handle.clone()));
+impl CameraZoom {
+    pub const MIN: f32 = 0.5;
+    pub const MAX: f32 = 8.0;
+}
 
-    // Initialize game grid (CPU-based simulation)
-    commands.insert_resource(GameGrid::default());
+/// Resource to track the camera pan target in world units
+#[derive(Resource, Clone, Copy, Default)]
+pub struct CameraPan(pub Vec2);
 
-    // Initialize spigots resource
-    commands.insert_resource(Spigots::default());
+/// How quickly the camera catches up to its zoom and pan targets (fraction per second)
+const CAMERA_SMOOTHING: f32 = 12.0;
 
-    // Resource to track selected element (for UI)
-    commands.insert_resource(SelectedElement(Element::RainbowSand));
-    
-    // Resource to track fall into void setting (default: true)
-    commands.insert_resource(FallIntoVoid(false));
-    
-    // Resource to track draw radius (default: 5.0)
-    commands.insert_resource(DrawRadius(5.0));
-    
-    // Resource to track overwrite mode (default: true, overwrite existing materials)
-    commands.insert_resource(OverwriteMode(true));
-    
-    // Resource to signal grid clearing
-    commands.insert_resource(ClearGrid::default());
-    
-    // Resources for save/load
-    commands.insert_resource(SaveGrid::default());
-    commands.insert_resource(LoadGrid::default());
-    
-    // Resource to track frame count for time-based effects
-    commands.insert_resource(FrameCount::default());
-    
-    // Resource to track simulation speed (0.0 = paused, 1.0 = normal, 2.0 = 2x speed)
-    commands.insert_resource(SimulationSpeed::default());
-    
-    // Resource to track RainbowSand placement counter for gradient effect
-    commands.insert_resource(RainbowSandPlacementCounter::default());
-    
-    // Resource to track RainbowSand placement times
-    commands.insert_resource(RainbowSandPlacementTimes::default());
-    
-    // Resource to track line drawing state for shift-key straight lines
-    commands.insert_resource(LineDrawingState::default());
-    
-    // Resource to track active tree branches for incremental growth
-    commands.insert_resource(ActiveTreeBranches::default());
-    
-    // Initialize particle system
-    commands.insert_resource(ParticleList::default());
-    
-    // Create particle texture (offscreen canvas for particles)
-    // Initialize with black pixels (transparent background)
-    let particle_pixel_data = vec![0u8; (SIZE.x * SIZE.y * 4) as usize];
-    let mut particle_image = Image::new_target_texture(SIZE.x, SIZE.y, TextureFormat::Rgba8Unorm);
-    particle_image.data = Some(particle_pixel_data);
-    particle_image.asset_usage = RenderAssetUsages::RENDER_WORLD;
-    particle_image.texture_descriptor.usage = TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
-    let particle_texture_handle = image_assets.add(particle_image);
-    commands.insert_resource(ParticleTexture(particle_texture_handle));
+/// Resource shaking the camera after a Nuke, see `trigger_camera_shake`
+/// The camera is jolted up to `magnitude` world units each frame, fading out over the last second
+#[derive(Resource, Clone, Copy, Default, Debug)]
+pub struct CameraShake {
+    pub magnitude: f32,
+    /// Seconds of shaking left
+    pub duration: f32,
 }
 
-/// Resource to store the render texture handle
-#[derive(Resource)]
-pub struct RenderTexture(pub Handle<Image>);
+/// How hard and how long a Nuke shakes the camera
+const NUKE_SHAKE: CameraShake = CameraShake { magnitude: 12.0, duration: 1.5 };
 
-/// UI system for the egui controls window.
-///
-/// # Errors
-/// Returns an error if the egui context cannot be accessed.
-pub fn ui_system(
-    mut contexts: EguiContexts,
-    mut selected_element: ResMut<SelectedElement>,
-    mut spigots: ResMut<Spigots>,
-    mut fall_into_void: ResMut<FallIntoVoid>,
-    mut draw_radius: ResMut<DrawRadius>,
-    mut overwrite_mode: ResMut<OverwriteMode>,
-    mut clear_grid: ResMut<ClearGrid>,
-    mut simulation_speed: ResMut<SimulationSpeed>,
-    mut save_grid: ResMut<SaveGrid>,
-    mut load_grid: ResMut<LoadGrid>,
-) {
-    if let Ok(ctx) = contexts.ctx_mut() {
-        egui::Window::new("Controls").show(ctx, |ui| {
-        // Element selection
-        ui.label("Selected Element:");
-        ui.horizontal_wrapped(|ui| {
-            for element in [Element::Sand, Element::RainbowSand, Element::Water, Element::Wall, Element::Fire, Element::Salt, Element::Oil, Element::Rock, Element::Ice, Element::Lava, Element::Steam, Element::SaltWater, Element::Plant, Element::Gunpowder, Element::Wax, Element::Concrete, Element::Nitro, Element::Napalm, Element::C4, Element::Fuse, Element::Acid, Element::Cryo, Element::Methane, Element::Soil, Element::WetSoil, Element::Thermite, Element::Spout, Element::Well, Element::Torch, Element::Branch, Element::Leaf, Element::Pollen, Element::FallingWax, Element::ChilledIce, Element::Mystery, Element::ChargedNitro, Element::BurningThermite] {
-                let is_selected = selected_element.0 == element;
-                let button_text = format!("{:?}", element);
-                let response = ui.selectable_label(is_selected, &button_text);
-                if response.clicked() {
-                    selected_element.0 = element;
-                }
+/// Where keyboard shortcuts are read from (and written to when missing)
+pub const SHORTCUTS_CONFIG_PATH: &str = "config.toml";
+
+/// Resource mapping keys to elements, plain and with Shift held
+#[derive(Resource, Clone)]
+pub struct KeyboardShortcuts {
+    pub keys: HashMap<KeyCode, Element>,
+    pub shift_keys: HashMap<KeyCode, Element>,
+}
+
+impl Default for KeyboardShortcuts {
+    fn default() -> Self {
+        let digits = [
+            KeyCode::Digit1,
+            KeyCode::Digit2,
+            KeyCode::Digit3,
+            KeyCode::Digit4,
+            KeyCode::Digit5,
+            KeyCode::Digit6,
+            KeyCode::Digit7,
+            KeyCode::Digit8,
+            KeyCode::Digit9,
+            KeyCode::Digit0,
+        ];
+        let keys = digits.into_iter().zip([
+            Element::Sand,
+            Element::Water,
+            Element::Fire,
+            Element::Wall,
+            Element::Oil,
+            Element::Ice,
+            Element::Lava,
+            Element::Gunpowder,
+            Element::Acid,
+            Element::Background,
+        ]);
+        let shift_keys = digits.into_iter().zip([
+            Element::Salt,
+            Element::Steam,
+            Element::Plant,
+            Element::SaltWater,
+            Element::Concrete,
+            Element::Nitro,
+            Element::Napalm,
+            Element::C4,
+            Element::Fuse,
+        ]);
+        Self {
+            keys: keys.collect(),
+            shift_keys: shift_keys.collect(),
+        }
+    }
+}
+
+/// Shortcuts as written in config.toml, keys are names like "1" or "Shift+1"
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ShortcutsConfig {
+    shortcuts: std::collections::BTreeMap<String, Element>,
+}
+
+impl KeyboardShortcuts {
+    /// Load shortcuts from config.toml, writing the defaults there if it doesn't exist
+    pub fn load_or_create() -> Self {
+        match std::fs::read_to_string(SHORTCUTS_CONFIG_PATH) {
+            Ok(text) => match toml::from_str::<ShortcutsConfig>(&text) {
+                Ok(config) => Self::from_config(&config),
+                Err(e) => {
+                    bevy::log::error!("Failed to parse {}: {}", SHORTCUTS_CONFIG_PATH, e);
+                    Self::default()
+                }
+            },
+            Err(_) => {
+                let shortcuts = Self::default();
+                match toml::to_string(&shortcuts.to_config()) {
+                    Ok(text) => {
+                        if let Err(e) = std::fs::write(SHORTCUTS_CONFIG_PATH, text) {
+                            bevy::log::error!("Failed to write {}: {}", SHORTCUTS_CONFIG_PATH, e);
+                        }
+                    }
+                    Err(e) => bevy::log::error!("Failed to serialize shortcuts: {}", e),
+                }
+                shortcuts
+            }
+        }
+    }
+
+    fn from_config(config: &ShortcutsConfig) -> Self {
+        let mut shortcuts = Self {
+            keys: HashMap::new(),
+            shift_keys: HashMap::new(),
+        };
+        for (name, element) in &config.shortcuts {
+            let (shift, key_name) = match name.strip_prefix("Shift+") {
+                Some(key_name) => (true, key_name),
+                None => (false, name.as_str()),
+            };
+            let Some(key) = key_from_name(key_name) else {
+                bevy::log::warn!("Unknown shortcut key \"{}\" in {}", name, SHORTCUTS_CONFIG_PATH);
+                continue;
+            };
+            if shift {
+                shortcuts.shift_keys.insert(key, *element);
+            } else {
+                shortcuts.keys.insert(key, *element);
+            }
+        }
+        shortcuts
+    }
+
+    fn to_config(&self) -> ShortcutsConfig {
+        let plain = self.keys.iter().filter_map(|(key, element)| Some((key_name(*key)?, *element)));
+        let shifted = self
+            .shift_keys
+            .iter()
+            .filter_map(|(key, element)| Some((format!("Shift+{}", key_name(*key)?), *element)));
+        ShortcutsConfig {
+            shortcuts: plain.chain(shifted).collect(),
+        }
+    }
+
+    /// Shortcut label for an element (like "1" or "Shift+1"), if it has one
+    pub fn label_for(&self, element: Element) -> Option<String> {
+        if let Some((key, _)) = self.keys.iter().find(|&(_, e)| *e == element) {
+            return key_name(*key);
+        }
+        self.shift_keys
+            .iter()
+            .find(|&(_, e)| *e == element)
+            .and_then(|(key, _)| Some(format!("Shift+{}", key_name(*key)?)))
+    }
+}
+
+/// Name of a digit or letter key as used in config.toml
+fn key_name(key: KeyCode) -> Option<String> {
+    let name = format!("{key:?}");
+    name.strip_prefix("Digit")
+        .or_else(|| name.strip_prefix("Key"))
+        .filter(|rest| rest.len() == 1)
+        .map(str::to_owned)
+}
+
+/// Digit or letter key for a name used in config.toml
+fn key_from_name(name: &str) -> Option<KeyCode> {
+    const KEYS: [KeyCode; 36] = [
+        KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+        KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+        KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF,
+        KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL,
+        KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+        KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX,
+        KeyCode::KeyY, KeyCode::KeyZ,
+    ];
+    KEYS.into_iter()
+        .find(|key| key_name(*key).is_some_and(|key_name| key_name.eq_ignore_ascii_case(name)))
+}
+
+/// Ctrl+Z undoes the last drawing operation and Ctrl+Y redoes it
+pub fn handle_undo_redo(
+    mut grid: ResMut<GameGrid>,
+    mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
+    mut undo_stack: ResMut<UndoStack>,
+    mut redo_stack: ResMut<RedoStack>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    egui_contexts: Option<EguiContexts>,
+) {
+    // Don't process input if egui is consuming it
+    if let Some(mut contexts) = egui_contexts {
+        if let Ok(ctx) = contexts.ctx_mut()
+            && ctx.wants_keyboard_input()
+        {
+            return;
+        }
+    }
+
+    if !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyZ) {
+        // Snapshots from before a resize can't be restored, so they are dropped
+        let current = GridSnapshot::capture(&grid, &rainbow_sand_times);
+        while let Some(snapshot) = undo_stack.0.pop_back() {
+            if snapshot.restore(&mut grid, &mut rainbow_sand_times) {
+                redo_stack.0.push(current);
+                break;
+            }
+        }
+    } else if keyboard_input.just_pressed(KeyCode::KeyY) {
+        let current = GridSnapshot::capture(&grid, &rainbow_sand_times);
+        while let Some(snapshot) = redo_stack.0.pop() {
+            if snapshot.restore(&mut grid, &mut rainbow_sand_times) {
+                undo_stack.0.push_back(current);
+                break;
+            }
+        }
+    }
+}
+
+/// Grid cell under the cursor, clamped to the grid
+fn cursor_grid_position(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    grid: &GameGrid,
+) -> Option<(u32, u32)> {
+    let position = cursor_grid_position_f32(windows, camera_query, grid)?;
+    let grid_x = position.x.clamp(0.0, grid.width as f32 - 1.0) as u32;
+    let grid_y = (position.y - 1.0).clamp(0.0, grid.height as f32 - 1.0) as u32;
+    Some((grid_x, grid_y))
+}
+
+/// Cursor position in grid cells (+y down, 0 at the top edge), not clamped to the grid
+fn cursor_grid_position_f32(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    grid: &GameGrid,
+) -> Option<Vec2> {
+    let window = windows.single().ok()?;
+    let cursor_position = window.cursor_position()?;
+    let (camera, camera_transform) = camera_query.single().ok()?;
+    let world_pos = camera.viewport_to_world_2d(camera_transform, cursor_position).ok()?;
+
+    // Convert world coordinates to grid coordinates
+    let display_factor_f32 = DISPLAY_FACTOR as f32;
+    Some(Vec2::new(
+        world_pos.x / display_factor_f32 + grid.width as f32 / 2.0,
+        grid.height as f32 / 2.0 - world_pos.y / display_factor_f32,
+    ))
+}
+
+/// Where the cursor was last frame
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CursorLocation {
+    #[default]
+    OffScreen,
+    OverUi,
+    OnGrid,
+}
+
+/// Resource with the grid cell and element under the cursor, shown at the bottom of the controls
+/// `grid_x`, `grid_y` and `element` are only meaningful when `location` is `OnGrid`
+#[derive(Resource)]
+pub struct CursorInfo {
+    pub grid_x: u32,
+    pub grid_y: u32,
+    pub element: Element,
+    pub location: CursorLocation,
+}
+
+impl Default for CursorInfo {
+    fn default() -> Self {
+        Self {
+            grid_x: 0,
+            grid_y: 0,
+            element: Element::Background,
+            location: CursorLocation::OffScreen,
+        }
+    }
+}
+
+/// Update `CursorInfo` before the UI draws it
+pub fn update_cursor_info(
+    grid: Res<GameGrid>,
+    mut cursor_info: ResMut<CursorInfo>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    egui_contexts: Option<EguiContexts>,
+) {
+    if let Some(mut contexts) = egui_contexts {
+        if let Ok(ctx) = contexts.ctx_mut()
+            && ctx.is_pointer_over_area()
+        {
+            cursor_info.location = CursorLocation::OverUi;
+            return;
+        }
+    }
+
+    match cursor_grid_position(&windows, &camera_query, &grid) {
+        Some((grid_x, grid_y)) => {
+            cursor_info.grid_x = grid_x;
+            cursor_info.grid_y = grid_y;
+            cursor_info.element = grid.get(grid_x, grid_y);
+            cursor_info.location = CursorLocation::OnGrid;
+        }
+        None => cursor_info.location = CursorLocation::OffScreen,
+    }
+}
+
+/// Ctrl+drag selects a rectangle, Ctrl+C copies it and Ctrl+V pastes at the cursor
+/// Ctrl+R rotates the selection clockwise, Ctrl+Shift+R counterclockwise
+pub fn handle_selection(
+    mut grid: ResMut<GameGrid>,
+    mut selection: ResMut<SelectionState>,
+    mut clipboard: ResMut<Clipboard>,
+    mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    egui_contexts: Option<EguiContexts>,
+) {
+    // Don't process input if egui is consuming it
+    if let Some(mut contexts) = egui_contexts {
+        if let Ok(ctx) = contexts.ctx_mut()
+            && (ctx.wants_pointer_input() || ctx.is_pointer_over_area() || ctx.wants_keyboard_input())
+        {
+            return;
+        }
+    }
+
+    if !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let cursor = cursor_grid_position(&windows, &camera_query, &grid);
+
+    // Ctrl+drag
+    if let Some(cell) = cursor {
+        if mouse_button_input.just_pressed(MouseButton::Left) {
+            selection.start = Some(cell);
+            selection.end = Some(cell);
+        } else if mouse_button_input.pressed(MouseButton::Left) && selection.start.is_some() {
+            selection.end = Some(cell);
+        }
+    }
+
+    // Ctrl+C
+    if keyboard_input.just_pressed(KeyCode::KeyC)
+        && let Some((x, y, w, h)) = selection.region()
+    {
+        let (width, height) = grid.clamp_region(x, y, w, h);
+        clipboard.elements = grid.copy_region(x, y, width, height);
+        clipboard.rainbow_times = (0..height)
+            .flat_map(|ry| (0..width).map(move |rx| (x + rx, y + ry)))
+            .map(|(cx, cy)| rainbow_sand_times.0.get(&grid.xy_to_index(cx, cy)).copied())
+            .collect();
+        clipboard.width = width;
+        clipboard.height = height;
+    }
+
+    // Ctrl+R, the selection follows the rotated region
+    if keyboard_input.just_pressed(KeyCode::KeyR)
+        && let Some((x, y, w, h)) = selection.region()
+    {
+        let rotation = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            Rotation::R270
+        } else {
+            Rotation::R90
+        };
+        if grid.rotate_region(x, y, w, h, rotation, &mut rainbow_sand_times.0) {
+            selection.start = Some((x, y));
+            selection.end = Some((x + h - 1, y + w - 1));
+        }
+    }
+
+    // Ctrl+V
+    if keyboard_input.just_pressed(KeyCode::KeyV)
+        && let Some((x, y)) = cursor
+        && !clipboard.elements.is_empty()
+    {
+        grid.paste_region(x, y, &clipboard);
+        // Pasted RainbowSand keeps the colors it was copied with
+        let (width, height) = grid.clamp_region(x, y, clipboard.width, clipboard.height);
+        for ry in 0..height {
+            for rx in 0..width {
+                let idx = grid.xy_to_index(x + rx, y + ry);
+                match clipboard.rainbow_times[(ry * clipboard.width + rx) as usize] {
+                    Some(time) => rainbow_sand_times.0.insert(idx, time),
+                    None => rainbow_sand_times.0.remove(&idx),
+                };
+            }
+        }
+    }
+}
+
+/// Select an element with its keyboard shortcut, Space steps the simulation while paused
+pub fn handle_keyboard_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    shortcuts: Res<KeyboardShortcuts>,
+    mut selected: ResMut<SelectedElement>,
+    (simulation_speed, mut step_mode): (Res<SimulationSpeed>, ResMut<StepMode>),
+    egui_contexts: Option<EguiContexts>,
+) {
+    // Don't select elements while typing into egui
+    if let Some(mut contexts) = egui_contexts {
+        if let Ok(ctx) = contexts.ctx_mut()
+            && ctx.wants_keyboard_input()
+        {
+            return;
+        }
+    }
+
+    if keyboard.just_pressed(KeyCode::Space) && simulation_speed.0 <= 0.0 {
+        step_mode.step_requested = true;
+    }
+
+    let shift_pressed = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let map = if shift_pressed { &shortcuts.shift_keys } else { &shortcuts.keys };
+    for key in keyboard.get_just_pressed() {
+        if let Some(element) = map.get(key) {
+            selected.0 = *element;
+        }
+    }
+}
+
+/// Resource to track the rectangular selection (corners in grid cells), set with Ctrl+drag
+#[derive(Resource, Default)]
+pub struct SelectionState {
+    pub start: Option<(u32, u32)>,
+    pub end: Option<(u32, u32)>,
+}
+
+impl SelectionState {
+    /// Top left corner and size of the selection
+    pub fn region(&self) -> Option<(u32, u32, u32, u32)> {
+        let ((start_x, start_y), (end_x, end_y)) = (self.start?, self.end?);
+        Some((
+            start_x.min(end_x),
+            start_y.min(end_y),
+            start_x.abs_diff(end_x) + 1,
+            start_y.abs_diff(end_y) + 1,
+        ))
+    }
+}
+
+/// Resource to toggle the minimap window
+#[derive(Resource, Clone, Copy)]
+pub struct ShowMinimap(pub bool);
+
+/// Resource to cache the minimap texture, re-uploaded every `MINIMAP_UPDATE_FRAMES` frames
+#[derive(Resource, Default)]
+pub struct MinimapTexture(pub Option<egui::TextureHandle>);
+
+/// Size of the minimap in pixels
+pub const MINIMAP_SIZE: (usize, usize) = (160, 90);
+
+/// How often the minimap is redrawn, in frames
+const MINIMAP_UPDATE_FRAMES: u32 = 15;
+
+/// Visual effect settings shown under "Visual Effects" in the UI
+#[derive(SystemParam)]
+pub struct VisualSettings<'w> {
+    pub glow_settings: ResMut<'w, GlowSettings>,
+    pub animated_colors: ResMut<'w, AnimatedColors>,
+    pub water_ripples: ResMut<'w, WaterRipples>,
+    pub show_minimap: ResMut<'w, ShowMinimap>,
+    pub render_mode: ResMut<'w, RenderMode>,
+    pub colorblind_mode: ResMut<'w, ColorblindMode>,
+}
+
+/// Resource to toggle the ripple effect on water surfaces
+#[derive(Resource, Clone, Copy)]
+pub struct WaterRipples(pub bool);
+
+/// Resource to control the glow drawn around Fire and Lava
+#[derive(Resource, Clone, Copy)]
+pub struct GlowSettings {
+    /// Blur radius in cells
+    pub radius: u32,
+    /// Opacity of the glow at its brightest
+    pub intensity: f32,
+    pub enabled: bool,
+}
+
+impl Default for GlowSettings {
+    fn default() -> Self {
+        Self {
+            radius: 4,
+            intensity: 0.4,
+            enabled: true,
+        }
+    }
+}
+
+/// Resource to store the glow texture handle, drawn over the grid
+#[derive(Resource)]
+pub struct GlowTexture(pub Handle<Image>);
+
+/// Resource to track placement counter for RainbowSand gradient effect
+/// This increments each time RainbowSand is placed, creating a gradient over time
+#[derive(Resource, Default)]
+pub struct RainbowSandPlacementCounter {
+    pub counter: u32,
+    pub last_mouse_pressed: bool,
+    pub frame_since_last_increment: u32,
+}
+
+/// Resource to track placement time for each RainbowSand element
+/// Maps grid index to the placement counter value when that position was last set to RainbowSand
+#[derive(Resource, Default)]
+pub struct RainbowSandPlacementTimes(pub HashMap<usize, u32>);
+
+/// Maximum number of drawing operations that can be undone
+pub const MAX_UNDO_STEPS: usize = 20;
+
+/// Grid state captured before a drawing operation
+pub struct GridSnapshot {
+    pub elements: Vec<Element>,
+    pub rainbow_times: HashMap<usize, u32>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl GridSnapshot {
+    pub fn capture(grid: &GameGrid, rainbow_sand_times: &RainbowSandPlacementTimes) -> Self {
+        Self {
+            elements: grid.elements.clone(),
+            rainbow_times: rainbow_sand_times.0.clone(),
+            width: grid.width,
+            height: grid.height,
+        }
+    }
+
+    /// Restore the snapshot, returns false if the grid has been resized since it was captured
+    pub fn restore(&self, grid: &mut GameGrid, rainbow_sand_times: &mut RainbowSandPlacementTimes) -> bool {
+        if self.width != grid.width || self.height != grid.height {
+            return false;
+        }
+        for (i, &element) in self.elements.iter().enumerate() {
+            grid.set_index(i, element);
+        }
+        rainbow_sand_times.0.clone_from(&self.rainbow_times);
+        true
+    }
+}
+
+/// Snapshots to restore with Ctrl+Z, oldest first
+#[derive(Resource, Default)]
+pub struct UndoStack(pub VecDeque<GridSnapshot>);
+
+/// Snapshots undone with Ctrl+Z that Ctrl+Y can reapply
+#[derive(Resource, Default)]
+pub struct RedoStack(pub Vec<GridSnapshot>);
+
+impl UndoStack {
+    /// Save the state before a new drawing operation, which also discards anything that could be redone
+    pub fn record(&mut self, redo_stack: &mut RedoStack, grid: &GameGrid, rainbow_sand_times: &RainbowSandPlacementTimes) {
+        if self.0.len() >= MAX_UNDO_STEPS {
+            self.0.pop_front();
+        }
+        self.0.push_back(GridSnapshot::capture(grid, rainbow_sand_times));
+        redo_stack.0.clear();
+    }
+}
+
+pub fn setup(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
+    // Create a single image for rendering (CPU-based, no double buffering needed)
+    // Use Rgba8Unorm for simpler byte-based updates
+    let mut image = Image::new_target_texture(SIZE.x, SIZE.y, TextureFormat::Rgba8Unorm);
+    image.asset_usage = RenderAssetUsages::RENDER_WORLD;
+    image.texture_descriptor.usage = TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
+    let texture_handle = image_assets.add(image);
+
+    commands.spawn((
+        Sprite {
+            image: texture_handle.clone(),
+            custom_size: Some(SIZE.as_vec2()),
+            ..default()
+        },
+        // DISPLAY_FACTOR is u32, cast to f32 for scale
+        Transform::from_scale(Vec3::splat(DISPLAY_FACTOR as f32)),
+    ));
+    commands.spawn(Camera2d);
+
+    // Glow texture, drawn over the grid sprite
+    let mut glow_image = Image::new_target_texture(SIZE.x, SIZE.y, TextureFormat::Rgba8Unorm);
+    glow_image.data = Some(vec![0u8; (SIZE.x * SIZE.y * 4) as usize]);
+    glow_image.asset_usage = RenderAssetUsages::RENDER_WORLD;
+    glow_image.texture_descriptor.usage = TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
+    let glow_texture_handle = image_assets.add(glow_image);
+    commands.spawn((
+        Sprite {
+            image: glow_texture_handle.clone(),
+            custom_size: Some(SIZE.as_vec2()),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 1.0).with_scale(Vec3::splat(DISPLAY_FACTOR as f32)),
+    ));
+    commands.insert_resource(GlowTexture(glow_texture_handle));
+    commands.insert_resource(GlowSettings::default());
+
+    // Store texture handle for rendering (the texture is updated in place each frame)
+    commands.insert_resource(RenderTexture(texture_handle.clone()));
+
+    // CPU copy of the texture's pixels and the region to upload each frame
+    commands.insert_resource(GridPixels::default());
+    commands.insert_resource(TextureUpload {
+        texture: texture_handle.clone(),
+        ..default()
+    });
+
+    // Initialize game grid (CPU-based simulation)
+    commands.insert_resource(GameGrid::default());
+
+    // Initialize spigots resource
+    commands.insert_resource(Spigots::default());
+    commands.insert_resource(SpigotPositionCache::default());
+    commands.insert_resource(SpigotPlacementState::default());
+    commands.insert_resource(AntiGravitySources::default());
+
+    // Resource to track selected element (for UI)
+    commands.insert_resource(SelectedElement(Element::RainbowSand));
+    
+    // Resource to track fall into void setting (default: true)
+    commands.insert_resource(FallIntoVoid(false));
+    commands.insert_resource(WorldWrap(false));
+    
+    // Resource to track draw radius (default: 5.0)
+    commands.insert_resource(DrawRadius(5.0));
+    
+    // Resource to track overwrite mode (default: true, overwrite existing materials)
+    commands.insert_resource(OverwriteMode(true));
+    commands.insert_resource(ElementFilter::default());
+    commands.insert_resource(SymmetryMode::default());
+    commands.insert_resource(DrawMode::default());
+    
+    // Resource to signal grid clearing
+    commands.insert_resource(ClearGrid::default());
+    
+    // Resources for save/load
+    commands.insert_resource(SaveGrid::default());
+    commands.insert_resource(LoadGrid::default());
+    commands.insert_resource(SavePng::default());
+    commands.insert_resource(LoadPng::default());
+    commands.insert_resource(SaveLoadTask::default());
+    commands.insert_resource(QuickSaveManager::scan());
+    commands.insert_resource(FrameTimings::default());
+    commands.insert_resource(CursorInfo::default());
+    commands.insert_resource(GridStats::default());
+    commands.insert_resource(SaveBrowser::default());
+    commands.insert_resource(AutoSave::default());
+    commands.insert_resource(WorldGenRequest::default());
+    commands.insert_resource(ReplaceElementRequest::default());
+    commands.insert_resource(ElementInteractionMatrix::default());
+    
+    // Resource to track frame count for time-based effects
+    commands.insert_resource(FrameCount::default());
+
+    // Resource to toggle animated element colors (default: true)
+    commands.insert_resource(AnimatedColors(true));
+
+    // Resource to toggle water surface ripples (default: true)
+    commands.insert_resource(WaterRipples(true));
+
+    // Resources for rectangular selection, copy and paste
+    commands.insert_resource(SelectionState::default());
+    commands.insert_resource(Clipboard::default());
+
+    // Resource to track keyboard shortcuts for element selection (remappable in config.toml)
+    commands.insert_resource(KeyboardShortcuts::load_or_create());
+
+    // Resource to track the render mode (default: element colors)
+    commands.insert_resource(RenderMode::default());
+    commands.insert_resource(ColorblindMode::default());
+
+    // Resources for the minimap (default: shown)
+    commands.insert_resource(ShowMinimap(true));
+    commands.insert_resource(MinimapTexture::default());
+
+    // Resources to track camera zoom and pan
+    commands.insert_resource(CameraZoom::default());
+    commands.insert_resource(CameraPan::default());
+    commands.insert_resource(CameraShake::default());
+
+    // Resource to track screenshots (F12)
+    commands.insert_resource(ScreenshotState::default());
+    
+    // Resource to track simulation speed (0.0 = paused, 1.0 = normal, 2.0 = 2x speed)
+    commands.insert_resource(SimulationSpeed::default());
+    commands.insert_resource(StepMode::default());
+    commands.insert_resource(SimulationSeed::default());
+    commands.insert_resource(RecordingState::default());
+    
+    // Resource to track RainbowSand placement counter for gradient effect
+    commands.insert_resource(RainbowSandPlacementCounter::default());
+    
+    // Resource to track RainbowSand placement times
+    commands.insert_resource(RainbowSandPlacementTimes::default());
+    commands.insert_resource(UndoStack::default());
+    commands.insert_resource(RedoStack::default());
+    
+    // Resource to track line drawing state for shift-key straight lines
+    commands.insert_resource(LineDrawingState::default());
+    
+    // Resource to track active tree branches for incremental growth
+    commands.insert_resource(ActiveTreeBranches::default());
+    
+    // Resources to track portal pairs and the portal waiting for its pair
+    commands.insert_resource(PortalRegistry::default());
+    commands.insert_resource(EmitterConfigs::default());
+    commands.insert_resource(EmitterEditor::default());
+    commands.insert_resource(PendingPortal::default());
+    
+    // Resource to track wind (default: calm)
+    commands.insert_resource(WindField::default());
+    commands.insert_resource(load_rules());
+    commands.insert_resource(Challenges::load());
+    commands.insert_resource(SimulationAudio::default());
+    commands.insert_resource(AudioSettings::default());
+    commands.insert_resource(RulesWatcher::start());
+    
+    // Initialize particle system
+    commands.insert_resource(ParticleList::default());
+    
+    // Create particle texture (offscreen canvas for particles)
+    // Initialized with transparent black pixels
+    let particle_texture_handle = image_assets.add(new_particle_image(SIZE.x, SIZE.y));
+    commands.insert_resource(ParticleTexture(particle_texture_handle));
+}
+
+/// Resource to store the render texture handle
+#[derive(Resource)]
+pub struct RenderTexture(pub Handle<Image>);
+
+/// CPU copy of the render texture's pixels (Rgba8Unorm), redrawn in place where the grid changed
+#[derive(Resource, Default)]
+pub struct GridPixels {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+    /// Area particles were composited onto last frame, redrawn from the grid this frame
+    pub particle_rect: Option<(u32, u32, u32, u32)>,
+    /// Area water ripples were drawn onto last frame, redrawn from the grid this frame
+    pub ripple_rect: Option<(u32, u32, u32, u32)>,
+}
+
+/// Region of the render texture to upload this frame, extracted to the render world
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct TextureUpload {
+    pub texture: Handle<Image>,
+    /// (min_x, min_y, max_x, max_y) of the region, None when nothing changed
+    pub rect: Option<(u32, u32, u32, u32)>,
+    /// Tightly packed rows of the region
+    pub data: Vec<u8>,
+}
+
+/// Smallest rect containing both rects
+pub fn union_rects(
+    a: Option<(u32, u32, u32, u32)>,
+    b: Option<(u32, u32, u32, u32)>,
+) -> Option<(u32, u32, u32, u32)> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some((a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// UI system for the egui controls window.
+///
+/// # Errors
+/// Returns an error if the egui context cannot be accessed.
+pub fn ui_system(
+    mut contexts: EguiContexts,
+    (mut selected_element, mut element_filter): (ResMut<SelectedElement>, ResMut<ElementFilter>),
+    mut spigots: ResMut<Spigots>,
+    (mut fall_into_void, mut world_wrap): (ResMut<FallIntoVoid>, ResMut<WorldWrap>),
+    mut draw_radius: ResMut<DrawRadius>,
+    mut overwrite_mode: ResMut<OverwriteMode>,
+    mut symmetry_mode: ResMut<SymmetryMode>,
+    mut draw_mode: ResMut<DrawMode>,
+    (mut clear_grid, mut world_gen, mut replace_request): (ResMut<ClearGrid>, ResMut<WorldGenRequest>, ResMut<ReplaceElementRequest>),
+    mut simulation_speed: ResMut<SimulationSpeed>,
+    (mut save_grid, mut load_grid, mut save_png, mut load_png, mut recording, grid, save_load_task, quick_saves, mut save_browser): (
+        ResMut<SaveGrid>,
+        ResMut<LoadGrid>,
+        ResMut<SavePng>,
+        ResMut<LoadPng>,
+        ResMut<RecordingState>,
+        Res<GameGrid>,
+        Res<SaveLoadTask>,
+        Res<QuickSaveManager>,
+        ResMut<SaveBrowser>,
+    ),
+    (portal_registry, pending_portal, undo_stack, frame_timings, cursor_info, mut emitter_configs, mut emitter_editor): (
+        Res<PortalRegistry>,
+        Res<PendingPortal>,
+        Res<UndoStack>,
+        Res<FrameTimings>,
+        Res<CursorInfo>,
+        ResMut<EmitterConfigs>,
+        ResMut<EmitterEditor>,
+    ),
+    shortcuts: Res<KeyboardShortcuts>,
+    (mut wind, mut rules, mut simulation_seed, grid_stats, mut auto_save, time, mut audio_settings, mut step_mode): (
+        ResMut<WindField>,
+        ResMut<SimulationRules>,
+        ResMut<SimulationSeed>,
+        Res<GridStats>,
+        ResMut<AutoSave>,
+        Res<Time>,
+        ResMut<AudioSettings>,
+        ResMut<StepMode>,
+    ),
+    mut visual_settings: VisualSettings,
+) {
+    if let Ok(ctx) = contexts.ctx_mut() {
+        egui::Window::new("Controls").show(ctx, |ui| {
+        // Element selection
+        ui.label("Selected Element:");
+
+        // Search box, Escape clears it
+        ui.horizontal(|ui| {
+            ui.label("Search:");
+            ui.text_edit_singleline(&mut element_filter.text);
+            if element_filter.is_active() && ui.small_button("x").clicked() {
+                element_filter.text.clear();
+                element_filter.group = None;
+            }
+        });
+        if ctx.input(|input| input.key_pressed(egui::Key::Escape)) {
+            element_filter.text.clear();
+        }
+
+        egui::CollapsingHeader::new("Groups").show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                if ui.selectable_label(element_filter.group.is_none(), "All").clicked() {
+                    element_filter.group = None;
+                }
+                for group in ElementGroup::ALL {
+                    if ui.selectable_label(element_filter.group == Some(group), format!("{group:?}")).clicked() {
+                        element_filter.group = Some(group);
+                    }
+                }
+            });
+        });
+
+        let palette = [Element::Sand, Element::RainbowSand, Element::Water, Element::Wall, Element::Fire, Element::Salt, Element::Oil, Element::Rock, Element::Ice, Element::Lava, Element::Steam, Element::SaltWater, Element::Plant, Element::Gunpowder, Element::Wax, Element::Concrete, Element::Nitro, Element::Napalm, Element::C4, Element::Fuse, Element::Acid, Element::Cryo, Element::Methane, Element::Soil, Element::WetSoil, Element::Thermite, Element::Spout, Element::Well, Element::Torch, Element::Branch, Element::Leaf, Element::Pollen, Element::FallingWax, Element::ChilledIce, Element::Mystery, Element::ChargedNitro, Element::BurningThermite, Element::Clone, Element::Bubble, Element::Virus, Element::Portal, Element::Mud, Element::Magma, Element::Quicksand, Element::Catalyst, Element::Slime, Element::Honey, Element::HardWall, Element::Emitter, Element::Creature, Element::AntiGravity, Element::Nuke];
+        let filtering = element_filter.is_active();
+        if filtering {
+            let matching = palette.iter().filter(|&&element| element_filter.matches(element)).count();
+            ui.label(format!("{matching} / {} elements", palette.len()));
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            for element in palette {
+                if filtering && !element_filter.matches(element) {
+                    continue;
+                }
+                let is_selected = selected_element.0 == element;
+                let button_text = match shortcuts.label_for(element) {
+                    Some(key) => format!("{element:?} [{key}]"),
+                    None => format!("{element:?}"),
+                };
+                let response = if filtering {
+                    // Outline search matches
+                    egui::Frame::new()
+                        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(90, 160, 255)))
+                        .corner_radius(2.0)
+                        .show(ui, |ui| ui.selectable_label(is_selected, &button_text))
+                        .inner
+                } else {
+                    ui.selectable_label(is_selected, &button_text)
+                };
+                if response.clicked() {
+                    selected_element.0 = element;
+                }
+                // Right-click to swap every cell of this element for the selected one
+                response.context_menu(|ui| {
+                    let to = selected_element.0;
+                    if ui.add_enabled(to != element, egui::Button::new(format!("Replace all {element:?} with {to:?}"))).clicked() {
+                        replace_request.0 = Some((element, to));
+                        ui.close();
+                    }
+                });
                 // Show tooltip on hover
                 response.on_hover_text(get_element_description(element));
             }
         });
 
+        // Portal link status
+        if selected_element.0 == Element::Portal {
+            ui.label(format!(
+                "Portals: {}/{} pairs linked{}",
+                portal_registry.pair_count(),
+                MAX_PORTAL_PAIRS,
+                if pending_portal.0.is_some() { ", 1 waiting for a pair" } else { "" }
+            ));
+        }
+
         ui.separator();
 
         // Draw radius
@@ -258,6 +1547,27 @@ pub fn ui_system(
             }
         });
 
+        // Brush shape
+        ui.horizontal(|ui| {
+            ui.label("Brush:");
+            egui::ComboBox::from_id_salt("draw_mode")
+                .selected_text(draw_mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in [DrawMode::Circle, DrawMode::Square, DrawMode::Spray { density: 0.2 }, DrawMode::Fill] {
+                        let is_selected = std::mem::discriminant(&*draw_mode) == std::mem::discriminant(&mode);
+                        if ui.selectable_label(is_selected, mode.label()).clicked() && !is_selected {
+                            *draw_mode = mode;
+                        }
+                    }
+                });
+        });
+        if let DrawMode::Spray { density } = &mut *draw_mode {
+            ui.horizontal(|ui| {
+                ui.label("Spray Density:");
+                ui.add(egui::Slider::new(density, 0.01..=1.0));
+            });
+        }
+
         ui.separator();
 
         // Fall into void toggle
@@ -267,6 +1577,13 @@ pub fn ui_system(
         }
         ui.label("When enabled, elements fall off screen edges. When disabled, elements stop at edges.");
 
+        // World wrap toggle
+        let mut wrap = world_wrap.0;
+        if ui.checkbox(&mut wrap, "World Wrap").changed() {
+            world_wrap.0 = wrap;
+        }
+        ui.label("When enabled, elements leaving one edge enter from the opposite edge. Overrides Fall Into Void.");
+
         ui.separator();
 
         // Overwrite mode toggle
@@ -276,6 +1593,18 @@ pub fn ui_system(
         }
         ui.label("When enabled, drawing overwrites existing materials. When disabled, only draws on empty spaces.");
 
+        // Symmetry mode dropdown
+        ui.horizontal(|ui| {
+            ui.label("Symmetry:");
+            egui::ComboBox::from_id_salt("symmetry_mode")
+                .selected_text(format!("{:?}", *symmetry_mode))
+                .show_ui(ui, |ui| {
+                    for mode in SymmetryMode::ALL {
+                        ui.selectable_value(&mut *symmetry_mode, mode, format!("{mode:?}"));
+                    }
+                });
+        });
+
         ui.separator();
 
         // Simulation speed slider
@@ -290,9 +1619,113 @@ pub fn ui_system(
             } else {
                 ui.label(format!("{:.1}x", speed));
             }
+            // Stepping only makes sense while paused
+            if ui
+                .add_enabled(speed == 0.0, egui::Button::new("Step"))
+                .on_hover_text("Advance one tick (Space)")
+                .on_disabled_hover_text("Pause the simulation to step it")
+                .clicked()
+            {
+                step_mode.step_requested = true;
+            }
         });
         ui.label("0.0 = Paused, 1.0 = Normal Speed, 2.0 = 2x Speed");
 
+        // Deterministic mode, changing the seed restarts the frame count
+        ui.horizontal(|ui| {
+            let mut changed = ui.checkbox(&mut simulation_seed.enabled, "Seeded").changed();
+            ui.label("Seed:");
+            changed |= ui.add(egui::DragValue::new(&mut simulation_seed.seed)).changed();
+            if changed {
+                simulation_seed.frame = 0;
+            }
+        });
+        ui.label("When seeded, the same seed and inputs always give the same result.");
+
+        ui.separator();
+
+        // Wind controls
+        ui.collapsing("Wind", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Direction:");
+                let mut degrees = wind.direction.y.atan2(wind.direction.x).to_degrees().rem_euclid(360.0);
+                if ui.add(egui::Slider::new(&mut degrees, 0.0..=359.0).suffix("°")).changed() {
+                    wind.direction = Vec2::from_angle(degrees.to_radians());
+                }
+                let arrow = match ((degrees + 22.5) / 45.0) as u32 % 8 {
+                    0 => "→",
+                    1 => "↗",
+                    2 => "↑",
+                    3 => "↖",
+                    4 => "←",
+                    5 => "↙",
+                    6 => "↓",
+                    _ => "↘",
+                };
+                ui.label(arrow);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Strength:");
+                let mut strength = wind.strength;
+                if ui.add(egui::Slider::new(&mut strength, 0.0..=WindField::MAX_STRENGTH)).changed() {
+                    wind.strength = strength;
+                    if wind.direction == Vec2::ZERO {
+                        wind.direction = Vec2::X;
+                    }
+                }
+            });
+            ui.label("Wind pushes gases and light powders, liquids and solids are unaffected.");
+        });
+
+        ui.separator();
+
+        // Visual effects
+        ui.collapsing("Visual Effects", |ui| {
+            let heat_map = *visual_settings.render_mode == RenderMode::HeatMap;
+            if ui.selectable_label(heat_map, "Heat Map").clicked() {
+                *visual_settings.render_mode = if heat_map { RenderMode::Normal } else { RenderMode::HeatMap };
+            }
+            ui.checkbox(&mut visual_settings.show_minimap.0, "Show Minimap");
+            ui.checkbox(&mut visual_settings.animated_colors.0, "Animated Colors");
+            ui.checkbox(&mut visual_settings.water_ripples.0, "Water Ripples");
+            ui.checkbox(&mut visual_settings.glow_settings.enabled, "Fire/Lava Glow");
+            ui.horizontal(|ui| {
+                ui.label("Glow Radius:");
+                ui.add(egui::Slider::new(&mut visual_settings.glow_settings.radius, 1..=16));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Glow Intensity:");
+                ui.add(egui::Slider::new(&mut visual_settings.glow_settings.intensity, 0.0..=1.0));
+            });
+        });
+
+        ui.separator();
+
+        ui.collapsing("Accessibility", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Color Vision:");
+                egui::ComboBox::from_id_salt("colorblind_mode")
+                    .selected_text(visual_settings.colorblind_mode.name())
+                    .show_ui(ui, |ui| {
+                        for mode in ColorblindMode::ALL {
+                            ui.selectable_value(&mut *visual_settings.colorblind_mode, mode, mode.name());
+                        }
+                    });
+            });
+            ui.label("Adjusts element colors so they stay distinct with color blindness.");
+        });
+
+        ui.separator();
+
+        ui.collapsing("Audio", |ui| {
+            ui.checkbox(&mut audio_settings.muted, "Mute");
+            ui.horizontal(|ui| {
+                ui.label("Volume:");
+                ui.add_enabled(!audio_settings.muted, egui::Slider::new(&mut audio_settings.volume, 0.0..=1.0));
+            });
+            ui.label("Fire crackles faster as it spreads, large bodies of water add a calm layer and explosions add drums.");
+        });
+
         ui.separator();
 
         // Save/Load buttons
@@ -303,6 +1736,72 @@ pub fn ui_system(
             if ui.button("Load").clicked() {
                 load_grid.0 = true;
             }
+            if ui.button("Save PNG").clicked() {
+                save_png.0 = true;
+            }
+            if ui.button("Load PNG").clicked() {
+                load_png.0 = true;
+            }
+            if ui.button("Browse...").clicked() {
+                save_browser.open = !save_browser.open;
+                save_browser.refresh_requested = true;
+            }
+            if save_load_task.is_saving() {
+                ui.spinner();
+                ui.label("Saving...");
+            }
+            if save_load_task.is_loading() {
+                ui.spinner();
+                ui.label("Loading...");
+            }
+        });
+
+        // Auto-save
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut auto_save.enabled, "Auto-Save");
+            ui.add(
+                egui::Slider::new(&mut auto_save.interval_secs, 30.0..=1800.0)
+                    .text("Auto-Save Interval")
+                    .suffix("s"),
+            );
+        });
+
+        // Quick-save slots (F1-F5 to save, Shift+F1-F5 to load)
+        ui.horizontal(|ui| {
+            for slot in 0..QUICK_SAVE_SLOTS {
+                let (text, color) = match (&quick_saves.slots[slot], quick_saves.last_saved[slot]) {
+                    (Some(_), Some(time)) => (format!("F{} {}", slot + 1, time_ago(time)), egui::Color32::GREEN),
+                    (Some(_), None) => (format!("F{} saved", slot + 1), egui::Color32::GREEN),
+                    (None, _) => (format!("F{} empty", slot + 1), egui::Color32::GRAY),
+                };
+                ui.label(egui::RichText::new(text).color(color));
+            }
+        });
+
+        // Recording controls
+        ui.horizontal(|ui| {
+            if recording.active {
+                ui.label(format!("Recording {}/{}", recording.frames.len(), MAX_RECORDED_FRAMES));
+            } else if recording.playing {
+                ui.label(format!("Playing {}/{}", recording.playback_frame, recording.frames.len()));
+            } else if ui.button("Record").clicked() {
+                recording.start(&grid);
+            }
+            if (recording.active || recording.playing) && ui.button("Stop").clicked() {
+                recording.active = false;
+                recording.playing = false;
+            }
+            if !recording.active && !recording.playing && !recording.frames.is_empty() && ui.button("Play").clicked() {
+                recording.play(&grid);
+            }
+        });
+        ui.horizontal(|ui| {
+            if !recording.frames.is_empty() && ui.button("Save Recording").clicked() {
+                recording.save();
+            }
+            if !recording.active && !recording.playing && ui.button("Load Recording").clicked() {
+                recording.load();
+            }
         });
 
         ui.separator();
@@ -312,14 +1811,38 @@ pub fn ui_system(
             clear_grid.0 = true;
         }
 
+        // World generation, an empty or non-numeric seed falls back to a random one
+        ui.horizontal(|ui| {
+            ui.label("World Seed:");
+            ui.add(egui::TextEdit::singleline(&mut world_gen.seed_text).desired_width(100.0));
+            if ui.button("Generate World").clicked() {
+                world_gen.generate = true;
+            }
+        });
+
         ui.separator();
 
         // Spigot controls
         ui.collapsing("Spigots", |ui| {
             let valid_elements = Element::spigot_valid_elements();
             let element_names: Vec<String> = valid_elements.iter().map(|e| format!("{:?}", e)).collect();
+
+            ui.horizontal(|ui| {
+                ui.label(format!("Count: {}", spigots.len()));
+                if ui.add_enabled(!spigots.is_empty(), egui::Button::new("−")).on_hover_text("Remove the last spigot").clicked() {
+                    let last = spigots.len() - 1;
+                    spigots.remove_spigot(last);
+                }
+                if ui
+                    .add_enabled(spigots.len() < MAX_SPIGOTS, egui::Button::new("+"))
+                    .on_hover_text(format!("Add a spigot (up to {})", MAX_SPIGOTS))
+                    .clicked()
+                {
+                    spigots.add_spigot(Element::Sand, DEFAULT_SPIGOT_SIZE);
+                }
+            });
             
-            for i in 0..NUM_SPIGOTS {
+            for i in 0..spigots.len() {
                 ui.group(|ui| {
                     ui.label(format!("Spigot {}", i + 1));
                     
@@ -334,6 +1857,11 @@ pub fn ui_system(
                     if spigots.sizes[i] == 0 {
                         ui.label("(Size 0 = disabled)");
                     }
+                    if spigots.custom_x.get(i).copied().flatten().is_some()
+                        && ui.button("Reset position").on_hover_text("Return to the even spacing").clicked()
+                    {
+                        spigots.custom_x[i] = None;
+                    }
                     
                     if spigots.sizes[i] > 0 {
                         // Element selection dropdown
@@ -361,126 +1889,1215 @@ pub fn ui_system(
                     }
                 });
                 
-                if i < NUM_SPIGOTS - 1 {
+                if i + 1 < spigots.len() {
                     ui.separator();
                 }
             }
-        });
-        });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(format!("Wall spigots: {}", spigots.wall_spigots.len()));
+                if ui
+                    .add_enabled(!spigots.wall_spigots.is_empty(), egui::Button::new("−"))
+                    .on_hover_text("Remove the last wall spigot")
+                    .clicked()
+                {
+                    spigots.wall_spigots.pop();
+                }
+                if ui
+                    .add_enabled(spigots.wall_spigots.len() < MAX_SPIGOTS, egui::Button::new("+"))
+                    .on_hover_text(format!("Add a spigot on a side wall (up to {})", MAX_SPIGOTS))
+                    .clicked()
+                {
+                    spigots.wall_spigots.push(WallSpigot::default());
+                }
+            });
+
+            for i in 0..spigots.wall_spigots.len() {
+                // Edit a copy so the spigots are only marked changed when something changed
+                let mut wall_spigot = spigots.wall_spigots[i];
+                ui.group(|ui| {
+                    ui.label(format!("Wall spigot {}", i + 1));
+                    ui.horizontal(|ui| {
+                        ui.label("Side:");
+                        ui.selectable_value(&mut wall_spigot.side, Side::Left, "Left");
+                        ui.selectable_value(&mut wall_spigot.side, Side::Right, "Right");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Size:");
+                        ui.add(egui::Slider::new(&mut wall_spigot.size, 0..=20));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Height:");
+                        ui.add(egui::Slider::new(&mut wall_spigot.position_fraction, 0.0..=1.0))
+                            .on_hover_text("0 is the top of the grid, 1 the bottom");
+                    });
+                    if wall_spigot.size == 0 {
+                        ui.label("(Size 0 = disabled)");
+                    } else {
+                        let current_idx = valid_elements
+                            .iter()
+                            .position(|&e| e == wall_spigot.element)
+                            .unwrap_or(0);
+                        ui.horizontal(|ui| {
+                            ui.label("Element:");
+                            bevy_egui::egui::ComboBox::from_id_salt(format!("wall_spigot_{}_element", i))
+                                .selected_text(&element_names[current_idx])
+                                .show_ui(ui, |ui| {
+                                    for (idx, element) in valid_elements.iter().enumerate() {
+                                        ui.selectable_value(&mut wall_spigot.element, *element, &element_names[idx]);
+                                    }
+                                });
+                        });
+                    }
+                });
+                if wall_spigot != spigots.wall_spigots[i] {
+                    spigots.wall_spigots[i] = wall_spigot;
+                }
+            }
+        });
+
+        ui.collapsing("Advanced Physics", |ui| {
+            ui.label("Per-tick probabilities used by the element rules");
+            egui::ScrollArea::vertical().id_salt("advanced_physics").max_height(320.0).show(ui, |ui| {
+                for (label, chance) in rules.fields_mut() {
+                    ui.add(egui::Slider::new(chance, 0.0..=1.0).text(label));
+                }
+            });
+            if ui.button("Reset to Defaults").clicked() {
+                *rules = SimulationRules::default();
+            }
+        });
+
+        ui.collapsing("Statistics", |ui| {
+            let total = grid_stats.statistics.total_cells;
+            egui::Grid::new("grid_stats").striped(true).show(ui, |ui| {
+                ui.label("Total cells");
+                ui.label(format_count(total));
+                ui.end_row();
+                ui.label("Non-empty cells");
+                ui.label(format_count(grid_stats.statistics.non_empty_cells));
+                ui.end_row();
+                ui.label("Empty");
+                ui.label(format!("{:.1}%", grid_stats.statistics.empty_pct));
+                ui.end_row();
+                if let Some(rarest) = grid_stats.statistics.least_common {
+                    ui.label("Rarest element");
+                    ui.label(format!("{rarest:?}"));
+                    ui.end_row();
+                }
+                ui.label("Active particles");
+                ui.label(format_count(grid_stats.active_particles));
+                ui.end_row();
+
+                for (element, count) in grid_stats.top_elements(10) {
+                    let color = element.color();
+                    let color = egui::Color32::from_rgb(
+                        (color.red * 255.0).clamp(0.0, 255.0) as u8,
+                        (color.green * 255.0).clamp(0.0, 255.0) as u8,
+                        (color.blue * 255.0).clamp(0.0, 255.0) as u8,
+                    );
+                    let percent = f64::from(count) / f64::from(total.max(1)) * 100.0;
+                    ui.label(egui::RichText::new(format!("{element:?}")).color(color));
+                    ui.label(egui::RichText::new(format!("{} ({percent:.1}%)", format_count(count))).color(color));
+                    ui.end_row();
+                }
+            });
+        });
+
+        ui.collapsing("Performance", |ui| {
+            let (sim_min, sim_max, sim_avg) = timing_stats(&frame_timings.sim_ms);
+            let sim_color = if frame_timings.sim_ms.back().is_some_and(|&ms| ms > FRAME_BUDGET_MS) {
+                egui::Color32::RED
+            } else {
+                egui::Color32::GREEN
+            };
+            ui.label(format!("Simulation: min {sim_min:.2} ms, max {sim_max:.2} ms, avg {sim_avg:.2} ms"));
+            timing_graph(ui, &frame_timings.sim_ms, frame_timings.capacity, sim_color);
+
+            let (render_min, render_max, render_avg) = timing_stats(&frame_timings.render_ms);
+            ui.label(format!("Render: min {render_min:.2} ms, max {render_max:.2} ms, avg {render_avg:.2} ms"));
+            timing_graph(ui, &frame_timings.render_ms, frame_timings.capacity, egui::Color32::LIGHT_BLUE);
+        });
+
+        ui.separator();
+        ui.label(format!("Undo: {}/{} (Ctrl+Z, Ctrl+Y to redo)", undo_stack.0.len(), MAX_UNDO_STEPS));
+        if auto_save.has_saved {
+            let seconds = (time.elapsed_secs() - auto_save.last_save_secs).max(0.0) as u64;
+            ui.label(format!("Last auto-save: {}", seconds_ago(seconds)));
+        } else {
+            ui.label("Last auto-save: never");
+        }
+        ui.label(match cursor_info.location {
+            CursorLocation::OnGrid => {
+                format!("Cursor: ({}, {}) — {:?}", cursor_info.grid_x, cursor_info.grid_y, cursor_info.element)
+            }
+            CursorLocation::OverUi => "Cursor: (UI)".to_string(),
+            CursorLocation::OffScreen => "Cursor: —".to_string(),
+        });
+        });
+
+        let mut open = save_browser.open;
+        egui::Window::new("Save Browser").open(&mut open).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Refresh").clicked() {
+                    save_browser.refresh_requested = true;
+                }
+                let target = save_browser.load_target.as_ref().map_or_else(
+                    || SAVE_PATH.to_string(),
+                    |path| path.display().to_string(),
+                );
+                if ui.button(format!("Load {target}")).clicked() {
+                    load_grid.0 = true;
+                }
+            });
+
+            // Save As... writes <name>.sand to the current directory
+            ui.horizontal(|ui| {
+                ui.label("Save As...");
+                ui.text_edit_singleline(&mut save_browser.save_as_name);
+                let name = save_browser.save_as_name.trim().to_string();
+                if ui.add_enabled(!name.is_empty(), egui::Button::new("Save")).clicked() {
+                    save_browser.save_target = Some(Path::new(&name).with_extension(SAVE_EXTENSION));
+                    save_grid.0 = true;
+                }
+            });
+
+            ui.separator();
+            if save_browser.saves.is_empty() {
+                ui.label(format!("No .{SAVE_EXTENSION} files in the current directory"));
+            }
+            let mut clicked = None;
+            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                egui::Grid::new("save_browser").striped(true).show(ui, |ui| {
+                    ui.strong("Name");
+                    ui.strong("Size");
+                    ui.strong("Modified");
+                    ui.strong("Elements");
+                    ui.strong("Dimensions");
+                    ui.end_row();
+
+                    for save in &save_browser.saves {
+                        let name = save.path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+                        let selected = save_browser.load_target.as_ref() == Some(&save.path);
+                        if ui.selectable_label(selected, name).clicked() {
+                            clicked = Some(save.path.clone());
+                        }
+                        ui.label(format!("{} KB", format_count((save.size_bytes / 1024) as u32)));
+                        ui.label(save.modified.map_or_else(|| "—".to_string(), time_ago));
+                        ui.label(save.element_count.map_or_else(|| "—".to_string(), format_count));
+                        ui.label(save.dimensions.map_or_else(|| "—".to_string(), |(width, height)| format!("{width}x{height}")));
+                        ui.end_row();
+                    }
+                });
+            });
+            if clicked.is_some() {
+                save_browser.load_target = clicked;
+            }
+        });
+        save_browser.open = open;
+
+        // Settings for the emitter picked with Shift+click, closed once the emitter is erased
+        if let Some(emitter_idx) = emitter_editor.0 {
+            if grid.get_index_checked(emitter_idx) == Some(Element::Emitter) {
+                let (x, y) = grid.index_to_xy(emitter_idx);
+                let current = emitter_configs.get(emitter_idx);
+                let mut config = current;
+                let mut open = true;
+                egui::Window::new("Emitter").open(&mut open).show(ctx, |ui| {
+                    ui.label(format!("Emitter at ({x}, {y})"));
+                    egui::ComboBox::from_id_salt("emitter_particle_type")
+                        .selected_text(format!("{:?}", config.particle_type))
+                        .show_ui(ui, |ui| {
+                            for particle_type in EMITTER_PARTICLE_TYPES {
+                                ui.selectable_value(&mut config.particle_type, particle_type, format!("{particle_type:?}"));
+                            }
+                        });
+                    ui.add(egui::Slider::new(&mut config.rate_per_tick, 0.0..=1.0).text("Rate per tick"));
+                    ui.add(egui::Slider::new(&mut config.angle_spread, 0.0..=std::f32::consts::TAU).text("Spread (rad)"));
+                    ui.add(egui::Slider::new(&mut config.velocity, 0.5..=10.0).text("Velocity"));
+                });
+                if config != current {
+                    emitter_configs.0.insert(emitter_idx, config);
+                }
+                if !open {
+                    emitter_editor.0 = None;
+                }
+            } else {
+                emitter_editor.0 = None;
+            }
+        }
+    }
+}
+
+/// Frames between recounts of `GridStats`
+pub const GRID_STATS_INTERVAL: u32 = 30;
+
+/// Resource with element counts for the statistics panel, recounted every `GRID_STATS_INTERVAL` frames
+#[derive(Resource, Default)]
+pub struct GridStats {
+    pub statistics: GridStatistics,
+    pub active_particles: u32,
+    pub frame: u32,
+}
+
+impl GridStats {
+    /// The `n` most common non-background elements with their counts, most common first
+    pub fn top_elements(&self, n: usize) -> Vec<(Element, u32)> {
+        let mut top: Vec<(Element, u32)> = self
+            .statistics
+            .histogram
+            .iter()
+            .enumerate()
+            .filter(|&(index, &count)| count > 0 && index != usize::from(Element::Background.index()))
+            .map(|(index, &count)| (Element::from_index(index as u8), count))
+            .collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1));
+        top.truncate(n);
+        top
+    }
+}
+
+/// Recount `GridStats` every `GRID_STATS_INTERVAL` frames
+pub fn update_grid_stats(grid: Res<GameGrid>, particle_list: Res<ParticleList>, mut stats: ResMut<GridStats>) {
+    stats.frame = stats.frame.wrapping_add(1);
+    if stats.frame % GRID_STATS_INTERVAL != 1 {
+        return;
+    }
+    stats.statistics = grid.statistics();
+    stats.active_particles = particle_list.active_count() as u32;
+}
+
+/// Format a count with thousands separators, 1234567 becomes "1,234,567"
+pub fn format_count(n: u32) -> String {
+    let digits = n.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Frames of timing history kept for the performance graphs
+pub const FRAME_TIMING_CAPACITY: usize = 120;
+
+/// Frame budget at 60 fps, in milliseconds
+const FRAME_BUDGET_MS: f32 = 16.0;
+
+/// Resource with rolling simulation and render timings in milliseconds, newest last
+#[derive(Resource)]
+pub struct FrameTimings {
+    pub render_ms: VecDeque<f32>,
+    pub sim_ms: VecDeque<f32>,
+    pub capacity: usize,
+}
+
+impl Default for FrameTimings {
+    fn default() -> Self {
+        Self {
+            render_ms: VecDeque::with_capacity(FRAME_TIMING_CAPACITY),
+            sim_ms: VecDeque::with_capacity(FRAME_TIMING_CAPACITY),
+            capacity: FRAME_TIMING_CAPACITY,
+        }
+    }
+}
+
+impl FrameTimings {
+    pub fn push_sim(&mut self, start: Instant) {
+        Self::push(&mut self.sim_ms, self.capacity, start);
+    }
+
+    pub fn push_render(&mut self, start: Instant) {
+        Self::push(&mut self.render_ms, self.capacity, start);
+    }
+
+    fn push(samples: &mut VecDeque<f32>, capacity: usize, start: Instant) {
+        if samples.len() >= capacity {
+            samples.pop_front();
+        }
+        samples.push_back(start.elapsed().as_secs_f32() * 1000.0);
+    }
+}
+
+/// Min, max and average of a set of timings
+fn timing_stats(samples: &VecDeque<f32>) -> (f32, f32, f32) {
+    if samples.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    let min = samples.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = samples.iter().copied().fold(0.0, f32::max);
+    let avg = samples.iter().sum::<f32>() / samples.len() as f32;
+    (min, max, avg)
+}
+
+/// Line graph of timings scaled to at least the frame budget, with the budget drawn as a dashed line
+fn timing_graph(ui: &mut egui::Ui, samples: &VecDeque<f32>, capacity: usize, color: egui::Color32) {
+    let (response, painter) = ui.allocate_painter(egui::vec2(ui.available_width().max(200.0), 60.0), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+    let scale = samples.iter().copied().fold(FRAME_BUDGET_MS, f32::max) * 1.1;
+    let to_y = |ms: f32| rect.bottom() - ms / scale * rect.height();
+    let budget_y = to_y(FRAME_BUDGET_MS);
+    painter.add(egui::Shape::dashed_line(
+        &[egui::pos2(rect.left(), budget_y), egui::pos2(rect.right(), budget_y)],
+        egui::Stroke::new(1.0, egui::Color32::DARK_GRAY),
+        4.0,
+        4.0,
+    ));
+
+    let step = rect.width() / capacity.saturating_sub(1).max(1) as f32;
+    let points: Vec<egui::Pos2> = samples
+        .iter()
+        .enumerate()
+        .map(|(n, &ms)| egui::pos2(rect.left() + n as f32 * step, to_y(ms)))
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
+
+/// Simulation ticks between sweeps of stale RainbowSand placement times and emitter settings
+pub const RAINBOW_PRUNE_INTERVAL: u32 = 600;
+
+/// Most simulation frames that can be queued up for a single update
+const MAX_ACCUMULATED_FRAMES: f32 = 2.0;
+
+/// Resource to track accumulated simulation frames for speed control
+#[derive(Resource, Default)]
+pub struct SimulationFrameAccumulator(pub f32);
+
+/// Resource to track screenshot saving, and the last saved screenshot for the toast
+#[derive(Resource, Default)]
+pub struct ScreenshotState {
+    pub saving: bool,
+    pub path: Option<PathBuf>,
+    /// Time (in seconds since startup) the last screenshot was saved
+    pub saved_at: Option<f32>,
+}
+
+/// How long the "Saved!" toast stays on screen, in seconds
+const SCREENSHOT_TOAST_SECONDS: f32 = 2.0;
+
+/// Save the grid texture to screenshots/sand_{timestamp}.png when F12 is pressed
+/// The texture is uploaded to the GPU region by region, so the pixels come from the CPU copy
+pub fn handle_screenshot(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    pixels: Res<GridPixels>,
+    time: Res<Time>,
+    mut screenshot_state: ResMut<ScreenshotState>,
+) {
+    if !keyboard.just_pressed(KeyCode::F12) || screenshot_state.saving {
+        return;
+    }
+    screenshot_state.saving = true;
+
+    let Some(buffer) = image::RgbaImage::from_raw(pixels.width, pixels.height, pixels.data.clone()) else {
+        bevy::log::error!("Failed to take screenshot: texture not rendered yet");
+        screenshot_state.saving = false;
+        return;
+    };
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    let path = PathBuf::from(format!("screenshots/sand_{timestamp}.png"));
+
+    let result = std::fs::create_dir_all("screenshots")
+        .map_err(image::ImageError::IoError)
+        .and_then(|()| image::DynamicImage::ImageRgba8(buffer).save(&path));
+    match result {
+        Ok(()) => {
+            bevy::log::info!("Screenshot saved to {}", path.display());
+            screenshot_state.path = Some(path);
+            screenshot_state.saved_at = Some(time.elapsed_secs());
+        }
+        Err(e) => bevy::log::error!("Failed to save screenshot: {}", e),
+    }
+    screenshot_state.saving = false;
+}
+
+/// Downsample the grid to a `MINIMAP_SIZE` image using nearest-neighbor sampling
+pub fn render_minimap(grid: &GameGrid, rainbow_times: &RainbowSandPlacementTimes, frame: u32) -> egui::ColorImage {
+    let (width, height) = MINIMAP_SIZE;
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for my in 0..height {
+        for mx in 0..width {
+            let x = (mx as u32 * grid.width / width as u32).min(grid.max_x());
+            let y = (my as u32 * grid.height / height as u32).min(grid.max_y());
+            let color = cell_color(grid, rainbow_times, grid.xy_to_index(x, y), true, frame);
+            rgba.push((color.red * 255.0).clamp(0.0, 255.0) as u8);
+            rgba.push((color.green * 255.0).clamp(0.0, 255.0) as u8);
+            rgba.push((color.blue * 255.0).clamp(0.0, 255.0) as u8);
+            rgba.push(255);
+        }
+    }
+    egui::ColorImage::from_rgba_unmultiplied([width, height], &rgba)
+}
+
+/// Minimap window, clicking it pans the camera to that part of the grid
+pub fn minimap_ui(
+    mut contexts: EguiContexts,
+    grid: Res<GameGrid>,
+    rainbow_sand_times: Res<RainbowSandPlacementTimes>,
+    frame_count: Res<FrameCount>,
+    show_minimap: Res<ShowMinimap>,
+    mut minimap: ResMut<MinimapTexture>,
+    mut pan: ResMut<CameraPan>,
+) {
+    if !show_minimap.0 || grid.width == 0 || grid.height == 0 {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    // Only redraw every few frames, the texture is cached in between
+    if minimap.0.is_none() || frame_count.0 % MINIMAP_UPDATE_FRAMES == 0 {
+        let image = render_minimap(&grid, &rainbow_sand_times, frame_count.0);
+        match minimap.0.as_mut() {
+            Some(texture) => texture.set(image, egui::TextureOptions::NEAREST),
+            None => minimap.0 = Some(ctx.load_texture("minimap", image, egui::TextureOptions::NEAREST)),
+        }
+    }
+    let Some(texture) = minimap.0.as_ref() else {
+        return;
+    };
+
+    egui::Window::new("Minimap").resizable(false).show(ctx, |ui| {
+        let size = egui::vec2(MINIMAP_SIZE.0 as f32, MINIMAP_SIZE.1 as f32);
+        let response = ui.add(
+            egui::Image::new(egui::load::SizedTexture::new(texture.id(), size)).sense(egui::Sense::click()),
+        );
+        if response.clicked()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            // Minimap position -> grid cell -> world position (same conversion as the sprite)
+            let local = (pointer - response.rect.min) / response.rect.size();
+            let grid_x = local.x * grid.width as f32;
+            let grid_y = local.y * grid.height as f32;
+            pan.0 = Vec2::new(
+                (grid_x - grid.width as f32 / 2.0) * DISPLAY_FACTOR as f32,
+                (grid.height as f32 / 2.0 - grid_y) * DISPLAY_FACTOR as f32,
+            );
+        }
+    });
+}
+
+/// Show a "Saved!" toast for a couple of seconds after a screenshot is saved
+pub fn screenshot_toast_ui(
+    mut contexts: EguiContexts,
+    screenshot_state: Res<ScreenshotState>,
+    time: Res<Time>,
+) {
+    let Some(saved_at) = screenshot_state.saved_at else {
+        return;
+    };
+    if time.elapsed_secs() - saved_at > SCREENSHOT_TOAST_SECONDS {
+        return;
+    }
+    if let Ok(ctx) = contexts.ctx_mut() {
+        egui::Area::new(egui::Id::new("screenshot_toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label("Saved!");
+                    if let Some(path) = &screenshot_state.path {
+                        ui.small(path.display().to_string());
+                    }
+                });
+            });
+    }
+}
+
+/// How long the "Challenge Complete!" toast stays on screen, in seconds
+const CHALLENGE_TOAST_SECONDS: f32 = 2.0;
+
+/// Frames between challenge checks
+pub const CHALLENGE_CHECK_INTERVAL: u32 = 30;
+
+/// Check the challenges against the grid every `CHALLENGE_CHECK_INTERVAL` frames
+pub fn check_challenges(
+    grid: Res<GameGrid>,
+    grid_stats: Res<GridStats>,
+    mut challenges: ResMut<Challenges>,
+    time: Res<Time>,
+    mut frames: Local<u32>,
+) {
+    *frames = frames.wrapping_add(1);
+    if *frames % CHALLENGE_CHECK_INTERVAL != 0 {
+        return;
+    }
+    if let Some(name) = challenges.update(&grid, &grid_stats.statistics).pop() {
+        bevy::log::info!("Challenge complete: {}", name);
+        challenges.completed = Some((name, time.elapsed_secs()));
+    }
+}
+
+/// Window listing the challenges with their progress, and a toast when one is completed
+pub fn challenges_ui(mut contexts: EguiContexts, grid: Res<GameGrid>, grid_stats: Res<GridStats>, challenges: Res<Challenges>, time: Res<Time>) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    if challenges.challenges.is_empty() {
+        return;
+    }
+    let done = challenges.challenges.iter().filter(|challenge| challenge.status == ChallengeStatus::Complete).count();
+    egui::Window::new(format!("Challenges ({done}/{})", challenges.challenges.len()))
+        .id(egui::Id::new("challenges"))
+        .default_open(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+        .show(ctx, |ui| {
+            for challenge in &challenges.challenges {
+                let complete = challenge.status == ChallengeStatus::Complete;
+                let progress = if complete { 1.0 } else { challenge.condition.progress(&grid, &grid_stats.statistics) };
+                ui.strong(if complete { format!("{} (complete)", challenge.name) } else { challenge.name.clone() });
+                ui.label(&challenge.description);
+                ui.add(egui::ProgressBar::new(progress).show_percentage());
+                ui.separator();
+            }
+        });
+
+    if let Some((name, completed_at)) = &challenges.completed
+        && time.elapsed_secs() - completed_at <= CHALLENGE_TOAST_SECONDS
+    {
+        egui::Area::new(egui::Id::new("challenge_toast"))
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, -60.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.heading("Challenge Complete!");
+                    ui.label(name);
+                });
+            });
+    }
+}
+
+/// How long the "Rules reloaded" toast stays on screen, in seconds
+const RULES_TOAST_SECONDS: f32 = 2.0;
+
+/// Rules from `RULES_CONFIG_PATH`, or the defaults if the file is missing or invalid
+fn load_rules() -> SimulationRules {
+    let path = Path::new(RULES_CONFIG_PATH);
+    if !path.exists() {
+        return SimulationRules::default();
+    }
+    SimulationRules::from_toml(path).unwrap_or_else(|e| {
+        bevy::log::error!("{}, using default rules", e);
+        SimulationRules::default()
+    })
+}
+
+/// Resource watching the rules file, and when it was last reloaded for the toast
+#[derive(Resource)]
+pub struct RulesWatcher {
+    /// Kept alive for as long as the resource, dropping it stops the watch
+    /// The mutex only makes the watcher `Sync`, it is never locked
+    _watcher: Option<std::sync::Mutex<notify::RecommendedWatcher>>,
+    changes: crossbeam_channel::Receiver<()>,
+    /// Time (in seconds since startup) the rules were last reloaded
+    pub reloaded_at: Option<f32>,
+}
+
+impl RulesWatcher {
+    /// Watch the rules file's directory, editors often save by replacing the file
+    /// Without the directory nothing is watched and the rules never reload
+    pub fn start() -> Self {
+        use notify::Watcher;
+        let (sender, changes) = crossbeam_channel::unbounded();
+        let path = Path::new(RULES_CONFIG_PATH);
+        let file_name = path.file_name().map(std::ffi::OsStr::to_os_string);
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event
+                && (event.kind.is_modify() || event.kind.is_create())
+                && event.paths.iter().any(|changed| changed.file_name() == file_name.as_deref())
+            {
+                let _ = sender.send(());
+            }
+        })
+        .and_then(|mut watcher| {
+            let dir = path.parent().unwrap_or_else(|| Path::new("."));
+            watcher.watch(dir, notify::RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+        let watcher = match watcher {
+            Ok(watcher) => Some(std::sync::Mutex::new(watcher)),
+            Err(e) => {
+                bevy::log::warn!("Not watching {} for changes: {}", RULES_CONFIG_PATH, e);
+                None
+            }
+        };
+        Self {
+            _watcher: watcher,
+            changes,
+            reloaded_at: None,
+        }
+    }
+}
+
+/// Reload the rules when the rules file changes, keeping the current rules if it doesn't parse
+pub fn reload_rules_on_change(mut watcher: ResMut<RulesWatcher>, mut rules: ResMut<SimulationRules>, time: Res<Time>) {
+    // One save can fire several events, reload once for all of them
+    if watcher.changes.try_iter().count() == 0 {
+        return;
+    }
+    match SimulationRules::from_toml(Path::new(RULES_CONFIG_PATH)) {
+        Ok(loaded) => {
+            *rules = loaded;
+            watcher.reloaded_at = Some(time.elapsed_secs());
+            bevy::log::info!("Rules reloaded from {}", RULES_CONFIG_PATH);
+        }
+        Err(e) => bevy::log::error!("{}, keeping the current rules", e),
+    }
+}
+
+/// Show a toast for a couple of seconds after the rules are reloaded
+pub fn rules_reloaded_toast_ui(mut contexts: EguiContexts, watcher: Res<RulesWatcher>, time: Res<Time>) {
+    let Some(reloaded_at) = watcher.reloaded_at else {
+        return;
+    };
+    if time.elapsed_secs() - reloaded_at > RULES_TOAST_SECONDS {
+        return;
+    }
+    if let Ok(ctx) = contexts.ctx_mut() {
+        egui::Area::new(egui::Id::new("rules_toast"))
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 20.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label("Rules reloaded from config");
+                });
+            });
+    }
+}
+
+/// Handle save/load operations
+pub fn handle_save_load(
+    mut save_grid: ResMut<SaveGrid>,
+    mut load_grid: ResMut<LoadGrid>,
+    (mut save_png, mut load_png): (ResMut<SavePng>, ResMut<LoadPng>),
+    mut save_load_task: ResMut<SaveLoadTask>,
+    mut save_browser: ResMut<SaveBrowser>,
+    grid: Res<GameGrid>,
+    spigots: Res<Spigots>,
+    (active_branches, particle_list): (Res<ActiveTreeBranches>, Res<ParticleList>),
+    mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
+    mut commands: Commands,
+) {
+    if save_browser.refresh_requested {
+        save_browser.refresh();
+    }
+
+    // Start a save, the snapshot is taken now and written in the background
+    if save_grid.0 && !save_load_task.is_saving() {
+        save_grid.0 = false;
+        let path = save_browser.save_target.take().unwrap_or_else(|| PathBuf::from(SAVE_PATH));
+        let save = WorldSaveFile::new(&grid, &spigots, &rainbow_sand_times.0)
+            .with_particles(&active_branches, &particle_list);
+        save_load_task.start_save(save, path);
+    }
+
+    // Start a load, the result is applied once the thread finishes
+    if load_grid.0 && !save_load_task.is_loading() {
+        load_grid.0 = false;
+        let path = save_browser.load_target.clone().unwrap_or_else(|| PathBuf::from(SAVE_PATH));
+        bevy::log::info!("Loading grid from {}", path.display());
+        save_load_task.load_thread = Some(std::thread::spawn(move || {
+            let data = std::fs::read(&path).map_err(|_| format!("No save file found ({})", path.display()))?;
+            WorldSaveFile::from_bytes(&data).map_err(|e| format!("Failed to deserialize grid data: {e}"))
+        }));
+    }
+
+    // Poll the save thread
+    if save_load_task.save_thread.as_ref().is_some_and(std::thread::JoinHandle::is_finished) {
+        if let Some(handle) = save_load_task.save_thread.take() {
+            let path = save_load_task.saving_path.take();
+            match handle.join() {
+                Ok(Ok(())) => {
+                    bevy::log::info!("Grid saved");
+                    save_load_task.last_saved = path;
+                    save_browser.refresh_requested = true;
+                }
+                Ok(Err(e)) => bevy::log::error!("{}", e),
+                Err(_) => bevy::log::error!("Save thread panicked"),
+            }
+        }
+    }
+
+    // Poll the load thread
+    if save_load_task.load_thread.as_ref().is_some_and(std::thread::JoinHandle::is_finished) {
+        if let Some(handle) = save_load_task.load_thread.take() {
+            match handle.join() {
+                Ok(Ok(save)) => save_load_task.pending_load = Some(save),
+                Ok(Err(e)) => bevy::log::warn!("{}", e),
+                Err(_) => bevy::log::error!("Load thread panicked"),
+            }
+        }
+    }
+
+    // Apply a finished load
+    if let Some(save) = save_load_task.pending_load.take() {
+        rainbow_sand_times.0 = save.rainbow_times;
+        commands.insert_resource(save.grid);
+        commands.insert_resource(save.spigots);
+        bevy::log::info!(
+            "Grid loaded ({}x{}, {} elements, {} branches and {} particles when saved)",
+            save.header.width,
+            save.header.height,
+            save.header.element_count,
+            save.active_branches_count,
+            save.particle_list_active.len()
+        );
+    }
+
+    // Handle PNG save
+    if save_png.0 {
+        save_png.0 = false;
+        if let Err(e) = save_as_png(&grid, &rainbow_sand_times, Path::new(PNG_SAVE_PATH)) {
+            bevy::log::error!("Failed to save PNG: {}", e);
+        } else {
+            bevy::log::info!("Grid saved to {}", PNG_SAVE_PATH);
+        }
+    }
+
+    // Handle PNG load
+    if load_png.0 {
+        load_png.0 = false;
+        match load_from_png(Path::new(PNG_SAVE_PATH)) {
+            Ok((loaded_grid, rainbow_times)) => {
+                rainbow_sand_times.0 = rainbow_times;
+                commands.insert_resource(loaded_grid);
+                bevy::log::info!("Grid loaded from {}", PNG_SAVE_PATH);
+            }
+            Err(e) => bevy::log::error!("Failed to load PNG: {}", e),
+        }
     }
 }
 
-/// Resource to track accumulated simulation frames for speed control
+/// Number of quick-save slots, bound to F1-F5
+pub const QUICK_SAVE_SLOTS: usize = 5;
+
+/// Function keys for the quick-save slots, Shift loads instead of saving
+const QUICK_SAVE_KEYS: [KeyCode; QUICK_SAVE_SLOTS] = [KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5];
+
+/// Resource tracking the quick-save files, `slots` is `Some` when the file exists
 #[derive(Resource, Default)]
-pub struct SimulationFrameAccumulator(pub f32);
+pub struct QuickSaveManager {
+    pub slots: [Option<PathBuf>; QUICK_SAVE_SLOTS],
+    pub last_saved: [Option<std::time::SystemTime>; QUICK_SAVE_SLOTS],
+}
 
-/// Handle save/load operations
-pub fn handle_save_load(
-    mut save_grid: ResMut<SaveGrid>,
-    mut load_grid: ResMut<LoadGrid>,
+impl QuickSaveManager {
+    /// File for a slot, quicksave_0.bin through quicksave_4.bin
+    pub fn slot_path(slot: usize) -> PathBuf {
+        PathBuf::from(format!("quicksave_{slot}.bin"))
+    }
+
+    /// Build the manager from whichever quick-save files are already on disk
+    pub fn scan() -> Self {
+        let mut manager = Self::default();
+        for slot in 0..QUICK_SAVE_SLOTS {
+            manager.refresh(slot);
+        }
+        manager
+    }
+
+    /// Update a slot's path and timestamp from the file metadata
+    pub fn refresh(&mut self, slot: usize) {
+        let path = Self::slot_path(slot);
+        match std::fs::metadata(&path) {
+            Ok(metadata) => {
+                self.last_saved[slot] = metadata.modified().ok();
+                self.slots[slot] = Some(path);
+            }
+            Err(_) => {
+                self.last_saved[slot] = None;
+                self.slots[slot] = None;
+            }
+        }
+    }
+}
+
+/// F1-F5 saves to a quick-save slot, Shift+F1-F5 loads it
+pub fn handle_quick_save(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut quick_saves: ResMut<QuickSaveManager>,
     grid: Res<GameGrid>,
+    spigots: Res<Spigots>,
+    (active_branches, particle_list): (Res<ActiveTreeBranches>, Res<ParticleList>),
+    mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
     mut commands: Commands,
 ) {
-    // Handle save
-    if save_grid.0 {
-        save_grid.0 = false;
-        if let Ok(data) = bincode::serialize(&*grid) {
-            if let Err(e) = std::fs::write("sand_save.bin", data) {
-                bevy::log::error!("Failed to save grid: {}", e);
-            } else {
-                bevy::log::info!("Grid saved to sand_save.bin");
-            }
+    let shift_pressed = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    for (slot, key) in QUICK_SAVE_KEYS.into_iter().enumerate() {
+        if !keyboard.just_pressed(key) {
+            continue;
         }
-    }
-    
-    // Handle load
-    if load_grid.0 {
-        load_grid.0 = false;
-        if let Ok(data) = std::fs::read("sand_save.bin") {
-            if let Ok(loaded_grid) = bincode::deserialize::<GameGrid>(&data) {
-                commands.insert_resource(loaded_grid);
-                bevy::log::info!("Grid loaded from sand_save.bin");
-            } else {
-                bevy::log::error!("Failed to deserialize grid data");
+        let path = QuickSaveManager::slot_path(slot);
+        if shift_pressed {
+            let Some(path) = &quick_saves.slots[slot] else {
+                bevy::log::warn!("Quick-save slot {} is empty", slot + 1);
+                continue;
+            };
+            match std::fs::read(path).map(|data| WorldSaveFile::from_bytes(&data)) {
+                Ok(Ok(save)) => {
+                    rainbow_sand_times.0 = save.rainbow_times;
+                    commands.insert_resource(save.grid);
+                    commands.insert_resource(save.spigots);
+                    bevy::log::info!("Quick-save slot {} loaded", slot + 1);
+                }
+                Ok(Err(e)) => bevy::log::error!("Failed to deserialize quick-save: {}", e),
+                Err(e) => bevy::log::error!("Failed to read quick-save: {}", e),
             }
         } else {
-            bevy::log::warn!("No save file found (sand_save.bin)");
+            let save = WorldSaveFile::new(&grid, &spigots, &rainbow_sand_times.0)
+                .with_particles(&active_branches, &particle_list);
+            match save.to_bytes() {
+                Ok(data) => {
+                    if let Err(e) = std::fs::write(&path, data) {
+                        bevy::log::error!("Failed to quick-save: {}", e);
+                    } else {
+                        bevy::log::info!("Quick-saved to {}", path.display());
+                    }
+                }
+                Err(e) => bevy::log::error!("Failed to serialize quick-save: {}", e),
+            }
+            quick_saves.refresh(slot);
         }
     }
 }
 
+/// Short "how long ago" label for a file timestamp
+fn time_ago(time: std::time::SystemTime) -> String {
+    seconds_ago(time.elapsed().map(|elapsed| elapsed.as_secs()).unwrap_or_default())
+}
+
+/// Short "how long ago" label for a number of seconds
+fn seconds_ago(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
 /// Update the game simulation (CPU-based, ported from TypeScript)
 /// Iterates bottom-to-top, zigzagging left-right/right-left
 /// Speed control: accumulates frames based on speed setting, only runs when >= 1.0
+/// While paused a requested step (see `StepMode`) runs exactly one tick
 pub fn update_game_simulation(
     mut grid: ResMut<GameGrid>,
-    spigots: Res<Spigots>,
-    fall_into_void: Res<FallIntoVoid>,
+    (spigots, mut spigot_positions): (Res<Spigots>, ResMut<SpigotPositionCache>),
+    (fall_into_void, world_wrap): (Res<FallIntoVoid>, Res<WorldWrap>),
     mut active_branches: ResMut<ActiveTreeBranches>,
     mut particle_list: ResMut<ParticleList>,
     mut clear_grid: ResMut<ClearGrid>,
     mut rainbow_sand_counter: ResMut<RainbowSandPlacementCounter>,
     mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
-    simulation_speed: Res<SimulationSpeed>,
-    mut frame_accumulator: Local<SimulationFrameAccumulator>,
+    mut portal_registry: ResMut<PortalRegistry>,
+    mut pending_portal: ResMut<PendingPortal>,
+    wind: Res<WindField>,
+    (rules, mut simulation_seed, mut frame_timings, interactions, mut emitter_configs): (
+        Res<SimulationRules>,
+        ResMut<SimulationSeed>,
+        ResMut<FrameTimings>,
+        Res<ElementInteractionMatrix>,
+        ResMut<EmitterConfigs>,
+    ),
+    (simulation_speed, mut step_mode, mut frame_accumulator, mut ticks_since_prune): (
+        Res<SimulationSpeed>,
+        ResMut<StepMode>,
+        Local<SimulationFrameAccumulator>,
+        Local<u32>,
+    ),
+    mut shockwaves: MessageWriter<ShockwaveEvent>,
+    mut recording: ResMut<RecordingState>,
 ) {
     // Handle simulation speed: accumulate frames and only run when we've accumulated >= 1.0
     // Speed 0.0 = paused (never accumulate, never run)
     // Speed 1.0 = normal (accumulate 1.0 per frame, run every frame)
     // Speed 2.0 = 2x (accumulate 2.0 per frame, run twice per frame)
-    if simulation_speed.0 <= 0.0 {
-        return; // Paused
+    let paused = simulation_speed.0 <= 0.0;
+    let step = step_mode.step_requested;
+    if step {
+        step_mode.step_requested = false;
+    }
+    if paused && !step {
+        // Paused, drop leftover frames so unpausing doesn't jump ahead
+        frame_accumulator.0 = 0.0;
+        return;
     }
     
-    frame_accumulator.0 += simulation_speed.0;
+    let start = Instant::now();
+
+    // Spigot sizes or elements were edited (or a save was loaded), lay them out again
+    if spigots.is_changed() {
+        spigot_positions.dirty = true;
+    }
+
+    if paused {
+        // Single step, ignoring whatever had accumulated
+        frame_accumulator.0 = 1.0;
+    } else {
+        // Cap at the max slider speed so a slow frame can't queue up a burst of steps
+        frame_accumulator.0 = (frame_accumulator.0 + simulation_speed.0).min(MAX_ACCUMULATED_FRAMES);
+    }
     
     // Only run simulation when we've accumulated at least 1.0 frames
     // If speed > 1.0, we might run multiple times per frame
     while frame_accumulator.0 >= 1.0 {
         frame_accumulator.0 -= 1.0;
+
+        // Reseed per frame in deterministic mode
+        if simulation_seed.enabled {
+            seed_simulation_rng(Some(frame_seed(simulation_seed.seed, simulation_seed.frame)));
+            simulation_seed.frame += 1;
+        } else {
+            seed_simulation_rng(None);
+        }
         
         // Run one frame of simulation
         run_simulation_frame(
             &mut grid,
             &spigots,
+            &mut spigot_positions,
             &fall_into_void,
+            *world_wrap,
             &mut active_branches,
             &mut particle_list,
             &mut clear_grid,
             &mut rainbow_sand_counter,
             &mut rainbow_sand_times,
+            &mut portal_registry,
+            &mut pending_portal,
+            &mut emitter_configs,
+            &wind,
+            &interactions,
+            &rules,
+        );
+
+        if recording.active {
+            recording.record_tick(&grid);
+        }
+
+        // Periodically drop placement times, emitter settings and creature routes for cells that were erased
+        *ticks_since_prune += 1;
+        if *ticks_since_prune >= RAINBOW_PRUNE_INTERVAL {
+            *ticks_since_prune = 0;
+            prune_stale_rainbow_times(&grid, &mut rainbow_sand_times.0);
+            emitter_configs.prune(&grid);
+            prune_creature_paths(&mut grid);
+        }
+    }
+    frame_timings.push_sim(start);
+    
+    // Forward shockwaves from this frame's explosions
+    if !grid.pending_shockwaves.is_empty() {
+        shockwaves.write_batch(grid.pending_shockwaves.drain(..));
+    }
+}
+
+/// Start anti-gravity zones for newly placed AntiGravity cells and expire old ones
+pub fn update_anti_gravity_sources(mut grid: ResMut<GameGrid>, mut sources: ResMut<AntiGravitySources>, time: Res<Time>) {
+    if sources.0.is_empty() && grid.placed_anti_gravity.is_empty() {
+        return;
+    }
+    // Bypass change detection so an idle zone list doesn't mark the grid changed every frame
+    sources.update(grid.bypass_change_detection(), time.elapsed_secs());
+}
+
+/// Pulsing ring around each active anti-gravity zone
+pub fn draw_anti_gravity_sources(mut gizmos: Gizmos, sources: Res<AntiGravitySources>, grid: Res<GameGrid>, time: Res<Time>) {
+    let display_factor_f32 = DISPLAY_FACTOR as f32;
+    let pulse = 1.0 + 0.1 * (time.elapsed_secs() * 6.0).sin();
+    for &(x, y, radius, _) in &sources.0 {
+        // Grid cells to world coordinates, +y is up in the world
+        let center = Vec2::new(
+            x as f32 + 0.5 - grid.width as f32 / 2.0,
+            grid.height as f32 / 2.0 - (y as f32 + 0.5),
+        ) * display_factor_f32;
+        gizmos.circle_2d(center, radius as f32 * pulse * display_factor_f32, Color::srgb(0.6, 0.3, 1.0));
+    }
+}
+
+/// Apply shockwave impulses from explosions to nearby elements
+pub fn process_shockwaves(
+    mut grid: ResMut<GameGrid>,
+    mut shockwaves: MessageReader<ShockwaveEvent>,
+) {
+    for shockwave in shockwaves.read() {
+        crate::simulation::apply_shockwave(&mut grid, shockwave);
+    }
+}
+
+/// Remember that an explosive detonated, for the percussion in `generate_music`
+pub fn detect_explosions(mut shockwaves: MessageReader<ShockwaveEvent>, mut audio: ResMut<SimulationAudio>) {
+    if shockwaves.read().count() > 0 {
+        audio.explosion_occurred = true;
+    }
+}
+
+/// Shake the camera when a Nuke goes off
+pub fn trigger_camera_shake(mut shockwaves: MessageReader<ShockwaveEvent>, mut shake: ResMut<CameraShake>) {
+    if shockwaves.read().any(|shockwave| shockwave.force >= NUKE_SHOCKWAVE_FORCE) {
+        *shake = NUKE_SHAKE;
+    }
+}
+
+/// Open the audio device for the music, the game runs silent without one
+pub fn setup_audio(world: &mut World) {
+    match AudioOutput::open() {
+        Ok(output) => world.insert_non_send_resource(output),
+        Err(e) => bevy::log::warn!("No audio output, music is disabled: {}", e),
+    }
+}
+
+/// Follow the simulation with the music every `MUSIC_UPDATE_INTERVAL` frames
+/// The embers loop speeds up and gets louder with the share of the grid on fire, the water layer fades in
+/// above `WATER_LAYER_COVERAGE` and a detonation plays a drum hit
+pub fn generate_music(
+    grid_stats: Res<GridStats>,
+    settings: Res<AudioSettings>,
+    mut audio: ResMut<SimulationAudio>,
+    output: Option<NonSend<AudioOutput>>,
+    mut frames: Local<u32>,
+) {
+    *frames = frames.wrapping_add(1);
+    if *frames % MUSIC_UPDATE_INTERVAL != 0 {
+        return;
+    }
+    let stats = &grid_stats.statistics;
+    let fire_count = stats.histogram[usize::from(Element::Fire.index())] as f32;
+    let water_count = stats.histogram[usize::from(Element::Water.index())] as f32;
+    audio.fire_count_smooth = smooth_toward(audio.fire_count_smooth, fire_count);
+    audio.water_count_smooth = smooth_toward(audio.water_count_smooth, water_count);
+    let explosion = std::mem::take(&mut audio.explosion_occurred);
+
+    let Some(output) = output else {
+        return;
+    };
+    let total = stats.total_cells.max(1) as f32;
+    let fire_coverage = audio.fire_count_smooth / total;
+    let volume = settings.effective_volume();
+    output.embers.set_speed(embers_speed(fire_coverage));
+    output.embers.set_volume(embers_volume(fire_coverage) * volume);
+    output.water.set_volume(water_volume(audio.water_count_smooth / total) * volume);
+    if explosion && volume > 0.0 {
+        output.play_percussion(volume);
+    }
+}
+
+/// Run `n` simulation ticks without Bevy, for the headless binary
+/// With a seed every tick is reseeded like the deterministic mode, otherwise the simulation RNG is random
+/// The simulation RNG is unseeded again once the ticks are done
+/// Elements stop at the edges and the world doesn't wrap, the game's defaults
+/// Particles are updated after each tick, like the `update_particles` system does
+pub fn simulate_ticks(
+    grid: &mut GameGrid,
+    spigots: &Spigots,
+    n: u32,
+    rules: &SimulationRules,
+    seed: Option<u64>,
+    rainbow_sand_times: &mut RainbowSandPlacementTimes,
+) {
+    let mut spigot_positions = SpigotPositionCache::default();
+    let mut active_branches = ActiveTreeBranches::default();
+    let mut particle_list = ParticleList::default();
+    let mut particle_scratch = Vec::new();
+    let mut rainbow_sand_counter = RainbowSandPlacementCounter::default();
+    let mut portal_registry = PortalRegistry::default();
+    let mut pending_portal = PendingPortal::default();
+    let mut emitter_configs = EmitterConfigs::default();
+    let interactions = ElementInteractionMatrix::default();
+    for frame in 0..u64::from(n) {
+        seed_simulation_rng(seed.map(|seed| frame_seed(seed, frame)));
+        run_simulation_frame(
+            grid,
+            spigots,
+            &mut spigot_positions,
+            &FallIntoVoid(false),
+            WorldWrap(false),
+            &mut active_branches,
+            &mut particle_list,
+            &mut ClearGrid(false),
+            &mut rainbow_sand_counter,
+            rainbow_sand_times,
+            &mut portal_registry,
+            &mut pending_portal,
+            &mut emitter_configs,
+            &WindField::default(),
+            &interactions,
+            rules,
         );
+        // Shockwaves are applied straight away instead of going through Bevy messages
+        for shockwave in std::mem::take(&mut grid.pending_shockwaves) {
+            crate::simulation::apply_shockwave(grid, &shockwave);
+        }
+        update_particles_cpu(&mut particle_list, grid, &mut particle_scratch);
     }
+    seed_simulation_rng(None);
 }
 
 /// Run a single frame of simulation
 fn run_simulation_frame(
     grid: &mut GameGrid,
     spigots: &Spigots,
+    spigot_positions: &mut SpigotPositionCache,
     fall_into_void: &FallIntoVoid,
+    world_wrap: WorldWrap,
     active_branches: &mut ActiveTreeBranches,
     particle_list: &mut ParticleList,
     clear_grid: &mut ClearGrid,
     rainbow_sand_counter: &mut RainbowSandPlacementCounter,
     rainbow_sand_times: &mut RainbowSandPlacementTimes,
+    portal_registry: &mut PortalRegistry,
+    pending_portal: &mut PendingPortal,
+    emitter_configs: &mut EmitterConfigs,
+    wind: &WindField,
+    interactions: &ElementInteractionMatrix,
+    rules: &SimulationRules,
 ) {
     // Check if grid should be cleared
     if clear_grid.0 {
         grid.clear();
         active_branches.branches.clear();
+        particle_list.clear_all();
         clear_grid.0 = false;
         // Also clear RainbowSand placement times
         rainbow_sand_times.0.clear();
+        // Also forget all portal links and emitter settings
+        portal_registry.0.clear();
+        pending_portal.0 = None;
+        emitter_configs.0.clear();
     }
     
+    // Elements moving into a portal look up its pair on the grid
+    portal_registry.prune(grid);
+    grid.portal_links.clone_from(&portal_registry.0);
+    
+    // Spread heat between neighboring cells
+    use crate::simulation::{apply_age_effects, diffuse_temperatures};
+    diffuse_temperatures(grid);
+    
     // Process tree branches incrementally (like particle system)
     use crate::simulation::process_tree_branches;
     process_tree_branches(grid, active_branches);
     
     // Update spigots first
-    update_spigots_cpu(grid, spigots, rainbow_sand_counter, rainbow_sand_times);
+    update_spigots_cpu(grid, spigots, spigot_positions, rainbow_sand_counter, rainbow_sand_times);
     
 
+    let mut ctx = SimContext {
+        fall_into_void: fall_into_void.0,
+        world_wrap: world_wrap.0,
+        particle_list: Some(particle_list),
+        active_branches: Some(active_branches),
+        emitter_configs: Some(emitter_configs),
+        wind: Some(wind),
+        interactions: Some(interactions),
+        rainbow_sand_times: Some(&mut rainbow_sand_times.0),
+    };
+
     // Iterate from bottom to top, zigzagging rows
     // This matches the TypeScript implementation
     let max_y = grid.max_y();
@@ -494,24 +3111,32 @@ fn run_simulation_frame(
             for x in (0..=max_x).rev() {
                 let i = grid.xy_to_index(x, y);
                 let element = grid.get_index(i);
-                if element == Element::Background {
-                    continue; // Skip background for optimization
+                if element == Element::Background || grid.is_settled(i) {
+                    continue; // Skip background and settled cells for optimization
                 }
                 
-                let mut times_opt = Some(&mut rainbow_sand_times.0);
-                execute_element_action(grid, x, y, i, fall_into_void.0, Some(particle_list), Some(active_branches), &mut times_opt);
+                let writes_before = grid.write_count;
+                execute_element_action(grid, x, y, i, rules, &mut ctx);
+                apply_age_effects(grid, i);
+                if grid.write_count == writes_before {
+                    grid.staleness[i] = grid.staleness[i].saturating_add(1);
+                }
             }
         } else {
             // Left to right
             for x in 0..=max_x {
                 let i = grid.xy_to_index(x, y);
                 let element = grid.get_index(i);
-                if element == Element::Background {
-                    continue; // Skip background for optimization
+                if element == Element::Background || grid.is_settled(i) {
+                    continue; // Skip background and settled cells for optimization
                 }
                 
-                let mut times_opt = Some(&mut rainbow_sand_times.0);
-                execute_element_action(grid, x, y, i, fall_into_void.0, Some(particle_list), Some(active_branches), &mut times_opt);
+                let writes_before = grid.write_count;
+                execute_element_action(grid, x, y, i, rules, &mut ctx);
+                apply_age_effects(grid, i);
+                if grid.write_count == writes_before {
+                    grid.staleness[i] = grid.staleness[i].saturating_add(1);
+                }
             }
         }
     }
@@ -521,49 +3146,76 @@ fn run_simulation_frame(
 fn update_spigots_cpu(
     grid: &mut GameGrid,
     spigots: &Spigots,
+    spigot_positions: &mut SpigotPositionCache,
     rainbow_sand_counter: &mut RainbowSandPlacementCounter,
     rainbow_sand_times: &mut RainbowSandPlacementTimes,
 ) {
-    let positions = spigots.get_spigot_positions(grid.width);
     let spigot_height = 10u32; // SPIGOT_HEIGHT from TypeScript
 
-    for (x, width, idx) in positions {
+    for &(x, width, idx) in spigot_positions.positions(spigots, grid.width) {
         // Size 0 means disabled, skip it
         if spigots.sizes[idx as usize] == 0 {
             continue;
         }
 
         let element = spigots.elements[idx as usize];
-        
-        // Increment RainbowSand counter every few frames for spigots
-        // This ensures colors change at a moderate pace
-        let current_placement_time = if element == Element::RainbowSand {
-            // Increment counter every 3 frames (same as mouse placement)
-            rainbow_sand_counter.frame_since_last_increment += 1;
-            if rainbow_sand_counter.frame_since_last_increment >= 3 {
-                rainbow_sand_counter.counter = rainbow_sand_counter.counter.wrapping_add(1);
-                rainbow_sand_counter.frame_since_last_increment = 0;
-            }
-            Some(rainbow_sand_counter.counter)
-        } else {
-            None
-        };
+        let current_placement_time = spigot_placement_time(element, rainbow_sand_counter);
         
         // Spawn elements at the top rows with 10% chance (matching TypeScript)
-        for h in 0..spigot_height.min(grid.height) {
-            for w in x..(x + width).min(grid.width) {
-                if rand::thread_rng().gen_bool(0.10) {
-                    let spawn_y = h;
-                    let spawn_idx = grid.xy_to_index(w, spawn_y);
-                    grid.set_index(spawn_idx, element);
-                    
-                    // Store placement time for RainbowSand from spigots
-                    if let Some(placement_time) = current_placement_time {
-                        rainbow_sand_times.0.insert(spawn_idx, placement_time);
-                    } else {
-                        // Remove from placement times if not RainbowSand
-                        rainbow_sand_times.0.remove(&spawn_idx);
-                    }
+        let rows = 0..spigot_height.min(grid.height);
+        spawn_spigot_band(grid, element, current_placement_time, x..(x + width).min(grid.width), rows, rainbow_sand_times);
+    }
+
+    // Wall spigots fill a band of rows in the left or right edge column
+    for (y, size, idx, side) in spigots.get_wall_spigot_positions(grid.height) {
+        let element = spigots.wall_spigots[idx as usize].element;
+        let current_placement_time = spigot_placement_time(element, rainbow_sand_counter);
+        let x = match side {
+            Side::Left => 0,
+            Side::Right => grid.width.saturating_sub(1),
+        };
+        spawn_spigot_band(grid, element, current_placement_time, x..x + 1, y..y + size, rainbow_sand_times);
+    }
+}
+
+/// Placement time for RainbowSand poured by a spigot, None for other elements
+fn spigot_placement_time(element: Element, rainbow_sand_counter: &mut RainbowSandPlacementCounter) -> Option<u32> {
+    // Increment RainbowSand counter every few frames for spigots
+    // This ensures colors change at a moderate pace
+    if element == Element::RainbowSand {
+        // Increment counter every 3 frames (same as mouse placement)
+        rainbow_sand_counter.frame_since_last_increment += 1;
+        if rainbow_sand_counter.frame_since_last_increment >= 3 {
+            rainbow_sand_counter.counter = rainbow_sand_counter.counter.wrapping_add(1);
+            rainbow_sand_counter.frame_since_last_increment = 0;
+        }
+        Some(rainbow_sand_counter.counter)
+    } else {
+        None
+    }
+}
+
+/// Set each cell in the band to element with a 10% chance
+fn spawn_spigot_band(
+    grid: &mut GameGrid,
+    element: Element,
+    placement_time: Option<u32>,
+    columns: std::ops::Range<u32>,
+    rows: std::ops::Range<u32>,
+    rainbow_sand_times: &mut RainbowSandPlacementTimes,
+) {
+    for h in rows {
+        for w in columns.clone() {
+            if sim_rng().gen_bool(0.10) {
+                let spawn_idx = grid.xy_to_index(w, h);
+                grid.set_index(spawn_idx, element);
+                
+                // Store placement time for RainbowSand from spigots
+                if let Some(placement_time) = placement_time {
+                    rainbow_sand_times.0.insert(spawn_idx, placement_time);
+                } else {
+                    // Remove from placement times if not RainbowSand
+                    rainbow_sand_times.0.remove(&spawn_idx);
                 }
             }
         }
@@ -574,20 +3226,11 @@ fn update_spigots_cpu(
 fn update_particle_safe(
     particle_list: &mut ParticleList,
     particle_idx: usize,
-    grid: &GameGrid,
+    grid: &mut GameGrid,
 ) -> bool {
     use crate::particles::actions::{particle_init, particle_action};
     
-    // Initialize particle if needed (first frame)
-    {
-        let particle = particle_list.get_particle_mut(particle_idx);
-        if let Some(particle) = particle {
-            if particle.active && particle.action_iterations == 0 && !particle.reinitialized {
-                particle_init(particle, grid);
-                particle.reinitialized = true; // Mark as initialized
-            }
-        }
-    }
+    // First frame initialization happens in `update_particles` before this is called
     
     // Update particle - handle tree particles specially
     let is_tree = {
@@ -599,14 +3242,9 @@ fn update_particle_safe(
         // For tree particles, we need to collect data first, then create branches
         // This avoids borrow conflicts
         
-        // First, ensure particle is initialized (velocity, angle, etc.)
+        // Ensure velocity is set (might be 0 if the particle was never initialized)
         {
             let particle = particle_list.get_particle_mut(particle_idx).unwrap();
-            if particle.action_iterations == 0 && !particle.reinitialized {
-                particle_init(particle, grid);
-                particle.reinitialized = true;
-            }
-            // Also ensure velocity is set (might be 0 if not initialized)
             if particle.velocity == 0.0 && particle.x_velocity == 0.0 && particle.y_velocity == 0.0 {
                 particle_init(particle, grid);
                 particle.reinitialized = true;
@@ -661,11 +3299,11 @@ fn update_particle_safe(
                 let leaf_branch = br + 1 >= mb;
                 let branch_angles = match tt {
                     0 => {
-                        let branch_angle = std::f32::consts::PI / 8.0 + rand::thread_rng().gen_range(0.0..1.0) * std::f32::consts::PI / 4.0;
+                        let branch_angle = std::f32::consts::PI / 8.0 + sim_rng().gen_range(0.0..1.0) * std::f32::consts::PI / 4.0;
                         vec![angle + branch_angle, angle - branch_angle]
                     }
                     1 => {
-                        let branch_angle = rand::thread_rng().gen_range(0.0..1.0) * std::f32::consts::PI / 16.0 + std::f32::consts::PI / 8.0;
+                        let branch_angle = sim_rng().gen_range(0.0..1.0) * std::f32::consts::PI / 16.0 + std::f32::consts::PI / 8.0;
                         vec![angle, angle + branch_angle, angle - branch_angle]
                     }
                     _ => vec![angle],
@@ -711,7 +3349,7 @@ fn update_particle_safe(
                     if updated_spacing > 45 {
                         updated_spacing = (updated_spacing as f32 * 0.8) as u32;
                     }
-                    let next_time = iter_val + (updated_spacing as f32 * (0.65 + rand::thread_rng().gen_range(0.0..1.0) * 0.35)) as u32;
+                    let next_time = iter_val + (updated_spacing as f32 * (0.65 + sim_rng().gen_range(0.0..1.0) * 0.35)) as u32;
                     particle.tree_next_branch = Some(next_time);
                     particle.tree_branch_spacing = Some(updated_spacing);
                 }
@@ -737,123 +3375,333 @@ fn update_particle_safe(
 /// Update particles each frame
 pub fn update_particles(
     mut particle_list: ResMut<ParticleList>,
-    grid: Res<GameGrid>,
+    mut grid: ResMut<GameGrid>,
+    mut scratch: Local<Vec<usize>>,
 ) {
-    // Get active particle indices (clone to avoid borrow issues)
-    let active_indices: Vec<usize> = particle_list.active_particles().to_vec();
+    update_particles_cpu(&mut particle_list, &mut grid, &mut scratch);
+}
+
+/// Move every active particle one step and let it act on the grid
+/// `scratch` holds the active indices and is reused across calls
+pub fn update_particles_cpu(particle_list: &mut ParticleList, grid: &mut GameGrid, scratch: &mut Vec<usize>) {
+    // Bucket particles by position for proximity queries this frame
+    particle_list.rebuild_spatial_hash();
+
+    // Copy active particle indices (to avoid borrow issues) into a buffer reused across frames
+    scratch.clear();
+    scratch.extend(particle_list.active_particles());
     
     // Update each active particle
-    for particle_idx in active_indices {
+    for &particle_idx in scratch.iter() {
         // Initialize particle if needed (first frame)
         {
             let particle = particle_list.get_particle_mut(particle_idx);
             if let Some(particle) = particle {
                 if particle.active && particle.action_iterations == 0 && !particle.reinitialized {
-                    particle_init(particle, &grid);
+                    particle_init(particle, grid);
                     particle.reinitialized = true; // Mark as initialized
                 }
             }
-        }
-        
-        // Update particle using helper function
-        let should_remove = update_particle_safe(&mut *particle_list, particle_idx, &grid);
-        
-        if should_remove {
-            particle_list.make_particle_inactive(particle_idx);
+        }
+        
+        // Update particle using helper function
+        let should_remove = update_particle_safe(particle_list, particle_idx, grid);
+        
+        if should_remove {
+            particle_list.make_particle_inactive(particle_idx);
+        }
+    }
+}
+
+/// Render particles to particle texture
+pub fn render_particles(
+    particle_list: Res<ParticleList>,
+    grid: Res<GameGrid>,
+    mut images: ResMut<Assets<Image>>,
+    mut particle_texture: ResMut<ParticleTexture>,
+) {
+    use crate::particles::render::render_particles_to_texture;
+    render_particles_to_texture(particle_list, grid, images, particle_texture);
+}
+
+/// Composite particles onto main texture
+pub fn composite_particles(
+    grid: Res<GameGrid>,
+    particle_list: Res<ParticleList>,
+    mut images: ResMut<Assets<Image>>,
+    mut particle_texture: ResMut<ParticleTexture>,
+    pixels: ResMut<GridPixels>,
+    upload: ResMut<TextureUpload>,
+) {
+    use crate::particles::render::composite_particles_to_main;
+    composite_particles_to_main(grid, particle_list, images, particle_texture, pixels, upload);
+}
+
+/// Render the game grid to the texture
+/// Only the cells inside the grid's dirty rect (plus where particles were drawn last frame) are
+/// redrawn, and only that region is uploaded
+/// The pixels themselves come from `crate::rendering::grid_image`, which has no ECS dependencies
+pub fn render_grid_to_texture(
+    mut grid: ResMut<GameGrid>,
+    rainbow_sand_times: Res<RainbowSandPlacementTimes>,
+    mut pixels: ResMut<GridPixels>,
+    mut upload: ResMut<TextureUpload>,
+    animated_colors: Res<AnimatedColors>,
+    water_ripples: Res<WaterRipples>,
+    render_mode: Res<RenderMode>,
+    colorblind_mode: Res<ColorblindMode>,
+    mut frame_count: ResMut<FrameCount>,
+    mut frame_timings: ResMut<FrameTimings>,
+) {
+    let start = Instant::now();
+    frame_count.0 = frame_count.0.wrapping_add(1);
+    let frame = frame_count.0;
+
+    // Turning animation or color correction on or off changes every cell's color, and temperatures
+    // change every tick without marking cells dirty
+    if animated_colors.is_changed()
+        || render_mode.is_changed()
+        || colorblind_mode.is_changed()
+        || *render_mode == RenderMode::HeatMap
+    {
+        grid.mark_all_dirty();
+    }
+
+    let rules = RenderRules {
+        render_mode: *render_mode,
+        colorblind_mode: *colorblind_mode,
+        animated_colors: animated_colors.0,
+        water_ripples: water_ripples.0,
+    };
+
+    // Animated cells change color every frame, so they're always redrawn
+    let mut animated_rect = None;
+    if animated_colors.0 {
+        for (idx, element) in grid.elements.iter().enumerate() {
+            if matches!(element, Element::Fire | Element::Water | Element::Lava) {
+                let (x, y) = grid.index_to_xy(idx);
+                animated_rect = union_rects(animated_rect, Some((x, y, x, y)));
+            }
+        }
+    }
+
+    // Surface water moves sideways every frame, both where it was and where it's drawn are redrawn
+    let ripples = if water_ripples.0 && *render_mode == RenderMode::Normal {
+        ripple_targets(&grid, frame)
+    } else {
+        Vec::new()
+    };
+    let ripple_rect = ripples.iter().fold(None, |rect, &(idx, target_idx)| {
+        let (x, y) = grid.index_to_xy(idx);
+        let (target_x, target_y) = grid.index_to_xy(target_idx);
+        union_rects(union_rects(rect, Some((x, y, x, y))), Some((target_x, target_y, target_x, target_y)))
+    });
+
+    // First frame (or after a resize) - draw and upload the whole texture
+    if pixels.width != grid.width || pixels.height != grid.height {
+        pixels.width = grid.width;
+        pixels.height = grid.height;
+        // Rgba8Unorm format: 4 u8 values per pixel (4 bytes per pixel)
+        pixels.data = grid_to_image(&grid, &rainbow_sand_times, frame, &rules);
+        pixels.particle_rect = None;
+        pixels.ripple_rect = ripple_rect;
+        grid.dirty_rect = None;
+        upload.rect = (grid.width > 0 && grid.height > 0).then(|| (0, 0, grid.max_x(), grid.max_y()));
+        frame_timings.push_render(start);
+        return;
+    }
+
+    let rect = union_rects(
+        union_rects(grid.dirty_rect.take(), pixels.particle_rect.take()),
+        union_rects(union_rects(animated_rect, pixels.ripple_rect.take()), ripple_rect),
+    );
+    upload.rect = rect;
+    let Some(rect) = rect else {
+        frame_timings.push_render(start);
+        return; // Nothing changed
+    };
+
+    draw_cells(&mut pixels.data, &grid, &rainbow_sand_times, frame, &rules, rect);
+    draw_ripples(&mut pixels.data, &grid, &rainbow_sand_times, frame, &rules, &ripples);
+    pixels.ripple_rect = ripple_rect;
+    frame_timings.push_render(start);
+}
+
+/// Indices of Water cells with Background directly above
+pub fn detect_water_surface(grid: &GameGrid) -> Vec<usize> {
+    grid.elements
+        .iter()
+        .enumerate()
+        .filter(|&(idx, element)| {
+            if *element != Element::Water {
+                return false;
+            }
+            let (x, y) = grid.index_to_xy(idx);
+            y > 0 && grid.get(x, y - 1) == Element::Background
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Blur the colors of Fire and Lava cells into the glow texture
+/// The glow sprite is alpha blended, so each pixel stores the glow's hue at full brightness with
+/// the glow strength in alpha, which adds light over the dark background
+pub fn render_glow(
+    grid: Res<GameGrid>,
+    glow_settings: Res<GlowSettings>,
+    glow_texture: Res<GlowTexture>,
+    mut images: ResMut<Assets<Image>>,
+    mut glowing_last_frame: Local<bool>,
+) {
+    let width = grid.width as usize;
+    let height = grid.height as usize;
+
+    // Seed the blur with the colors of glowing cells
+    let mut source = vec![Vec3::ZERO; width * height];
+    let mut any_glowing = false;
+    if glow_settings.enabled {
+        for (idx, element) in grid.elements.iter().enumerate() {
+            if matches!(element, Element::Fire | Element::Lava) {
+                let color = element.to_encoded_color();
+                source[idx] = Vec3::new(color.red, color.green, color.blue);
+                any_glowing = true;
+            }
+        }
+    }
+
+    // Nothing glowing now or last frame, the texture is already clear
+    if !any_glowing && !*glowing_last_frame {
+        return;
+    }
+    *glowing_last_frame = any_glowing;
+
+    let Some(image) = images.get_mut(&glow_texture.0) else {
+        return;
+    };
+    if image.width() != grid.width || image.height() != grid.height {
+        image.resize(Extent3d {
+            width: grid.width,
+            height: grid.height,
+            depth_or_array_layers: 1,
+        });
+    }
+    let Some(data) = image.data.as_mut() else {
+        return;
+    };
+    data.fill(0);
+    if !any_glowing {
+        return;
+    }
+
+    // Separable Gaussian blur, sigma is half the radius
+    let radius = glow_settings.radius.max(1) as i32;
+    let sigma = radius as f32 / 2.0;
+    let weights: Vec<f32> = (-radius..=radius)
+        .map(|offset| (-(offset * offset) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+
+    let mut horizontal = vec![Vec3::ZERO; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = Vec3::ZERO;
+            for (k, weight) in weights.iter().enumerate() {
+                let sx = x as i32 + k as i32 - radius;
+                if sx >= 0 && (sx as usize) < width {
+                    sum += source[y * width + sx as usize] * *weight;
+                }
+            }
+            horizontal[y * width + x] = sum / total;
         }
     }
-}
 
-/// Render particles to particle texture
-pub fn render_particles(
-    particle_list: Res<ParticleList>,
-    grid: Res<GameGrid>,
-    mut images: ResMut<Assets<Image>>,
-    mut particle_texture: ResMut<ParticleTexture>,
-) {
-    use crate::particles::render::render_particles_to_texture;
-    render_particles_to_texture(particle_list, grid, images, particle_texture);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = Vec3::ZERO;
+            for (k, weight) in weights.iter().enumerate() {
+                let sy = y as i32 + k as i32 - radius;
+                if sy >= 0 && (sy as usize) < height {
+                    sum += horizontal[sy as usize * width + x] * *weight;
+                }
+            }
+            let glow = sum / total;
+            let brightness = glow.max_element();
+            if brightness <= 0.0 {
+                continue;
+            }
+            let hue = glow / brightness;
+            let alpha = (brightness * glow_settings.intensity).clamp(0.0, 1.0);
+            let offset = (y * width + x) * 4;
+            data[offset] = (hue.x * 255.0) as u8;
+            data[offset + 1] = (hue.y * 255.0) as u8;
+            data[offset + 2] = (hue.z * 255.0) as u8;
+            data[offset + 3] = (alpha * 255.0) as u8;
+        }
+    }
 }
 
-/// Composite particles onto main texture
-pub fn composite_particles(
-    grid: Res<GameGrid>,
-    particle_list: Res<ParticleList>,
-    mut images: ResMut<Assets<Image>>,
-    mut particle_texture: ResMut<ParticleTexture>,
+/// Copy the changed region of the pixels into the upload for the render world
+pub fn prepare_texture_upload(
+    pixels: Res<GridPixels>,
     render_texture: Res<RenderTexture>,
+    mut upload: ResMut<TextureUpload>,
 ) {
-    use crate::particles::render::composite_particles_to_main;
-    composite_particles_to_main(grid, particle_list, images, particle_texture, render_texture);
+    upload.texture = render_texture.0.clone();
+    upload.data.clear();
+    let Some((min_x, min_y, max_x, max_y)) = upload.rect else {
+        return;
+    };
+    for y in min_y..=max_y {
+        let start = ((y * pixels.width + min_x) * 4) as usize;
+        let end = ((y * pixels.width + max_x + 1) * 4) as usize;
+        upload.data.extend_from_slice(&pixels.data[start..end]);
+    }
 }
 
-/// Render the game grid to the texture
-pub fn render_grid_to_texture(
-    grid: Res<GameGrid>,
-    rainbow_sand_times: Res<RainbowSandPlacementTimes>,
-    mut images: ResMut<Assets<Image>>,
-    mut sprite_query: Query<&mut Sprite, Without<Camera>>,
-    mut render_texture: ResMut<RenderTexture>,
+/// Write the changed region into the render texture on the GPU (runs in the render world)
+/// Uses queue.write_texture so only the dirty rect is copied, instead of re-uploading the whole image
+pub fn upload_texture_rect(
+    upload: Res<TextureUpload>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    queue: Res<RenderQueue>,
 ) {
-    // Create pixel data from grid
-    // Rgba8Unorm format: 4 u8 values per pixel (4 bytes per pixel)
-    let mut pixel_data = Vec::with_capacity((grid.width * grid.height * 4) as usize);
-    
-    for (idx, element) in grid.elements.iter().enumerate() {
-        let color = if *element == Element::RainbowSand {
-            // RainbowSand: use placement time to determine color
-            // The color is determined when placed and stays fixed
-            // Get the placement time for this position, or use position-based hash as fallback
-            let placement_time = rainbow_sand_times.0.get(&idx).copied();
-            let (x, y) = grid.index_to_xy(idx);
-            
-            let placement_time = placement_time.unwrap_or_else(|| {
-                // Fallback: if no placement time found, use position hash
-                // This handles cases where sand moved and we lost the placement time
-                (x.wrapping_mul(73856093)).wrapping_add(y.wrapping_mul(19349663)) as u32
-            });
-            
-            // Use placement time to create color shift across full 360 degree hue range
-            // Use modulo 256 to get full u8 range, which will be mapped to 0-360 degrees
-            let shift = (placement_time % 256) as u8;
-            
-            element.to_encoded_color_with_shift(shift)
-        } else {
-            // Normal elements: no color shift
-            element.to_encoded_color()
-        };
-        
-        // Convert LinearRgba to u8 values (Rgba8Unorm format)
-        pixel_data.push((color.red * 255.0).clamp(0.0, 255.0) as u8);
-        pixel_data.push((color.green * 255.0).clamp(0.0, 255.0) as u8);
-        pixel_data.push((color.blue * 255.0).clamp(0.0, 255.0) as u8);
-        pixel_data.push((color.alpha * 255.0).clamp(0.0, 255.0) as u8);
-    }
-
-    // Create a new image from the pixel data each frame
-    // This forces Bevy to recognize the change
-    let mut new_image = Image::new_target_texture(grid.width, grid.height, TextureFormat::Rgba8Unorm);
-    new_image.data = Some(pixel_data);
-    new_image.asset_usage = RenderAssetUsages::RENDER_WORLD;
-    new_image.texture_descriptor.usage = TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
-    
-    // Add the new image and update the sprite
-    let new_handle = images.add(new_image);
-    
-    // Update the RenderTexture resource so composite_particles can use it
-    render_texture.0 = new_handle.clone();
-    
-    // Update the sprite to use the new image handle
-    // This forces Bevy to re-render with the new data
-    for mut sprite in sprite_query.iter_mut() {
-        sprite.image = new_handle.clone();
+    let Some((min_x, min_y, max_x, max_y)) = upload.rect else {
+        return;
+    };
+    let Some(gpu_image) = gpu_images.get(&upload.texture) else {
+        return; // Texture not prepared yet
+    };
+    if max_x >= gpu_image.texture.width() || max_y >= gpu_image.texture.height() {
+        return; // Texture is being resized, the full upload follows next frame
     }
+    let width = max_x - min_x + 1;
+    let height = max_y - min_y + 1;
+    queue.write_texture(
+        TexelCopyTextureInfo {
+            texture: &gpu_image.texture,
+            mip_level: 0,
+            origin: Origin3d { x: min_x, y: min_y, z: 0 },
+            aspect: TextureAspect::All,
+        },
+        &upload.data,
+        TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(width * 4),
+            rows_per_image: Some(height),
+        },
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
 }
 
-/// Handle mouse scroll to adjust draw radius
+/// Handle mouse scroll to adjust draw radius (Ctrl+scroll zooms the camera instead)
 pub fn handle_mouse_scroll(
     mut draw_radius: ResMut<DrawRadius>,
     mut scroll_evr: bevy::prelude::MessageReader<MouseWheel>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
     egui_contexts: Option<EguiContexts>,
 ) {
     // Don't process scroll if egui is consuming the input
@@ -870,6 +3718,10 @@ pub fn handle_mouse_scroll(
         total_scroll += ev.y;
     }
 
+    if keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return; // Zooming, handled by handle_camera_controls
+    }
+
     if total_scroll != 0.0 {
         // Adjust radius: scroll up increases, scroll down decreases
         // Clamp between 1.0 and 50.0
@@ -877,14 +3729,82 @@ pub fn handle_mouse_scroll(
     }
 }
 
+/// Ctrl+scroll zooms and middle-mouse drag pans, the camera eases toward the new zoom and pan
+/// Clicks and the circle preview go through `viewport_to_world_2d`, so they follow the camera
+pub fn handle_camera_controls(
+    mut zoom: ResMut<CameraZoom>,
+    mut pan: ResMut<CameraPan>,
+    mut scroll_evr: MessageReader<MouseWheel>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut shake: ResMut<CameraShake>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+    egui_contexts: Option<EguiContexts>,
+) {
+    let mut total_scroll = 0.0;
+    for ev in scroll_evr.read() {
+        total_scroll += ev.y;
+    }
+
+    // Don't take input if egui is consuming it, but keep easing the camera
+    let egui_wants_pointer = egui_contexts.is_some_and(|mut contexts| {
+        contexts
+            .ctx_mut()
+            .is_ok_and(|ctx| ctx.wants_pointer_input() || ctx.is_pointer_over_area())
+    });
+
+    if !egui_wants_pointer {
+        if total_scroll != 0.0 && keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+            zoom.0 = (zoom.0 * 1.1_f32.powf(total_scroll)).clamp(CameraZoom::MIN, CameraZoom::MAX);
+        }
+        if mouse_button_input.pressed(MouseButton::Middle) {
+            // Screen y points down, world y points up
+            let delta = mouse_motion.delta / zoom.0;
+            pan.0 += Vec2::new(-delta.x, delta.y);
+        }
+    }
+
+    // The camera eases back to the pan target after each jolt
+    let jolt = if shake.duration > 0.0 {
+        shake.duration = (shake.duration - time.delta_secs()).max(0.0);
+        let angle = sim_rng().gen_range(0.0..std::f32::consts::TAU);
+        Vec2::from_angle(angle) * shake.magnitude * shake.duration.min(1.0)
+    } else {
+        Vec2::ZERO
+    };
+
+    let t = (CAMERA_SMOOTHING * time.delta_secs()).min(1.0);
+    for mut transform in &mut camera_query {
+        let scale = transform.scale.x.lerp(1.0 / zoom.0, t);
+        let translation = transform.translation.truncate().lerp(pan.0, t) + jolt;
+        *transform = Transform::from_translation(translation.extend(transform.translation.z))
+            .with_scale(Vec3::splat(scale));
+    }
+}
+
 /// Draw circle outline to show where material will be placed
 pub fn draw_circle_preview(
     mut gizmos: Gizmos,
     draw_radius: Res<DrawRadius>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    selection: Res<SelectionState>,
+    grid: Res<GameGrid>,
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
     egui_contexts: Option<EguiContexts>,
 ) {
+    // Draw the selection rectangle (grid cells to world coordinates, +y is up in the world)
+    if let Some((x, y, w, h)) = selection.region() {
+        let display_factor_f32 = DISPLAY_FACTOR as f32;
+        let center = Vec2::new(
+            x as f32 + w as f32 / 2.0 - grid.width as f32 / 2.0,
+            grid.height as f32 / 2.0 - (y as f32 + h as f32 / 2.0),
+        ) * display_factor_f32;
+        gizmos.rect_2d(center, Vec2::new(w as f32, h as f32) * display_factor_f32, Color::srgb(0.3, 0.7, 1.0));
+    }
+
     // Don't draw if egui is consuming the input
     if let Some(mut contexts) = egui_contexts {
         if let Ok(ctx) = contexts.ctx_mut()
@@ -911,6 +3831,15 @@ pub fn draw_circle_preview(
         return;
     };
 
+    // Eyedropper mode: a crosshair box around the picked cell instead of the brush circle
+    if keyboard_input.pressed(KeyCode::AltLeft) {
+        let cell_size = DISPLAY_FACTOR as f32;
+        gizmos.rect_2d(world_pos, Vec2::splat(cell_size * 3.0), Color::WHITE);
+        gizmos.line_2d(world_pos - Vec2::X * cell_size * 3.0, world_pos + Vec2::X * cell_size * 3.0, Color::WHITE);
+        gizmos.line_2d(world_pos - Vec2::Y * cell_size * 3.0, world_pos + Vec2::Y * cell_size * 3.0, Color::WHITE);
+        return;
+    }
+
     // Draw circle outline at cursor position
     // Convert radius from texture space to world space
     let world_radius = draw_radius.0 * DISPLAY_FACTOR as f32;
@@ -918,20 +3847,128 @@ pub fn draw_circle_preview(
 }
 
 /// Handle mouse clicks for drawing (CPU version)
+/// Left-click a spigot's indicator triangle and drag it sideways to move the spigot
+/// The new position is applied on release, `draw_spigot_indicators` previews it meanwhile
+pub fn handle_spigot_drag(
+    mut spigots: ResMut<Spigots>,
+    mut placement: ResMut<SpigotPlacementState>,
+    grid: Res<GameGrid>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    egui_contexts: Option<EguiContexts>,
+) {
+    if let Some(index) = placement.dragging {
+        if mouse_button_input.pressed(MouseButton::Left) {
+            return;
+        }
+        let start_x = placement.drag_start_x;
+        *placement = SpigotPlacementState::default();
+        // A click without moving leaves the spigot where it is
+        if let (Some(cursor), Some(start_x)) = (cursor_grid_position_f32(&windows, &camera_query, &grid), start_x)
+            && (cursor.x - start_x).abs() >= 1.0
+            && index < spigots.len()
+        {
+            spigots.custom_x[index] = Some(spigot_drag_x(cursor.x, spigots.sizes[index], grid.width));
+        }
+        return;
+    }
+
+    if !mouse_button_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    if let Some(mut contexts) = egui_contexts {
+        if let Ok(ctx) = contexts.ctx_mut()
+            && (ctx.wants_pointer_input() || ctx.is_pointer_over_area())
+        {
+            return;
+        }
+    }
+    let Some(cursor) = cursor_grid_position_f32(&windows, &camera_query, &grid) else {
+        return;
+    };
+    if let Some(&(_, _, index)) = spigots
+        .get_spigot_positions(grid.width)
+        .iter()
+        .find(|&&(x, size, _)| spigot_indicator_contains(x, size, cursor))
+    {
+        placement.dragging = Some(index as usize);
+        placement.drag_start_x = Some(cursor.x);
+    }
+}
+
+/// Downward triangle in the spigot's element color at the top of the grid over each spigot
+/// While dragging, the spigot is also previewed where it will land
+pub fn draw_spigot_indicators(
+    mut gizmos: Gizmos,
+    spigots: Res<Spigots>,
+    placement: Res<SpigotPlacementState>,
+    grid: Res<GameGrid>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+) {
+    // Grid cells to world coordinates, +y is up in the world
+    let display_factor_f32 = DISPLAY_FACTOR as f32;
+    let to_world = |x: f32, y: f32| Vec2::new(x - grid.width as f32 / 2.0, grid.height as f32 / 2.0 - y) * display_factor_f32;
+    let triangle = |center_x: f32| {
+        [
+            to_world(center_x - SPIGOT_INDICATOR_SIZE / 2.0, 0.0),
+            to_world(center_x + SPIGOT_INDICATOR_SIZE / 2.0, 0.0),
+            to_world(center_x, SPIGOT_INDICATOR_SIZE),
+            to_world(center_x - SPIGOT_INDICATOR_SIZE / 2.0, 0.0),
+        ]
+    };
+
+    for (x, size, index) in spigots.get_spigot_positions(grid.width) {
+        let color = Color::from(spigots.elements[index as usize].to_encoded_color());
+        gizmos.linestrip_2d(triangle(x as f32 + size as f32 / 2.0), color);
+    }
+
+    let Some(index) = placement.dragging else {
+        return;
+    };
+    // The spigot may have been removed from the UI mid-drag
+    let (Some(cursor), Some(&size)) = (cursor_grid_position_f32(&windows, &camera_query, &grid), spigots.sizes.get(index)) else {
+        return;
+    };
+    let preview_x = spigot_drag_x(cursor.x, size, grid.width) as f32;
+    let center_x = preview_x + size as f32 / 2.0;
+    gizmos.linestrip_2d(triangle(center_x), Color::WHITE);
+    gizmos.rect_2d(
+        to_world(center_x, SPIGOT_HEIGHT as f32 / 2.0),
+        Vec2::new(size as f32, SPIGOT_HEIGHT as f32) * display_factor_f32,
+        Color::WHITE,
+    );
+}
+
 pub fn handle_mouse_clicks_cpu(
     mut grid: ResMut<GameGrid>,
-    selected_element: Res<SelectedElement>,
+    mut selected_element: ResMut<SelectedElement>,
     draw_radius: Res<DrawRadius>,
     overwrite_mode: Res<OverwriteMode>,
+    symmetry_mode: Res<SymmetryMode>,
+    draw_mode: Res<DrawMode>,
     mut rainbow_sand_counter: ResMut<RainbowSandPlacementCounter>,
     mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut line_state: ResMut<LineDrawingState>,
+    (mut portal_registry, mut pending_portal, mut emitter_editor, spigot_placement): (
+        ResMut<PortalRegistry>,
+        ResMut<PendingPortal>,
+        ResMut<EmitterEditor>,
+        Res<SpigotPlacementState>,
+    ),
+    (mut undo_stack, mut redo_stack): (ResMut<UndoStack>, ResMut<RedoStack>),
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
     egui_contexts: Option<EguiContexts>,
 ) {
+    // Dragging a spigot doesn't draw (see handle_spigot_drag)
+    if spigot_placement.dragging.is_some() {
+        return;
+    }
+
     // Don't process clicks if egui is consuming the input
     if let Some(mut contexts) = egui_contexts {
         if let Ok(ctx) = contexts.ctx_mut()
@@ -971,15 +4008,75 @@ pub fn handle_mouse_clicks_cpu(
         return;
     }
 
+    // Ctrl+drag selects instead of drawing (see handle_selection)
+    if keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    // Eyedropper: Alt+click picks the hovered element, and nothing is drawn while Alt is held
+    if keyboard_input.pressed(KeyCode::AltLeft) {
+        if mouse_button_input.just_pressed(MouseButton::Left) {
+            let picked = grid.get(grid_x, grid_y);
+            if picked != Element::Background {
+                selected_element.0 = picked;
+            }
+        }
+        return;
+    }
+
     // Check if shift is pressed
     let shift_pressed = keyboard_input.pressed(KeyCode::ShiftLeft) || keyboard_input.pressed(KeyCode::ShiftRight);
     line_state.shift_pressed = shift_pressed;
+
+    // Shift+click on an emitter opens its settings instead of starting a line
+    if shift_pressed
+        && line_state.start_x.is_none()
+        && mouse_button_input.pressed(MouseButton::Left)
+        && grid.get(grid_x, grid_y) == Element::Emitter
+    {
+        emitter_editor.0 = Some(grid.xy_to_index(grid_x, grid_y));
+        return;
+    }
     
+    // Save the grid at the start of each stroke so it can be undone
+    if mouse_button_input.any_just_pressed([MouseButton::Left, MouseButton::Right]) {
+        undo_stack.record(&mut redo_stack, &grid, &rainbow_sand_times);
+    }
+
     // Draw circle of elements
     let radius = draw_radius.0;
-    let radius_sq = radius * radius;
 
-    if mouse_button_input.pressed(MouseButton::Left) {
+    if mouse_button_input.pressed(MouseButton::Left) && selected_element.0 == Element::Portal {
+        // Portals are placed one cell per click so they can be paired
+        if mouse_button_input.just_pressed(MouseButton::Left)
+            && (overwrite_mode.0 || grid.get(grid_x, grid_y) == Element::Background)
+        {
+            let idx = grid.xy_to_index(grid_x, grid_y);
+            rainbow_sand_times.0.remove(&idx);
+            if !place_portal(&mut grid, &mut portal_registry, &mut pending_portal, idx) {
+                bevy::log::warn!("Maximum of {} portal pairs reached", MAX_PORTAL_PAIRS);
+            }
+        }
+    } else if mouse_button_input.pressed(MouseButton::Left) && *draw_mode == DrawMode::Fill {
+        // Fill once per click, at the cursor and its mirrored positions
+        if mouse_button_input.just_pressed(MouseButton::Left) {
+            let placement_time = (selected_element.0 == Element::RainbowSand).then(|| {
+                rainbow_sand_counter.counter = rainbow_sand_counter.counter.wrapping_add(1);
+                rainbow_sand_counter.counter
+            });
+            let mut points = vec![(grid_x, grid_y)];
+            points.extend(symmetry_mode.mirror_points(&grid, grid_x, grid_y));
+            for (x, y) in points {
+                for idx in grid.flood_fill(x, y, Element::Background, selected_element.0) {
+                    if let Some(placement_time) = placement_time {
+                        rainbow_sand_times.0.insert(idx, placement_time);
+                    } else {
+                        rainbow_sand_times.0.remove(&idx);
+                    }
+                }
+            }
+        }
+    } else if mouse_button_input.pressed(MouseButton::Left) {
         // Handle shift-key straight line drawing
         if shift_pressed {
             // Store start position on first click
@@ -997,10 +4094,12 @@ pub fn handle_mouse_clicks_cpu(
                     grid_x,
                     grid_y,
                     radius,
+                    *draw_mode,
                     selected_element.0,
                     overwrite_mode.0,
                     &mut rainbow_sand_counter,
                     &mut rainbow_sand_times,
+                    *symmetry_mode,
                 );
             }
         } else {
@@ -1025,42 +4124,47 @@ pub fn handle_mouse_clicks_cpu(
             };
             
             // Add elements
-            for dy in -(radius as i32)..=(radius as i32) {
-                for dx in -(radius as i32)..=(radius as i32) {
-                    let dist_sq = (dx * dx + dy * dy) as f32;
-                    if dist_sq <= radius_sq {
-                        let x = (grid_x as i32 + dx).max(0).min(grid.width as i32 - 1) as u32;
-                        let y = (grid_y as i32 + dy).max(0).min(grid.height as i32 - 1) as u32;
-                        
-                        // Check overwrite mode: if disabled, only draw on empty spaces
-                        if overwrite_mode.0 || grid.get(x, y) == Element::Background {
-                            let idx = grid.xy_to_index(x, y);
-                            grid.set(x, y, selected_element.0);
-                            
-                            // Store placement time for RainbowSand
-                            if let Some(placement_time) = current_placement_time {
-                                rainbow_sand_times.0.insert(idx, placement_time);
-                            } else {
-                                // Remove from placement times if not RainbowSand
-                                rainbow_sand_times.0.remove(&idx);
-                            }
-                        }
-                    }
-                }
-            }
+            fill_brush(
+                &mut grid,
+                grid_x,
+                grid_y,
+                radius,
+                *draw_mode,
+                selected_element.0,
+                overwrite_mode.0,
+                current_placement_time,
+                &mut rainbow_sand_times,
+            );
+            mirror_draw(
+                &mut grid,
+                grid_x,
+                grid_y,
+                radius,
+                *draw_mode,
+                selected_element.0,
+                overwrite_mode.0,
+                current_placement_time,
+                &mut rainbow_sand_times,
+                *symmetry_mode,
+            );
         }
     } else if mouse_button_input.pressed(MouseButton::Right) {
         // Remove elements (set to background)
-        for dy in -(radius as i32)..=(radius as i32) {
-            for dx in -(radius as i32)..=(radius as i32) {
-                let dist_sq = (dx * dx + dy * dy) as f32;
-                if dist_sq <= radius_sq {
-                    let x = (grid_x as i32 + dx).max(0).min(grid.width as i32 - 1) as u32;
-                    let y = (grid_y as i32 + dy).max(0).min(grid.height as i32 - 1) as u32;
-                    grid.set(x, y, Element::Background);
-                }
-            }
-        }
+        // Fill has no erase shape of its own, so it erases a circle
+        let erase_mode = if *draw_mode == DrawMode::Fill { DrawMode::Circle } else { *draw_mode };
+        fill_brush(&mut grid, grid_x, grid_y, radius, erase_mode, Element::Background, true, None, &mut rainbow_sand_times);
+        mirror_draw(
+            &mut grid,
+            grid_x,
+            grid_y,
+            radius,
+            erase_mode,
+            Element::Background,
+            true,
+            None,
+            &mut rainbow_sand_times,
+            *symmetry_mode,
+        );
     } else {
         // Reset mouse pressed state when button is released
         rainbow_sand_counter.last_mouse_pressed = false;
@@ -1071,7 +4175,91 @@ pub fn handle_mouse_clicks_cpu(
     }
 }
 
-/// Draw a line between two points using Bresenham's line algorithm
+/// Fill the brush shape centered on (cx, cy), clamped to the grid
+fn fill_brush(
+    grid: &mut GameGrid,
+    cx: u32,
+    cy: u32,
+    radius: f32,
+    brush: DrawMode,
+    element: Element,
+    overwrite: bool,
+    placement_time: Option<u32>,
+    rainbow_sand_times: &mut RainbowSandPlacementTimes,
+) {
+    let radius_sq = radius * radius;
+    if let DrawMode::Spray { density } = brush {
+        // Each row of the circle is scattered as a one cell tall strip
+        let mut rng = sim_rng();
+        for dy in -(radius as i32)..=(radius as i32) {
+            let y = cy as i32 + dy;
+            if y < 0 || y >= grid.height as i32 {
+                continue;
+            }
+            let half_width = (radius_sq - (dy * dy) as f32).max(0.0).sqrt() as i32;
+            let x0 = (cx as i32 - half_width).max(0);
+            let x1 = (cx as i32 + half_width).min(grid.width as i32 - 1);
+            if x1 < x0 {
+                continue;
+            }
+            for idx in grid.apply_noise(x0 as u32, y as u32, (x1 - x0 + 1) as u32, 1, element, density, overwrite, &mut rng) {
+                if let Some(placement_time) = placement_time {
+                    rainbow_sand_times.0.insert(idx, placement_time);
+                } else {
+                    rainbow_sand_times.0.remove(&idx);
+                }
+            }
+        }
+        return;
+    }
+    for dy in -(radius as i32)..=(radius as i32) {
+        for dx in -(radius as i32)..=(radius as i32) {
+            let dist_sq = (dx * dx + dy * dy) as f32;
+            let in_brush = match brush {
+                DrawMode::Square => true,
+                DrawMode::Circle | DrawMode::Fill | DrawMode::Spray { .. } => dist_sq <= radius_sq,
+            };
+            if in_brush {
+                let x = (cx as i32 + dx).max(0).min(grid.width as i32 - 1) as u32;
+                let y = (cy as i32 + dy).max(0).min(grid.height as i32 - 1) as u32;
+
+                // Check overwrite mode: if disabled, only draw on empty spaces
+                if overwrite || grid.get(x, y) == Element::Background {
+                    let idx = grid.xy_to_index(x, y);
+                    grid.set(x, y, element);
+
+                    // Store placement time for RainbowSand
+                    if let Some(placement_time) = placement_time {
+                        rainbow_sand_times.0.insert(idx, placement_time);
+                    } else {
+                        // Remove from placement times if not RainbowSand
+                        rainbow_sand_times.0.remove(&idx);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fill the brush at the positions mirrored from (x, y) by the symmetry mode
+fn mirror_draw(
+    grid: &mut GameGrid,
+    x: u32,
+    y: u32,
+    radius: f32,
+    brush: DrawMode,
+    element: Element,
+    overwrite: bool,
+    placement_time: Option<u32>,
+    rainbow_sand_times: &mut RainbowSandPlacementTimes,
+    mode: SymmetryMode,
+) {
+    for (mirror_x, mirror_y) in mode.mirror_points(grid, x, y) {
+        fill_brush(grid, mirror_x, mirror_y, radius, brush, element, overwrite, placement_time, rainbow_sand_times);
+    }
+}
+
+/// Draw a line between two points using Bresenham's line algorithm, plus its mirrored copies
 fn draw_line(
     grid: &mut GameGrid,
     x0: u32,
@@ -1079,21 +4267,13 @@ fn draw_line(
     x1: u32,
     y1: u32,
     radius: f32,
+    brush: DrawMode,
     element: Element,
     overwrite: bool,
     rainbow_sand_counter: &mut RainbowSandPlacementCounter,
     rainbow_sand_times: &mut RainbowSandPlacementTimes,
+    mode: SymmetryMode,
 ) {
-    let dx = (x1 as i32 - x0 as i32).abs();
-    let dy = (y1 as i32 - y0 as i32).abs();
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let sy = if y0 < y1 { 1 } else { -1 };
-    let mut err = dx - dy;
-    
-    let mut x = x0 as i32;
-    let mut y = y0 as i32;
-    let radius_sq = radius * radius;
-    
     // Get placement time for RainbowSand
     let current_placement_time = if element == Element::RainbowSand {
         rainbow_sand_counter.frame_since_last_increment += 1;
@@ -1105,34 +4285,38 @@ fn draw_line(
     } else {
         None
     };
-    
-    loop {
-        // Draw circle at each point along the line
-        for dy in -(radius as i32)..=(radius as i32) {
-            for dx in -(radius as i32)..=(radius as i32) {
-                let dist_sq = (dx * dx + dy * dy) as f32;
-                if dist_sq <= radius_sq {
-                    let px = (x + dx).max(0).min(grid.width as i32 - 1) as u32;
-                    let py = (y + dy).max(0).min(grid.height as i32 - 1) as u32;
-                    
-                    if overwrite || grid.get(px, py) == Element::Background {
-                        let idx = grid.xy_to_index(px, py);
-                        grid.set(px, py, element);
-                        
-                        if let Some(placement_time) = current_placement_time {
-                            rainbow_sand_times.0.insert(idx, placement_time);
-                        } else {
-                            rainbow_sand_times.0.remove(&idx);
-                        }
-                    }
-                }
-            }
+
+    // Mirror the line endpoints, mirror_points returns them in the same order for both ends
+    let mut lines = vec![((x0, y0), (x1, y1))];
+    lines.extend(mode.mirror_points(grid, x0, y0).into_iter().zip(mode.mirror_points(grid, x1, y1)));
+
+    for ((x0, y0), (x1, y1)) in lines {
+        // Draw the brush at each point along the line
+        for (x, y) in line_points(x0, y0, x1, y1) {
+            fill_brush(grid, x, y, radius, brush, element, overwrite, current_placement_time, rainbow_sand_times);
         }
-        
+    }
+}
+
+/// Every cell on the line from (x0, y0) to (x1, y1), both ends included
+/// The error term steps x, y or both each iteration, so steep lines step once per row and leave no gaps
+pub fn line_points(x0: u32, y0: u32, x1: u32, y1: u32) -> Vec<(u32, u32)> {
+    let dx = (x1 as i32 - x0 as i32).abs();
+    let dy = (y1 as i32 - y0 as i32).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx - dy;
+    let mut x = x0 as i32;
+    let mut y = y0 as i32;
+    let mut points = Vec::with_capacity(dx.max(dy) as usize + 1);
+
+    loop {
+        points.push((x as u32, y as u32));
+
         if x == x1 as i32 && y == y1 as i32 {
-            break;
+            return points;
         }
-        
+
         let e2 = 2 * err;
         if e2 > -dy {
             err -= dy;
@@ -1154,6 +4338,10 @@ pub fn handle_window_resize(
     mut particle_texture: ResMut<ParticleTexture>,
     mut sprite_query: Query<&mut Sprite>,
     mut rainbow_sand_times: ResMut<RainbowSandPlacementTimes>,
+    mut undo_stack: ResMut<UndoStack>,
+    mut redo_stack: ResMut<RedoStack>,
+    (mut portal_registry, mut pending_portal, mut emitter_configs): (ResMut<PortalRegistry>, ResMut<PendingPortal>, ResMut<EmitterConfigs>),
+    (mut active_branches, mut particle_list): (ResMut<ActiveTreeBranches>, ResMut<ParticleList>),
 ) {
     for event in resize_events.read() {
         // Calculate new grid size based on window size and display factor
@@ -1167,6 +4355,19 @@ pub fn handle_window_resize(
             
             // Clear RainbowSand placement times
             rainbow_sand_times.0.clear();
+
+            // Snapshots of the old size can't be restored
+            undo_stack.0.clear();
+            redo_stack.0.clear();
+
+            // Portal links and emitter settings point at cells of the old grid
+            portal_registry.0.clear();
+            pending_portal.0 = None;
+            emitter_configs.0.clear();
+
+            // Branches and particles have positions on the old grid
+            active_branches.branches.clear();
+            particle_list.clear_all();
             
             // Resize render texture
             if let Some(image) = images.get_mut(&render_texture.0) {
@@ -1178,12 +4379,7 @@ pub fn handle_window_resize(
             
             // Resize particle texture
             if let Some(image) = images.get_mut(&particle_texture.0) {
-                let particle_pixel_data = vec![0u8; (new_width * new_height * 4) as usize];
-                let mut new_particle_image = Image::new_target_texture(new_width, new_height, TextureFormat::Rgba8Unorm);
-                new_particle_image.data = Some(particle_pixel_data);
-                new_particle_image.asset_usage = RenderAssetUsages::RENDER_WORLD;
-                new_particle_image.texture_descriptor.usage = TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
-                *image = new_particle_image;
+                *image = new_particle_image(new_width, new_height);
             }
             
             // Update sprite size
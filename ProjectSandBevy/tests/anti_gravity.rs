@@ -0,0 +1,81 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{
+    do_gravity, AntiGravitySources, GameGrid, ANTI_GRAVITY_LIFETIME, ANTI_GRAVITY_RADIUS, SETTLE_THRESHOLD,
+};
+
+#[test]
+fn placing_anti_gravity_starts_a_zone_that_expires() {
+    let mut grid = GameGrid::new(64, 64);
+    let mut sources = AntiGravitySources::default();
+    grid.set(20, 20, Element::AntiGravity);
+    // Inside the first zone, doesn't start another
+    grid.set(22, 20, Element::AntiGravity);
+
+    sources.update(&mut grid, 1.0);
+
+    assert_eq!(sources.0, vec![(20, 20, ANTI_GRAVITY_RADIUS, 1.0 + ANTI_GRAVITY_LIFETIME)]);
+    assert!(grid.gravity_inverted(20, 20 - ANTI_GRAVITY_RADIUS));
+    assert!(!grid.gravity_inverted(20, 20 + ANTI_GRAVITY_RADIUS + 1));
+
+    sources.update(&mut grid, 1.0 + ANTI_GRAVITY_LIFETIME);
+
+    assert!(sources.0.is_empty());
+    assert!(!grid.gravity_inverted(20, 20));
+}
+
+#[test]
+fn sand_falls_upward_inside_a_zone() {
+    let mut grid = GameGrid::new(64, 64);
+    grid.anti_gravity_zones.push((10, 10, 5));
+    grid.set(10, 12, Element::Sand);
+    grid.set(40, 12, Element::Sand);
+
+    let inside = grid.xy_to_index(10, 12);
+    let outside = grid.xy_to_index(40, 12);
+    assert!(do_gravity(&mut grid, 10, 12, inside, false, 1.0, false, &mut None, false));
+    assert!(do_gravity(&mut grid, 40, 12, outside, false, 1.0, false, &mut None, false));
+
+    assert_eq!(grid.get(10, 11), Element::Sand);
+    assert_eq!(grid.get(40, 13), Element::Sand);
+}
+
+#[test]
+fn settled_sand_wakes_when_a_zone_starts_and_expires() {
+    let mut grid = GameGrid::new(64, 64);
+    let mut sources = AntiGravitySources::default();
+    grid.set(20, 30, Element::Sand);
+    grid.staleness.fill(SETTLE_THRESHOLD);
+    // Far enough from the sand that placing it doesn't wake it
+    grid.set(20, 25, Element::AntiGravity);
+    let sand = grid.xy_to_index(20, 30);
+    assert!(grid.is_settled(sand));
+
+    sources.update(&mut grid, 1.0);
+
+    assert!(!grid.is_settled(sand));
+    assert!(do_gravity(&mut grid, 20, 30, sand, false, 1.0, false, &mut None, false));
+    assert_eq!(grid.get(20, 29), Element::Sand);
+
+    // Settles again under the zone, then wakes and falls back once it runs out
+    grid.staleness.fill(SETTLE_THRESHOLD);
+    let sand = grid.xy_to_index(20, 29);
+    sources.update(&mut grid, 1.0 + ANTI_GRAVITY_LIFETIME);
+
+    assert!(!grid.is_settled(sand));
+    assert!(do_gravity(&mut grid, 20, 29, sand, false, 1.0, false, &mut None, false));
+    assert_eq!(grid.get(20, 30), Element::Sand);
+}
+
+#[test]
+fn clearing_the_grid_ends_its_zones() {
+    let mut grid = GameGrid::new(64, 64);
+    let mut sources = AntiGravitySources::default();
+    grid.set(20, 20, Element::AntiGravity);
+    sources.update(&mut grid, 1.0);
+
+    grid.clear();
+    sources.update(&mut grid, 2.0);
+
+    assert!(sources.0.is_empty());
+    assert!(!grid.gravity_inverted(20, 20));
+}
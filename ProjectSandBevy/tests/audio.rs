@@ -0,0 +1,57 @@
+use ProjectSandBevy::audio::{
+    embers_loop, embers_speed, embers_volume, percussion_hit, smooth_toward, water_loop, water_volume, AudioSettings,
+    SAMPLE_RATE, WATER_LAYER_COVERAGE,
+};
+
+#[test]
+fn smoothed_count_converges_on_target() {
+    let mut count = 0.0;
+    for _ in 0..100 {
+        let next = smooth_toward(count, 500.0);
+        // Never overshoots, and stops moving once the f32 reaches the target
+        assert!(next >= count && next <= 500.0);
+        count = next;
+    }
+    assert!((count - 500.0).abs() < 1.0, "{count}");
+}
+
+#[test]
+fn embers_speed_up_and_get_louder_with_fire() {
+    assert_eq!(embers_volume(0.0), 0.0);
+    assert!(embers_speed(0.01) > embers_speed(0.0));
+    assert!(embers_volume(0.01) > embers_volume(0.001));
+    // Capped once the grid is well alight
+    assert_eq!(embers_speed(0.5), embers_speed(1.0));
+    assert_eq!(embers_volume(1.0), 1.0);
+}
+
+#[test]
+fn water_layer_needs_enough_coverage() {
+    assert_eq!(water_volume(0.0), 0.0);
+    assert_eq!(water_volume(WATER_LAYER_COVERAGE), 0.0);
+    assert!(water_volume(WATER_LAYER_COVERAGE + 0.05) > 0.0);
+    assert_eq!(water_volume(1.0), 1.0);
+}
+
+#[test]
+fn mute_silences_everything() {
+    let settings = AudioSettings { muted: true, volume: 0.8 };
+    assert_eq!(settings.effective_volume(), 0.0);
+    assert_eq!(AudioSettings { muted: false, ..settings }.effective_volume(), 0.8);
+}
+
+#[test]
+fn generated_sounds_are_audible_and_in_range() {
+    for (name, samples, seconds) in [("embers", embers_loop(), 2.0), ("water", water_loop(), 4.0), ("percussion", percussion_hit(), 0.5)] {
+        assert_eq!(samples.len(), (SAMPLE_RATE as f32 * seconds) as usize, "{name}");
+        assert!(samples.iter().all(|sample| (-1.0..=1.0).contains(sample)), "{name} clips");
+        assert!(samples.iter().any(|sample| sample.abs() > 0.05), "{name} is silent");
+    }
+}
+
+#[test]
+fn water_loop_repeats_without_a_click() {
+    let samples = water_loop();
+    let (first, last) = (samples[0], samples[samples.len() - 1]);
+    assert!((first - last).abs() < 0.01, "{first} vs {last}");
+}
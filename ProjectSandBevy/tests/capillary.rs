@@ -0,0 +1,66 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{do_capillary, execute_element_action, GameGrid, SimContext, SimulationRules};
+
+const WIDTH: u32 = 5;
+const HEIGHT: u32 = 12;
+const CHANNEL_X: u32 = 2;
+const CHANNEL_TOP: u32 = 2;
+const CHANNEL_BOTTOM: u32 = 9;
+
+/// Build a U-shaped Wall channel one cell wide with water resting at the bottom
+fn build_channel() -> GameGrid {
+    let mut grid = GameGrid::new(WIDTH, HEIGHT);
+    for y in CHANNEL_TOP..=CHANNEL_BOTTOM {
+        grid.set(CHANNEL_X - 1, y, Element::Wall);
+        grid.set(CHANNEL_X + 1, y, Element::Wall);
+    }
+    for x in CHANNEL_X - 1..=CHANNEL_X + 1 {
+        grid.set(x, CHANNEL_BOTTOM + 1, Element::Wall);
+    }
+    grid.set(CHANNEL_X, CHANNEL_BOTTOM, Element::Water);
+    grid
+}
+
+#[test]
+fn capillary_moves_water_up_the_channel() {
+    let mut grid = build_channel();
+    let i = grid.xy_to_index(CHANNEL_X, CHANNEL_BOTTOM);
+
+    // 20% chance per call, so this succeeds well within 1000 attempts
+    let moved = (0..1000).any(|_| do_capillary(&mut grid, CHANNEL_X, CHANNEL_BOTTOM, i, SimulationRules::default().water_capillary_chance));
+
+    assert!(moved);
+    assert_eq!(grid.get(CHANNEL_X, CHANNEL_BOTTOM), Element::Background);
+    assert_eq!(grid.get(CHANNEL_X, CHANNEL_BOTTOM - 1), Element::Water);
+}
+
+#[test]
+fn capillary_requires_walls_on_both_sides() {
+    let mut grid = build_channel();
+    grid.set(CHANNEL_X + 1, CHANNEL_BOTTOM - 1, Element::Background);
+    let i = grid.xy_to_index(CHANNEL_X, CHANNEL_BOTTOM);
+
+    let moved = (0..1000).any(|_| do_capillary(&mut grid, CHANNEL_X, CHANNEL_BOTTOM, i, SimulationRules::default().water_capillary_chance));
+
+    assert!(!moved);
+    assert_eq!(grid.get(CHANNEL_X, CHANNEL_BOTTOM), Element::Water);
+}
+
+#[test]
+fn water_rises_in_channel_during_simulation() {
+    let mut grid = build_channel();
+    let mut rose = false;
+
+    for _ in 0..1000 {
+        let i = grid.xy_to_index(CHANNEL_X, CHANNEL_BOTTOM);
+        if grid.get_index(i) == Element::Water {
+            execute_element_action(&mut grid, CHANNEL_X, CHANNEL_BOTTOM, i, &SimulationRules::default(), &mut SimContext::default());
+        }
+        if grid.get(CHANNEL_X, CHANNEL_BOTTOM - 1) == Element::Water {
+            rose = true;
+            break;
+        }
+    }
+
+    assert!(rose);
+}
@@ -0,0 +1,52 @@
+use ProjectSandBevy::challenges::{evaluate_challenge, parse_challenges, ChallengeCondition, ChallengeStatus, Challenges};
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::GameGrid;
+
+#[test]
+fn shipped_challenges_parse() {
+    let text = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/challenges.toml")).unwrap();
+    let challenges = parse_challenges(&text).unwrap();
+    assert_eq!(challenges.len(), 5);
+    assert!(challenges.iter().all(|challenge| challenge.status == ChallengeStatus::Active));
+    assert!(challenges.iter().any(|challenge| challenge.condition == ChallengeCondition::ElementAbsent(Element::Fire)));
+}
+
+#[test]
+fn conditions_follow_the_grid() {
+    let mut grid = GameGrid::new(10, 10);
+    for x in 0..10 {
+        grid.set(x, 9, Element::Sand);
+    }
+    grid.set(4, 4, Element::Fire);
+    let stats = grid.statistics();
+
+    assert!(evaluate_challenge(&grid, &stats, &ChallengeCondition::ElementCount { element: Element::Sand, min: 10 }));
+    assert!(!evaluate_challenge(&grid, &stats, &ChallengeCondition::ElementCount { element: Element::Sand, min: 11 }));
+    assert!(evaluate_challenge(&grid, &stats, &ChallengeCondition::ElementAbsent(Element::Water)));
+    assert!(!evaluate_challenge(&grid, &stats, &ChallengeCondition::ElementAbsent(Element::Fire)));
+    assert!(evaluate_challenge(&grid, &stats, &ChallengeCondition::FireAt { x: 4, y: 4 }));
+    assert!(!evaluate_challenge(&grid, &stats, &ChallengeCondition::FireAt { x: 40, y: 4 }));
+
+    let half = ChallengeCondition::ElementCount { element: Element::Sand, min: 20 };
+    assert!((half.progress(&grid, &stats) - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn absent_challenges_wait_for_their_element() {
+    let text = r#"
+        [[challenges]]
+        name = "Firefighter"
+        description = "Start a fire, then put it out"
+        condition = { ElementAbsent = "Fire" }
+    "#;
+    let mut challenges = Challenges { challenges: parse_challenges(text).unwrap(), completed: None };
+    let mut grid = GameGrid::new(10, 10);
+
+    // Nothing has burned yet
+    assert!(challenges.update(&grid, &grid.statistics()).is_empty());
+    grid.set(5, 5, Element::Fire);
+    assert!(challenges.update(&grid, &grid.statistics()).is_empty());
+    grid.set(5, 5, Element::Background);
+    assert_eq!(challenges.update(&grid, &grid.statistics()), ["Firefighter"]);
+    assert_eq!(challenges.challenges[0].status, ChallengeStatus::Complete);
+}
@@ -0,0 +1,83 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::rendering::{apply_colorblind, apply_colorblind_bytes, ColorblindMode, COLOR_LUT};
+
+/// Machado et al. 2009 simulations of how each deficiency sees an rgb color
+fn simulate(color: [f32; 3], mode: ColorblindMode) -> [f32; 3] {
+    let matrix = match mode {
+        ColorblindMode::Normal => return color,
+        ColorblindMode::Deuteranopia => [
+            [0.367_322, 0.860_646, -0.227_968],
+            [0.280_085, 0.672_501, 0.047_413],
+            [-0.011_820, 0.042_940, 0.968_881],
+        ],
+        ColorblindMode::Protanopia => [
+            [0.152_286, 1.052_583, -0.204_868],
+            [0.114_503, 0.786_281, 0.099_216],
+            [-0.003_882, -0.048_116, 1.051_998],
+        ],
+        ColorblindMode::Tritanopia => [
+            [1.255_528, -0.076_749, -0.178_779],
+            [-0.078_411, 0.930_809, 0.147_602],
+            [0.004_733, 0.691_367, 0.303_900],
+        ],
+    };
+    matrix.map(|row| row[0] * color[0] + row[1] * color[1] + row[2] * color[2])
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter().zip(&b).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt()
+}
+
+/// Every element's unshifted color, RainbowSand's changes with position so it's left out
+fn element_colors() -> Vec<(Element, [f32; 3])> {
+    (0..=u8::MAX)
+        .map(|index| (index, Element::from_index(index)))
+        .filter(|&(index, element)| element.index() == index && element != Element::RainbowSand)
+        .map(|(_, element)| {
+            let color = element.color();
+            (element, [color.red, color.green, color.blue])
+        })
+        .collect()
+}
+
+#[test]
+fn normal_mode_leaves_colors_unchanged() {
+    for color in COLOR_LUT.iter() {
+        assert_eq!(apply_colorblind_bytes(*color, &ColorblindMode::Normal), *color);
+    }
+    assert_eq!(apply_colorblind([0.2, 0.4, 0.6], &ColorblindMode::Normal), [0.2, 0.4, 0.6]);
+}
+
+#[test]
+fn corrected_colors_stay_in_range_and_keep_alpha() {
+    for mode in ColorblindMode::ALL {
+        for (_, color) in element_colors() {
+            assert!(apply_colorblind(color, &mode).iter().all(|c| (0.0..=1.0).contains(c)), "{mode:?}");
+        }
+        assert_eq!(apply_colorblind_bytes([255, 0, 0, 77], &mode)[3], 77);
+    }
+}
+
+/// Pairs of visibly different elements that look alike to each deficiency must not increase with correction
+#[test]
+fn correction_reduces_confusable_elements() {
+    const CONFUSABLE: f32 = 0.1;
+    let colors = element_colors();
+    for mode in [ColorblindMode::Deuteranopia, ColorblindMode::Protanopia, ColorblindMode::Tritanopia] {
+        let (mut before, mut after) = (0, 0);
+        for (i, &(a, color_a)) in colors.iter().enumerate() {
+            for &(b, color_b) in &colors[i + 1..] {
+                if distance(color_a, color_b) < 0.05 {
+                    continue; // Alike for everyone
+                }
+                before += usize::from(distance(simulate(color_a, mode), simulate(color_b, mode)) < CONFUSABLE);
+                let corrected_a = simulate(apply_colorblind(color_a, &mode), mode);
+                let corrected_b = simulate(apply_colorblind(color_b, &mode), mode);
+                let d = distance(corrected_a, corrected_b);
+                after += usize::from(d < CONFUSABLE);
+                assert!(d > 0.0, "{a:?} and {b:?} look identical under {mode:?}");
+            }
+        }
+        assert!(after < before, "{mode:?}: {after} confusable pairs after correction, {before} before");
+    }
+}
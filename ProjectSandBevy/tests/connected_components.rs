@@ -0,0 +1,41 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::GameGrid;
+
+fn fill_block(grid: &mut GameGrid, x0: u32, y0: u32, element: Element) {
+    for y in y0..y0 + 3 {
+        for x in x0..x0 + 3 {
+            grid.set(x, y, element);
+        }
+    }
+}
+
+#[test]
+fn two_separate_blocks_are_two_components() {
+    let mut grid = GameGrid::new(10, 10);
+    fill_block(&mut grid, 1, 1, Element::Sand);
+    fill_block(&mut grid, 6, 6, Element::Sand);
+
+    let components = grid.connected_components(Element::Sand);
+    assert_eq!(components.len(), 2);
+    for component in &components {
+        assert_eq!(component.len(), 9);
+        assert!(component.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+    assert_eq!(components[0][0], grid.xy_to_index(1, 1));
+    assert_eq!(components[1][0], grid.xy_to_index(6, 6));
+    assert!(grid.connected_components(Element::Water).is_empty());
+}
+
+#[test]
+fn diagonal_cells_are_not_connected() {
+    let mut grid = GameGrid::new(10, 10);
+    fill_block(&mut grid, 0, 0, Element::Sand);
+    fill_block(&mut grid, 3, 3, Element::Sand);
+    assert_eq!(grid.connected_components(Element::Sand).len(), 2);
+
+    // A bridge cell joins them
+    grid.set(3, 2, Element::Sand);
+    let components = grid.connected_components(Element::Sand);
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].len(), 19);
+}
@@ -0,0 +1,66 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::particles::actions::debris_particle_init;
+use ProjectSandBevy::particles::{particle_action, Particle, ParticleType};
+use ProjectSandBevy::simulation::GameGrid;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+fn debris(x: f32, y: f32, element: Element) -> Particle {
+    let mut particle = Particle::new();
+    particle.particle_type = ParticleType::Debris;
+    particle.active = true;
+    particle.x = x;
+    particle.y = y;
+    debris_particle_init(&mut particle, element, &mut SmallRng::seed_from_u64(3));
+    particle
+}
+
+#[test]
+fn debris_init_is_small_and_fast() {
+    for seed in 0..50 {
+        let mut particle = Particle::new();
+        debris_particle_init(&mut particle, Element::C4, &mut SmallRng::seed_from_u64(seed));
+        assert_eq!(particle.color, Element::C4);
+        assert_eq!(particle.size, 2.0);
+        assert!((3.0..=8.0).contains(&particle.velocity));
+        assert_eq!(particle.y_acceleration, Some(0.15));
+    }
+}
+
+#[test]
+fn debris_lands_on_the_floor_as_its_element() {
+    let mut grid = GameGrid::new(32, 32);
+    for x in 0..32 {
+        grid.set(x, 31, Element::Wall);
+    }
+    let mut particle = debris(16.0, 4.0, Element::Gunpowder);
+    particle.x_velocity = 0.0;
+    particle.y_velocity = 0.0;
+
+    let mut removed = false;
+    for _ in 0..200 {
+        if particle_action(&mut particle, None, 0, &mut grid) {
+            removed = true;
+            break;
+        }
+    }
+
+    assert!(removed);
+    assert_eq!(grid.get(16, 30), Element::Gunpowder);
+    assert_eq!(grid.get(16, 31), Element::Wall);
+}
+
+#[test]
+fn fast_debris_does_not_pass_through_thin_walls() {
+    let mut grid = GameGrid::new(32, 32);
+    for y in 0..32 {
+        grid.set(10, y, Element::Wall);
+    }
+    let mut particle = debris(5.0, 16.0, Element::C4);
+    particle.x_velocity = 8.0;
+    particle.y_velocity = -0.15;
+
+    assert!(particle_action(&mut particle, None, 0, &mut grid));
+    assert_eq!(grid.get(9, 16), Element::C4);
+    assert!((11..32).all(|x| grid.get(x, 16) == Element::Background));
+}
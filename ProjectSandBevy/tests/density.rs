@@ -0,0 +1,65 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{
+    do_density_liquid, do_density_sink, seed_simulation_rng, GameGrid, QUICKSAND_SINK_CHANCE,
+};
+
+/// Try to sink `element` at (2, 1) into `below` at (2, 2), returning what ends up on top
+fn sink(element: Element, below: Element) -> Element {
+    let mut grid = GameGrid::new(5, 4);
+    grid.set(2, 1, element);
+    grid.set(2, 2, below);
+    let i = grid.xy_to_index(2, 1);
+    do_density_sink(&mut grid, 2, 1, i, false, 1.0, false, &mut None);
+    grid.get(2, 1)
+}
+
+#[test]
+fn anything_heavier_sinks_through_a_lighter_liquid() {
+    // Not only the old hardcoded pairs: every element sinks through any liquid less dense than it
+    for element in [Element::Sand, Element::Salt, Element::Gunpowder, Element::Rock, Element::Concrete] {
+        assert_eq!(sink(element, Element::Water), Element::Water, "{element:?} through Water");
+        assert_eq!(sink(element, Element::Oil), Element::Oil, "{element:?} through Oil");
+    }
+    assert_eq!(sink(Element::Salt, Element::SaltWater), Element::SaltWater);
+}
+
+#[test]
+fn nothing_sinks_through_solids_or_denser_liquids() {
+    assert_eq!(sink(Element::Sand, Element::Soil), Element::Sand);
+    assert_eq!(sink(Element::Sand, Element::Rock), Element::Sand);
+    assert_eq!(sink(Element::Sand, Element::Lava), Element::Sand);
+}
+
+#[test]
+fn heavier_liquid_sinks_below_a_lighter_one() {
+    let mut grid = GameGrid::new(5, 4);
+    grid.set(2, 1, Element::Water);
+    grid.set(2, 2, Element::Oil);
+    let i = grid.xy_to_index(2, 1);
+
+    assert!(do_density_liquid(&mut grid, 2, 1, i, 1.0, 0.0, &mut None));
+
+    assert_eq!(grid.get(2, 1), Element::Oil);
+    assert_eq!(grid.get(2, 2), Element::Water);
+}
+
+#[test]
+fn sinking_into_quicksand_ignores_the_callers_chance() {
+    seed_simulation_rng(Some(7));
+    let trials = 2000;
+    for chance in [0.25, 0.95] {
+        let mut sunk = 0;
+        for _ in 0..trials {
+            let mut grid = GameGrid::new(5, 4);
+            grid.set(2, 1, Element::Sand);
+            grid.set(2, 2, Element::Quicksand);
+            let i = grid.xy_to_index(2, 1);
+            if do_density_sink(&mut grid, 2, 1, i, false, chance, false, &mut None) {
+                sunk += 1;
+            }
+        }
+        let rate = f64::from(sunk) / f64::from(trials);
+        assert!((rate - QUICKSAND_SINK_CHANCE).abs() < 0.04, "{rate} with a caller chance of {chance}");
+    }
+    seed_simulation_rng(None);
+}
@@ -0,0 +1,121 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::particles::ParticleList;
+use ProjectSandBevy::simulation::{
+    apply_age_effects, diffuse_temperatures, execute_element_action, frame_seed, seed_simulation_rng, GameGrid,
+    SimContext, SimulationRules,
+};
+use ProjectSandBevy::systems::update_particles_cpu;
+
+const WIDTH: u32 = 48;
+const HEIGHT: u32 = 48;
+const FRAMES: u64 = 200;
+
+/// Piles of sand, salt and oil over a pool of water with a fire and a plant
+fn build_scene() -> GameGrid {
+    let mut grid = GameGrid::new(WIDTH, HEIGHT);
+    for x in 0..WIDTH {
+        grid.set(x, HEIGHT - 1, Element::Wall);
+        for y in HEIGHT - 8..HEIGHT - 1 {
+            grid.set(x, y, Element::Water);
+        }
+    }
+    for y in 4..16 {
+        for x in 4..14 {
+            grid.set(x, y, Element::Sand);
+            grid.set(x + 16, y, Element::Salt);
+            grid.set(x + 30, y, Element::Oil);
+        }
+    }
+    grid.set(34, 20, Element::Fire);
+    grid.set(24, HEIGHT - 9, Element::Plant);
+    grid
+}
+
+/// Run the scene bottom to top like the simulation loop, reseeding every frame
+fn run_seeded(seed: u64) -> Vec<Element> {
+    let mut grid = build_scene();
+    let rules = SimulationRules::default();
+    for frame in 0..FRAMES {
+        seed_simulation_rng(Some(frame_seed(seed, frame)));
+        diffuse_temperatures(&mut grid);
+        for y in (0..HEIGHT).rev() {
+            for x in 0..WIDTH {
+                let i = grid.xy_to_index(x, y);
+                if grid.get_index(i) != Element::Background {
+                    execute_element_action(&mut grid, x, y, i, &rules, &mut SimContext::default());
+                    apply_age_effects(&mut grid, i);
+                }
+            }
+        }
+    }
+    seed_simulation_rng(None);
+    grid.elements
+}
+
+#[test]
+fn same_seed_gives_identical_results() {
+    assert_eq!(run_seeded(42), run_seeded(42));
+}
+
+#[test]
+fn different_seeds_diverge() {
+    assert_ne!(run_seeded(42), run_seeded(43));
+}
+
+/// Gunpowder, C4 and Napalm lit by fire, so explosions throw particles around
+fn build_explosive_scene() -> GameGrid {
+    let mut grid = GameGrid::new(WIDTH, HEIGHT);
+    for x in 0..WIDTH {
+        grid.set(x, HEIGHT - 1, Element::Wall);
+    }
+    for y in HEIGHT - 6..HEIGHT - 1 {
+        for x in 4..12 {
+            grid.set(x, y, Element::Gunpowder);
+            grid.set(x + 16, y, Element::C4);
+            grid.set(x + 30, y, Element::Napalm);
+        }
+    }
+    for x in [8, 24, 38] {
+        grid.set(x, HEIGHT - 7, Element::Fire);
+    }
+    grid
+}
+
+/// Like `run_seeded`, but with a particle list that is updated after every frame
+/// Also returns the most particles that were active at once
+fn run_particles_seeded(seed: u64) -> (Vec<Element>, Vec<(f32, f32)>, usize) {
+    let mut grid = build_explosive_scene();
+    let mut particle_list = ParticleList::default();
+    let mut scratch = Vec::new();
+    let mut most_active = 0;
+    let rules = SimulationRules::default();
+    for frame in 0..FRAMES {
+        seed_simulation_rng(Some(frame_seed(seed, frame)));
+        for y in (0..HEIGHT).rev() {
+            for x in 0..WIDTH {
+                let i = grid.xy_to_index(x, y);
+                if grid.get_index(i) != Element::Background {
+                    let mut ctx = SimContext { particle_list: Some(&mut particle_list), ..SimContext::default() };
+                    execute_element_action(&mut grid, x, y, i, &rules, &mut ctx);
+                }
+            }
+        }
+        update_particles_cpu(&mut particle_list, &mut grid, &mut scratch);
+        most_active = most_active.max(particle_list.active_count());
+    }
+    seed_simulation_rng(None);
+    let positions = particle_list
+        .active_particles()
+        .filter_map(|idx| particle_list.get_particle(idx).map(|p| (p.x, p.y)))
+        .collect();
+    (grid.elements, positions, most_active)
+}
+
+#[test]
+fn particles_replay_identically_with_the_same_seed() {
+    let (elements, positions, most_active) = run_particles_seeded(42);
+    assert!(most_active > 0, "the scene should throw particles");
+    let (replay_elements, replay_positions, _) = run_particles_seeded(42);
+    assert_eq!(elements, replay_elements);
+    assert_eq!(positions, replay_positions);
+}
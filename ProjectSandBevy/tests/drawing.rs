@@ -0,0 +1,46 @@
+use ProjectSandBevy::systems::line_points;
+
+/// Consecutive points must touch (including diagonally) for the line to have no gaps
+fn assert_connected(points: &[(u32, u32)]) {
+    for pair in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        assert!(x0.abs_diff(x1) <= 1 && y0.abs_diff(y1) <= 1, "gap between {:?} and {:?}", pair[0], pair[1]);
+    }
+}
+
+#[test]
+fn test_vertical_line_has_no_gaps() {
+    let points = line_points(5, 2, 5, 40);
+
+    assert_eq!(points, (2..=40).map(|y| (5, y)).collect::<Vec<_>>());
+
+    // Upward and nearly vertical lines cover every row too
+    let up = line_points(5, 40, 5, 2);
+    assert_eq!(up.len(), 39);
+    let steep = line_points(3, 0, 7, 50);
+    assert_eq!(steep.len(), 51);
+    for (row, &(_, y)) in steep.iter().enumerate() {
+        assert_eq!(y, row as u32);
+    }
+    assert_connected(&steep);
+    assert_eq!(steep.last(), Some(&(7, 50)));
+}
+
+#[test]
+fn test_diagonal_line_coverage() {
+    let points = line_points(0, 0, 20, 20);
+    assert_eq!(points, (0..=20).map(|n| (n, n)).collect::<Vec<_>>());
+
+    // Every octant reaches its end point without gaps
+    let center = (50, 50);
+    for &(x1, y1) in &[(90, 60), (60, 90), (40, 90), (10, 60), (10, 40), (40, 10), (60, 10), (90, 40)] {
+        let points = line_points(center.0, center.1, x1, y1);
+        assert_eq!(points.first(), Some(&center));
+        assert_eq!(points.last(), Some(&(x1, y1)));
+        assert_eq!(points.len() as u32, center.0.abs_diff(x1).max(center.1.abs_diff(y1)) + 1);
+        assert_connected(&points);
+    }
+
+    // A single point line is just that point
+    assert_eq!(line_points(7, 7, 7, 7), vec![(7, 7)]);
+}
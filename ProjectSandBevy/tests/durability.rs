@@ -0,0 +1,83 @@
+use ProjectSandBevy::elements::{element_default_durability, Element};
+use ProjectSandBevy::save::WorldSaveFile;
+use ProjectSandBevy::simulation::{do_gravity, ignite_at, GameGrid, EXPLOSION_DAMAGE};
+use ProjectSandBevy::spigots::Spigots;
+use std::collections::HashMap;
+
+#[test]
+fn cells_start_with_their_element_durability() {
+    let mut grid = GameGrid::new(8, 8);
+    assert_eq!(element_default_durability(Element::Wall), 255);
+    assert_eq!(element_default_durability(Element::Rock), 200);
+    assert_eq!(element_default_durability(Element::Sand), 50);
+    assert_eq!(element_default_durability(Element::Plant), 30);
+    assert_eq!(element_default_durability(Element::Branch), 40);
+
+    grid.set(2, 2, Element::Rock);
+    let i = grid.xy_to_index(2, 2);
+    assert_eq!(grid.durability[i], 200);
+    assert!(!grid.damage(i, 60));
+    assert_eq!(grid.durability[i], 140);
+
+    // A new element starts fresh
+    grid.set(2, 2, Element::Sand);
+    assert_eq!(grid.durability[i], 50);
+    grid.clear();
+    assert_eq!(grid.durability[i], element_default_durability(Element::Background));
+}
+
+#[test]
+fn damaged_grain_keeps_its_durability_while_falling() {
+    let mut grid = GameGrid::new(4, 4);
+    grid.set(1, 0, Element::Sand);
+    let (start, landed) = (grid.xy_to_index(1, 0), grid.xy_to_index(1, 1));
+    assert!(!grid.damage(start, 30));
+
+    assert!(do_gravity(&mut grid, 1, 0, start, false, 1.0, false, &mut None, false));
+    assert_eq!(grid.get_index(landed), Element::Sand);
+    assert_eq!(grid.durability[landed], 20);
+    // The cell it left is Background at full durability again
+    assert_eq!(grid.durability[start], element_default_durability(Element::Background));
+}
+
+#[test]
+fn damage_destroys_once_durability_runs_out() {
+    let mut grid = GameGrid::new(4, 4);
+    grid.set(1, 1, Element::Sand);
+    let i = grid.xy_to_index(1, 1);
+    assert!(!grid.damage(i, 30));
+    assert!(grid.damage(i, 30));
+    // Cells without durability go on the first hit, indices off the grid are never destroyed
+    assert!(grid.damage(0, 1));
+    assert!(!grid.damage(1000, 255));
+}
+
+#[test]
+fn explosions_wear_down_rock_before_burning_it() {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(4, 4, Element::Gunpowder);
+    grid.set(5, 4, Element::Rock);
+    grid.set(3, 4, Element::Sand);
+    assert!(u32::from(EXPLOSION_DAMAGE) < 200);
+
+    ignite_at(&mut grid, 4, 4);
+    assert_eq!(grid.get(4, 4), Element::Fire);
+    assert_eq!(grid.get(3, 4), Element::Fire);
+    assert_eq!(grid.get(5, 4), Element::Rock);
+
+    ignite_at(&mut grid, 4, 4);
+    assert_eq!(grid.get(5, 4), Element::Fire);
+}
+
+#[test]
+fn loading_a_save_forgets_damage() {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(1, 1, Element::Wall);
+    let i = grid.xy_to_index(1, 1);
+    grid.damage(i, 200);
+
+    let bytes = WorldSaveFile::new(&grid, &Spigots::default(), &HashMap::new()).to_bytes().unwrap();
+    let loaded = WorldSaveFile::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.grid.durability.len(), loaded.grid.elements.len());
+    assert_eq!(loaded.grid.durability[i], 255);
+}
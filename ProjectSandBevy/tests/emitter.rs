@@ -0,0 +1,36 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::particles::{ParticleList, ParticleType};
+use ProjectSandBevy::simulation::{execute_element_action, EmitterConfig, EmitterConfigs, GameGrid, SimContext, SimulationRules};
+
+#[test]
+fn emitter_sprays_configured_particles() {
+    let mut grid = GameGrid::new(16, 16);
+    grid.set(8, 8, Element::Emitter);
+    let i = grid.xy_to_index(8, 8);
+    let mut configs = EmitterConfigs::default();
+    configs.0.insert(i, EmitterConfig { particle_type: ParticleType::Methane, rate_per_tick: 1.0, angle_spread: 0.0, velocity: 3.0 });
+    let mut particle_list = ParticleList::default();
+
+    execute_element_action(&mut grid, 8, 8, i, &SimulationRules::default(), &mut SimContext { particle_list: Some(&mut particle_list), emitter_configs: Some(&configs), ..SimContext::default() });
+
+    assert_eq!(particle_list.particle_count(ParticleType::Methane), 1);
+    let particle = particle_list.get_particle(particle_list.active_particles().next().unwrap()).unwrap();
+    assert!((particle.velocity - 3.0).abs() < f32::EPSILON);
+    // Straight up with no spread
+    assert!(particle.y_velocity < 0.0 && particle.x_velocity.abs() < 1e-4);
+    assert_eq!(grid.get(8, 8), Element::Emitter);
+}
+
+#[test]
+fn erased_emitters_lose_their_settings() {
+    let mut grid = GameGrid::new(16, 16);
+    grid.set(2, 2, Element::Emitter);
+    let mut configs = EmitterConfigs::default();
+    configs.0.insert(grid.xy_to_index(2, 2), EmitterConfig::default());
+    configs.0.insert(grid.xy_to_index(3, 3), EmitterConfig::default());
+
+    configs.prune(&grid);
+
+    assert_eq!(configs.0.len(), 1);
+    assert!(configs.0.contains_key(&grid.xy_to_index(2, 2)));
+}
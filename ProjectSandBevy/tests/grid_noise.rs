@@ -0,0 +1,36 @@
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::GameGrid;
+
+#[test]
+fn noise_stays_inside_the_region() {
+    let mut grid = GameGrid::new(20, 20);
+    let mut rng = SmallRng::seed_from_u64(1);
+    let placed = grid.apply_noise(5, 5, 4, 3, Element::Sand, 1.0, true, &mut rng);
+    assert_eq!(placed.len(), 12);
+    for (i, &element) in grid.elements.iter().enumerate() {
+        let (x, y) = grid.index_to_xy(i);
+        let inside = (5..9).contains(&x) && (5..8).contains(&y);
+        assert_eq!(element == Element::Sand, inside, "cell ({x}, {y})");
+    }
+
+    // Zero density places nothing, and a region past the edge is clamped
+    assert!(grid.apply_noise(0, 0, 20, 20, Element::Water, 0.0, true, &mut rng).is_empty());
+    assert_eq!(grid.apply_noise(18, 18, 10, 10, Element::Water, 1.0, true, &mut rng).len(), 4);
+}
+
+#[test]
+fn noise_without_overwrite_only_fills_background() {
+    let mut grid = GameGrid::new(10, 10);
+    grid.set(2, 2, Element::Wall);
+    let mut rng = SmallRng::seed_from_u64(7);
+    let placed = grid.apply_noise(0, 0, 10, 10, Element::Sand, 1.0, false, &mut rng);
+    assert_eq!(placed.len(), 99);
+    assert_eq!(grid.get(2, 2), Element::Wall);
+
+    // Roughly half the cells at density 0.5
+    let mut grid = GameGrid::new(100, 100);
+    let placed = grid.apply_noise(0, 0, 100, 100, Element::Sand, 0.5, false, &mut rng);
+    assert!((4000..6000).contains(&placed.len()));
+}
@@ -0,0 +1,32 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::GameGrid;
+
+#[test]
+fn statistics_count_every_cell() {
+    let mut grid = GameGrid::new(10, 10);
+    for x in 0..10 {
+        grid.set(x, 9, Element::Sand);
+    }
+    for x in 0..3 {
+        grid.set(x, 8, Element::Water);
+    }
+    grid.set(5, 5, Element::Oil);
+
+    let stats = grid.statistics();
+    assert_eq!(stats.total_cells, 100);
+    assert_eq!(stats.non_empty_cells, 14);
+    assert_eq!(stats.histogram[usize::from(Element::Water.index())], 3);
+    assert_eq!(stats.histogram.iter().sum::<u32>(), 100);
+    assert_eq!(stats.most_common, Element::Sand);
+    assert_eq!(stats.least_common, Some(Element::Oil));
+    assert!((stats.empty_pct - 86.0).abs() < 1e-4);
+}
+
+#[test]
+fn empty_grid_has_no_common_elements() {
+    let stats = GameGrid::new(4, 4).statistics();
+    assert_eq!(stats.non_empty_cells, 0);
+    assert_eq!(stats.most_common, Element::Background);
+    assert_eq!(stats.least_common, None);
+    assert!((stats.empty_pct - 100.0).abs() < 1e-4);
+}
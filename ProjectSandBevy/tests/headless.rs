@@ -0,0 +1,63 @@
+use rand::Rng;
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{sim_rng, GameGrid, SimulationRules};
+use ProjectSandBevy::spigots::Spigots;
+use ProjectSandBevy::systems::{simulate_ticks, RainbowSandPlacementTimes};
+
+fn run(seed: u64) -> GameGrid {
+    let mut grid = GameGrid::new(64, 48);
+    let mut times = RainbowSandPlacementTimes::default();
+    simulate_ticks(&mut grid, &Spigots::default(), 50, &SimulationRules::default(), Some(seed), &mut times);
+    grid
+}
+
+#[test]
+fn seeded_headless_runs_repeat_exactly() {
+    let first = run(7);
+    // The spigots have poured something onto the empty grid
+    assert!(first.statistics().non_empty_cells > 0);
+    assert_eq!(first.elements, run(7).elements);
+}
+
+#[test]
+fn simulation_rng_is_unseeded_afterwards() {
+    run(7);
+    let first: u64 = sim_rng().gen_range(0..u64::MAX);
+    run(7);
+    let second: u64 = sim_rng().gen_range(0..u64::MAX);
+    assert_ne!(first, second);
+}
+
+/// Gunpowder in a walled pit with fire above it, on a Wall floor
+fn gunpowder_pit() -> GameGrid {
+    let mut grid = GameGrid::new(64, 48);
+    for x in 0..64 {
+        grid.set(x, 47, Element::Wall);
+    }
+    for y in 40..47 {
+        grid.set(28, y, Element::Wall);
+        grid.set(36, y, Element::Wall);
+    }
+    for x in 29..36 {
+        for y in 44..47 {
+            grid.set(x, y, Element::Gunpowder);
+        }
+    }
+    grid.set(32, 43, Element::Fire);
+    grid
+}
+
+#[test]
+fn particles_are_updated_between_ticks() {
+    // Only debris thrown by the blasts can get Gunpowder over the pit walls
+    let spigots = Spigots { sizes: vec![0; Spigots::default().len()], ..Spigots::default() };
+    let thrown_out = (0..6).any(|seed| {
+        let mut grid = gunpowder_pit();
+        let mut times = RainbowSandPlacementTimes::default();
+        simulate_ticks(&mut grid, &spigots, 200, &SimulationRules::default(), Some(seed), &mut times);
+        (0..64)
+            .filter(|x| !(28..=36).contains(x))
+            .any(|x| (0..48).any(|y| grid.get(x, y) == Element::Gunpowder))
+    });
+    assert!(thrown_out, "no debris landed outside the pit");
+}
@@ -0,0 +1,117 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{
+    execute_element_action, seed_simulation_rng, ElementInteractionMatrix, GameGrid, SimContext,
+    SimulationRules,
+};
+
+/// Lava resting on a wall floor with water to its right
+fn lava_by_water() -> GameGrid {
+    let mut grid = GameGrid::new(8, 8);
+    for x in 0..8 {
+        grid.set(x, 7, Element::Wall);
+    }
+    grid.set(4, 6, Element::Lava);
+    grid.set(5, 6, Element::Water);
+    grid
+}
+
+fn step_lava(grid: &mut GameGrid, interactions: Option<&ElementInteractionMatrix>) {
+    seed_simulation_rng(Some(7));
+    let i = grid.xy_to_index(4, 6);
+    execute_element_action(grid, 4, 6, i, &SimulationRules::default(), &mut SimContext { interactions, ..SimContext::default() });
+    seed_simulation_rng(None);
+}
+
+fn freeze_neighbor(grid: &mut GameGrid, _i: usize, neighbor: usize) {
+    grid.set_index(neighbor, Element::Ice);
+}
+
+#[test]
+fn lava_quenching_goes_through_the_matrix() {
+    let mut grid = lava_by_water();
+    step_lava(&mut grid, None);
+    assert_eq!(grid.get(4, 6), Element::Rock);
+    assert_eq!(grid.get(5, 6), Element::Steam);
+
+    // Without the built-in reactions registered, lava and water leave each other alone
+    let mut grid = lava_by_water();
+    step_lava(&mut grid, Some(&ElementInteractionMatrix::empty()));
+    assert_ne!(grid.get(4, 6), Element::Rock);
+    assert_eq!(grid.get(5, 6), Element::Water);
+}
+
+#[test]
+fn registered_reaction_replaces_a_built_in_one() {
+    let mut interactions = ElementInteractionMatrix::default();
+    interactions.register(Element::Lava, Element::Water, freeze_neighbor);
+    let mut grid = lava_by_water();
+    step_lava(&mut grid, Some(&interactions));
+    assert_ne!(grid.get(5, 6), Element::Steam);
+    assert_ne!(grid.get(4, 6), Element::Rock);
+}
+
+#[test]
+fn registered_reaction_runs_against_cardinal_neighbors() {
+    let mut interactions = ElementInteractionMatrix::empty();
+    interactions.register(Element::Wall, Element::Water, freeze_neighbor);
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(4, 4, Element::Wall);
+    grid.set(5, 4, Element::Water);
+    grid.set(4, 3, Element::Water);
+    // Diagonal neighbors aren't checked
+    grid.set(5, 5, Element::Water);
+
+    let i = grid.xy_to_index(4, 4);
+    execute_element_action(&mut grid, 4, 4, i, &SimulationRules::default(), &mut SimContext { interactions: Some(&interactions), ..SimContext::default() });
+
+    assert_eq!(grid.get(5, 4), Element::Ice);
+    assert_eq!(grid.get(4, 3), Element::Ice);
+    assert_eq!(grid.get(5, 5), Element::Water);
+    assert_eq!(grid.get(4, 4), Element::Wall);
+}
+
+/// Fire in the air with water below it
+fn fire_over_water() -> GameGrid {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(4, 4, Element::Fire);
+    grid.set(4, 5, Element::Water);
+    grid
+}
+
+fn step_fire(grid: &mut GameGrid, rules: &SimulationRules, interactions: Option<&ElementInteractionMatrix>) {
+    let i = grid.xy_to_index(4, 4);
+    execute_element_action(grid, 4, 4, i, rules, &mut SimContext { interactions, ..SimContext::default() });
+}
+
+#[test]
+fn water_puts_out_fire_with_the_rules_chance() {
+    let always = SimulationRules { fire_extinguish_chance: 1.0, ..SimulationRules::default() };
+    let mut grid = fire_over_water();
+    step_fire(&mut grid, &always, None);
+    assert_eq!(grid.get(4, 4), Element::Background);
+    assert_eq!(grid.get(4, 5), Element::Steam);
+
+    let never = SimulationRules { fire_extinguish_chance: 0.0, ..SimulationRules::default() };
+    let mut grid = fire_over_water();
+    step_fire(&mut grid, &never, None);
+    assert_eq!(grid.get(4, 5), Element::Water);
+
+    // The match no longer handles it, so without the matrix the water is left alone
+    let mut grid = fire_over_water();
+    step_fire(&mut grid, &always, Some(&ElementInteractionMatrix::empty()));
+    assert_eq!(grid.get(4, 5), Element::Water);
+}
+
+#[test]
+fn salt_kills_plant_through_the_matrix() {
+    let rules = SimulationRules { plant_salt_death_chance: 1.0, plant_growth_chance: 0.0, ..SimulationRules::default() };
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(4, 4, Element::Plant);
+    grid.set(5, 4, Element::Salt);
+
+    let i = grid.xy_to_index(4, 4);
+    execute_element_action(&mut grid, 4, 4, i, &rules, &mut SimContext::default());
+
+    assert_eq!(grid.get(4, 4), Element::Background);
+    assert_eq!(grid.get(5, 4), Element::Salt);
+}
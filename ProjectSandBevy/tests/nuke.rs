@@ -0,0 +1,41 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{execute_element_action, nuke_radius, GameGrid, SimContext, SimulationRules, NUKE_SHOCKWAVE_FORCE};
+
+#[test]
+fn fire_detonates_a_nuke_clearing_everything_but_wall() {
+    let mut grid = GameGrid::new(80, 60);
+    // Radius is a quarter of the shorter side
+    assert_eq!(nuke_radius(&grid), 15);
+    for x in 0..80 {
+        grid.set(x, 40, Element::Sand);
+        grid.set(x, 41, Element::Wall);
+    }
+    grid.set(40, 30, Element::Nuke);
+    grid.set(41, 30, Element::Fire);
+
+    let i = grid.xy_to_index(40, 30);
+    execute_element_action(&mut grid, 40, 30, i, &SimulationRules::default(), &mut SimContext::default());
+
+    assert_eq!(grid.get(40, 30), Element::Background);
+    assert_eq!(grid.get(41, 30), Element::Background);
+    // Inside the radius only Wall is left
+    assert_eq!(grid.get(40, 40), Element::Background);
+    assert_eq!(grid.get(40, 41), Element::Wall);
+    // Outside the radius nothing changed
+    assert_eq!(grid.get(10, 40), Element::Sand);
+    assert!(grid.pending_shockwaves.iter().any(|shockwave| shockwave.force >= NUKE_SHOCKWAVE_FORCE));
+}
+
+#[test]
+fn nuke_without_fire_stays_put() {
+    let mut grid = GameGrid::new(40, 40);
+    grid.set(20, 20, Element::Nuke);
+    grid.set(21, 20, Element::Sand);
+
+    let i = grid.xy_to_index(20, 20);
+    execute_element_action(&mut grid, 20, 20, i, &SimulationRules::default(), &mut SimContext::default());
+
+    assert_eq!(grid.get(20, 20), Element::Nuke);
+    assert_eq!(grid.get(21, 20), Element::Sand);
+    assert!(grid.pending_shockwaves.is_empty());
+}
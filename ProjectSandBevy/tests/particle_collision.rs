@@ -0,0 +1,75 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::particles::{particle_action, Particle, ParticleType};
+use ProjectSandBevy::simulation::GameGrid;
+
+fn moving_particle(particle_type: ParticleType, x: f32, y: f32, x_velocity: f32, y_velocity: f32) -> Particle {
+    let mut particle = Particle::new();
+    particle.particle_type = particle_type;
+    particle.active = true;
+    particle.x = x;
+    particle.y = y;
+    particle.init_x = x;
+    particle.init_y = y;
+    particle.x_velocity = x_velocity;
+    particle.y_velocity = y_velocity;
+    particle.size = 8.0;
+    particle
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "expected {expected}, got {actual}");
+}
+
+#[test]
+fn nitro_bounces_off_a_side_wall() {
+    let mut grid = GameGrid::new(16, 16);
+    for y in 0..16 {
+        grid.set(8, y, Element::Wall);
+    }
+    let mut particle = moving_particle(ParticleType::Nitro, 6.0, 6.0, 2.0, 1.0);
+
+    let removed = particle_action(&mut particle, None, 0, &mut grid);
+
+    assert!(!removed);
+    // Horizontal velocity flips, vertical is kept, both scaled by the restitution
+    assert_close(particle.x_velocity, -1.4);
+    assert_close(particle.y_velocity, 0.7);
+    assert_close(particle.x, 4.6);
+    assert_eq!(grid.get(8, 6), Element::Wall);
+}
+
+#[test]
+fn nitro_bounces_off_a_floor() {
+    let mut grid = GameGrid::new(16, 16);
+    for x in 0..16 {
+        grid.set(x, 10, Element::Wall);
+    }
+    let mut particle = moving_particle(ParticleType::Nitro, 5.0, 8.0, 1.0, 2.0);
+
+    particle_action(&mut particle, None, 0, &mut grid);
+
+    assert_close(particle.x_velocity, 0.7);
+    assert_close(particle.y_velocity, -1.4);
+    assert!(particle.y < 8.0);
+}
+
+#[test]
+fn nitro_without_walls_moves_freely() {
+    let mut grid = GameGrid::new(16, 16);
+    let mut particle = moving_particle(ParticleType::Nitro, 5.0, 5.0, 2.0, 1.0);
+
+    particle_action(&mut particle, None, 0, &mut grid);
+
+    assert_close(particle.x_velocity, 2.0);
+    assert_close(particle.x, 7.0);
+}
+
+#[test]
+fn lava_stops_at_walls() {
+    let mut grid = GameGrid::new(16, 16);
+    grid.set(7, 5, Element::Wall);
+    let mut particle = moving_particle(ParticleType::Lava, 5.0, 5.0, 2.0, 0.0);
+
+    assert!(particle_action(&mut particle, None, 0, &mut grid));
+    assert_close(particle.x, 5.0);
+}
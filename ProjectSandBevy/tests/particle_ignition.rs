@@ -0,0 +1,59 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::particles::{particle_action, Particle, ParticleType};
+use ProjectSandBevy::simulation::GameGrid;
+
+/// A stationary fire particle of the given type sitting on (x, y)
+fn fire_particle(particle_type: ParticleType, x: f32, y: f32) -> Particle {
+    let mut particle = Particle::new();
+    particle.particle_type = particle_type;
+    particle.active = true;
+    particle.x = x;
+    particle.y = y;
+    particle.size = 8.0;
+    particle.lifetime = Some(10);
+    particle
+}
+
+#[test]
+fn nitro_particle_ignites_methane_and_fuse() {
+    let mut grid = GameGrid::new(16, 16);
+    grid.set(4, 4, Element::Methane);
+    grid.set(9, 9, Element::Fuse);
+
+    particle_action(&mut fire_particle(ParticleType::Nitro, 4.2, 3.8), None, 0, &mut grid);
+    particle_action(&mut fire_particle(ParticleType::Nitro, 9.0, 9.0), None, 0, &mut grid);
+
+    assert_eq!(grid.get(4, 4), Element::Fire);
+    assert_eq!(grid.get(9, 9), Element::Fire);
+}
+
+#[test]
+fn napalm_particle_explodes_gunpowder() {
+    let mut grid = GameGrid::new(16, 16);
+    for y in 4..=8 {
+        for x in 4..=8 {
+            grid.set(x, y, Element::Gunpowder);
+        }
+    }
+
+    particle_action(&mut fire_particle(ParticleType::Napalm, 6.0, 6.0), None, 0, &mut grid);
+
+    for y in 5..=7 {
+        for x in 5..=7 {
+            assert_eq!(grid.get(x, y), Element::Fire);
+        }
+    }
+    assert_eq!(grid.get(4, 4), Element::Gunpowder);
+    assert_eq!(grid.pending_shockwaves.len(), 1);
+}
+
+#[test]
+fn particles_off_the_grid_leave_it_alone() {
+    let mut grid = GameGrid::new(16, 16);
+    grid.set(0, 0, Element::Methane);
+
+    particle_action(&mut fire_particle(ParticleType::Nitro, -3.0, -3.0), None, 0, &mut grid);
+    particle_action(&mut fire_particle(ParticleType::Nitro, 40.0, 2.0), None, 0, &mut grid);
+
+    assert_eq!(grid.get(0, 0), Element::Methane);
+}
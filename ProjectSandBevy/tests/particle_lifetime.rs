@@ -0,0 +1,43 @@
+use ProjectSandBevy::particles::actions::tick_lifetime;
+use ProjectSandBevy::particles::{particle_action, Particle, ParticleType};
+use ProjectSandBevy::simulation::GameGrid;
+
+#[test]
+fn lifetime_counts_down_and_fades_over_the_last_quarter() {
+    let mut particle = Particle::new();
+    particle.lifetime = Some(8);
+
+    let mut alphas = Vec::new();
+    for tick in 1..=8 {
+        particle.action_iterations += 1;
+        let removed = tick_lifetime(&mut particle);
+        assert_eq!(removed, tick == 8, "tick {tick}");
+        alphas.push(particle.alpha);
+    }
+
+    assert_eq!(&alphas[..6], &[1.0; 6]);
+    assert!((alphas[6] - 0.5).abs() < 1e-6);
+    assert_eq!(alphas[7], 0.0);
+}
+
+#[test]
+fn particles_without_a_lifetime_never_expire() {
+    let mut particle = Particle::new();
+    for _ in 0..1000 {
+        particle.action_iterations += 1;
+        assert!(!tick_lifetime(&mut particle));
+    }
+    assert_eq!(particle.alpha, 1.0);
+}
+
+#[test]
+fn methane_particles_last_three_iterations() {
+    let mut grid = GameGrid::new(16, 16);
+    let mut particle = Particle::new();
+    particle.particle_type = ParticleType::Methane;
+    particle.active = true;
+    particle.lifetime = Some(3);
+
+    let removed: Vec<bool> = (0..3).map(|_| particle_action(&mut particle, None, 0, &mut grid)).collect();
+    assert_eq!(removed, [false, false, true]);
+}
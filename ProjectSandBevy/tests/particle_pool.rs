@@ -0,0 +1,70 @@
+use ProjectSandBevy::particles::{ParticleList, ParticleType, MAX_NUM_PARTICLES};
+use std::hint::black_box;
+use std::time::Instant;
+
+#[test]
+fn deactivation_keeps_flags_and_count_in_step() {
+    let mut particle_list = ParticleList::default();
+    let indices: Vec<usize> = (0..10)
+        .map(|n| particle_list.add_active_particle(ParticleType::Nitro, n as f32, 0.0, 0).unwrap())
+        .collect();
+    assert_eq!(particle_list.active_count(), 10);
+
+    // Remove from the middle, and twice, which must be a no-op the second time
+    particle_list.make_particle_inactive(indices[4]);
+    particle_list.make_particle_inactive(indices[4]);
+    particle_list.make_particle_inactive(indices[0]);
+    assert_eq!(particle_list.active_count(), 8);
+    assert!(!particle_list.is_active[indices[4]]);
+
+    let mut expected: Vec<usize> = indices.iter().copied().filter(|&i| i != indices[4] && i != indices[0]).collect();
+    expected.sort_unstable();
+    assert_eq!(particle_list.active_particles().collect::<Vec<_>>(), expected);
+
+    particle_list.clear_all();
+    assert_eq!(particle_list.active_count(), 0);
+    assert_eq!(particle_list.active_particles().count(), 0);
+}
+
+#[test]
+fn full_pool_drains_and_refills() {
+    let mut particle_list = ParticleList::default();
+    while particle_list.add_active_particle(ParticleType::Methane, 0.0, 0.0, 0).is_some() {}
+    assert_eq!(particle_list.active_count(), MAX_NUM_PARTICLES);
+
+    for i in 0..MAX_NUM_PARTICLES {
+        particle_list.make_particle_inactive(i);
+    }
+    assert_eq!(particle_list.active_count(), 0);
+    assert_eq!(particle_list.particle_count(ParticleType::Methane), 0);
+    assert!(particle_list.add_active_particle(ParticleType::Methane, 0.0, 0.0, 0).is_some());
+}
+
+/// Deactivating a full pool in the worst order for a list search (oldest first)
+/// should cost about the same per particle as deactivating a handful
+/// Run with `cargo test --release --test particle_pool -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_deactivation_is_constant_time() {
+    const ROUNDS: u32 = 200;
+    let time_draining = |count: usize| {
+        let mut particle_list = ParticleList::default();
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            let indices: Vec<usize> = (0..count)
+                .filter_map(|_| particle_list.add_active_particle(ParticleType::Nitro, 0.0, 0.0, 0))
+                .collect();
+            for &i in &indices {
+                particle_list.make_particle_inactive(i);
+            }
+            black_box(&mut particle_list);
+        }
+        start.elapsed().as_secs_f64() / (f64::from(ROUNDS) * count as f64)
+    };
+
+    let small = time_draining(16);
+    let full = time_draining(MAX_NUM_PARTICLES);
+    println!("per particle: {:.1} ns with 16 active, {:.1} ns with {MAX_NUM_PARTICLES} active", small * 1e9, full * 1e9);
+    // A linear search would make the full pool ~100x slower per particle
+    assert!(full < small * 10.0);
+}
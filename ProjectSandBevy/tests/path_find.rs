@@ -0,0 +1,44 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{execute_element_action, GameGrid, SimContext, SimulationRules};
+
+#[test]
+fn path_goes_around_a_wall() {
+    let mut grid = GameGrid::new(10, 10);
+    // Wall across x = 5 with a gap at the bottom
+    for y in 0..9 {
+        grid.set(5, y, Element::Wall);
+    }
+    let path = grid.path_find((2, 2), (8, 2), &[Element::Background]).unwrap();
+    assert_eq!(path.first(), Some(&(2, 2)));
+    assert_eq!(path.last(), Some(&(8, 2)));
+    // The start, then 3 right and 7 down to the gap at (5, 9), then 3 right and 7 up to the goal
+    assert_eq!(path.len(), 1 + 10 + 10);
+    assert!(path.contains(&(5, 9)));
+    for pair in path.windows(2) {
+        assert_eq!(pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1), 1);
+        assert_eq!(grid.get(pair[1].0, pair[1].1), Element::Background);
+    }
+
+    // Closing the gap leaves no route
+    grid.set(5, 9, Element::Wall);
+    assert!(grid.path_find((2, 2), (8, 2), &[Element::Background]).is_none());
+}
+
+#[test]
+fn creature_walks_to_the_well() {
+    let mut grid = GameGrid::new(12, 6);
+    grid.set(1, 3, Element::Creature);
+    grid.set(10, 3, Element::Well);
+
+    for _ in 0..20 {
+        let i = grid.elements.iter().position(|&element| element == Element::Creature).unwrap();
+        let (x, y) = grid.index_to_xy(i);
+        execute_element_action(&mut grid, x, y, i, &SimulationRules::default(), &mut SimContext::default());
+    }
+
+    // Eight steps to the cell beside the Well, then it waits there
+    assert_eq!(grid.get(9, 3), Element::Creature);
+    assert_eq!(grid.get(10, 3), Element::Well);
+    assert_eq!(grid.elements.iter().filter(|&&element| element == Element::Creature).count(), 1);
+    assert_eq!(grid.creature_paths.len(), 1);
+}
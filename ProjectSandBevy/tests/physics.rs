@@ -0,0 +1,90 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{above_adjacent, below_adjacent, GameGrid};
+
+const SIZE: u32 = 5;
+
+/// Empty grid with a single element at (x, y)
+fn grid_with(x: u32, y: u32, element: Element) -> GameGrid {
+    let mut grid = GameGrid::new(SIZE, SIZE);
+    grid.set(x, y, element);
+    grid
+}
+
+fn below(grid: &GameGrid, x: u32, y: u32) -> Option<usize> {
+    below_adjacent(grid, x, y, grid.xy_to_index(x, y), Element::Sand, false)
+}
+
+fn above(grid: &GameGrid, x: u32, y: u32) -> Option<usize> {
+    above_adjacent(grid, x, y, grid.xy_to_index(x, y), Element::Sand, false)
+}
+
+#[test]
+fn left_edge_diagonals_do_not_wrap() {
+    let max = SIZE - 1;
+    // From (0, 2), i - 1 offsets land on the right edge of another row
+    assert_eq!(below(&grid_with(max, 2, Element::Sand), 0, 2), None);
+    assert_eq!(above(&grid_with(max, 0, Element::Sand), 0, 2), None);
+
+    // Real diagonals are still found
+    let grid = grid_with(1, 3, Element::Sand);
+    assert_eq!(below(&grid, 0, 2), Some(grid.xy_to_index(1, 3)));
+    let grid = grid_with(1, 1, Element::Sand);
+    assert_eq!(above(&grid, 0, 2), Some(grid.xy_to_index(1, 1)));
+}
+
+#[test]
+fn right_edge_diagonals_do_not_wrap() {
+    let max = SIZE - 1;
+    // From (max, 2), i + 1 offsets land on the left edge of another row
+    assert_eq!(below(&grid_with(0, 4, Element::Sand), max, 2), None);
+    assert_eq!(above(&grid_with(0, 2, Element::Sand), max, 2), None);
+
+    let grid = grid_with(max - 1, 3, Element::Sand);
+    assert_eq!(below(&grid, max, 2), Some(grid.xy_to_index(max - 1, 3)));
+    let grid = grid_with(max - 1, 1, Element::Sand);
+    assert_eq!(above(&grid, max, 2), Some(grid.xy_to_index(max - 1, 1)));
+}
+
+#[test]
+fn top_row_has_nothing_above() {
+    let max = SIZE - 1;
+    // The last cells of the grid are what an unchecked i - width would wrap to
+    for x in 0..SIZE {
+        let mut grid = GameGrid::new(SIZE, SIZE);
+        for fill_x in 0..SIZE {
+            grid.set(fill_x, max, Element::Sand);
+        }
+        assert_eq!(above(&grid, x, 0), None, "x = {x}");
+    }
+    // Including the corner where above_idx would be 0
+    assert_eq!(above(&grid_with(0, 0, Element::Sand), 1, 1), Some(0));
+    assert_eq!(above(&GameGrid::new(SIZE, SIZE), 0, 0), None);
+}
+
+#[test]
+fn bottom_row_has_nothing_below() {
+    let max = SIZE - 1;
+    for x in 0..SIZE {
+        let mut grid = GameGrid::new(SIZE, SIZE);
+        for fill_x in 0..SIZE {
+            grid.set(fill_x, 0, Element::Sand);
+        }
+        assert_eq!(below(&grid, x, max), None, "x = {x}");
+    }
+    // Including the corner where below-right is the last cell
+    let grid = grid_with(max, max, Element::Sand);
+    assert_eq!(below(&grid, max - 1, max - 1), Some(grid.xy_to_index(max, max)));
+}
+
+#[test]
+fn world_wrap_diagonals_cross_to_the_other_side() {
+    let max = SIZE - 1;
+    let grid = grid_with(max, 3, Element::Sand);
+    let i = grid.xy_to_index(0, 2);
+
+    assert_eq!(below_adjacent(&grid, 0, 2, i, Element::Sand, true), Some(grid.xy_to_index(max, 3)));
+
+    let grid = grid_with(1, max, Element::Sand);
+    let i = grid.xy_to_index(0, 0);
+    assert_eq!(above_adjacent(&grid, 0, 0, i, Element::Sand, true), Some(grid.xy_to_index(1, max)));
+}
@@ -0,0 +1,55 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::save::png::{load_from_png, save_as_png};
+use ProjectSandBevy::simulation::GameGrid;
+use ProjectSandBevy::systems::RainbowSandPlacementTimes;
+
+/// Every element that has an index (unknown indices decode to Background)
+fn all_elements() -> Vec<Element> {
+    let mut elements: Vec<Element> = (0..64).map(Element::from_index).collect();
+    elements.sort_by_key(|element| element.index());
+    elements.dedup();
+    elements
+}
+
+#[test]
+fn png_round_trips_every_element() {
+    let elements = all_elements();
+    let mut grid = GameGrid::new(elements.len() as u32, 2);
+    for (x, &element) in elements.iter().enumerate() {
+        grid.set(x as u32, 0, element);
+        grid.set(x as u32, 1, element);
+    }
+    let path = std::env::temp_dir().join("sand_png_round_trip.png");
+
+    save_as_png(&grid, &RainbowSandPlacementTimes::default(), &path).unwrap();
+    let (loaded, _) = load_from_png(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(loaded.width, grid.width);
+    assert_eq!(loaded.height, grid.height);
+    assert_eq!(loaded.elements, grid.elements);
+}
+
+#[test]
+fn png_keeps_rainbow_sand_hue() {
+    let mut grid = GameGrid::new(4, 1);
+    let mut rainbow_times = RainbowSandPlacementTimes::default();
+    for (x, time) in [0u32, 64, 128, 200].into_iter().enumerate() {
+        grid.set(x as u32, 0, Element::RainbowSand);
+        rainbow_times.0.insert(x, time);
+    }
+    let path = std::env::temp_dir().join("sand_png_rainbow.png");
+
+    save_as_png(&grid, &rainbow_times, &path).unwrap();
+    let (loaded, loaded_times) = load_from_png(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(loaded.elements, grid.elements);
+    // The hue is stored in the color, so it comes back within a couple of steps
+    for (i, &time) in &rainbow_times.0 {
+        let loaded_time = loaded_times[i];
+        let diff = loaded_time.abs_diff(time);
+        // Hue is circular, 255 is next to 0
+        assert!(diff.min(256 - diff) <= 3, "cell {i}: saved {time}, loaded {loaded_time}");
+    }
+}
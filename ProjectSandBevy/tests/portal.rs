@@ -0,0 +1,77 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{do_gravity, do_rise, place_portal, GameGrid, PendingPortal, PortalRegistry};
+
+/// Grid with linked portals at (5, 10) and (25, 3)
+fn linked_portals() -> GameGrid {
+    let mut grid = GameGrid::new(32, 32);
+    let mut registry = PortalRegistry::default();
+    let mut pending = PendingPortal::default();
+    for (x, y) in [(5, 10), (25, 3)] {
+        let i = grid.xy_to_index(x, y);
+        assert!(place_portal(&mut grid, &mut registry, &mut pending, i));
+    }
+    grid.portal_links = registry.0;
+    grid
+}
+
+#[test]
+fn falling_sand_comes_out_below_the_paired_portal() {
+    let mut grid = linked_portals();
+    grid.set(5, 9, Element::Sand);
+
+    let i = grid.xy_to_index(5, 9);
+    assert!(do_gravity(&mut grid, 5, 9, i, true, 1.0, false, &mut None, false));
+
+    assert_eq!(grid.get(5, 9), Element::Background);
+    assert_eq!(grid.get(25, 4), Element::Sand);
+    // The portals themselves stay put
+    assert_eq!(grid.get(5, 10), Element::Portal);
+    assert_eq!(grid.get(25, 3), Element::Portal);
+}
+
+#[test]
+fn rising_steam_comes_out_above_the_paired_portal() {
+    let mut grid = linked_portals();
+    grid.set(25, 4, Element::Steam);
+
+    let i = grid.xy_to_index(25, 4);
+    assert!(do_rise(&mut grid, 25, 4, i, 1.0, 0.0, false, 0.0, false));
+
+    assert_eq!(grid.get(25, 4), Element::Background);
+    assert_eq!(grid.get(5, 9), Element::Steam);
+}
+
+#[test]
+fn blocked_exit_leaves_the_element_in_place() {
+    let mut grid = linked_portals();
+    grid.set(5, 9, Element::Sand);
+    grid.set(25, 4, Element::Wall);
+
+    let i = grid.xy_to_index(5, 9);
+    do_gravity(&mut grid, 5, 9, i, false, 1.0, false, &mut None, false);
+
+    assert_eq!(grid.get(5, 9), Element::Sand);
+    assert_eq!(grid.get(25, 4), Element::Wall);
+}
+
+#[test]
+fn unlinked_portal_is_a_solid_block() {
+    let mut grid = GameGrid::new(32, 32);
+    grid.set(5, 10, Element::Portal);
+    grid.set(5, 9, Element::Sand);
+
+    let i = grid.xy_to_index(5, 9);
+    assert!(!do_gravity(&mut grid, 5, 9, i, false, 1.0, false, &mut None, false));
+    assert_eq!(grid.get(5, 9), Element::Sand);
+}
+
+#[test]
+fn clearing_the_grid_forgets_portal_links_and_gravity_zones() {
+    let mut grid = linked_portals();
+    grid.anti_gravity_zones.push((10, 10, 5));
+
+    grid.clear();
+
+    assert!(grid.portal_links.is_empty());
+    assert!(!grid.gravity_inverted(10, 10));
+}
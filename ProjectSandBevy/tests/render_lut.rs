@@ -0,0 +1,51 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::rendering::{color_to_bytes, COLOR_LUT};
+use std::hint::black_box;
+use std::time::Instant;
+
+#[test]
+fn color_lut_matches_float_conversion() {
+    for index in 0..=u8::MAX {
+        let element = Element::from_index(index);
+        if element.index() != index {
+            assert_eq!(COLOR_LUT[index as usize], [0; 4], "index {index} has no element");
+            continue;
+        }
+        assert_eq!(COLOR_LUT[index as usize], color_to_bytes(element.to_encoded_color()), "{element:?}");
+    }
+}
+
+/// Compare the lookup table against converting every cell's color from floats
+/// Run with `cargo test --release --test render_lut -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_color_lut_against_float_path() {
+    const CELLS: usize = 1 << 20;
+    const ROUNDS: u32 = 20;
+    let elements: Vec<Element> = (0..CELLS).map(|i| Element::from_index((i % 59) as u8)).collect();
+    let mut pixels = vec![0u8; CELLS * 4];
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for (pixel, element) in pixels.chunks_exact_mut(4).zip(&elements) {
+            pixel.copy_from_slice(&color_to_bytes(element.to_encoded_color()));
+        }
+        black_box(&mut pixels);
+    }
+    let float_time = start.elapsed();
+
+    let lut = &*COLOR_LUT;
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for (pixel, element) in pixels.chunks_exact_mut(4).zip(&elements) {
+            pixel.copy_from_slice(&lut[element.index() as usize]);
+        }
+        black_box(&mut pixels);
+    }
+    let lut_time = start.elapsed();
+
+    let throughput = |seconds: f64| f64::from(ROUNDS) * CELLS as f64 / seconds / 1e6;
+    println!("float path: {float_time:?} ({:.0} Mcells/s)", throughput(float_time.as_secs_f64()));
+    println!("lookup table: {lut_time:?} ({:.0} Mcells/s)", throughput(lut_time.as_secs_f64()));
+    assert!(lut_time <= float_time);
+}
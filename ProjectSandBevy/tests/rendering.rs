@@ -0,0 +1,65 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::rendering::{color_to_bytes, grid_to_image, RenderRules};
+use ProjectSandBevy::simulation::GameGrid;
+use ProjectSandBevy::systems::RainbowSandPlacementTimes;
+use std::collections::HashSet;
+
+fn pixel(image: &[u8], grid: &GameGrid, x: u32, y: u32) -> [u8; 4] {
+    let offset = grid.xy_to_index(x, y) * 4;
+    [image[offset], image[offset + 1], image[offset + 2], image[offset + 3]]
+}
+
+#[test]
+fn test_sand_pixel_color() {
+    let mut grid = GameGrid::new(4, 4);
+    grid.set(1, 2, Element::Sand);
+    let image = grid_to_image(&grid, &RainbowSandPlacementTimes::default(), 0, &RenderRules::default());
+
+    assert_eq!(image.len(), 4 * 4 * 4);
+    assert_eq!(pixel(&image, &grid, 1, 2), color_to_bytes(Element::Sand.to_encoded_color()));
+    assert_eq!(pixel(&image, &grid, 1, 2)[3], 255);
+}
+
+#[test]
+fn test_background_is_black() {
+    let grid = GameGrid::new(3, 3);
+    let image = grid_to_image(&grid, &RainbowSandPlacementTimes::default(), 0, &RenderRules::default());
+    for chunk in image.chunks_exact(4) {
+        assert_eq!(chunk[..3], [0, 0, 0]);
+    }
+}
+
+#[test]
+fn test_rainbow_sand_varies_by_shift() {
+    let mut grid = GameGrid::new(2, 1);
+    grid.set(0, 0, Element::RainbowSand);
+    grid.set(1, 0, Element::RainbowSand);
+    let mut rainbow_times = RainbowSandPlacementTimes::default();
+    rainbow_times.0.insert(0, 0);
+    rainbow_times.0.insert(1, 128);
+
+    let image = grid_to_image(&grid, &rainbow_times, 0, &RenderRules::default());
+    assert_ne!(pixel(&image, &grid, 0, 0), pixel(&image, &grid, 1, 0));
+
+    // The color follows the placement time, not the frame
+    let later = grid_to_image(&grid, &rainbow_times, 500, &RenderRules::default());
+    assert_eq!(image, later);
+}
+
+#[test]
+fn test_all_elements_have_distinct_colors() {
+    let elements: Vec<Element> = (0..=u8::MAX)
+        .map(Element::from_index)
+        .enumerate()
+        .filter(|&(index, element)| usize::from(element.index()) == index)
+        .map(|(_, element)| element)
+        .collect();
+    let mut grid = GameGrid::new(elements.len() as u32, 1);
+    for (x, &element) in elements.iter().enumerate() {
+        grid.set(x as u32, 0, element);
+    }
+
+    let image = grid_to_image(&grid, &RainbowSandPlacementTimes::default(), 0, &RenderRules::default());
+    let colors: HashSet<[u8; 4]> = (0..grid.width).map(|x| pixel(&image, &grid, x, 0)).collect();
+    assert_eq!(colors.len(), elements.len());
+}
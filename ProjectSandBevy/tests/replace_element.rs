@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{rainbow_position_hash, GameGrid};
+
+#[test]
+fn replace_counts_and_swaps_every_cell() {
+    let mut grid = GameGrid::new(8, 8);
+    for x in 0..8 {
+        grid.set(x, 7, Element::Sand);
+    }
+    grid.set(3, 3, Element::Water);
+    let mut times = HashMap::new();
+
+    assert_eq!(grid.replace_element(Element::Sand, Element::Water, &mut times), 8);
+    assert_eq!(grid.elements.iter().filter(|&&element| element == Element::Water).count(), 9);
+    assert!(!grid.elements.contains(&Element::Sand));
+
+    // Nothing left to replace, and replacing an element with itself is a no-op
+    assert_eq!(grid.replace_element(Element::Sand, Element::Water, &mut times), 0);
+    assert_eq!(grid.replace_element(Element::Water, Element::Water, &mut times), 0);
+}
+
+#[test]
+fn replace_keeps_rainbow_times_in_sync() {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(1, 1, Element::Sand);
+    grid.set(2, 2, Element::RainbowSand);
+    let mut times = HashMap::from([(grid.xy_to_index(2, 2), 42)]);
+
+    grid.replace_element(Element::Sand, Element::RainbowSand, &mut times);
+    assert_eq!(times.get(&grid.xy_to_index(1, 1)), Some(&rainbow_position_hash(1, 1)));
+    assert_eq!(times.get(&grid.xy_to_index(2, 2)), Some(&42));
+
+    assert_eq!(grid.replace_element(Element::RainbowSand, Element::Salt, &mut times), 2);
+    assert!(times.is_empty());
+}
@@ -0,0 +1,64 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::save::{rle_decode, rle_encode};
+use ProjectSandBevy::simulation::GameGrid;
+
+/// Typical mixed scene: mostly empty, a sand pile, a pool of water, a wall floor and some scattered cells
+fn build_scene() -> GameGrid {
+    let mut grid = GameGrid::new(640, 360);
+    for x in 0..640 {
+        grid.set(x, 359, Element::Wall);
+        for y in 300..359 {
+            grid.set(x, y, if x < 320 { Element::Water } else { Element::Sand });
+        }
+    }
+    for y in 200..300 {
+        for x in 100..200 {
+            grid.set(x, y, Element::Rock);
+        }
+    }
+    for n in 0..500 {
+        grid.set((n * 37) % 640, (n * 13) % 200, Element::Steam);
+    }
+    grid
+}
+
+#[test]
+fn rle_round_trips() {
+    let elements = vec![
+        Element::Background,
+        Element::Background,
+        Element::Sand,
+        Element::Water,
+        Element::Water,
+        Element::Water,
+        Element::Background,
+    ];
+
+    let pairs = rle_encode(&elements);
+
+    assert_eq!(pairs.len(), 4);
+    assert_eq!(rle_decode(&pairs), elements);
+}
+
+#[test]
+fn rle_splits_runs_longer_than_u16() {
+    let elements = vec![Element::Sand; usize::from(u16::MAX) * 2 + 5];
+
+    let pairs = rle_encode(&elements);
+
+    assert_eq!(pairs, vec![(Element::Sand.index(), u16::MAX), (Element::Sand.index(), u16::MAX), (Element::Sand.index(), 5)]);
+    assert_eq!(rle_decode(&pairs), elements);
+}
+
+#[test]
+fn rle_shrinks_typical_scene() {
+    let grid = build_scene();
+
+    let raw_size = bincode::serialize(&grid.elements).unwrap().len();
+    let pairs = rle_encode(&grid.elements);
+    let rle_size = bincode::serialize(&pairs).unwrap().len();
+
+    assert_eq!(rle_decode(&pairs), grid.elements);
+    // Large uniform regions should cut the element data by at least half
+    assert!(rle_size * 2 <= raw_size, "RLE {rle_size} bytes vs raw {raw_size} bytes");
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{GameGrid, Rotation};
+
+/// 3 wide, 2 tall block at (2, 2):
+/// Sand  Water Oil
+/// Salt  Wall  RainbowSand
+fn build_block() -> (GameGrid, HashMap<usize, u32>) {
+    let mut grid = GameGrid::new(10, 10);
+    let rows = [[Element::Sand, Element::Water, Element::Oil], [Element::Salt, Element::Wall, Element::RainbowSand]];
+    for (ry, row) in rows.iter().enumerate() {
+        for (rx, &element) in row.iter().enumerate() {
+            grid.set(2 + rx as u32, 2 + ry as u32, element);
+        }
+    }
+    let times = HashMap::from([(grid.xy_to_index(4, 3), 77)]);
+    (grid, times)
+}
+
+fn region(grid: &GameGrid, x: u32, y: u32, w: u32, h: u32) -> Vec<Element> {
+    grid.copy_region(x, y, w, h)
+}
+
+#[test]
+fn rotate_90_turns_clockwise() {
+    let (mut grid, mut times) = build_block();
+    assert!(grid.rotate_region(2, 2, 3, 2, Rotation::R90, &mut times));
+    assert_eq!(
+        region(&grid, 2, 2, 2, 3),
+        [Element::Salt, Element::Sand, Element::Wall, Element::Water, Element::RainbowSand, Element::Oil]
+    );
+    // The old region's right column is no longer covered
+    assert_eq!(grid.get(4, 2), Element::Background);
+    assert_eq!(times, HashMap::from([(grid.xy_to_index(2, 4), 77)]));
+}
+
+#[test]
+fn rotate_180_flips_both_axes() {
+    let (mut grid, mut times) = build_block();
+    assert!(grid.rotate_region(2, 2, 3, 2, Rotation::R180, &mut times));
+    assert_eq!(
+        region(&grid, 2, 2, 3, 2),
+        [Element::RainbowSand, Element::Wall, Element::Salt, Element::Oil, Element::Water, Element::Sand]
+    );
+    assert_eq!(times, HashMap::from([(grid.xy_to_index(2, 2), 77)]));
+}
+
+#[test]
+fn rotate_270_turns_counterclockwise() {
+    let (mut grid, mut times) = build_block();
+    assert!(grid.rotate_region(2, 2, 3, 2, Rotation::R270, &mut times));
+    assert_eq!(
+        region(&grid, 2, 2, 2, 3),
+        [Element::Oil, Element::RainbowSand, Element::Water, Element::Wall, Element::Sand, Element::Salt]
+    );
+    assert_eq!(times, HashMap::from([(grid.xy_to_index(3, 2), 77)]));
+
+    // Four quarter turns come back to the start
+    let (original, _) = build_block();
+    for _ in 0..3 {
+        let (w, h) = if grid.get(4, 2) == Element::Background { (2, 3) } else { (3, 2) };
+        assert!(grid.rotate_region(2, 2, w, h, Rotation::R270, &mut times));
+    }
+    assert_eq!(grid.elements, original.elements);
+}
+
+#[test]
+fn rotation_outside_the_grid_is_refused() {
+    let (mut grid, mut times) = build_block();
+    let before = grid.elements.clone();
+    // 3x2 at the bottom edge would become 2x3 and hang off the grid
+    assert!(!grid.rotate_region(2, 8, 3, 2, Rotation::R90, &mut times));
+    assert!(!grid.rotate_region(8, 8, 3, 3, Rotation::R180, &mut times));
+    assert_eq!(grid.elements, before);
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{execute_element_action, GameGrid, SimContext, SimulationRules, RULES_CONFIG_PATH};
+
+#[test]
+fn shipped_rules_match_the_defaults() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(RULES_CONFIG_PATH);
+    assert_eq!(SimulationRules::from_toml(&path).unwrap(), SimulationRules::default());
+}
+
+#[test]
+fn missing_fields_keep_their_defaults() {
+    let path = std::env::temp_dir().join(format!("sand_rules_{}.toml", std::process::id()));
+    std::fs::write(&path, "sand_gravity_chance = 0.5\nfire_rise_chance = 0.1\n").unwrap();
+    let rules = SimulationRules::from_toml(&path).unwrap();
+    std::fs::write(&path, "sand_gravity_chance = \"fast\"\n").unwrap();
+    let invalid = SimulationRules::from_toml(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!((rules.sand_gravity_chance - 0.5).abs() < f64::EPSILON);
+    assert!((rules.fire_rise_chance - 0.1).abs() < f64::EPSILON);
+    assert!((rules.water_sink_chance - SimulationRules::default().water_sink_chance).abs() < f64::EPSILON);
+    assert!(invalid.is_err());
+    assert!(SimulationRules::from_toml(Path::new("does/not/exist.toml")).is_err());
+}
+
+#[test]
+fn element_probabilities_come_from_the_rules() {
+    let step_magma = |rules: &SimulationRules| {
+        let mut grid = GameGrid::new(8, 8);
+        grid.set(4, 7, Element::Magma);
+        let i = grid.xy_to_index(4, 7);
+        execute_element_action(&mut grid, 4, 7, i, rules, &mut SimContext::default());
+        grid.get(4, 7)
+    };
+
+    let always = SimulationRules { magma_erupt_chance: 0.0, magma_cool_chance: 1.0, ..SimulationRules::default() };
+    assert_eq!(step_magma(&always), Element::Rock);
+    let never = SimulationRules { magma_erupt_chance: 0.0, magma_cool_chance: 0.0, ..SimulationRules::default() };
+    assert_eq!(step_magma(&never), Element::Magma);
+}
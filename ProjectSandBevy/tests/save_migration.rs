@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::save::{migrate_v1_to_v2, LegacyGameGridV1, WorldSaveFile, SAVE_VERSION};
+use ProjectSandBevy::simulation::GameGrid;
+use ProjectSandBevy::spigots::Spigots;
+
+/// Small grid with a few different elements in it
+fn build_grid() -> GameGrid {
+    let mut grid = GameGrid::new(16, 12);
+    grid.set(0, 0, Element::Sand);
+    grid.set(5, 7, Element::Water);
+    grid.set(15, 11, Element::Wall);
+    grid.set(8, 3, Element::RainbowSand);
+    grid
+}
+
+/// Version 1 save data of the grid
+fn v1_bytes(grid: &GameGrid) -> Vec<u8> {
+    let legacy = LegacyGameGridV1 { elements: grid.elements.clone(), width: grid.width, height: grid.height };
+    bincode::serialize(&legacy).unwrap()
+}
+
+#[test]
+fn v1_save_migrates_to_current_version() {
+    let grid = build_grid();
+    let v1_data = v1_bytes(&grid);
+
+    let save = migrate_v1_to_v2(&v1_data).unwrap();
+
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.header.width, 16);
+    assert_eq!(save.header.height, 12);
+    assert_eq!(save.header.element_count, 4);
+    assert_eq!(save.grid.elements, grid.elements);
+    assert!(save.rainbow_times.is_empty());
+}
+
+#[test]
+fn v1_save_with_the_wrong_element_count_is_rejected() {
+    let legacy = LegacyGameGridV1 { elements: vec![Element::Sand; 10], width: 4, height: 4 };
+    assert!(migrate_v1_to_v2(&bincode::serialize(&legacy).unwrap()).is_err());
+}
+
+#[test]
+fn v1_save_loads_through_version_dispatch() {
+    let grid = build_grid();
+    let v1_data = v1_bytes(&grid);
+
+    let save = WorldSaveFile::from_bytes(&v1_data).unwrap();
+
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.grid.elements, grid.elements);
+    assert_eq!(save.grid.velocities.len(), grid.elements.len());
+    assert_eq!(save.grid.staleness.len(), grid.elements.len());
+}
+
+#[test]
+fn migrated_v1_save_round_trips_as_current_version() {
+    let grid = build_grid();
+    let migrated = migrate_v1_to_v2(&v1_bytes(&grid)).unwrap();
+    let data = migrated.to_bytes().unwrap();
+
+    assert_eq!(data[..4], SAVE_VERSION.to_le_bytes());
+    let save = WorldSaveFile::from_bytes(&data).unwrap();
+    assert_eq!(save.grid.elements, grid.elements);
+    assert_eq!(save.header, migrated.header);
+}
+
+#[test]
+fn current_save_keeps_spigots_and_rainbow_times() {
+    let grid = build_grid();
+    let mut spigots = Spigots::default();
+    spigots.elements[0] = Element::Lava;
+    spigots.sizes[1] = 0;
+    let rainbow_times = HashMap::from([(grid.xy_to_index(8, 3), 42)]);
+
+    let data = WorldSaveFile::new(&grid, &spigots, &rainbow_times).to_bytes().unwrap();
+    let save = WorldSaveFile::from_bytes(&data).unwrap();
+
+    assert_eq!(save.spigots.elements, spigots.elements);
+    assert_eq!(save.spigots.sizes, spigots.sizes);
+    assert_eq!(save.rainbow_times, rainbow_times);
+}
+
+#[test]
+fn custom_spigot_config_survives_save_and_load() {
+    let grid = build_grid();
+    let mut spigots = Spigots::default();
+    // "Spigot 2" in the UI
+    spigots.elements[1] = Element::Oil;
+    spigots.sizes[1] = 3;
+
+    let data = WorldSaveFile::new(&grid, &spigots, &HashMap::new()).to_bytes().unwrap();
+    let save = WorldSaveFile::from_bytes(&data).unwrap();
+
+    assert_eq!(save.spigots.elements[1], Element::Oil);
+    assert_eq!(save.spigots.sizes[1], 3);
+    assert_eq!(save.spigots.elements, spigots.elements);
+    assert_eq!(save.spigots.sizes, spigots.sizes);
+    assert_eq!(save.active_branches_count, 0);
+    assert!(save.particle_list_active.is_empty());
+}
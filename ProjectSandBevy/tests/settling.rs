@@ -0,0 +1,42 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{GameGrid, SimulationRules};
+use ProjectSandBevy::spigots::Spigots;
+use ProjectSandBevy::systems::{simulate_ticks, RainbowSandPlacementTimes};
+
+/// Run `n` seeded ticks with every spigot turned off
+fn run(grid: &mut GameGrid, n: u32) {
+    let spigots = Spigots { sizes: vec![0; Spigots::default().len()], ..Spigots::default() };
+    let mut times = RainbowSandPlacementTimes::default();
+    simulate_ticks(grid, &spigots, n, &SimulationRules::default(), Some(11), &mut times);
+}
+
+/// 16x8 grid with Sand boxed in at (4, 6) by a Wall floor, a Wall on its left and `neighbor` on its right
+fn boxed_in_sand_beside(neighbor: Element) -> GameGrid {
+    let mut grid = GameGrid::new(16, 8);
+    for x in 0..16 {
+        grid.set(x, 7, Element::Wall);
+    }
+    grid.set(3, 6, Element::Wall);
+    grid.set(4, 6, Element::Sand);
+    grid.set(5, 6, neighbor);
+    grid
+}
+
+#[test]
+fn sand_that_cannot_move_settles() {
+    let mut grid = boxed_in_sand_beside(Element::Wall);
+    run(&mut grid, 20);
+    assert!(grid.is_settled(grid.xy_to_index(4, 6)));
+}
+
+#[test]
+fn sand_beside_quicksand_stays_awake_and_converts() {
+    let mut grid = boxed_in_sand_beside(Element::Quicksand);
+    let sand = grid.xy_to_index(4, 6);
+    run(&mut grid, 20);
+    assert!(!grid.is_settled(sand));
+
+    // 0.2% a tick, so it converts long before this runs out
+    run(&mut grid, 5000);
+    assert_eq!(grid.get_index(sand), Element::Quicksand);
+}
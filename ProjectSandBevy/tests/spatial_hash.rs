@@ -0,0 +1,36 @@
+use ProjectSandBevy::particles::{ParticleList, ParticleType};
+
+#[test]
+fn query_radius_matches_a_linear_scan() {
+    let mut particle_list = ParticleList::default();
+    for n in 0..200u32 {
+        // Spread particles over several hash cells, including negative coordinates
+        let x = ((n * 37) % 150) as f32 - 20.0;
+        let y = ((n * 53) % 120) as f32 - 10.0;
+        particle_list.add_active_particle(ParticleType::Methane, x, y, 0);
+    }
+    particle_list.rebuild_spatial_hash();
+
+    for &(x, y, r) in &[(10.0, 10.0, 8.0), (-15.0, 40.0, 20.0), (64.0, 64.0, 0.5), (100.0, 5.0, 33.0)] {
+        let mut expected: Vec<(f32, f32)> = particle_list
+            .active_particles()
+            .filter_map(|idx| particle_list.get_particle(idx))
+            .filter(|p| (p.x - x).powi(2) + (p.y - y).powi(2) <= r * r)
+            .map(|p| (p.x, p.y))
+            .collect();
+        let mut found: Vec<(f32, f32)> = particle_list.query_radius(x, y, r).map(|p| (p.x, p.y)).collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(found, expected, "query at ({x}, {y}) r={r}");
+    }
+}
+
+#[test]
+fn removed_particles_are_not_returned() {
+    let mut particle_list = ParticleList::default();
+    let idx = particle_list.add_active_particle(ParticleType::Methane, 5.0, 5.0, 0).unwrap();
+    assert_eq!(particle_list.query_radius(5.0, 5.0, 1.0).count(), 1);
+
+    particle_list.make_particle_inactive(idx);
+    assert_eq!(particle_list.query_radius(5.0, 5.0, 1.0).count(), 0);
+}
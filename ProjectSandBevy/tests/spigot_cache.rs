@@ -0,0 +1,17 @@
+use ProjectSandBevy::spigots::{SpigotPositionCache, Spigots};
+
+#[test]
+fn cache_recomputes_only_when_stale() {
+    let mut spigots = Spigots::default();
+    let mut cache = SpigotPositionCache::default();
+    assert_eq!(cache.positions(&spigots, 200), spigots.get_spigot_positions(200).as_slice());
+
+    // Edits aren't seen until the cache is marked dirty
+    spigots.sizes[0] = 0;
+    assert_eq!(cache.positions(&spigots, 200).len(), 4);
+    cache.dirty = true;
+    assert_eq!(cache.positions(&spigots, 200), spigots.get_spigot_positions(200).as_slice());
+
+    // A resized grid always lays the spigots out again
+    assert_eq!(cache.positions(&spigots, 500), spigots.get_spigot_positions(500).as_slice());
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::save::WorldSaveFile;
+use ProjectSandBevy::simulation::GameGrid;
+use ProjectSandBevy::spigots::{Spigots, DEFAULT_NUM_SPIGOTS};
+
+#[test]
+fn added_spigots_are_laid_out_after_the_others() {
+    let mut spigots = Spigots::default();
+    spigots.add_spigot(Element::Lava, 3);
+    spigots.add_spigot(Element::Acid, 2);
+
+    let positions = spigots.get_spigot_positions(300);
+
+    assert_eq!(spigots.len(), DEFAULT_NUM_SPIGOTS + 2);
+    assert_eq!(positions.len(), DEFAULT_NUM_SPIGOTS + 2);
+    assert_eq!((positions[DEFAULT_NUM_SPIGOTS].1, positions[DEFAULT_NUM_SPIGOTS].2), (3, DEFAULT_NUM_SPIGOTS as u32));
+    assert!(positions.windows(2).all(|pair| pair[0].0 < pair[1].0));
+}
+
+#[test]
+fn removing_a_spigot_shifts_the_later_ones_down() {
+    let mut spigots = Spigots::default();
+    spigots.custom_x[2] = Some(40);
+
+    spigots.remove_spigot(1);
+
+    assert_eq!(spigots.elements, vec![Element::RainbowSand, Element::Salt, Element::Oil]);
+    assert_eq!(spigots.sizes.len(), 3);
+    assert_eq!(spigots.custom_x, vec![None, Some(40), None]);
+
+    // Out of range is ignored
+    spigots.remove_spigot(10);
+    assert_eq!(spigots.len(), 3);
+}
+
+#[test]
+fn no_spigots_spawn_nothing() {
+    let mut spigots = Spigots::default();
+    while !spigots.is_empty() {
+        spigots.remove_spigot(0);
+    }
+
+    assert!(spigots.get_spigot_positions(200).is_empty());
+}
+
+#[test]
+fn spigot_count_survives_save_and_load() {
+    let grid = GameGrid::new(64, 48);
+    let mut spigots = Spigots::default();
+    spigots.add_spigot(Element::Lava, 4);
+    spigots.remove_spigot(0);
+
+    let data = WorldSaveFile::new(&grid, &spigots, &HashMap::new()).to_bytes().unwrap();
+    let save = WorldSaveFile::from_bytes(&data).unwrap();
+
+    assert_eq!(save.spigots.elements, spigots.elements);
+    assert_eq!(save.spigots.sizes, spigots.sizes);
+    assert_eq!(save.spigots.custom_x, spigots.custom_x);
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use bevy::math::Vec2;
+use ProjectSandBevy::save::WorldSaveFile;
+use ProjectSandBevy::simulation::GameGrid;
+use ProjectSandBevy::spigots::{spigot_drag_x, spigot_indicator_contains, Spigots, SPIGOT_INDICATOR_SIZE};
+
+#[test]
+fn custom_x_overrides_even_spacing() {
+    let mut spigots = Spigots::default();
+    let even = spigots.get_spigot_positions(200);
+    spigots.custom_x[2] = Some(7);
+
+    let positions = spigots.get_spigot_positions(200);
+
+    assert_eq!(positions[2], (7, even[2].1, 2));
+    for i in [0, 1, 3] {
+        assert_eq!(positions[i], even[i]);
+    }
+}
+
+#[test]
+fn custom_x_is_clamped_to_the_width() {
+    let mut spigots = Spigots::default();
+    spigots.custom_x[0] = Some(500);
+
+    let (x, size, _) = spigots.get_spigot_positions(100)[0];
+
+    assert_eq!(x + size, 100);
+}
+
+#[test]
+fn drag_centers_the_spigot_on_the_cursor_inside_the_grid() {
+    assert_eq!(spigot_drag_x(50.0, 4, 100), 48);
+    assert_eq!(spigot_drag_x(-20.0, 4, 100), 0);
+    assert_eq!(spigot_drag_x(120.0, 4, 100), 96);
+}
+
+#[test]
+fn indicator_is_hit_only_near_the_top_of_its_spigot() {
+    // Spigot covering cells 20 to 24, centered on 22.5
+    assert!(spigot_indicator_contains(20, 5, Vec2::new(22.5, 1.0)));
+    assert!(spigot_indicator_contains(20, 5, Vec2::new(22.5 + SPIGOT_INDICATOR_SIZE, 0.0)));
+    assert!(!spigot_indicator_contains(20, 5, Vec2::new(22.5 + SPIGOT_INDICATOR_SIZE + 1.0, 1.0)));
+    assert!(!spigot_indicator_contains(20, 5, Vec2::new(22.5, SPIGOT_INDICATOR_SIZE * 2.0)));
+    assert!(!spigot_indicator_contains(20, 5, Vec2::new(22.5, -1.0)));
+}
+
+#[test]
+fn dragged_positions_survive_save_and_load() {
+    let grid = GameGrid::new(64, 48);
+    let mut spigots = Spigots::default();
+    spigots.custom_x[1] = Some(12);
+
+    let data = WorldSaveFile::new(&grid, &spigots, &HashMap::new()).to_bytes().unwrap();
+    let save = WorldSaveFile::from_bytes(&data).unwrap();
+
+    assert_eq!(save.spigots.custom_x, vec![None, Some(12), None, None]);
+}
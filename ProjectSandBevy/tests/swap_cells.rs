@@ -0,0 +1,49 @@
+use bevy::math::Vec2;
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{GameGrid, SETTLE_THRESHOLD};
+
+#[test]
+fn swapping_carries_all_cell_state() {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(3, 3, Element::Concrete);
+    let (from, to) = (grid.xy_to_index(3, 3), grid.xy_to_index(3, 4));
+    grid.ages[from] = 900;
+    grid.durability[from] = 7;
+    grid.temperatures[from] = 80.0;
+    grid.virus_age[from] = 12;
+    grid.trapped_depth[from] = 3;
+    grid.slime_fire_immunity[from] = 4;
+    grid.velocities[from] = Vec2::new(1.0, 2.0);
+
+    grid.swap_cells(from, to);
+
+    assert_eq!(grid.get_index(to), Element::Concrete);
+    assert_eq!(grid.ages[to], 900);
+    assert_eq!(grid.durability[to], 7);
+    assert_eq!(grid.temperatures[to], 80.0);
+    assert_eq!(grid.virus_age[to], 12);
+    assert_eq!(grid.trapped_depth[to], 3);
+    assert_eq!(grid.slime_fire_immunity[to], 4);
+    assert_eq!(grid.velocities[to], Vec2::new(1.0, 2.0));
+    // The Background that moved the other way keeps its own state too
+    assert_eq!(grid.get_index(from), Element::Background);
+    assert_eq!(grid.ages[from], 0);
+    assert_eq!(grid.velocities[from], Vec2::ZERO);
+}
+
+#[test]
+fn swapping_wakes_neighbors_and_marks_both_cells_dirty() {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(3, 3, Element::Sand);
+    grid.staleness.fill(SETTLE_THRESHOLD);
+    grid.dirty_rect = None;
+    let writes = grid.write_count;
+
+    grid.swap_cells(grid.xy_to_index(3, 3), grid.xy_to_index(4, 4));
+
+    assert_eq!(grid.write_count, writes + 1);
+    assert_eq!(grid.staleness[grid.xy_to_index(2, 2)], 0);
+    assert_eq!(grid.staleness[grid.xy_to_index(5, 5)], 0);
+    assert_eq!(grid.staleness[grid.xy_to_index(6, 6)], SETTLE_THRESHOLD);
+    assert_eq!(grid.dirty_rect, Some((3, 3, 4, 4)));
+}
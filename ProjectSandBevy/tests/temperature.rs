@@ -0,0 +1,58 @@
+use ProjectSandBevy::elements::{element_base_temp, Element};
+use ProjectSandBevy::simulation::{diffuse_temperatures, do_gravity, execute_element_action, GameGrid, SimContext, SimulationRules};
+
+#[test]
+fn placed_ice_survives_without_heat_nearby() {
+    let mut grid = GameGrid::new(16, 16);
+    grid.set(8, 8, Element::Ice);
+    let i = grid.xy_to_index(8, 8);
+    assert_eq!(grid.temperatures[i], element_base_temp(Element::Ice));
+
+    for _ in 0..20 {
+        diffuse_temperatures(&mut grid);
+        execute_element_action(&mut grid, 8, 8, i, &SimulationRules::default(), &mut SimContext::default());
+    }
+
+    assert_eq!(grid.get(8, 8), Element::Ice);
+    assert!(grid.temperatures[i] < 0.0, "{}", grid.temperatures[i]);
+}
+
+#[test]
+fn temperature_moves_with_a_falling_element() {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(4, 2, Element::Sand);
+    let i = grid.xy_to_index(4, 2);
+    grid.temperatures[i] = 500.0;
+
+    assert!(do_gravity(&mut grid, 4, 2, i, false, 1.0, false, &mut None, false));
+
+    assert_eq!(grid.get(4, 3), Element::Sand);
+    assert_eq!(grid.temperatures[grid.xy_to_index(4, 3)], 500.0);
+    assert!(grid.temperatures[i] < 500.0);
+}
+
+/// Run heat diffusion and the Ice cell's action at (3, 3) until it melts, returning the ticks it took
+fn ticks_to_melt(neighbor: Element) -> Option<u32> {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(3, 3, Element::Ice);
+    grid.set(4, 3, neighbor);
+    let i = grid.xy_to_index(3, 3);
+    (1..=200).find(|_| {
+        diffuse_temperatures(&mut grid);
+        execute_element_action(&mut grid, 3, 3, i, &SimulationRules::default(), &mut SimContext::default());
+        grid.get(3, 3) != Element::Ice
+    })
+}
+
+#[test]
+fn heat_from_a_neighbor_melts_ice() {
+    // No fixed melt chances: Steam, Fire and Lava melt Ice by warming it past ICE_MELT_TEMP
+    for neighbor in [Element::Steam, Element::Fire, Element::Lava] {
+        assert!(ticks_to_melt(neighbor).is_some(), "{neighbor:?}");
+    }
+    // Lava is far hotter than Steam, so it melts Ice sooner
+    assert!(ticks_to_melt(Element::Lava) < ticks_to_melt(Element::Steam));
+    // Ice and Cryo keep it frozen
+    assert_eq!(ticks_to_melt(Element::Ice), None);
+    assert_eq!(ticks_to_melt(Element::Cryo), None);
+}
@@ -0,0 +1,80 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{process_tree_branches, seed_simulation_rng, start_tree_generation, ActiveTreeBranches, GameGrid};
+
+const WIDTH: u32 = 300;
+const HEIGHT: u32 = 300;
+const MAX_FRAMES: u32 = 5000;
+
+/// Grow one tree from the bottom middle until every branch has finished
+/// Returns the most branches that were growing at once and the final grid
+fn grow_tree(seed: u64) -> (usize, GameGrid) {
+    seed_simulation_rng(Some(seed));
+    let mut grid = GameGrid::new(WIDTH, HEIGHT);
+    let mut active_branches = ActiveTreeBranches::default();
+    start_tree_generation(&mut active_branches, WIDTH / 2, HEIGHT - 1);
+
+    let mut peak = active_branches.branches.len();
+    let mut frames = 0;
+    while !active_branches.branches.is_empty() {
+        process_tree_branches(&mut grid, &mut active_branches);
+        peak = peak.max(active_branches.branches.len());
+        frames += 1;
+        assert!(frames < MAX_FRAMES, "seed {seed}: tree never finished growing");
+    }
+    seed_simulation_rng(None);
+    (peak, grid)
+}
+
+#[test]
+fn large_trees_finish_with_leaves() {
+    let mut grew_large_tree = false;
+    for seed in 0..30 {
+        let (peak, grid) = grow_tree(seed);
+        if peak >= 10 {
+            grew_large_tree = true;
+            // Every branch that finishes ends in a leaf, removing the wrong branch would strand some
+            assert!(grid.elements.contains(&Element::Leaf), "seed {seed}: no leaves placed");
+            assert!(grid.elements.contains(&Element::Branch), "seed {seed}: no branches placed");
+        }
+    }
+    assert!(grew_large_tree, "no seed grew a tree with 10 or more branches at once");
+}
+
+#[test]
+fn only_the_blocked_branches_are_removed() {
+    const TREES: [u32; 4] = [30, 90, 150, 210];
+    const FRAMES: u32 = 4;
+    seed_simulation_rng(Some(7));
+    let mut grid = GameGrid::new(WIDTH, HEIGHT);
+    let mut active_branches = ActiveTreeBranches::default();
+    for x in TREES {
+        start_tree_generation(&mut active_branches, x, HEIGHT - 1);
+    }
+    // Cap the first and third trees so both of their branches finish in the same call
+    for x in [TREES[0], TREES[2]] {
+        for y in HEIGHT - 3..HEIGHT - 1 {
+            for wall_x in x - 1..=x + 1 {
+                grid.set(wall_x, y, Element::Wall);
+            }
+        }
+    }
+
+    process_tree_branches(&mut grid, &mut active_branches);
+    assert_eq!(active_branches.branches.len(), 2);
+    for _ in 1..FRAMES {
+        process_tree_branches(&mut grid, &mut active_branches);
+    }
+    seed_simulation_rng(None);
+
+    // The open trees kept growing one cell per call, the capped ones never placed a branch
+    let branch_cells = |tree_x: u32| {
+        (tree_x - 5..=tree_x + 5)
+            .flat_map(|x| (0..HEIGHT).map(move |y| (x, y)))
+            .filter(|&(x, y)| grid.get(x, y) == Element::Branch)
+            .count()
+    };
+    assert_eq!(branch_cells(TREES[0]), 0);
+    assert_eq!(branch_cells(TREES[2]), 0);
+    assert!(branch_cells(TREES[1]) >= 3, "second tree stopped growing");
+    assert!(branch_cells(TREES[3]) >= 3, "fourth tree stopped growing");
+}
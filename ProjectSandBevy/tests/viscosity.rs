@@ -0,0 +1,27 @@
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{do_viscous_gravity, seed_simulation_rng, GameGrid};
+
+/// Fraction of `trials` ticks in which `element` falls with the given base chance
+fn fall_rate(element: Element, chance: f64, trials: u32) -> f64 {
+    let mut fell = 0;
+    for _ in 0..trials {
+        let mut grid = GameGrid::new(5, 4);
+        grid.set(2, 1, element);
+        let i = grid.xy_to_index(2, 1);
+        if do_viscous_gravity(&mut grid, 2, 1, i, false, chance, false, &mut None, false) {
+            fell += 1;
+        }
+    }
+    f64::from(fell) / f64::from(trials)
+}
+
+#[test]
+fn viscosity_scales_the_base_fall_chance() {
+    seed_simulation_rng(Some(3));
+    for (element, chance) in [(Element::Water, 0.95), (Element::Oil, 0.95), (Element::Honey, 1.0), (Element::Honey, 0.5)] {
+        let expected = chance * f64::from(element.viscosity());
+        let rate = fall_rate(element, chance, 4000);
+        assert!((rate - expected).abs() < 0.03, "{element:?} at {chance}: {rate} vs {expected}");
+    }
+    seed_simulation_rng(None);
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::save::WorldSaveFile;
+use ProjectSandBevy::simulation::GameGrid;
+use ProjectSandBevy::spigots::{Side, Spigots, WallSpigot};
+
+#[test]
+fn wall_spigot_band_follows_the_height_fraction() {
+    let mut spigots = Spigots::default();
+    spigots.wall_spigots.push(WallSpigot { element: Element::Water, size: 10, side: Side::Left, position_fraction: 0.0 });
+    spigots.wall_spigots.push(WallSpigot { element: Element::Lava, size: 10, side: Side::Right, position_fraction: 1.0 });
+    spigots.wall_spigots.push(WallSpigot { element: Element::Sand, size: 10, side: Side::Left, position_fraction: 0.5 });
+
+    let positions = spigots.get_wall_spigot_positions(110);
+
+    assert_eq!(positions, vec![(0, 10, 0, Side::Left), (100, 10, 1, Side::Right), (50, 10, 2, Side::Left)]);
+}
+
+#[test]
+fn disabled_and_oversized_wall_spigots() {
+    let mut spigots = Spigots::default();
+    spigots.wall_spigots.push(WallSpigot { size: 0, ..WallSpigot::default() });
+    spigots.wall_spigots.push(WallSpigot { size: 50, position_fraction: 2.0, ..WallSpigot::default() });
+
+    // The disabled one is skipped and the band is kept inside the grid
+    assert_eq!(spigots.get_wall_spigot_positions(20), vec![(0, 20, 1, Side::Left)]);
+}
+
+#[test]
+fn wall_spigots_survive_save_and_load() {
+    let grid = GameGrid::new(64, 48);
+    let mut spigots = Spigots::default();
+    let wall_spigot = WallSpigot { element: Element::Acid, size: 4, side: Side::Right, position_fraction: 0.25 };
+    spigots.wall_spigots.push(wall_spigot);
+
+    let data = WorldSaveFile::new(&grid, &spigots, &HashMap::new()).to_bytes().unwrap();
+    let save = WorldSaveFile::from_bytes(&data).unwrap();
+
+    assert_eq!(save.spigots.wall_spigots, vec![wall_spigot]);
+}
@@ -0,0 +1,42 @@
+use bevy::math::Vec2;
+use ProjectSandBevy::elements::Element;
+use ProjectSandBevy::simulation::{execute_element_action, GameGrid, SimContext, SimulationRules, WindField};
+
+#[test]
+fn wind_splits_into_drift_and_lift() {
+    let updraft = WindField { direction: Vec2::Y, strength: WindField::MAX_STRENGTH };
+    assert_eq!(updraft.drift(), 0.0);
+    assert_eq!(updraft.lift(), 1.0);
+
+    let gust = WindField { direction: Vec2::new(-1.0, -1.0).normalize(), strength: WindField::MAX_STRENGTH / 2.0 };
+    assert!(gust.drift() < 0.0);
+    assert!(gust.lift() < 0.0);
+    assert!((gust.drift() - gust.lift()).abs() < f32::EPSILON);
+}
+
+/// Tick the Pollen resting on the floor at (4, 7) until it moves, returning where it went
+fn tick_pollen(wind: &WindField) -> Option<(u32, u32)> {
+    let mut grid = GameGrid::new(8, 8);
+    grid.set(4, 7, Element::Pollen);
+    let i = grid.xy_to_index(4, 7);
+    let mut ctx = SimContext { wind: Some(wind), ..SimContext::default() };
+    for _ in 0..100 {
+        execute_element_action(&mut grid, 4, 7, i, &SimulationRules::default(), &mut ctx);
+        if grid.get(4, 7) != Element::Pollen {
+            let moved = grid.elements.iter().position(|&element| element == Element::Pollen)?;
+            return Some(grid.index_to_xy(moved));
+        }
+    }
+    None
+}
+
+#[test]
+fn updraft_lifts_pollen_off_the_floor() {
+    let updraft = WindField { direction: Vec2::Y, strength: WindField::MAX_STRENGTH };
+    assert_eq!(tick_pollen(&updraft), Some((4, 6)));
+
+    // Calm air and a downdraft leave it resting on the floor
+    assert_eq!(tick_pollen(&WindField::default()), None);
+    let downdraft = WindField { direction: Vec2::NEG_Y, strength: WindField::MAX_STRENGTH };
+    assert_eq!(tick_pollen(&downdraft), None);
+}
@@ -6,6 +6,15 @@
 // Uses gather/advection approach: each thread determines what should be at its location by reading
 // from source locations (above, diagonal above). This eliminates race conditions and ensures full
 // parallelism without atomic operations.
+// Every cell decides where it wants to move with `wants_move`, which only reads the input texture, so
+// the source and destination threads always agree. A destination accepts the first neighbor (in a
+// fixed priority order) that wants to move into it, and a source only leaves if it was accepted.
+// Reactions (burning, freezing, dissolving, ...) are written from the point of view of the cell that
+// changes, since each thread may only write its own location. `element_action` mirrors
+// `execute_element_action` in ProjectSandBevy.
+// Each workgroup first loads its cells plus a border into workgroup memory (`tile`), so neighbor
+// lookups don't go back to the textures. Results are collected in workgroup memory and written back
+// once the whole workgroup is done.
 
 @group(0) @binding(0) var input: texture_storage_2d<rgba32float, read>;
 
@@ -17,6 +26,11 @@
 
 @group(0) @binding(4) var<uniform> config: FallingSandUniforms;
 
+// Velocity textures (rgba32float - (x_vel, y_vel, 0, 0) in cells per step)
+@group(0) @binding(5) var velocity_input: texture_storage_2d<rgba32float, read>;
+
+@group(0) @binding(6) var velocity_output: texture_storage_2d<rgba32float, write>;
+
 struct FallingSandUniforms {
     size: vec2<u32>,
     click_position: vec2<i32>,
@@ -25,11 +39,38 @@ struct FallingSandUniforms {
     click_radius: f32, // Radius of the circle for placing/removing sand
     selected_element: u32,
     sim_step: u32, // Simulation step counter for alternating diagonal movement
-    bit_field: u32, // Bit field for various flags, 0 = overwrite mode, 1 = fall into void
+    bit_field: u32, // Bit field for various flags, 0 = overwrite mode, 1 = fall into void, 2 = fire enabled
+    gravity_chance: f32, // Chance per step that a powder falls
+    liquid_flow: f32, // Multiplier on each liquid's viscosity (chance per step that it flows)
+    gas_rise_chance: f32, // Chance per step that a gas rises
+    fire_spread_chance: f32, // Multiplier on each element's flammability
+    fire_die_chance: f32, // Chance per step that fire with nothing to burn goes out
+    plant_growth_chance: f32, // Chance per step that plant grows into touching water
+    workgroup_size_x: u32, // Must match @workgroup_size below
+    workgroup_size_y: u32,
+    gravity: f32, // Velocity sand gains per step while falling
+    max_velocity: f32, // Most cells sand can fall in one step
 }
 
+// Workgroup tile: 8x8 cells plus a 2 cell border on each side
+const TILE_BORDER: i32 = 2;
+const TILE_WIDTH: u32 = 12u;
+const TILE_CELLS: u32 = 144u;
+const WORKGROUP_CELLS: u32 = 64u;
+
+var<workgroup> tile: array<vec4<f32>, 144>;
+var<workgroup> tile_ids: array<u32, 144>;
+var<workgroup> result_colors: array<vec4<f32>, 64>;
+var<workgroup> result_ids: array<u32, 64>;
+var<workgroup> result_velocities: array<vec4<f32>, 64>;
+
+// Grid location of the tile's top left cell, and this thread's slot in the result arrays
+var<private> tile_origin: vec2<i32>;
+var<private> result_index: u32;
+
 const OVERWRITE_MODE_BIT: u32 = 0u;
 const FALL_INTO_VOID_BIT: u32 = 1u;
+const FIRE_ENABLED_BIT: u32 = 2u;
 
 const BACKGROUND_COLOR: vec4<f32> = vec4<f32>(0.0, 0.0, 0.0, 1.0);
 const WALL_COLOR: vec4<f32> = vec4<f32>(0.49803922, 0.49803922, 0.49803922, 1.0);
@@ -73,37 +114,115 @@ const ZOMBIE_WET_COLOR: vec4<f32> = vec4<f32>(0.92549020, 0.87450980, 0.96078431
 const ZOMBIE_BURNING_COLOR: vec4<f32> = vec4<f32>(0.98039216, 0.50980392, 0.50980392, 1.0);
 const ZOMBIE_FROZEN_COLOR: vec4<f32> = vec4<f32>(0.74509804, 0.74509804, 0.98039216, 1.0);
 
-// Element type IDs (stored in separate texture)
+// Element type IDs (stored in separate texture, must match elements.rs)
 const BACKGROUND_ID: u32 = 0u;
 const WALL_ID: u32 = 1u;
 const SAND_ID: u32 = 2u;
-const RAINBOW_SAND_ID: u32 = 3u;
+const WATER_ID: u32 = 3u;
+const FIRE_ID: u32 = 4u;
+const SALT_ID: u32 = 5u;
+const OIL_ID: u32 = 6u;
+const ROCK_ID: u32 = 7u;
+const ICE_ID: u32 = 8u;
+const LAVA_ID: u32 = 9u;
+const STEAM_ID: u32 = 10u;
+const SALT_WATER_ID: u32 = 11u;
+const PLANT_ID: u32 = 12u;
+const GUNPOWDER_ID: u32 = 13u;
+const WAX_ID: u32 = 14u;
+const CONCRETE_ID: u32 = 15u;
+const NITRO_ID: u32 = 16u;
+const NAPALM_ID: u32 = 17u;
+const C4_ID: u32 = 18u;
+const FUSE_ID: u32 = 19u;
+const ACID_ID: u32 = 20u;
+const CRYO_ID: u32 = 21u;
+const METHANE_ID: u32 = 22u;
+const SOIL_ID: u32 = 23u;
+const WET_SOIL_ID: u32 = 24u;
+const THERMITE_ID: u32 = 25u;
+const SPOUT_ID: u32 = 26u;
+const WELL_ID: u32 = 27u;
+const TORCH_ID: u32 = 28u;
+const BRANCH_ID: u32 = 29u;
+const LEAF_ID: u32 = 30u;
+const POLLEN_ID: u32 = 31u;
+const FALLING_WAX_ID: u32 = 32u;
+const CHILLED_ICE_ID: u32 = 33u;
+const MYSTERY_ID: u32 = 34u;
+const CHARGED_NITRO_ID: u32 = 35u;
+const BURNING_THERMITE_ID: u32 = 36u;
+const RAINBOW_SAND_ID: u32 = 37u;
+
+// Movement classes
+const MOVE_STATIC: u32 = 0u;
+const MOVE_POWDER: u32 = 1u;
+const MOVE_LIQUID: u32 = 2u;
+const MOVE_GAS: u32 = 3u;
+
+// Random salts so the decisions a cell makes in one step are independent
+const SALT_MOVE: u32 = 0u;
+const SALT_DIRECTION: u32 = 1u;
+const SALT_REACTION: u32 = 2u;
+
+const NO_SOURCE: vec2<i32> = vec2<i32>(-1, -1);
 
 fn bit_field_get(bit: u32) -> bool {
     return ((config.bit_field >> bit) & 1u) == 1u;
 }
 
-// Get element type from element type texture
+// Index of a location in the workgroup tile, or -1 if it's outside the tile
+fn tile_index(location: vec2<i32>) -> i32 {
+    let tile_offset = location - tile_origin;
+    if (any(tile_offset < vec2<i32>(0)) || any(tile_offset >= vec2<i32>(i32(TILE_WIDTH)))) {
+        return -1;
+    }
+    return tile_offset.y * i32(TILE_WIDTH) + tile_offset.x;
+}
+
+// Get element type from the tile, falling back to the element type texture past the border
 fn get_element_type(location: vec2<i32>) -> u32 {
+    let index = tile_index(location);
+    if (index >= 0) {
+        return tile_ids[index];
+    }
     return textureLoad(element_type_input, location).r;
 }
 
-// Hash-based PRNG to generate a deterministic random value between 0.0 and 1.0
-// Based on position and sim_step to ensure each particle has its own random sequence
-fn hash_random(pos_x: i32, pos_y: i32, sim_step: u32) -> f32 {
-    // Use large prime numbers for better hash distribution
-    let hash = u32(pos_x) * 73856093u + u32(pos_y) * 19349663u + sim_step * 83492791u;
-    // Convert to float in range [0.0, 1.0)
-    // Use modulo with a large number to get good distribution
-    return f32(hash % 1000000u) / 1000000.0;
+// Get color from the tile, falling back to the color texture past the border
+fn get_color(location: vec2<i32>) -> vec4<f32> {
+    let index = tile_index(location);
+    if (index >= 0) {
+        return tile[index];
+    }
+    return textureLoad(input, location);
+}
+
+// PCG32 (RXS-M-XS variant): advance the state and return the next random value
+fn pcg32(state: ptr<function, u32>) -> u32 {
+    let old_state = *state;
+    *state = old_state * 747796405u + 2891336453u;
+    let word = ((old_state >> ((old_state >> 28u) + 4u)) ^ old_state) * 277803737u;
+    return (word >> 22u) ^ word;
 }
 
-// Check if a sand particle should be affected by gravity (95% chance)
-// Returns true if gravity should apply, false if particle should stay in place
-fn should_apply_gravity(pos_x: i32, pos_y: i32, sim_step: u32) -> bool {
-    let random_value = hash_random(pos_x, pos_y, sim_step);
-    // 95% chance to be affected by gravity (0.95 threshold)
-    return random_value < 0.95;
+// Convert a PCG32 output to a float in [0.0, 1.0)
+fn pcg32_float(state: ptr<function, u32>) -> f32 {
+    return f32(pcg32(state) >> 8u) / 16777216.0;
+}
+
+// Seed for decisions only this invocation makes (like spigots spawning)
+fn invocation_seed(workgroup_id: vec3<u32>, local_id: vec3<u32>) -> u32 {
+    return workgroup_id.x * 1000003u + workgroup_id.y * 1000033u + local_id.x * 31u + local_id.y * 37u + config.sim_step;
+}
+
+// Random value in [0.0, 1.0) for a decision about the cell at a location
+// Neighboring threads call `wants_move` for the same cell and must agree, so this is seeded by the
+// cell's position, sim_step and a salt rather than by the invocation
+fn random(location: vec2<i32>, salt: u32) -> f32 {
+    var state = (u32(location.x) * 73856093u) ^ (u32(location.y) * 19349663u) ^ (config.sim_step * 83492791u) ^ (salt * 2654435761u);
+    pcg32(&state);
+    return pcg32_float(&state);
 }
 
 // Calculate rainbow sand color based on sim_step and position
@@ -168,6 +287,524 @@ fn is_background(location: vec2<i32>, offset_x: i32, offset_y: i32) -> bool {
     return get_element_type(check_location) == BACKGROUND_ID;
 }
 
+fn in_bounds(location: vec2<i32>) -> bool {
+    return location.x >= 0 && location.y >= 0 && location.x < i32(config.size.x) && location.y < i32(config.size.y);
+}
+
+// Element at a location, out of bounds counts as wall so nothing moves off the grid
+fn element_at(location: vec2<i32>) -> u32 {
+    if (!in_bounds(location)) {
+        return WALL_ID;
+    }
+    return get_element_type(location);
+}
+
+// Color for a newly created element (rainbow sand is colored by the caller)
+fn element_color(id: u32) -> vec4<f32> {
+    var color = BACKGROUND_COLOR;
+    switch id {
+        case WALL_ID: { color = WALL_COLOR; }
+        case SAND_ID, RAINBOW_SAND_ID: { color = SAND_COLOR; }
+        case WATER_ID: { color = WATER_COLOR; }
+        case FIRE_ID: { color = FIRE_COLOR; }
+        case SALT_ID: { color = SALT_COLOR; }
+        case OIL_ID: { color = OIL_COLOR; }
+        case ROCK_ID: { color = ROCK_COLOR; }
+        case ICE_ID: { color = ICE_COLOR; }
+        case LAVA_ID: { color = LAVA_COLOR; }
+        case STEAM_ID: { color = STEAM_COLOR; }
+        case SALT_WATER_ID: { color = SALT_WATER_COLOR; }
+        case PLANT_ID: { color = PLANT_COLOR; }
+        case GUNPOWDER_ID: { color = GUNPOWDER_COLOR; }
+        case WAX_ID: { color = WAX_COLOR; }
+        case CONCRETE_ID: { color = CONCRETE_COLOR; }
+        case NITRO_ID: { color = NITRO_COLOR; }
+        case NAPALM_ID: { color = NAPALM_COLOR; }
+        case C4_ID: { color = C4_COLOR; }
+        case FUSE_ID: { color = FUSE_COLOR; }
+        case ACID_ID: { color = ACID_COLOR; }
+        case CRYO_ID: { color = CRYO_COLOR; }
+        case METHANE_ID: { color = METHANE_COLOR; }
+        case SOIL_ID: { color = SOIL_COLOR; }
+        case WET_SOIL_ID: { color = WET_SOIL_COLOR; }
+        case THERMITE_ID: { color = THERMITE_COLOR; }
+        case SPOUT_ID: { color = SPOUT_COLOR; }
+        case WELL_ID: { color = WELL_COLOR; }
+        case TORCH_ID: { color = TORCH_COLOR; }
+        case BRANCH_ID: { color = BRANCH_COLOR; }
+        case LEAF_ID: { color = LEAF_COLOR; }
+        case POLLEN_ID: { color = POLLEN_COLOR; }
+        case FALLING_WAX_ID: { color = FALLING_WAX_COLOR; }
+        case CHILLED_ICE_ID: { color = CHILLED_ICE_COLOR; }
+        case MYSTERY_ID: { color = MYSTERY_COLOR; }
+        case CHARGED_NITRO_ID: { color = CHARGED_NITRO_COLOR; }
+        case BURNING_THERMITE_ID: { color = BURNING_THERMITE_COLOR; }
+        default: {}
+    }
+    return color;
+}
+
+fn movement_class(id: u32) -> u32 {
+    var movement = MOVE_STATIC;
+    switch id {
+        case SAND_ID, RAINBOW_SAND_ID, SALT_ID, ROCK_ID, GUNPOWDER_ID, CONCRETE_ID, SOIL_ID, WET_SOIL_ID,
+            THERMITE_ID, POLLEN_ID, FALLING_WAX_ID, MYSTERY_ID, CHARGED_NITRO_ID, BURNING_THERMITE_ID: {
+            movement = MOVE_POWDER;
+        }
+        case WATER_ID, OIL_ID, LAVA_ID, SALT_WATER_ID, NITRO_ID, NAPALM_ID, ACID_ID, CRYO_ID: {
+            movement = MOVE_LIQUID;
+        }
+        case STEAM_ID, METHANE_ID: {
+            movement = MOVE_GAS;
+        }
+        default: {}
+    }
+    return movement;
+}
+
+// Heavy powders drop straight down, everything else that moves can slide diagonally
+fn falls_diagonally(id: u32) -> bool {
+    return !(id == ROCK_ID || id == THERMITE_ID || id == BURNING_THERMITE_ID || id == SOIL_ID || id == FALLING_WAX_ID);
+}
+
+// Density relative to water, matches ELEMENT_DENSITY in ProjectSandBevy
+fn density(id: u32) -> f32 {
+    var value = 0.0;
+    switch id {
+        case OIL_ID: { value = 0.85; }
+        case NAPALM_ID: { value = 0.9; }
+        case WATER_ID, CRYO_ID: { value = 1.0; }
+        case SALT_WATER_ID: { value = 1.025; }
+        case ACID_ID: { value = 1.2; }
+        case NITRO_ID, SOIL_ID: { value = 1.3; }
+        case WET_SOIL_ID: { value = 1.4; }
+        case CHARGED_NITRO_ID: { value = 1.5; }
+        case SAND_ID, RAINBOW_SAND_ID, MYSTERY_ID: { value = 1.6; }
+        case GUNPOWDER_ID: { value = 1.7; }
+        case THERMITE_ID, BURNING_THERMITE_ID: { value = 2.0; }
+        case SALT_ID: { value = 2.2; }
+        case CONCRETE_ID: { value = 2.4; }
+        case ROCK_ID: { value = 2.7; }
+        case LAVA_ID: { value = 3.1; }
+        default: {}
+    }
+    return value;
+}
+
+// Fluidity of liquids, matches ELEMENT_VISCOSITY in ProjectSandBevy
+fn viscosity(id: u32) -> f32 {
+    var value = 1.0;
+    switch id {
+        case OIL_ID: { value = 0.8; }
+        case LAVA_ID: { value = 0.3; }
+        default: {}
+    }
+    return value;
+}
+
+// Chance per step that an element of this kind tries to move
+fn move_chance(id: u32) -> f32 {
+    let movement = movement_class(id);
+    if (movement == MOVE_POWDER) {
+        return config.gravity_chance;
+    }
+    if (movement == MOVE_LIQUID) {
+        return config.liquid_flow * viscosity(id);
+    }
+    if (movement == MOVE_GAS) {
+        // Methane is heavier than steam and rises more slowly
+        return config.gas_rise_chance * select(1.0, 0.35, id == METHANE_ID);
+    }
+    return 0.0;
+}
+
+fn is_lighter_liquid(neighbor: u32, id: u32) -> bool {
+    return movement_class(neighbor) == MOVE_LIQUID && density(neighbor) < density(id);
+}
+
+// A liquid with nowhere to go this step, so a heavier element can safely swap places with it
+fn is_stuck(location: vec2<i32>) -> bool {
+    let id = element_at(location);
+    let below = location + vec2<i32>(0, 1);
+    if (!in_bounds(below)) {
+        if (bit_field_get(FALL_INTO_VOID_BIT)) {
+            return false;
+        }
+    } else {
+        let below_id = element_at(below);
+        if (below_id == BACKGROUND_ID || is_lighter_liquid(below_id, id)) {
+            return false;
+        }
+    }
+    return element_at(location + vec2<i32>(-1, 1)) != BACKGROUND_ID
+        && element_at(location + vec2<i32>(1, 1)) != BACKGROUND_ID
+        && element_at(location + vec2<i32>(-1, 0)) != BACKGROUND_ID
+        && element_at(location + vec2<i32>(1, 0)) != BACKGROUND_ID;
+}
+
+// Elements that build up speed while falling
+fn uses_velocity(id: u32) -> bool {
+    return id == SAND_ID || id == RAINBOW_SAND_ID;
+}
+
+// Velocity of the element at this location after this step's gravity, clamped to max_velocity
+fn next_velocity(location: vec2<i32>, id: u32) -> vec2<f32> {
+    if (!uses_velocity(id)) {
+        return vec2<f32>(0.0);
+    }
+    let velocity = textureLoad(velocity_input, location).xy;
+    return vec2<f32>(0.0, min(velocity.y + config.gravity, config.max_velocity));
+}
+
+// How far an element falls straight down through empty cells, given the first cell below is empty
+// Sand advances as many cells as its velocity allows, everything else one cell
+fn fall_destination(location: vec2<i32>, vertical: vec2<i32>, id: u32) -> vec2<i32> {
+    if (!uses_velocity(id)) {
+        return vertical;
+    }
+    var destination = vertical;
+    let steps = max(1, i32(next_velocity(location, id).y));
+    for (var k = 1; k < steps; k++) {
+        let next = destination + vec2<i32>(0, 1);
+        if (!in_bounds(next)) {
+            if (bit_field_get(FALL_INTO_VOID_BIT)) {
+                return next;
+            }
+            break;
+        }
+        if (element_at(next) != BACKGROUND_ID) {
+            break;
+        }
+        destination = next;
+    }
+    return destination;
+}
+
+// Where the element at this location wants to move this step (its own location if it stays)
+// Only reads the input texture, so every thread computes the same answer for the same cell
+fn wants_move(location: vec2<i32>) -> vec2<i32> {
+    let id = element_at(location);
+    let movement = movement_class(id);
+    if (movement == MOVE_STATIC || random(location, SALT_MOVE) >= move_chance(id)) {
+        return location;
+    }
+
+    // Gases rise, everything else falls
+    let vertical = location + vec2<i32>(0, select(1, -1, movement == MOVE_GAS));
+    if (!in_bounds(vertical)) {
+        if (bit_field_get(FALL_INTO_VOID_BIT)) {
+            // Off the grid - the element disappears
+            return vertical;
+        }
+    } else {
+        let vertical_id = element_at(vertical);
+        if (vertical_id == BACKGROUND_ID) {
+            return fall_destination(location, vertical, id);
+        }
+        // Heavier elements sink through lighter liquids that aren't going anywhere
+        if (movement != MOVE_GAS && is_lighter_liquid(vertical_id, id) && is_stuck(vertical)) {
+            return vertical;
+        }
+    }
+
+    let side = select(-1, 1, random(location, SALT_DIRECTION) < 0.5);
+    if (falls_diagonally(id)) {
+        if (element_at(vertical + vec2<i32>(side, 0)) == BACKGROUND_ID) {
+            return vertical + vec2<i32>(side, 0);
+        }
+        if (element_at(vertical - vec2<i32>(side, 0)) == BACKGROUND_ID) {
+            return vertical - vec2<i32>(side, 0);
+        }
+    }
+
+    // Liquids and gases spread sideways
+    if (movement == MOVE_LIQUID || movement == MOVE_GAS) {
+        if (element_at(location + vec2<i32>(side, 0)) == BACKGROUND_ID) {
+            return location + vec2<i32>(side, 0);
+        }
+        if (element_at(location - vec2<i32>(side, 0)) == BACKGROUND_ID) {
+            return location - vec2<i32>(side, 0);
+        }
+    }
+
+    return location;
+}
+
+// The neighbor whose element moves into this location this step, or NO_SOURCE
+// Falling from above wins over sliding sideways, which wins over rising from below
+fn accepted_source(location: vec2<i32>) -> vec2<i32> {
+    var offsets = array<vec2<i32>, 8>(
+        vec2<i32>(0, -1), vec2<i32>(-1, -1), vec2<i32>(1, -1),
+        vec2<i32>(-1, 0), vec2<i32>(1, 0),
+        vec2<i32>(0, 1), vec2<i32>(-1, 1), vec2<i32>(1, 1),
+    );
+    // Sand falling more than one cell: only the nearest element above can fall straight through
+    // the empty cells between, so stop at the first one found
+    for (var k = 2; k <= i32(config.max_velocity); k++) {
+        if (element_at(location - vec2<i32>(0, k - 1)) != BACKGROUND_ID) {
+            break;
+        }
+        let source = location - vec2<i32>(0, k);
+        if (element_at(source) != BACKGROUND_ID) {
+            if (in_bounds(source) && all(wants_move(source) == location)) {
+                return source;
+            }
+            break;
+        }
+    }
+    for (var k = 0; k < 8; k++) {
+        let source = location + offsets[k];
+        if (element_at(source) != BACKGROUND_ID && in_bounds(source) && all(wants_move(source) == location)) {
+            return source;
+        }
+    }
+    return NO_SOURCE;
+}
+
+// Check the 4 direct neighbors for an element
+fn touching(location: vec2<i32>, other: u32) -> bool {
+    return element_at(location + vec2<i32>(0, -1)) == other
+        || element_at(location + vec2<i32>(0, 1)) == other
+        || element_at(location + vec2<i32>(-1, 0)) == other
+        || element_at(location + vec2<i32>(1, 0)) == other;
+}
+
+// Check all 8 neighbors for an element
+fn touching_adjacent(location: vec2<i32>, other: u32) -> bool {
+    return touching(location, other)
+        || element_at(location + vec2<i32>(-1, -1)) == other
+        || element_at(location + vec2<i32>(1, -1)) == other
+        || element_at(location + vec2<i32>(-1, 1)) == other
+        || element_at(location + vec2<i32>(1, 1)) == other;
+}
+
+fn touching_heat(location: vec2<i32>) -> bool {
+    return touching_adjacent(location, FIRE_ID)
+        || touching_adjacent(location, LAVA_ID)
+        || touching_adjacent(location, BURNING_THERMITE_ID);
+}
+
+// Chance per step that a touching heat source ignites this element (before the fire_spread_chance multiplier)
+fn flammability(id: u32) -> f32 {
+    var value = 0.0;
+    switch id {
+        case GUNPOWDER_ID: { value = 0.95; }
+        case FUSE_ID: { value = 0.8; }
+        case C4_ID: { value = 0.6; }
+        case THERMITE_ID: { value = 0.5; }
+        case NITRO_ID, CHARGED_NITRO_ID: { value = 0.3; }
+        case OIL_ID, NAPALM_ID, METHANE_ID: { value = 0.25; }
+        case PLANT_ID, POLLEN_ID: { value = 0.2; }
+        case LEAF_ID: { value = 0.05; }
+        case BRANCH_ID: { value = 0.03; }
+        case WAX_ID: { value = 0.01; }
+        default: {}
+    }
+    return value;
+}
+
+// What a flammable element turns into when it catches fire
+fn burned_into(id: u32) -> u32 {
+    if (id == THERMITE_ID) {
+        return BURNING_THERMITE_ID;
+    }
+    if (id == WAX_ID) {
+        return FALLING_WAX_ID;
+    }
+    return FIRE_ID;
+}
+
+// Elements acid can't dissolve
+fn is_acid_immune(id: u32) -> bool {
+    return id == BACKGROUND_ID || id == ACID_ID || id == WATER_ID || id == SALT_WATER_ID || id == ICE_ID
+        || id == CHILLED_ICE_ID || id == STEAM_ID || id == CRYO_ID;
+}
+
+fn is_flammable(e: u32) -> bool {
+    return flammability(e) > 0.0;
+}
+
+fn has_flammable_neighbor(location: vec2<i32>) -> bool {
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            if (is_flammable(element_at(location + vec2<i32>(dx, dy)))) {
+                return true;
+            }
+        }
+    }
+    return false;
+}
+
+// Element-specific reactions for a cell that isn't moving this step
+// Mirrors execute_element_action, rewritten from the point of view of the cell that changes
+// Returns the element this cell becomes
+fn element_action(location: vec2<i32>, id: u32) -> u32 {
+    let roll = random(location, SALT_REACTION);
+    let fire_enabled = bit_field_get(FIRE_ENABLED_BIT);
+    var result = id;
+    switch id {
+        case BACKGROUND_ID: {
+            // Producers fill empty space around them
+            if (touching_adjacent(location, TORCH_ID) && roll < 0.25) {
+                result = FIRE_ID;
+            } else if (touching_adjacent(location, WELL_ID) && roll < 0.10) {
+                result = OIL_ID;
+            } else if (touching_adjacent(location, SPOUT_ID) && roll < 0.05) {
+                result = WATER_ID;
+            } else if (fire_enabled && element_at(location + vec2<i32>(0, 1)) == FIRE_ID && roll < 0.50) {
+                // Fire rises
+                result = FIRE_ID;
+            }
+        }
+        case FIRE_ID: {
+            // Water puts fire out (the water turns to steam on its side), and it flames out with nothing
+            // left to burn
+            if ((touching(location, WATER_ID) || touching(location, SALT_WATER_ID)) && roll < 0.80) {
+                result = STEAM_ID;
+            } else if (fire_enabled && !has_flammable_neighbor(location) && roll < config.fire_die_chance) {
+                result = BACKGROUND_ID;
+            }
+        }
+        case WATER_ID: {
+            if ((touching(location, FIRE_ID) || touching(location, LAVA_ID)) && roll < 0.80) {
+                result = STEAM_ID;
+            } else if (touching_adjacent(location, CRYO_ID) && roll < 0.10) {
+                result = ICE_ID;
+            } else if (touching_adjacent(location, PLANT_ID) && roll < config.plant_growth_chance) {
+                result = PLANT_ID;
+            } else if (touching(location, SALT_ID) && roll < 0.25) {
+                result = SALT_WATER_ID;
+            }
+        }
+        case SALT_WATER_ID: {
+            if ((touching(location, FIRE_ID) || touching(location, LAVA_ID)) && roll < 0.80) {
+                result = STEAM_ID;
+            }
+        }
+        case SALT_ID: {
+            // Salt dissolves in water
+            if (touching(location, WATER_ID) && roll < 0.125) {
+                result = BACKGROUND_ID;
+            }
+        }
+        case LAVA_ID: {
+            if (touching(location, WATER_ID) || touching(location, SALT_WATER_ID) || touching(location, CRYO_ID)) {
+                result = ROCK_ID;
+            }
+        }
+        case ICE_ID: {
+            if ((touching_heat(location) || touching(location, SALT_ID) || touching(location, SALT_WATER_ID)) && roll < 0.10) {
+                result = WATER_ID;
+            }
+        }
+        case CHILLED_ICE_ID: {
+            // Chilled ice thaws back to regular ice
+            if (roll < 0.06) {
+                result = ICE_ID;
+            }
+        }
+        case STEAM_ID: {
+            // Steam condenses back to water
+            if ((touching(location, WATER_ID) && roll < 0.05) || roll < 0.02) {
+                result = WATER_ID;
+            }
+        }
+        case PLANT_ID: {
+            if ((touching(location, SALT_ID) || touching(location, SALT_WATER_ID)) && roll < 0.05) {
+                result = BACKGROUND_ID;
+            }
+        }
+        case LEAF_ID: {
+            if ((touching(location, SALT_ID) || touching(location, SALT_WATER_ID)) && roll < 0.20) {
+                result = BACKGROUND_ID;
+            }
+        }
+        case SOIL_ID: {
+            // Soil absorbs water above it
+            if (element_at(location + vec2<i32>(0, -1)) == WATER_ID && roll < 0.15) {
+                result = WET_SOIL_ID;
+            }
+        }
+        case WET_SOIL_ID: {
+            if (!touching_adjacent(location, WATER_ID) && roll < 0.05) {
+                result = SOIL_ID;
+            }
+        }
+        case NITRO_ID: {
+            // Soil charges nitro
+            if (touching_adjacent(location, SOIL_ID) && roll < 0.25) {
+                result = CHARGED_NITRO_ID;
+            }
+        }
+        case FALLING_WAX_ID: {
+            // Falling wax sets once it lands
+            if (element_at(location + vec2<i32>(0, 1)) != BACKGROUND_ID) {
+                result = WAX_ID;
+            }
+        }
+        case CONCRETE_ID: {
+            // Concrete hardens against walls, and very slowly on its own
+            if ((touching_adjacent(location, WALL_ID) && roll < 0.01) || roll < 0.0005) {
+                result = WALL_ID;
+            }
+        }
+        case WALL_ID: {
+            // Burning thermite burns through walls
+            if (touching(location, BURNING_THERMITE_ID) && roll < 0.08) {
+                result = BACKGROUND_ID;
+            }
+        }
+        case BURNING_THERMITE_ID: {
+            // Burning thermite consumes itself
+            if (roll < 0.02) {
+                result = BACKGROUND_ID;
+            }
+        }
+        default: {}
+    }
+
+    if (result != id) {
+        return result;
+    }
+
+    // Flammable elements catch fire from touching heat sources
+    if (fire_enabled && touching_heat(location) && roll < flammability(id) * config.fire_spread_chance) {
+        return burned_into(id);
+    }
+
+    // Acid dissolves anything that isn't immune (walls resist a bit)
+    if (!is_acid_immune(id) && touching(location, ACID_ID) && roll < select(0.10, 0.075, id == WALL_ID)) {
+        return BACKGROUND_ID;
+    }
+
+    return id;
+}
+
+// Write the new state of this thread's cell, stored to the output textures at the end of `update`
+// Anything written this way starts at rest
+fn write_cell(location: vec2<i32>, color: vec4<f32>, id: u32) {
+    result_colors[result_index] = color;
+    result_ids[result_index] = id;
+    result_velocities[result_index] = vec4<f32>(0.0);
+}
+
+// Move the element at source into location, carrying its velocity
+fn copy_cell(location: vec2<i32>, source: vec2<i32>) {
+    let id = element_at(source);
+    write_cell(location, get_color(source), id);
+    result_velocities[result_index] = vec4<f32>(next_velocity(source, id), 0.0, 0.0);
+}
+
+// Keep the element at location, applying its reactions
+fn stay_cell(location: vec2<i32>, id: u32) {
+    let new_id = element_action(location, id);
+    if (new_id == id) {
+        write_cell(location, get_color(location), id);
+    } else {
+        write_cell(location, element_color(new_id), new_id);
+    }
+}
+
 @compute @workgroup_size(8, 8, 1)
 fn init(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
     let location = vec2<i32>(i32(invocation_id.x), i32(invocation_id.y));
@@ -176,11 +813,51 @@ fn init(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
     let color = BACKGROUND_COLOR;
     textureStore(output, location, color);
     textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
+    textureStore(velocity_output, location, vec4<f32>(0.0));
 }
 
 @compute @workgroup_size(8, 8, 1)
-fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
-    let location = vec2<i32>(i32(invocation_id.x), i32(invocation_id.y));
+fn update(
+    @builtin(global_invocation_id) invocation_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(local_invocation_index) local_index: u32,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+) {
+    let workgroup_size = vec2<i32>(i32(config.workgroup_size_x), i32(config.workgroup_size_y));
+    tile_origin = vec2<i32>(workgroup_id.xy) * workgroup_size - vec2<i32>(TILE_BORDER);
+    result_index = local_index;
+
+    // Cooperatively load the tile, each thread loads every 64th cell
+    for (var i = local_index; i < TILE_CELLS; i += WORKGROUP_CELLS) {
+        let tile_location = tile_origin + vec2<i32>(i32(i % TILE_WIDTH), i32(i / TILE_WIDTH));
+        if (in_bounds(tile_location)) {
+            tile[i] = textureLoad(input, tile_location);
+            tile_ids[i] = textureLoad(element_type_input, tile_location).r;
+        } else {
+            tile[i] = BACKGROUND_COLOR;
+            tile_ids[i] = WALL_ID;
+        }
+    }
+    workgroupBarrier();
+
+    let location = vec2<i32>(invocation_id.xy);
+    let valid = in_bounds(location);
+    if (valid) {
+        simulate(location, invocation_seed(workgroup_id, local_id));
+    }
+    workgroupBarrier();
+
+    // Cooperatively write the results back
+    if (valid) {
+        textureStore(output, location, result_colors[local_index]);
+        textureStore(element_type_output, location, vec4<u32>(result_ids[local_index], 0u, 0u, 0u));
+        textureStore(velocity_output, location, result_velocities[local_index]);
+    }
+}
+
+// Determine the new state of a single cell, the result goes through `write_cell`
+fn simulate(location: vec2<i32>, seed: u32) {
+    var rng = seed;
     let size = vec2<i32>(i32(config.size.x), i32(config.size.y));
     
     // Check bounds
@@ -206,26 +883,13 @@ fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
             let overwrite_mode = bit_field_get(OVERWRITE_MODE_BIT);
             // Don't overwrite walls unless overwrite mode is enabled
             if (overwrite_mode || (is_bg && !is_wall_pixel)) {
-                var element_color: vec4<f32>;
-                var element_type_id: u32;
-                if (config.selected_element == SAND_ID) {
-                    element_color = SAND_COLOR;
-                    element_type_id = SAND_ID;
-                } else if (config.selected_element == RAINBOW_SAND_ID) {
+                var color = element_color(config.selected_element);
+                if (config.selected_element == RAINBOW_SAND_ID) {
                     // Rainbow sand - all pixels in circle have same color, shifts over time
                     let rainbow_color = calculate_rainbow_sand_color(config.sim_step, click_pos.x, click_pos.y, false);
-                    element_color = vec4(rainbow_color.rgb, 1.0);
-                    element_type_id = RAINBOW_SAND_ID;
-                } else if (config.selected_element == WALL_ID) {
-                    element_color = WALL_COLOR;
-                    element_type_id = WALL_ID;
-                } else {
-                    // Anything unimplemented should be treated as background / eraser
-                    element_color = BACKGROUND_COLOR;
-                    element_type_id = BACKGROUND_ID;
+                    color = vec4(rainbow_color.rgb, 1.0);
                 }
-                textureStore(output, location, element_color);
-                textureStore(element_type_output, location, vec4<u32>(element_type_id, 0u, 0u, 0u));
+                write_cell(location, color, config.selected_element);
                 return;
             }
         }
@@ -280,10 +944,8 @@ fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
         if (in_spigot) {
             let current_element_type = get_element_type(location);
             let is_bg = current_element_type == BACKGROUND_ID;
-            // Spawn sand if location is empty (10% chance per frame, matching ProjectSandBevy)
-            // Use a simple hash-based random to get ~10% chance
-            let hash = u32(location.x) * 73856093u + u32(location.y) * 19349663u + config.sim_step;
-            if ((hash % 10u) == 0u && is_bg) {
+            // Spawn the spigot's element if location is empty (10% chance per frame, matching ProjectSandBevy)
+            if ((pcg32(&rng) % 10u) == 0u && is_bg) {
                 // Determine which spigot this location belongs to and use its type
                 var spigot_type = config.spigot_elements.x;
                 
@@ -297,7 +959,7 @@ fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
                     spigot_type = config.spigot_elements.w;
                 }
                 
-                // Use rainbow sand color if spigot type is rainbow, otherwise use regular sand color
+                // Use rainbow sand color if spigot type is rainbow, otherwise the element's color
                 var final_color: vec4<f32>;
                 var element_type_id: u32;
                 if (spigot_type == RAINBOW_SAND_ID) {
@@ -306,267 +968,50 @@ fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
                     final_color = vec4(rainbow_color.rgb, 1.0);
                     element_type_id = RAINBOW_SAND_ID;
                 } else {
-                    final_color = SAND_COLOR;
-                    element_type_id = SAND_ID;
+                    final_color = element_color(spigot_type);
+                    element_type_id = spigot_type;
                 }
-                textureStore(output, location, final_color);
-                textureStore(element_type_output, location, vec4<u32>(element_type_id, 0u, 0u, 0u));
+                write_cell(location, final_color, element_type_id);
                 return;
             }
         }
     }
     
     // Priority 3: Gather/advection - determine what should be at this location
-    // by reading from source locations (above, diagonal above)
-    
-    // Read current element type to check if it's empty
-    let current_element_type = get_element_type(location);
-    let current_is_bg = current_element_type == BACKGROUND_ID;
-    
-    // If current location has a wall, keep it in place (walls don't move)
-    if (current_element_type == WALL_ID) {
-        let current_pixel = textureLoad(input, location);
-        textureStore(output, location, current_pixel);
-        textureStore(element_type_output, location, vec4<u32>(WALL_ID, 0u, 0u, 0u));
-        return;
-    }
-    
-    let fall_into_void = bit_field_get(FALL_INTO_VOID_BIT);
-    // If current location has sand (regular or rainbow), check if it should move (and clear if it does)
-    let current_is_sand = current_element_type == SAND_ID || current_element_type == RAINBOW_SAND_ID;
-    if (current_is_sand) {
-        // Check if this sand particle should be affected by gravity (95% chance)
-        // If not affected by gravity, it stays in place regardless of whether it could move
-        if (!should_apply_gravity(location.x, location.y, config.sim_step)) {
-            // 5% chance: sand is not affected by gravity this frame - stays in place
-            let current_pixel = textureLoad(input, location);
-            textureStore(output, location, current_pixel);
-            textureStore(element_type_output, location, vec4<u32>(current_element_type, 0u, 0u, 0u));
-            return;
-        }
-        
-        // 95% chance: sand is affected by gravity - check if it can move
-        // Match ProjectSandBevy's do_gravity priority: below_adjacent (down, below-left, below-right), then adjacent (left/right)
-        
-        if (location.y >= size.y - 1) {
-            // At bottom edge - check fall_into_void
-            if (fall_into_void) {
-                textureStore(output, location, BACKGROUND_COLOR);
-                textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
+    let current_element_type = element_at(location);
+
+    if (current_element_type != BACKGROUND_ID) {
+        let destination = wants_move(location);
+        if (any(destination != location)) {
+            if (!in_bounds(destination)) {
+                // Fell (or rose) off the edge of the grid
+                write_cell(location, BACKGROUND_COLOR, BACKGROUND_ID);
                 return;
             }
-            // Can't move - stays in place
-            let current_pixel = textureLoad(input, location);
-            textureStore(output, location, current_pixel);
-            textureStore(element_type_output, location, vec4<u32>(current_element_type, 0u, 0u, 0u));
-            return;
-        }
-        
-        // Check below_adjacent: directly below, then below-left, then below-right
-        let below = vec2<i32>(location.x, location.y + 1);
-        let below_element_type = get_element_type(below);
-        let below_is_bg = below_element_type == BACKGROUND_ID;
-        let below_is_wall = below_element_type == WALL_ID;
-        
-        // Priority 1: Directly below (if not a wall)
-        if (below_is_bg && !below_is_wall) {
-            // Clear this location (the thread below will write the sand)
-            textureStore(output, location, BACKGROUND_COLOR);
-            textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-            return;
-        }
-        
-        // Priority 2: Below-left (if directly below is occupied and not a wall)
-        if (!below_is_wall) {
-            let below_left = vec2<i32>(location.x - 1, location.y + 1);
-            if (below_left.x >= 0 && below_left.y < size.y) {
-                let below_left_element_type = get_element_type(below_left);
-                let below_left_is_bg = below_left_element_type == BACKGROUND_ID;
-                let below_left_is_wall = below_left_element_type == WALL_ID;
-                
-                if (below_left_is_bg && !below_left_is_wall) {
-                    // Clear this location (the thread at below_left will write the sand)
-                    textureStore(output, location, BACKGROUND_COLOR);
-                    textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-                    return;
-                }
+            if (all(accepted_source(destination) == location)) {
+                // Moved away - whatever was at the destination (background or a lighter liquid) takes its place
+                copy_cell(location, destination);
+                return;
             }
-            
-            // Priority 3: Below-right
-            let below_right = vec2<i32>(location.x + 1, location.y + 1);
-            if (below_right.x < size.x && below_right.y < size.y) {
-                let below_right_element_type = get_element_type(below_right);
-                let below_right_is_bg = below_right_element_type == BACKGROUND_ID;
-                let below_right_is_wall = below_right_element_type == WALL_ID;
-                
-                if (below_right_is_bg && !below_right_is_wall) {
-                    // Clear this location (the thread at below_right will write the sand)
-                    textureStore(output, location, BACKGROUND_COLOR);
-                    textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-                    return;
-                }
+        } else if (movement_class(current_element_type) == MOVE_LIQUID) {
+            // A heavier element may be sinking into this liquid
+            let source = accepted_source(location);
+            if (all(source != NO_SOURCE)) {
+                copy_cell(location, source);
+                return;
             }
         }
-        
-        // Priority 4: Horizontal adjacent (left/right) - DISABLED for now to test
-        // Horizontal movement might be causing the collapse issue
-        // TODO: Re-enable with stricter conditions if needed
-        
-        // Sand can't move - stays in place
-        let current_pixel = textureLoad(input, location);
-        textureStore(output, location, current_pixel);
-        textureStore(element_type_output, location, vec4<u32>(current_element_type, 0u, 0u, 0u));
+        stay_cell(location, current_element_type);
         return;
     }
-    
-    // Current location is empty - check if sand would fall here
-    // Match ProjectSandBevy's below_adjacent then adjacent priority
-    // Note: If current location is a wall, we already handled it above (walls stay in place)
-    
-    // Priority 1: Directly above (below_adjacent checks directly below first)
-    let above = vec2<i32>(location.x, location.y - 1);
-    if (above.y >= 0) {
-        let above_element_type = get_element_type(above);
-        let above_is_sand = above_element_type == SAND_ID || above_element_type == RAINBOW_SAND_ID;
-        
-        if (above_is_sand) {
-            // Check if the source particle (above) is affected by gravity (95% chance)
-            if (should_apply_gravity(above.x, above.y, config.sim_step)) {
-                // Check if space directly below the source is empty (can fall straight)
-                let directly_below_above = vec2<i32>(above.x, above.y + 1);
-                if (directly_below_above.y < size.y) {
-                    let below_above_element_type = get_element_type(directly_below_above);
-                    let below_above_is_bg = below_above_element_type == BACKGROUND_ID;
-                    let below_above_is_wall = below_above_element_type == WALL_ID;
-                    
-                    // Only gather if space directly below source is empty (not a wall)
-                    if (below_above_is_bg && !below_above_is_wall) {
-                        // Check if we're at the bottom edge and fall_into_void is enabled
-                        if (location.y >= size.y - 1 && fall_into_void) {
-                            // Sand would fall into void - disappear (write background)
-                            textureStore(output, location, BACKGROUND_COLOR);
-                            textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-                            return;
-                        }
-                        // Sand falls straight down here
-                        let above_pixel = textureLoad(input, above);
-                        textureStore(output, location, above_pixel);
-                        textureStore(element_type_output, location, vec4<u32>(above_element_type, 0u, 0u, 0u));
-                        return;
-                    }
-                } else {
-                    // Source is at bottom edge - would fall into void
-                    if (fall_into_void) {
-                        textureStore(output, location, BACKGROUND_COLOR);
-                        textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-                        return;
-                    }
-                }
-            }
-        }
-    }
-    
-    // Priority 2: Above-left (below_adjacent checks below-left second)
-    // Only gather from diagonal if the source particle can't move straight down
-    let above_left = vec2<i32>(location.x - 1, location.y - 1);
-    if (above_left.y >= 0 && above_left.x >= 0) {
-        let above_left_element_type = get_element_type(above_left);
-        let above_left_is_sand = above_left_element_type == SAND_ID || above_left_element_type == RAINBOW_SAND_ID;
-        
-        if (above_left_is_sand) {
-            // Check if the source particle is affected by gravity (95% chance)
-            if (should_apply_gravity(above_left.x, above_left.y, config.sim_step)) {
-                // Check if space directly below the source is occupied (forcing diagonal fall)
-                let directly_below_above_left = vec2<i32>(above_left.x, above_left.y + 1);
-                if (directly_below_above_left.y < size.y) {
-                    let below_above_left_element_type = get_element_type(directly_below_above_left);
-                    let below_above_left_is_bg = below_above_left_element_type == BACKGROUND_ID;
-                    let below_above_left_is_wall = below_above_left_element_type == WALL_ID;
-                    
-                    // Only allow diagonal fall if:
-                    // 1. Space directly below source is occupied (not background, not wall)
-                    // 2. This ensures the source particle truly can't move straight down
-                    if (!below_above_left_is_bg && !below_above_left_is_wall) {
-                        // Additional check: make sure the source particle would actually move diagonally
-                        // by verifying it's not trying to move straight down (which would be handled by Priority 1)
-                        // Check if we're at the edge and fall_into_void
-                        if (location.y >= size.y - 1 && fall_into_void) {
-                            textureStore(output, location, BACKGROUND_COLOR);
-                            textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-                            return;
-                        }
-                        // Sand falls diagonally from above-left
-                        let above_left_pixel = textureLoad(input, above_left);
-                        textureStore(output, location, above_left_pixel);
-                        textureStore(element_type_output, location, vec4<u32>(above_left_element_type, 0u, 0u, 0u));
-                        return;
-                    }
-                } else {
-                    // Source is at bottom edge - would fall into void
-                    if (fall_into_void) {
-                        textureStore(output, location, BACKGROUND_COLOR);
-                        textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-                        return;
-                    }
-                }
-            }
-        }
-    }
-    
-    // Priority 3: Above-right (below_adjacent checks below-right third)
-    // Only gather from diagonal if the source particle can't move straight down
-    let above_right = vec2<i32>(location.x + 1, location.y - 1);
-    if (above_right.y >= 0 && above_right.x < size.x) {
-        let above_right_element_type = get_element_type(above_right);
-        let above_right_is_sand = above_right_element_type == SAND_ID || above_right_element_type == RAINBOW_SAND_ID;
-        
-        if (above_right_is_sand) {
-            // Check if the source particle is affected by gravity (95% chance)
-            if (should_apply_gravity(above_right.x, above_right.y, config.sim_step)) {
-                // Check if space directly below the source is occupied (forcing diagonal fall)
-                let directly_below_above_right = vec2<i32>(above_right.x, above_right.y + 1);
-                if (directly_below_above_right.y < size.y) {
-                    let below_above_right_element_type = get_element_type(directly_below_above_right);
-                    let below_above_right_is_bg = below_above_right_element_type == BACKGROUND_ID;
-                    let below_above_right_is_wall = below_above_right_element_type == WALL_ID;
-                    
-                    // Only allow diagonal fall if:
-                    // 1. Space directly below source is occupied (not background, not wall)
-                    // 2. This ensures the source particle truly can't move straight down
-                    if (!below_above_right_is_bg && !below_above_right_is_wall) {
-                        // Additional check: make sure the source particle would actually move diagonally
-                        // by verifying it's not trying to move straight down (which would be handled by Priority 1)
-                        // Check if we're at the edge and fall_into_void
-                        if (location.y >= size.y - 1 && fall_into_void) {
-                            textureStore(output, location, BACKGROUND_COLOR);
-                            textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-                            return;
-                        }
-                        // Sand falls diagonally from above-right
-                        let above_right_pixel = textureLoad(input, above_right);
-                        textureStore(output, location, above_right_pixel);
-                        textureStore(element_type_output, location, vec4<u32>(above_right_element_type, 0u, 0u, 0u));
-                        return;
-                    }
-                } else {
-                    // Source is at bottom edge - would fall into void
-                    if (fall_into_void) {
-                        textureStore(output, location, BACKGROUND_COLOR);
-                        textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-                        return;
-                    }
-                }
-            }
-        }
+
+    // Current location is empty - check if a neighbor moves here
+    let source = accepted_source(location);
+    if (all(source != NO_SOURCE)) {
+        copy_cell(location, source);
+        return;
     }
-    
-    // Priority 4: Horizontal adjacent (left/right) - DISABLED for now to test
-    // Horizontal movement might be causing the collapse issue
-    // TODO: Re-enable with stricter conditions if needed
-    
-    // No sand moving here - write background
-    // (We already checked current_is_bg above, so if we reach here, location is empty)
-    textureStore(output, location, BACKGROUND_COLOR);
-    textureStore(element_type_output, location, vec4<u32>(BACKGROUND_ID, 0u, 0u, 0u));
-}
 
+    // Nothing moving here - background reactions (producers, rising fire)
+    stay_cell(location, BACKGROUND_ID);
+}
@@ -1,12 +1,51 @@
 use bevy::{color::LinearRgba, ecs::resource::Resource};
 
+/// Element types in the simulation.
+///
+/// The IDs match `ProjectSandBevy` so both variants agree on element indices.
+/// They are stored in the element type texture and must stay in sync with the `*_ID` constants in
+/// `falling_sand.wgsl`.
 #[derive(Debug, Resource, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 #[repr(u32)]
 pub enum Element {
     Background = 0,
     Wall = 1,
     Sand = 2,
-    RainbowSand = 3,
+    Water = 3,
+    Fire = 4,
+    Salt = 5,
+    Oil = 6,
+    Rock = 7,
+    Ice = 8,
+    Lava = 9,
+    Steam = 10,
+    SaltWater = 11,
+    Plant = 12,
+    Gunpowder = 13,
+    Wax = 14,
+    Concrete = 15,
+    Nitro = 16,
+    Napalm = 17,
+    C4 = 18,
+    Fuse = 19,
+    Acid = 20,
+    Cryo = 21,
+    Methane = 22,
+    Soil = 23,
+    WetSoil = 24,
+    Thermite = 25,
+    Spout = 26,
+    Well = 27,
+    Torch = 28,
+    Branch = 29,
+    Leaf = 30,
+    Pollen = 31,
+    FallingWax = 32,
+    ChilledIce = 33,
+    Mystery = 34,
+    ChargedNitro = 35,
+    BurningThermite = 36,
+    RainbowSand = 37,
 }
 
 impl Default for Element {
@@ -16,12 +55,88 @@ impl Default for Element {
 }
 
 impl Element {
+    /// Every element, in ID order
+    pub const ALL: [Element; 38] = [
+        Element::Background,
+        Element::Wall,
+        Element::Sand,
+        Element::Water,
+        Element::Fire,
+        Element::Salt,
+        Element::Oil,
+        Element::Rock,
+        Element::Ice,
+        Element::Lava,
+        Element::Steam,
+        Element::SaltWater,
+        Element::Plant,
+        Element::Gunpowder,
+        Element::Wax,
+        Element::Concrete,
+        Element::Nitro,
+        Element::Napalm,
+        Element::C4,
+        Element::Fuse,
+        Element::Acid,
+        Element::Cryo,
+        Element::Methane,
+        Element::Soil,
+        Element::WetSoil,
+        Element::Thermite,
+        Element::Spout,
+        Element::Well,
+        Element::Torch,
+        Element::Branch,
+        Element::Leaf,
+        Element::Pollen,
+        Element::FallingWax,
+        Element::ChilledIce,
+        Element::Mystery,
+        Element::ChargedNitro,
+        Element::BurningThermite,
+        Element::RainbowSand,
+    ];
+
     /// Get the color for this element as LinearRgba
     pub fn color(&self) -> LinearRgba {
         match self {
             Element::Background => LinearRgba::rgb(0.0, 0.0, 0.0),
             Element::Wall => LinearRgba::rgb(0.5, 0.5, 0.5), // 127, 127, 127
             Element::Sand => LinearRgba::rgb(0.76, 0.70, 0.50), // 223, 193, 99
+            Element::Water => LinearRgba::rgb(0.0, 0.04, 1.0), // 0, 10, 255
+            Element::Fire => LinearRgba::rgb(1.0, 0.0, 0.04), // 255, 0, 10
+            Element::Salt => LinearRgba::rgb(0.99, 0.99, 0.99), // 253, 253, 253
+            Element::Oil => LinearRgba::rgb(0.59, 0.24, 0.0), // 150, 60, 0
+            Element::Rock => LinearRgba::rgb(0.27, 0.16, 0.03), // 68, 40, 8
+            Element::Ice => LinearRgba::rgb(0.63, 0.91, 1.0), // 161, 232, 255
+            Element::Lava => LinearRgba::rgb(0.96, 0.43, 0.16), // 245, 110, 40
+            Element::Steam => LinearRgba::rgb(0.76, 0.84, 0.92), // 195, 214, 235
+            Element::SaltWater => LinearRgba::rgb(0.50, 0.69, 1.0), // 127, 175, 255
+            Element::Plant => LinearRgba::rgb(0.0, 0.86, 0.0), // 0, 220, 0
+            Element::Gunpowder => LinearRgba::rgb(0.67, 0.67, 0.55), // 170, 170, 140
+            Element::Wax => LinearRgba::rgb(0.94, 0.88, 0.83), // 239, 225, 211
+            Element::Concrete => LinearRgba::rgb(0.71, 0.71, 0.71), // 180, 180, 180
+            Element::Nitro => LinearRgba::rgb(0.0, 0.59, 0.10), // 0, 150, 26
+            Element::Napalm => LinearRgba::rgb(0.86, 0.50, 0.27), // 220, 128, 70
+            Element::C4 => LinearRgba::rgb(0.94, 0.90, 0.59), // 240, 230, 150
+            Element::Fuse => LinearRgba::rgb(0.86, 0.69, 0.78), // 219, 175, 199
+            Element::Acid => LinearRgba::rgb(0.62, 0.94, 0.16), // 157, 240, 40
+            Element::Cryo => LinearRgba::rgb(0.0, 0.84, 1.0), // 0, 213, 255
+            Element::Methane => LinearRgba::rgb(0.55, 0.55, 0.55), // 140, 140, 140
+            Element::Soil => LinearRgba::rgb(0.47, 0.29, 0.13), // 120, 75, 33
+            Element::WetSoil => LinearRgba::rgb(0.27, 0.14, 0.04), // 70, 35, 10
+            Element::Thermite => LinearRgba::rgb(0.76, 0.55, 0.27), // 195, 140, 70
+            Element::Spout => LinearRgba::rgb(0.46, 0.74, 0.99), // 117, 189, 252
+            Element::Well => LinearRgba::rgb(0.51, 0.04, 0.11), // 131, 11, 28
+            Element::Torch => LinearRgba::rgb(0.78, 0.02, 0.0), // 200, 5, 0
+            Element::Branch => LinearRgba::rgb(0.65, 0.50, 0.39), // 166, 128, 100
+            Element::Leaf => LinearRgba::rgb(0.32, 0.42, 0.18), // 82, 107, 45
+            Element::Pollen => LinearRgba::rgb(0.90, 0.92, 0.43), // 230, 235, 110
+            Element::FallingWax => LinearRgba::rgb(0.94, 0.88, 0.83), // 240, 225, 211
+            Element::ChilledIce => LinearRgba::rgb(0.08, 0.60, 0.86), // 20, 153, 220
+            Element::Mystery => LinearRgba::rgb(0.64, 0.91, 0.77), // 162, 232, 196
+            Element::ChargedNitro => LinearRgba::rgb(0.96, 0.38, 0.31), // 245, 98, 78
+            Element::BurningThermite => LinearRgba::rgb(1.0, 0.51, 0.51), // 255, 130, 130
             Element::RainbowSand => LinearRgba::rgb(0.76, 0.70, 0.50), // Base color similar to sand, but will be shifted
         }
     }
@@ -37,7 +152,41 @@ impl Element {
             0 => Element::Background,
             1 => Element::Wall,
             2 => Element::Sand,
-            3 => Element::RainbowSand,
+            3 => Element::Water,
+            4 => Element::Fire,
+            5 => Element::Salt,
+            6 => Element::Oil,
+            7 => Element::Rock,
+            8 => Element::Ice,
+            9 => Element::Lava,
+            10 => Element::Steam,
+            11 => Element::SaltWater,
+            12 => Element::Plant,
+            13 => Element::Gunpowder,
+            14 => Element::Wax,
+            15 => Element::Concrete,
+            16 => Element::Nitro,
+            17 => Element::Napalm,
+            18 => Element::C4,
+            19 => Element::Fuse,
+            20 => Element::Acid,
+            21 => Element::Cryo,
+            22 => Element::Methane,
+            23 => Element::Soil,
+            24 => Element::WetSoil,
+            25 => Element::Thermite,
+            26 => Element::Spout,
+            27 => Element::Well,
+            28 => Element::Torch,
+            29 => Element::Branch,
+            30 => Element::Leaf,
+            31 => Element::Pollen,
+            32 => Element::FallingWax,
+            33 => Element::ChilledIce,
+            34 => Element::Mystery,
+            35 => Element::ChargedNitro,
+            36 => Element::BurningThermite,
+            37 => Element::RainbowSand,
             _ => Element::Background,
         }
     }
@@ -47,6 +196,23 @@ impl Element {
         vec![
             Element::Sand,
             Element::RainbowSand,
+            Element::Water,
+            Element::Salt,
+            Element::Oil,
+            Element::Rock,
+            Element::Lava,
+            Element::SaltWater,
+            Element::Gunpowder,
+            Element::Concrete,
+            Element::Nitro,
+            Element::Napalm,
+            Element::Acid,
+            Element::Soil,
+            Element::WetSoil,
+            Element::Thermite,
+            Element::Pollen,
+            Element::Mystery,
+            Element::ChargedNitro,
         ]
     }
-}
\ No newline at end of file
+}
@@ -1,12 +1,12 @@
 use crate::systems::{init_falling_sand_pipeline, prepare_bind_group, ClearGrid, SimulationSpeed, SimulationFrameAccumulator};
-use crate::{SHADER_ASSET_PATH, SIZE, WORKGROUP_SIZE};
+use crate::{SHADER_ASSET_PATH, SIZE};
 use bevy::{
     prelude::*,
     render::{
         Render, RenderApp, RenderStartup, RenderSystems,
         extract_resource::{ExtractResourcePlugin, ExtractResource},
         render_graph::{self, RenderGraph, RenderLabel},
-        render_resource::{CachedPipelineState, ComputePassDescriptor, PipelineCache, BindGroup, BindGroupLayout, CachedComputePipelineId, ShaderType},
+        render_resource::{CachedPipelineState, ComputePassDescriptor, PipelineCache, BindGroup, BindGroupLayout, CachedComputePipelineId, ShaderType, UniformBuffer},
         renderer::RenderContext,
     },
     shader::PipelineCacheError,
@@ -23,6 +23,8 @@ pub struct FallingSandImages {
     pub texture_b: Handle<Image>,
     pub element_type_a: Handle<Image>,
     pub element_type_b: Handle<Image>,
+    pub velocity_a: Handle<Image>,
+    pub velocity_b: Handle<Image>,
 }
 
 pub const NUM_SPIGOTS: usize = 4;
@@ -37,6 +39,16 @@ pub struct FallingSandUniforms {
     pub selected_element: u32, // 0 = sand, 1 = rainbow sand, 2 = wall
     pub sim_step: u32,         // Simulation step counter for alternating diagonal movement
     pub bit_field: u32,        // Bit field for various flags
+    pub gravity_chance: f32,      // Chance per step that a powder falls
+    pub liquid_flow: f32,         // Multiplier on each liquid's viscosity (chance per step that it flows)
+    pub gas_rise_chance: f32,     // Chance per step that a gas rises
+    pub fire_spread_chance: f32,  // Multiplier on each element's flammability
+    pub fire_die_chance: f32,     // Chance per step that fire with nothing to burn goes out
+    pub plant_growth_chance: f32, // Chance per step that plant grows into touching water
+    pub workgroup_size_x: u32,    // Must match @workgroup_size in the shader
+    pub workgroup_size_y: u32,
+    pub gravity: f32,             // Velocity sand gains per step while falling
+    pub max_velocity: f32,        // Most cells sand can fall in one step
 }
 
 #[derive(Resource)]
@@ -49,6 +61,11 @@ pub struct FallingSandPipeline {
 #[derive(Resource)]
 pub struct FallingSandImageBindGroups(pub [BindGroup; 2]);
 
+/// Uniform buffers that alternate each frame alongside the texture ping-pong
+/// Uploading into the buffer the GPU isn't reading avoids stalling on the previous frame
+#[derive(Resource)]
+pub struct FallingSandUniformBuffers(pub [UniformBuffer<FallingSandUniforms>; 2]);
+
 /// Resource to store how many compute shader runs were executed this frame
 /// Used to update the frame accumulator correctly
 #[derive(Resource, Default)]
@@ -205,6 +222,9 @@ impl render_graph::Node for FallingSandNode {
         let bind_groups = &world.resource::<FallingSandImageBindGroups>().0;
         let pipeline_cache = world.resource::<PipelineCache>();
         let pipeline = world.resource::<FallingSandPipeline>();
+        let uniforms = world.resource::<FallingSandUniforms>();
+        let workgroups_x = SIZE.x.div_ceil(uniforms.workgroup_size_x);
+        let workgroups_y = SIZE.y.div_ceil(uniforms.workgroup_size_y);
         
         // Get simulation speed
         let simulation_speed = world.get_resource::<SimulationSpeed>()
@@ -239,7 +259,7 @@ impl render_graph::Node for FallingSandNode {
                         .unwrap();
                     pass.set_bind_group(0, &bind_groups[0], &[]);
                     pass.set_pipeline(init_pipeline);
-                    pass.dispatch_workgroups(SIZE.x / WORKGROUP_SIZE, SIZE.y / WORKGROUP_SIZE, 1);
+                    pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
                 }
                 FallingSandState::Update(index) => {
                     let update_pipeline = pipeline_cache
@@ -247,7 +267,7 @@ impl render_graph::Node for FallingSandNode {
                         .unwrap();
                     pass.set_bind_group(0, &bind_groups[index], &[]);
                     pass.set_pipeline(update_pipeline);
-                    pass.dispatch_workgroups(SIZE.x / WORKGROUP_SIZE, SIZE.y / WORKGROUP_SIZE, 1);
+                    pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
                 }
             }
         }
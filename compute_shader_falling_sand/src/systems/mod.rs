@@ -7,9 +7,10 @@
 )]
 
 use crate::plugins::{
-    FallingSandImageBindGroups, FallingSandImages, FallingSandPipeline, FallingSandUniforms, NUM_SPIGOTS,
+    FallingSandImageBindGroups, FallingSandImages, FallingSandPipeline, FallingSandUniformBuffers,
+    FallingSandUniforms, NUM_SPIGOTS,
 };
-use crate::{DISPLAY_FACTOR, SHADER_ASSET_PATH, SIZE};
+use crate::{DISPLAY_FACTOR, SHADER_ASSET_PATH, SIZE, WORKGROUP_SIZE};
 use bevy::{
     asset::RenderAssetUsages, render::render_resource::TextureUsages, window::PrimaryWindow,
 };
@@ -59,6 +60,16 @@ impl Default for OverwriteMode {
     }
 }
 
+/// Resource to track whether fire spreads and burns out
+#[derive(Resource, Clone, Copy)]
+pub struct FireEnabled(pub bool);
+
+impl Default for FireEnabled {
+    fn default() -> Self {
+        Self(true) // Fire enabled by default
+    }
+}
+
 /// Resource to track whether elements fall into the void or stop at edges
 #[derive(Resource, Clone, Copy)]
 pub struct FallIntoVoid(pub bool);
@@ -78,7 +89,7 @@ pub fn setup(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
     let texture_a_handle = image_assets.add(image.clone());
     let texture_b_handle = image_assets.add(image);
     
-    // Element type textures (r32uint - stores element type ID: 0=background, 1=wall, 2=sand, 3=rainbow_sand)
+    // Element type textures (r32uint - stores element type ID, see Element)
     let mut element_type_image = Image::new_target_texture(SIZE.x, SIZE.y, TextureFormat::R32Uint);
     element_type_image.asset_usage = RenderAssetUsages::RENDER_WORLD;
     element_type_image.texture_descriptor.usage =
@@ -86,6 +97,14 @@ pub fn setup(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
     let element_type_a_handle = image_assets.add(element_type_image.clone());
     let element_type_b_handle = image_assets.add(element_type_image);
 
+    // Velocity textures (rgba32float - stores (x_vel, y_vel, 0, 0) in cells per step)
+    let mut velocity_image = Image::new_target_texture(SIZE.x, SIZE.y, TextureFormat::Rgba32Float);
+    velocity_image.asset_usage = RenderAssetUsages::RENDER_WORLD;
+    velocity_image.texture_descriptor.usage =
+        TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING;
+    let velocity_a_handle = image_assets.add(velocity_image.clone());
+    let velocity_b_handle = image_assets.add(velocity_image);
+
     commands.spawn((
         Sprite {
             image: texture_a_handle.clone(),
@@ -102,6 +121,8 @@ pub fn setup(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
         texture_b: texture_b_handle,
         element_type_a: element_type_a_handle,
         element_type_b: element_type_b_handle,
+        velocity_a: velocity_a_handle,
+        velocity_b: velocity_b_handle,
     });
 
     commands.insert_resource(FallingSandUniforms {
@@ -110,9 +131,19 @@ pub fn setup(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
         spigot_sizes: UVec4::new(3, 3, 3, 3).into(),
         spigot_elements: UVec4::new(Element::RainbowSand as u32, Element::RainbowSand as u32, Element::RainbowSand as u32, Element::RainbowSand as u32).into(),
         click_radius: 5.0,                  // Default radius
-        selected_element: 0,                 // 0 = sand
+        selected_element: Element::RainbowSand.index(), // Matches the selected Element resource
         sim_step: 0,                        // Start at step 0
-        bit_field: 1,                  // Overwrite by default
+        bit_field: 0b101,              // Overwrite and fire enabled by default
+        gravity_chance: 0.95,               // Matches ProjectSandBevy's do_gravity chance for sand
+        liquid_flow: 1.0,                   // Liquids flow at their natural viscosity
+        gas_rise_chance: 0.70,              // Matches steam in ProjectSandBevy
+        fire_spread_chance: 1.0,            // Elements burn at their natural flammability
+        fire_die_chance: 0.40,              // Matches fire flaming out in ProjectSandBevy
+        plant_growth_chance: 0.50,          // Matches plant growth in ProjectSandBevy
+        workgroup_size_x: WORKGROUP_SIZE,
+        workgroup_size_y: WORKGROUP_SIZE,
+        gravity: 0.2,                       // Sand reaches 2 cells per step after 10 steps
+        max_velocity: 8.0,                  // Matches MAX_VELOCITY in ProjectSandBevy
     });
     
     // Initialize simulation speed and clear grid resources
@@ -120,6 +151,7 @@ pub fn setup(mut commands: Commands, mut image_assets: ResMut<Assets<Image>>) {
     commands.insert_resource(ClearGrid::default());
     commands.insert_resource(OverwriteMode::default());
     commands.insert_resource(FallIntoVoid::default());
+    commands.insert_resource(FireEnabled::default());
     commands.insert_resource(Element::RainbowSand);
 }
 
@@ -134,28 +166,18 @@ pub fn ui_system(
     mut simulation_speed: ResMut<SimulationSpeed>,
     mut overwrite_mode: ResMut<OverwriteMode>,
     mut fall_into_void: ResMut<FallIntoVoid>,
+    mut fire_enabled: ResMut<FireEnabled>,
     mut selected_element: ResMut<Element>,
 ) -> Result {
     egui::Window::new("Controls").show(contexts.ctx_mut()?, |ui| {
         // Element selection buttons
         ui.label("Selected Element:");
-        ui.horizontal(|ui| {
-            let sand_selected = *selected_element == Element::Sand;
-            if ui.selectable_label(sand_selected, "Sand").clicked() {
-                *selected_element = Element::Sand;
-                uniforms.selected_element = Element::Sand.index();
-            }
-            
-            let rainbow_selected = *selected_element == Element::RainbowSand;
-            if ui.selectable_label(rainbow_selected, "Rainbow Sand").clicked() {
-                *selected_element = Element::RainbowSand;
-                uniforms.selected_element = Element::RainbowSand.index();
-            }
-            
-            let wall_selected = *selected_element == Element::Wall;
-            if ui.selectable_label(wall_selected, "Wall").clicked() {
-                *selected_element = Element::Wall;
-                uniforms.selected_element = Element::Wall.index();
+        ui.horizontal_wrapped(|ui| {
+            for element in Element::ALL.into_iter().skip(1) {
+                if ui.selectable_label(*selected_element == element, format!("{element:?}")).clicked() {
+                    *selected_element = element;
+                    uniforms.selected_element = element.index();
+                }
             }
         });
         
@@ -192,6 +214,17 @@ pub fn ui_system(
         }
         ui.label("When enabled, drawing overwrites existing materials. When disabled, only draws on empty spaces.");
 
+        ui.separator();
+
+        // Fire toggle
+        let mut fire = fire_enabled.0;
+        if ui.checkbox(&mut fire, "Fire Enabled").changed() {
+            fire_enabled.0 = fire;
+            // fire enabled is bit 2 in ((bit_field >> 2u) & 1u), so we need to set or clear that bit
+            uniforms.bit_field = (uniforms.bit_field & !(1u32 << 2u32)) | ((fire as u32) << 2u32);
+        }
+        ui.label("When disabled, fire doesn't spread to flammable elements or burn out.");
+
         ui.separator();
         
         ui.label("Controls: Left Click = Place Selected Element, Right Click = Remove");
@@ -217,6 +250,20 @@ pub fn ui_system(
 
         ui.separator();
 
+        // Element behavior parameters (passed to the shader as uniforms)
+        ui.collapsing("Element Rules", |ui| {
+            ui.add(egui::Slider::new(&mut uniforms.gravity_chance, 0.0..=1.0).text("Gravity chance"));
+            ui.add(egui::Slider::new(&mut uniforms.liquid_flow, 0.0..=1.0).text("Liquid flow"));
+            ui.add(egui::Slider::new(&mut uniforms.gas_rise_chance, 0.0..=1.0).text("Gas rise chance"));
+            ui.add(egui::Slider::new(&mut uniforms.fire_spread_chance, 0.0..=1.0).text("Fire spread chance"));
+            ui.add(egui::Slider::new(&mut uniforms.fire_die_chance, 0.0..=1.0).text("Fire die chance"));
+            ui.add(egui::Slider::new(&mut uniforms.plant_growth_chance, 0.0..=1.0).text("Plant growth chance"));
+            ui.add(egui::Slider::new(&mut uniforms.gravity, 0.0..=1.0).text("Gravity"));
+            ui.add(egui::Slider::new(&mut uniforms.max_velocity, 1.0..=8.0).text("Max velocity"));
+        });
+
+        ui.separator();
+
         // Clear button
         if ui.button("Clear Grid").clicked() {
             clear_grid.0 = true;
@@ -477,10 +524,14 @@ pub fn sync_ui_settings_to_uniforms(
     mut uniforms: ResMut<FallingSandUniforms>,
     overwrite_mode: Res<OverwriteMode>,
     fall_into_void: Res<FallIntoVoid>,
+    fire_enabled: Res<FireEnabled>,
 ) {
+    let bit_field = u32::from(overwrite_mode.0) << 0u32
+        | u32::from(fall_into_void.0) << 1u32
+        | u32::from(fire_enabled.0) << 2u32;
     // Only update if changed to avoid unnecessary writes
-    if uniforms.bit_field != u32::from(overwrite_mode.0) << 0u32 | u32::from(fall_into_void.0) << 1u32 {
-        uniforms.bit_field = u32::from(overwrite_mode.0) << 0u32 | u32::from(fall_into_void.0) << 1u32;
+    if uniforms.bit_field != bit_field {
+        uniforms.bit_field = bit_field;
     }
 }
 
@@ -494,6 +545,8 @@ pub fn prepare_bind_group(
     gpu_images: Res<RenderAssets<GpuImage>>,
     falling_sand_images: Res<FallingSandImages>,
     falling_sand_uniforms: Res<FallingSandUniforms>,
+    mut uniform_buffers: Option<ResMut<FallingSandUniformBuffers>>,
+    mut frame: Local<usize>,
     render_device: Res<RenderDevice>,
     queue: Res<RenderQueue>,
 ) {
@@ -501,11 +554,33 @@ pub fn prepare_bind_group(
     let view_b = gpu_images.get(&falling_sand_images.texture_b).unwrap();
     let element_type_view_a = gpu_images.get(&falling_sand_images.element_type_a).unwrap();
     let element_type_view_b = gpu_images.get(&falling_sand_images.element_type_b).unwrap();
+    let velocity_view_a = gpu_images.get(&falling_sand_images.velocity_a).unwrap();
+    let velocity_view_b = gpu_images.get(&falling_sand_images.velocity_b).unwrap();
 
     // Uniform buffer is used here to demonstrate how to set up a uniform in a compute shader
     // Alternatives such as storage buffers or push constants may be more suitable for your use case
-    let mut uniform_buffer = UniformBuffer::from(*falling_sand_uniforms);
-    uniform_buffer.write_buffer(&render_device, &queue);
+    // Two buffers alternate each frame: this frame's uniforms are uploaded into one while the
+    // shader reads the one uploaded last frame, so the upload never waits on the GPU
+    let write_index = *frame % 2;
+    let read_index = (*frame + 1) % 2;
+    *frame += 1;
+
+    let mut new_buffers = None;
+    let buffers = match uniform_buffers.as_deref_mut() {
+        Some(uniform_buffers) => &mut uniform_buffers.0,
+        None => {
+            // First frame - create both buffers on the GPU
+            let buffers = new_buffers.insert([
+                UniformBuffer::from(*falling_sand_uniforms),
+                UniformBuffer::from(*falling_sand_uniforms),
+            ]);
+            buffers[read_index].write_buffer(&render_device, &queue);
+            buffers
+        }
+    };
+    buffers[write_index].set(*falling_sand_uniforms);
+    buffers[write_index].write_buffer(&render_device, &queue);
+    let uniform_buffer = &buffers[read_index];
 
     let bind_group_0 = render_device.create_bind_group(
         None,
@@ -515,7 +590,9 @@ pub fn prepare_bind_group(
             &view_b.texture_view,
             &element_type_view_a.texture_view,
             &element_type_view_b.texture_view,
-            &uniform_buffer,
+            uniform_buffer,
+            &velocity_view_a.texture_view,
+            &velocity_view_b.texture_view,
         )),
     );
     let bind_group_1 = render_device.create_bind_group(
@@ -526,10 +603,15 @@ pub fn prepare_bind_group(
             &view_a.texture_view,
             &element_type_view_b.texture_view,
             &element_type_view_a.texture_view,
-            &uniform_buffer,
+            uniform_buffer,
+            &velocity_view_b.texture_view,
+            &velocity_view_a.texture_view,
         )),
     );
     commands.insert_resource(FallingSandImageBindGroups([bind_group_0, bind_group_1]));
+    if let Some(buffers) = new_buffers {
+        commands.insert_resource(FallingSandUniformBuffers(buffers));
+    }
 }
 
 // Initialize the simulation frame accumulator and runs counter in the render world
@@ -558,6 +640,8 @@ pub fn init_falling_sand_pipeline(
                 texture_storage_2d(TextureFormat::R32Uint, StorageTextureAccess::ReadOnly),
                 texture_storage_2d(TextureFormat::R32Uint, StorageTextureAccess::WriteOnly),
                 uniform_buffer::<FallingSandUniforms>(false),
+                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadOnly),
+                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::WriteOnly),
             ),
         ),
     );